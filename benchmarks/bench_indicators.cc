@@ -0,0 +1,152 @@
+#include <benchmark/benchmark.h>
+#include <cmath>
+#include <vector>
+#include "dynamic.h"
+#include "indicators.h"
+#include "statistics.h"
+#include "transforms.h"
+#include "volatility.h"
+
+using namespace tzu;
+
+namespace {
+
+// a random walk, long enough for the largest windows to slide
+const std::vector<Ohlcv>& sample_bars() {
+    static std::vector<Ohlcv> bars;
+    if (bars.empty()) {
+        double c = 100.0;
+        unsigned seed = 12345;
+        for (int i = 0; i < 100000; i++) {
+            seed = seed * 1103515245u + 12345u;
+            c *= 1.0 + (static_cast<int>((seed >> 16) % 2001) - 1000) * 1e-5;
+            bars.push_back(Ohlcv(i * 60, c, c * 1.002, c * 0.998, c,
+                    1.0 + (seed >> 16) % 100));
+        }
+    }
+    return bars;
+}
+
+const std::vector<double>& sample_closes() {
+    static std::vector<double> closes;
+    if (closes.empty())
+        for (const auto& bar : sample_bars()) closes.push_back(bar.close);
+    return closes;
+}
+
+// latency of one update, the hot path of a live process
+template <typename Ind>
+void update(benchmark::State& state, Ind ind) {
+    const std::vector<Ohlcv>& bars = sample_bars();
+    size_t i = 0;
+    for (auto _ : state) {
+        benchmark::DoNotOptimize(detail::feed_input(ind, bars[i]));
+        i = i + 1 < bars.size() ? i + 1 : 0;
+    }
+    state.SetItemsProcessed(state.iterations());
+}
+
+// throughput over a whole series, as in a backtest
+template <typename Ind, typename Input>
+void batch(benchmark::State& state, Ind ind, const std::vector<Input>& xs) {
+    for (auto _ : state) {
+        Ind fresh = ind;
+        benchmark::DoNotOptimize(fresh.compute_batch(xs));
+    }
+    state.SetItemsProcessed(state.iterations() * xs.size());
+}
+
+template <typename Ind>
+void batch_closes(benchmark::State& state, Ind ind) {
+    batch(state, ind, sample_closes());
+}
+
+template <typename Ind>
+void batch_bars(benchmark::State& state, Ind ind) {
+    batch(state, ind, sample_bars());
+}
+
+// several indicators on one stream, as a strategy would use them
+void composition(benchmark::State& state) {
+    MACD macd(12, 26, 9);
+    RSI rsi(14);
+    MVar mvar(20, 1);
+    SMA sma(20);
+    const std::vector<Ohlcv>& bars = sample_bars();
+    size_t i = 0;
+    for (auto _ : state) {
+        const Ohlcv& bar = bars[i];
+        benchmark::DoNotOptimize(macd.update(bar.close));
+        benchmark::DoNotOptimize(rsi.update(bar));
+        benchmark::DoNotOptimize(mvar.update(bar.close));
+        benchmark::DoNotOptimize(sma.update(bar.close));
+        i = i + 1 < bars.size() ? i + 1 : 0;
+    }
+    state.SetItemsProcessed(state.iterations());
+}
+
+// the same indicators behind DynIndicator, to measure the virtual calls
+void dynamic_composition(benchmark::State& state) {
+    std::vector<DynIndicator> inds;
+    inds.push_back(DynIndicator(MACD(12, 26, 9)));
+    inds.push_back(DynIndicator(RSI(14)));
+    inds.push_back(DynIndicator(MVar(20, 1)));
+    inds.push_back(DynIndicator(SMA(20)));
+    const std::vector<Ohlcv>& bars = sample_bars();
+    size_t i = 0;
+    for (auto _ : state) {
+        for (auto& ind : inds)
+            benchmark::DoNotOptimize(ind.update(bars[i]));
+        i = i + 1 < bars.size() ? i + 1 : 0;
+    }
+    state.SetItemsProcessed(state.iterations());
+}
+
+} // namespace
+
+BENCHMARK_CAPTURE(update, SMA, SMA(20));
+BENCHMARK_CAPTURE(update, EMA, EMA(20));
+BENCHMARK_CAPTURE(update, WMA, WMA(20));
+BENCHMARK_CAPTURE(update, HMA, HMA(20));
+BENCHMARK_CAPTURE(update, ZLEMA, ZLEMA(20));
+BENCHMARK_CAPTURE(update, T3, T3(5));
+BENCHMARK_CAPTURE(update, KAMA, KAMA(10));
+BENCHMARK_CAPTURE(update, MVar, MVar(20, 1));
+BENCHMARK_CAPTURE(update, Sharpe, Sharpe(20));
+BENCHMARK_CAPTURE(update, Sortino, Sortino(20));
+BENCHMARK_CAPTURE(update, RSI, RSI(14));
+BENCHMARK_CAPTURE(update, MACD, MACD(12, 26, 9));
+BENCHMARK_CAPTURE(update, LinReg, LinReg(20));
+BENCHMARK_CAPTURE(update, ATR, ATR(14));
+BENCHMARK_CAPTURE(update, ADX, ADX(14));
+BENCHMARK_CAPTURE(update, ElderRay, ElderRay(13));
+BENCHMARK_CAPTURE(update, Highest, Highest(20));
+BENCHMARK_CAPTURE(update, Aroon, Aroon(25));
+BENCHMARK_CAPTURE(update, StochRSI, StochRSI(14, 14, 3, 3));
+BENCHMARK_CAPTURE(update, Pivots, Pivots());
+BENCHMARK_CAPTURE(update, TimeSMA, TimeSMA(3600));
+BENCHMARK_CAPTURE(update, TimeMVar, TimeMVar(3600, 1));
+BENCHMARK_CAPTURE(update, EWMAVol, EWMAVol(20));
+BENCHMARK_CAPTURE(update, P2Quantile, P2Quantile(0.95));
+BENCHMARK_CAPTURE(update, Quantile, Quantile(0.95, 100));
+BENCHMARK_CAPTURE(update, FracDiff, FracDiff(0.4, 100));
+BENCHMARK_CAPTURE(update, WaveletDenoise, WaveletDenoise(64));
+BENCHMARK_CAPTURE(update, HeikinAshi, HeikinAshi());
+
+// long windows, where a scan of the window per update would dominate
+BENCHMARK_CAPTURE(update, SMA_1000, SMA(1000));
+BENCHMARK_CAPTURE(update, MVar_1000, MVar(1000, 1));
+BENCHMARK_CAPTURE(update, Highest_1000, Highest(1000));
+BENCHMARK_CAPTURE(update, Aroon_1000, Aroon(1000));
+BENCHMARK_CAPTURE(update, LinReg_1000, LinReg(1000));
+
+BENCHMARK_CAPTURE(batch_closes, SMA, SMA(20));
+BENCHMARK_CAPTURE(batch_closes, EMA, EMA(20));
+BENCHMARK_CAPTURE(batch_closes, MVar, MVar(20, 1));
+BENCHMARK_CAPTURE(batch_closes, MACD, MACD(12, 26, 9));
+BENCHMARK_CAPTURE(batch_bars, RSI, RSI(14));
+BENCHMARK_CAPTURE(batch_bars, ATR, ATR(14));
+BENCHMARK_CAPTURE(batch_bars, ADX, ADX(14));
+
+BENCHMARK(composition);
+BENCHMARK(dynamic_composition);
@@ -2,5 +2,54 @@
 #define TZU_H
 
 #include "tzu/runners.h"
+#include "tzu/transforms.h"
+#include "tzu/universe.h"
+#include "tzu/currency.h"
+#include "tzu/store.h"
+#include "tzu/validation.h"
+#include "tzu/labeling.h"
+#include "tzu/volatility.h"
+#include "tzu/risk.h"
+#include "tzu/broker.h"
+#include "tzu/connectors.h"
+#include "tzu/statistics.h"
+#include "tzu/scripting.h"
+#include "tzu/fixtures.h"
+#include "tzu/datetime.h"
+#include "tzu/serialize.h"
+#include "tzu/bars.h"
+#include "tzu/divergence.h"
+#include "tzu/ledger.h"
+#include "tzu/metrics.h"
+#include "tzu/costs.h"
+#include "tzu/orders.h"
+#include "tzu/optimize.h"
+#include "tzu/feed.h"
+#include "tzu/columnar.h"
+#include "tzu/interop.h"
+#include "tzu/numeric.h"
+#include "tzu/validate.h"
+#include "tzu/state.h"
+#include "tzu/dynamic.h"
+#include "tzu/engine.h"
+#include "tzu/registry.h"
+#include "tzu/expr.h"
+#include "tzu/config.h"
+#include "tzu/screener.h"
+#include "tzu/alerts.h"
+#include "tzu/events.h"
+#include "tzu/live.h"
+#include "tzu/paper.h"
+#include "tzu/alpaca.h"
+#include "tzu/download.h"
+#include "tzu/orderbook.h"
+#include "tzu/calendar.h"
+#include "tzu/synthetic.h"
+#include "tzu/regime.h"
+#include "tzu/filters.h"
+#include "tzu/ehlers.h"
+#include "tzu/swings.h"
+#include "tzu/levels.h"
+#include "tzu/anchored.h"
 
 #endif // TZU_H
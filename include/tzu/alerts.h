@@ -0,0 +1,227 @@
+#ifndef ALERTS_H
+#define ALERTS_H
+
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <map>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "expr.h"
+#include "feed.h"
+#include "registry.h"
+
+/**
+ * This header defines alerts: conditions watched on the bars of one or
+ * more symbols, which notify sinks when they trigger, so live monitors
+ * can be wired up quickly. Conditions are rule expressions (see
+ * `expr.h`), for thresholds and crosses, or callables, for anything
+ * else, such as pattern or divergence detections. Sinks are callables
+ * receiving the alerts; there are built-in sinks writing them to a
+ * stream, posting them to a webhook, and pushing them to a `Channel`
+ * read by another thread. As in `feed.h`, no network I/O is done here:
+ * webhooks are posted by a callable given by the caller, which any
+ * HTTP client can provide.
+ */
+
+namespace tzu {
+
+/**
+ * A triggered alert: the name of the condition, the symbol and the bar
+ * where it triggered, and a message, which is the rule for conditions
+ * given as expressions.
+ */
+struct Alert {
+    std::string name;
+    std::string symbol;
+    int64_t timestamp = 0;
+    double price = 0.0;
+    std::string message;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Alert& a) {
+    os << "alert:" << a.name << " symbol:" << a.symbol
+        << " timestamp:" << a.timestamp << " price:" << a.price
+        << " message:" << a.message;
+    return os;
+}
+
+/**
+ * An alert as a JSON object, as posted by `webhook_sink`.
+ */
+inline std::string to_json(const Alert& a) {
+    std::ostringstream os;
+    os.precision(17);
+    os << "{\"name\":\"" << detail::json_escape(a.name)
+        << "\",\"symbol\":\"" << detail::json_escape(a.symbol)
+        << "\",\"timestamp\":" << a.timestamp
+        << ",\"price\":" << a.price
+        << ",\"message\":\"" << detail::json_escape(a.message) << "\"}";
+    return os.str();
+}
+
+using AlertSink = std::function<void(const Alert&)>;
+
+/**
+ * Sink writing each alert on a line of a stream, e.g. `std::cout`,
+ * which must outlive it.
+ */
+inline AlertSink stream_sink(std::ostream& os) {
+    std::ostream* out = &os;
+    return [out](const Alert& a) { *out << a << std::endl; };
+}
+
+/**
+ * Sink posting each alert as JSON to a URL. `post` takes the URL and
+ * the body, and sends the request with the HTTP client of the caller.
+ */
+inline AlertSink webhook_sink(const std::string& url,
+        std::function<void(const std::string&, const std::string&)> post) {
+    return [url, post](const Alert& a) { post(url, to_json(a)); };
+}
+
+/**
+ * Sink pushing each alert to a channel, which must outlive it, to be
+ * consumed by another thread.
+ */
+inline AlertSink channel_sink(Channel<Alert>& channel) {
+    Channel<Alert>* ch = &channel;
+    return [ch](const Alert& a) { ch->push(a); };
+}
+
+/**
+ * AlertMonitor
+ *
+ * Keeps a copy of each condition for every symbol, created when its
+ * first bar arrives, and evaluates them at each bar of the symbol.
+ * Expressions trigger when they become true, so a threshold breached
+ * for several bars alerts once. Callables are stateful detectors,
+ * copied for each symbol like the expressions; they trigger each time
+ * they return true, with the message they set. Every alert is sent to
+ * all the sinks, in the order they were added. Bars not newer than the
+ * previous bar of their symbol are rejected.
+ *
+ * Example:
+ *
+ *     AlertMonitor monitor;
+ *     std::string error;
+ *     monitor.add("oversold", "rsi(14) < 30", error);
+ *     monitor.add("golden cross", "sma(50) crosses_above sma(200)", error);
+ *     monitor.add_sink(stream_sink(std::cout));
+ *     monitor.update("AAPL", bar);
+ */
+class AlertMonitor {
+public:
+    using Detector = std::function<bool(const Ohlcv&, std::string&)>;
+
+private:
+    struct Condition {
+        std::string name;
+        std::string rule;
+        Expression expr;
+        Detector detect;
+        bool active = false;
+    };
+    struct SymbolState {
+        std::vector<Condition> conditions;
+        int64_t timestamp = INT64_MIN;
+    };
+    const IndicatorRegistry* registry;
+    std::vector<Condition> prototypes;
+    std::map<std::string, SymbolState> states;
+    std::vector<AlertSink> sinks;
+    size_t sent = 0;
+
+    SymbolState& state(const std::string& symbol) {
+        auto it = states.find(symbol);
+        if (it == states.end()) {
+            it = states.emplace(symbol, SymbolState()).first;
+            it->second.conditions = prototypes;
+        }
+        return it->second;
+    }
+
+    void add(const Condition& c) {
+        prototypes.push_back(c);
+        for (auto& st : states) st.second.conditions.push_back(c);
+    }
+
+public:
+    explicit AlertMonitor(
+            const IndicatorRegistry& registry = builtin_indicators())
+        : registry(&registry) {}
+
+    /**
+     * Adds a condition given as an expression. Returns false, with the
+     * reason in `error`, if it can't be parsed.
+     */
+    bool add(const std::string& name, const std::string& rule,
+            std::string& error) {
+        Condition c;
+        if (!c.expr.parse(rule, error, std::vector<std::string>(),
+                    *registry))
+            return false;
+        c.name = name;
+        c.rule = rule;
+        add(c);
+        return true;
+    }
+
+    /**
+     * Adds a condition given as a detector, which is called with each
+     * bar and returns true, setting the message, to trigger.
+     */
+    void add(const std::string& name, Detector detect) {
+        Condition c;
+        c.name = name;
+        c.detect = detect;
+        add(c);
+    }
+
+    void add_sink(AlertSink sink) { sinks.push_back(sink); }
+
+    /**
+     * Feeds a bar of a symbol to its conditions and sends the alerts
+     * triggered. Returns the number of alerts, zero if the bar was
+     * rejected for not being newer than the previous one.
+     */
+    size_t update(const std::string& symbol, const Ohlcv& bar) {
+        SymbolState& st = state(symbol);
+        if (bar.timestamp <= st.timestamp) return 0;
+        st.timestamp = bar.timestamp;
+        size_t count = 0;
+        for (auto& c : st.conditions) {
+            Alert alert;
+            if (c.detect) {
+                if (!c.detect(bar, alert.message)) continue;
+            } else {
+                bool was = c.active;
+                c.active = c.expr.test(bar);
+                if (!c.active || was) continue;
+                alert.message = c.rule;
+            }
+            alert.name = c.name;
+            alert.symbol = symbol;
+            alert.timestamp = bar.timestamp;
+            alert.price = bar.close;
+            for (auto& sink : sinks) sink(alert);
+            count++;
+        }
+        sent += count;
+        return count;
+    }
+
+    size_t num_conditions() const noexcept { return prototypes.size(); }
+
+    /**
+     * Number of alerts triggered so far.
+     */
+    size_t num_alerts() const noexcept { return sent; }
+};
+
+} // namespace tzu
+
+#endif // ALERTS_H
@@ -0,0 +1,254 @@
+#ifndef ALPACA_H
+#define ALPACA_H
+
+#include <cmath>
+#include <cstdint>
+#include <sstream>
+#include <string>
+#include <vector>
+#include "broker.h"
+#include "connectors.h"
+#include "datetime.h"
+#include "feed.h"
+#include "orders.h"
+
+/**
+ * This header defines a connector to the Alpaca trading API (v2), as
+ * the reference implementation of the `Broker` interface for REST
+ * venues. No network I/O is done here: requests are sent by the
+ * `HttpClient` given by the caller, so any HTTP library can be used,
+ * and the connector can be tested without a network. Responses are
+ * parsed with the flat JSON helpers of `feed.h`, which is enough for
+ * orders without legs, positions and accounts.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+inline double json_price(const std::string& msg, const std::string& key) {
+    double value;
+    return json_number(msg, key, value) ? value : std::nan("");
+}
+
+inline OrderStatus alpaca_status(const std::string& status) {
+    if (status == "partially_filled") return OrderStatus::PARTIAL;
+    if (status == "filled") return OrderStatus::FILLED;
+    if (status == "canceled" || status == "expired" || status == "replaced"
+            || status == "done_for_day")
+        return OrderStatus::CANCELED;
+    if (status == "rejected" || status == "suspended")
+        return OrderStatus::REJECTED;
+    return OrderStatus::NEW;
+}
+
+inline OrderType alpaca_type(const std::string& type) {
+    if (type == "limit") return OrderType::LIMIT;
+    if (type == "stop") return OrderType::STOP;
+    if (type == "stop_limit") return OrderType::STOP_LIMIT;
+    return OrderType::MARKET;
+}
+
+inline const char* alpaca_type(OrderType type) {
+    switch (type) {
+        case OrderType::MARKET: return "market";
+        case OrderType::LIMIT: return "limit";
+        case OrderType::STOP: return "stop";
+        case OrderType::STOP_LIMIT: return "stop_limit";
+    }
+    return "market";
+}
+
+/**
+ * An order as returned by the API. Quantities are signed by side.
+ */
+inline Order alpaca_order(const std::string& msg) {
+    Order order;
+    std::string text;
+    if (json_field(msg, "id", text)) order.id = text;
+    if (json_field(msg, "symbol", text)) order.symbol = text;
+    if (json_field(msg, "type", text)) order.type = alpaca_type(text);
+    if (json_field(msg, "status", text)) order.status = alpaca_status(text);
+    if (json_field(msg, "submitted_at", text))
+        parse_time(text.c_str(), "%Y-%m-%dT%H:%M:%SZ", order.timestamp);
+    double sign = json_field(msg, "side", text) && text == "sell" ? -1.0 : 1.0;
+    double value;
+    if (json_number(msg, "qty", value)) order.quantity = sign * value;
+    if (json_number(msg, "filled_qty", value)) order.filled = sign * value;
+    if (json_number(msg, "filled_avg_price", value))
+        order.avg_fill_price = value;
+    order.limit_price = json_price(msg, "limit_price");
+    order.stop_price = json_price(msg, "stop_price");
+    return order;
+}
+
+} // namespace detail
+
+/**
+ * AlpacaBroker
+ *
+ * Routes orders to Alpaca. `base_url` selects the environment, e.g.
+ * `https://paper-api.alpaca.markets` for paper accounts, and `key` and
+ * `secret` authenticate the requests. Orders are sent with the given
+ * time in force (`day`, or `gtc` as crypto requires). The id of an
+ * order, if set, is sent as its client order id, so a retried
+ * submission isn't executed twice (see `Retry`). Failed requests leave
+ * the reason in `last_error`.
+ *
+ * Example:
+ *
+ *     AlpacaBroker alpaca("https://paper-api.alpaca.markets", key,
+ *             secret, curl_client);
+ *     std::string id = alpaca.submit(now, Order::limit("AAPL", 10, 150));
+ *     if (id.empty()) std::cerr << alpaca.last_error() << "\n";
+ */
+class AlpacaBroker: public Broker {
+    std::string base_url;
+    std::string key;
+    std::string secret;
+    HttpClient http;
+    std::string time_in_force;
+    mutable std::string error;
+
+    bool request(const std::string& method, const std::string& path,
+            const std::string& body, std::string& out) const {
+        HttpRequest req;
+        req.method = method;
+        req.url = base_url + path;
+        req.headers.push_back(std::make_pair("APCA-API-KEY-ID", key));
+        req.headers.push_back(std::make_pair("APCA-API-SECRET-KEY", secret));
+        if (!body.empty())
+            req.headers.push_back(std::make_pair("Content-Type",
+                        "application/json"));
+        req.body = body;
+        HttpResponse res;
+        if (!http(req, res)) {
+            error = method + " " + path + ": no response";
+            return false;
+        }
+        if (res.status < 200 || res.status >= 300) {
+            std::string message;
+            detail::json_field(res.body, "message", message);
+            error = method + " " + path + ": HTTP "
+                + std::to_string(res.status) + " " + message;
+            return false;
+        }
+        out = res.body;
+        return true;
+    }
+
+    static std::string number(double value) {
+        std::ostringstream os;
+        os.precision(15);
+        os << value;
+        return os.str();
+    }
+
+public:
+    AlpacaBroker(const std::string& base_url, const std::string& key,
+            const std::string& secret, HttpClient http,
+            const std::string& time_in_force = "day")
+        : base_url(base_url), key(key), secret(secret), http(http),
+          time_in_force(time_in_force) {}
+
+    std::string submit(int64_t, Order order) override {
+        std::string body = "{\"symbol\":\""
+            + detail::json_escape(order.symbol)
+            + "\",\"qty\":\"" + number(std::fabs(order.quantity))
+            + "\",\"side\":\"" + (order.quantity < 0.0 ? "sell" : "buy")
+            + "\",\"type\":\"" + detail::alpaca_type(order.type)
+            + "\",\"time_in_force\":\"" + detail::json_escape(time_in_force)
+            + "\"";
+        if (!std::isnan(order.limit_price))
+            body += ",\"limit_price\":\"" + number(order.limit_price) + "\"";
+        if (!std::isnan(order.stop_price))
+            body += ",\"stop_price\":\"" + number(order.stop_price) + "\"";
+        if (!order.id.empty())
+            body += ",\"client_order_id\":\"" + detail::json_escape(order.id)
+                + "\"";
+        body += "}";
+        std::string res;
+        if (!request("POST", "/v2/orders", body, res)) return "";
+        return detail::alpaca_order(res).id;
+    }
+
+    bool cancel(const std::string& id) override {
+        std::string res;
+        return request("DELETE", "/v2/orders/" + id, "", res);
+    }
+
+    std::string replace(int64_t, const std::string& id, double quantity,
+            double limit_price = std::nan(""),
+            double stop_price = std::nan("")) override {
+        std::string body = "{\"qty\":\"" + number(std::fabs(quantity)) + "\"";
+        if (!std::isnan(limit_price))
+            body += ",\"limit_price\":\"" + number(limit_price) + "\"";
+        if (!std::isnan(stop_price))
+            body += ",\"stop_price\":\"" + number(stop_price) + "\"";
+        body += "}";
+        std::string res;
+        if (!request("PATCH", "/v2/orders/" + id, body, res)) return "";
+        return detail::alpaca_order(res).id;
+    }
+
+    Order order(const std::string& id) const override {
+        std::string res;
+        if (request("GET", "/v2/orders/" + id, "", res))
+            return detail::alpaca_order(res);
+        Order missing;
+        missing.id = id;
+        missing.status = OrderStatus::REJECTED;
+        return missing;
+    }
+
+    std::vector<Order> open_orders() const override {
+        std::vector<Order> orders;
+        std::string res;
+        if (!request("GET", "/v2/orders?status=open", "", res)) return orders;
+        for (const auto& msg : detail::json_objects(res))
+            orders.push_back(detail::alpaca_order(msg));
+        return orders;
+    }
+
+    /**
+     * The account as reported by Alpaca. If any request fails, the
+     * state is incomplete, and `last_error` tells why.
+     */
+    AccountState state() const override {
+        AccountState s;
+        std::string res;
+        error.clear();
+        if (request("GET", "/v2/account", "", res))
+            detail::json_number(res, "cash", s.cash);
+        if (request("GET", "/v2/positions", "", res)) {
+            for (const auto& msg : detail::json_objects(res)) {
+                std::string symbol, side;
+                double qty;
+                if (!detail::json_field(msg, "symbol", symbol)
+                        || !detail::json_number(msg, "qty", qty))
+                    continue;
+                detail::json_field(msg, "side", side);
+                s.positions[symbol] = side == "short"
+                    ? -std::fabs(qty) : qty;
+            }
+        }
+        for (const auto& o : open_orders()) {
+            OpenOrder& open = s.orders[o.id];
+            open.id = o.id;
+            open.symbol = o.symbol;
+            open.quantity = o.remaining();
+            open.price = o.type == OrderType::STOP ? o.stop_price
+                : o.type == OrderType::MARKET ? 0.0 : o.limit_price;
+        }
+        return s;
+    }
+
+    /**
+     * Reason of the last failed request.
+     */
+    const std::string& last_error() const noexcept { return error; }
+};
+
+} // namespace tzu
+
+#endif // ALPACA_H
@@ -0,0 +1,244 @@
+#ifndef ANCHORED_H
+#define ANCHORED_H
+
+#include <algorithm>
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <deque>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines anchored indicators, computed from a chosen
+ * moment on, e.g. an earnings release or a swing low, rather than over
+ * a rolling window: `Anchored` runs copies of an indicator from one or
+ * more anchors, such as the cumulative `VWAP` for the anchored VWAP or
+ * the `CumulativeReturn` for the momentum since an event.
+ */
+
+namespace tzu {
+
+/**
+ * Volume Weighted Average Price (VWAP)
+ *
+ * Average of the typical prices (high + low + close) / 3 of all the
+ * bars added, weighted by their volume. Anchored, it gives the average
+ * price paid since the anchor. Returns NaN until some volume is traded.
+ */
+class VWAP: public Indicator<VWAP, Ohlcv, double> {
+    double data = std::nan("");
+    double pv = 0.0;
+    double volume = 0.0;
+public:
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(const Ohlcv& bar) {
+        pv += (bar.high + bar.low + bar.close) / 3.0 * bar.volume;
+        volume += bar.volume;
+        if (volume > 0.0) data = pv / volume;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, pv, volume);
+    }
+    void reset_state() {
+        data = std::nan("");
+        pv = volume = 0.0;
+    }
+};
+
+/**
+ * Cumulative Return (CumulativeReturn)
+ *
+ * Change of the value since the first one, as a fraction of it.
+ * Anchored, it gives the momentum since the anchor. Returns NaN while
+ * the first value is zero.
+ */
+class CumulativeReturn: public Indicator<CumulativeReturn, double, double> {
+    double data = std::nan("");
+    double first = std::nan("");
+public:
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(double value) {
+        if (std::isnan(first)) first = value;
+        if (first != 0.0) data = value / first - 1.0;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, first);
+    }
+    void reset_state() {
+        data = first = std::nan("");
+    }
+};
+
+/**
+ * Anchored
+ *
+ * Runs copies of an indicator from up to `max_anchors` anchors, each
+ * fed with the bars from its timestamp on: whole to indicators taking
+ * bars, and by `field` to the others. Anchors may be in the future, as
+ * for a scheduled release, or in the past, as for a swing low known
+ * late, if the last `history` bars kept reach back to them. Adding an
+ * anchor when all are in use replaces the oldest one added. `get()` is
+ * the output of the latest anchor started, and the others are available
+ * with `value`.
+ *
+ * Example:
+ *
+ *     Anchored<VWAP> avwap(VWAP(), 4, 50);
+ *     if (swings.update(bar))
+ *         for (const auto& s : swings.get())
+ *             if (s.type == SwingType::LOW) avwap.anchor(s.timestamp);
+ *     double support = avwap.update(bar);
+ */
+template <class Ind>
+class Anchored: public Indicator<Anchored<Ind>, Ohlcv,
+        typename Ind::output_type> {
+    using Out = typename Ind::output_type;
+    Ind prototype;
+    Out data;
+    OhlcvField field;
+    std::vector<Ind> slots;
+    std::vector<int64_t> starts;
+    // order in which the anchors were added, zero for a free slot
+    std::vector<size_t> added;
+    size_t count = 0;
+    size_t keep;
+    std::deque<Ohlcv> history;
+    size_t seen = 0;
+    int64_t now = INT64_MIN;
+
+    void feed(size_t i, const Ohlcv& bar) {
+        slots[i].update(detail::from_bar<typename Ind::input_type>(bar,
+                    field));
+    }
+
+    void refresh() {
+        data = prototype.get();
+        int64_t latest = INT64_MIN;
+        for (size_t i = 0; i < slots.size(); i++) {
+            if (added[i] > 0 && starts[i] <= now && starts[i] >= latest) {
+                latest = starts[i];
+                data = slots[i].get();
+            }
+        }
+    }
+
+public:
+    explicit Anchored(const Ind& ind, size_t max_anchors = 4,
+            size_t history = 0, OhlcvField field = OhlcvField::CLOSE)
+        : prototype(ind), data(ind.get()), field(field),
+          slots(std::max<size_t>(max_anchors, 1), ind),
+          starts(slots.size(), 0), added(slots.size(), 0), keep(history) {}
+
+    Out get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return prototype.required_warmup();
+    }
+
+    Out update(const Ohlcv& bar) {
+        now = bar.timestamp;
+        seen++;
+        if (keep > 0) {
+            history.push_back(bar);
+            if (history.size() > keep) history.pop_front();
+        }
+        for (size_t i = 0; i < slots.size(); i++)
+            if (added[i] > 0 && starts[i] <= bar.timestamp) feed(i, bar);
+        refresh();
+        return data;
+    }
+
+    /**
+     * Starts the indicator at `timestamp`, from the first bar at or
+     * after it, restarting it if it was already anchored there. Returns
+     * false, adding nothing, if the anchor is in the past and the bars
+     * kept don't reach back to it.
+     */
+    bool anchor(int64_t timestamp) {
+        bool past = seen > 0 && timestamp <= now;
+        if (past && seen > history.size()
+                && (history.empty() || history.front().timestamp > timestamp))
+            return false;
+        size_t slot = 0;
+        for (size_t i = 0; i < slots.size(); i++) {
+            if (added[i] > 0 && starts[i] == timestamp) {
+                slot = i;
+                break;
+            }
+            if (added[i] < added[slot]) slot = i;
+        }
+        slots[slot] = prototype;
+        starts[slot] = timestamp;
+        added[slot] = ++count;
+        if (past)
+            for (const auto& bar : history)
+                if (bar.timestamp >= timestamp) feed(slot, bar);
+        refresh();
+        return true;
+    }
+
+    /**
+     * Removes the anchor at `timestamp`. Returns false if there is none.
+     */
+    bool remove(int64_t timestamp) {
+        for (size_t i = 0; i < slots.size(); i++) {
+            if (added[i] > 0 && starts[i] == timestamp) {
+                added[i] = 0;
+                refresh();
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /**
+     * Output of the indicator anchored at `timestamp`. Returns false if
+     * there is no such anchor.
+     */
+    bool value(int64_t timestamp, Out& out) const {
+        for (size_t i = 0; i < slots.size(); i++) {
+            if (added[i] > 0 && starts[i] == timestamp) {
+                out = slots[i].get();
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /**
+     * Timestamps of the anchors, in increasing order.
+     */
+    std::vector<int64_t> anchors() const {
+        std::vector<int64_t> out;
+        for (size_t i = 0; i < slots.size(); i++)
+            if (added[i] > 0) out.push_back(starts[i]);
+        std::sort(out.begin(), out.end());
+        return out;
+    }
+
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, slots, starts, added, count, history, seen, now);
+    }
+
+    void reset_state() {
+        data = prototype.get();
+        for (auto& slot : slots) slot = prototype;
+        std::fill(starts.begin(), starts.end(), 0);
+        std::fill(added.begin(), added.end(), 0);
+        count = seen = 0;
+        history.clear();
+        now = INT64_MIN;
+    }
+};
+
+} // namespace tzu
+
+#endif // ANCHORED_H
@@ -0,0 +1,655 @@
+#ifndef BARS_H
+#define BARS_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <vector>
+#include "calendar.h"
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines bar builders. They consume a stream of bars or
+ * ticks and emit new bars, which can be fed to the indicators and
+ * strategies as any other `Ohlcv` data. Builders share an interface:
+ * `update` returns true when a bar has been completed, `get` returns
+ * the last completed bar, and `flush` completes the bar in progress at
+ * the end of the data. Builders that can complete several bars with a
+ * single update, on price jumps, also return them all in `completed`.
+ */
+
+namespace tzu {
+
+enum class GapPolicy {
+    SKIP,
+    FORWARD_FILL,
+    FLAT_BAR
+};
+
+inline const char* to_string(GapPolicy policy) {
+    switch (policy) {
+        case GapPolicy::SKIP: return "skip";
+        case GapPolicy::FORWARD_FILL: return "forward_fill";
+        case GapPolicy::FLAT_BAR: return "flat_bar";
+    }
+    return "";
+}
+
+/**
+ * Most bars filled for a single gap. Longer gaps, e.g. after a corrupt
+ * timestamp, are left unfilled rather than exhausting the memory.
+ */
+const size_t MAX_GAP_FILL = 100000;
+
+namespace detail {
+
+/**
+ * Bar standing for a missing period after `prev`. Filled bars have no
+ * volume, so volume-weighted indicators are not affected by them.
+ */
+inline Ohlcv fill_bar(const Ohlcv& prev, int64_t timestamp, GapPolicy policy) {
+    if (policy == GapPolicy::FORWARD_FILL)
+        return Ohlcv(timestamp, prev.open, prev.high, prev.low, prev.close, 0.0);
+    return Ohlcv(timestamp, prev.close, prev.close, prev.close, prev.close, 0.0);
+}
+
+} // namespace detail
+
+/**
+ * Aggregates bars or ticks into bars of a longer timeframe, e.g. one
+ * minute bars into one hour bars. Periods are `timeframe` long, in the
+ * unit of the timestamps (at least 1), and aligned to `offset` (e.g. to
+ * start days at a given hour). A bar is completed when data from a
+ * later period arrives, and it is stamped with the start of its period.
+ * Data older than the period in progress is dropped and counted.
+ *
+ * Periods without data are skipped by default. With another `gaps`
+ * policy, a bar is completed for each of them too, after the bar
+ * preceding the gap, so read them all from `completed()`. Gaps longer
+ * than `MAX_GAP_FILL` periods are not filled.
+ */
+class Resampler {
+    int64_t timeframe;
+    int64_t offset;
+    GapPolicy gaps;
+    Ohlcv current;
+    Ohlcv last;
+    std::vector<Ohlcv> bars;
+    bool open = false;
+    size_t late = 0;
+
+    bool add(int64_t timestamp, double o, double h, double l, double c,
+            double v) {
+        int64_t start = bucket_start(timestamp, timeframe, offset);
+        if (open && start < current.timestamp) {
+            late++;
+            return false;
+        }
+        bars.clear();
+        if (open && start > current.timestamp) {
+            bars.push_back(current);
+            int64_t missing = (start - current.timestamp) / timeframe - 1;
+            if (gaps != GapPolicy::SKIP
+                    && missing <= static_cast<int64_t>(MAX_GAP_FILL)) {
+                for (int64_t t = current.timestamp + timeframe; t < start;
+                        t += timeframe)
+                    bars.push_back(detail::fill_bar(current, t, gaps));
+            }
+            last = bars.back();
+            open = false;
+        }
+        if (!open) {
+            current = Ohlcv(start, o, h, l, c, v);
+            open = true;
+            return !bars.empty();
+        }
+        if (h > current.high) current.high = h;
+        if (l < current.low) current.low = l;
+        current.close = c;
+        current.volume += v;
+        return false;
+    }
+
+public:
+    explicit Resampler(int64_t timeframe, int64_t offset = 0,
+            GapPolicy gaps = GapPolicy::SKIP)
+        : timeframe(timeframe > 0 ? timeframe : 1), offset(offset),
+          gaps(gaps) {}
+
+    bool update(const Ohlcv& bar) {
+        return add(bar.timestamp, bar.open, bar.high, bar.low, bar.close,
+                bar.volume);
+    }
+
+    bool update(const Tick& tick) {
+        return add(tick.timestamp, tick.price, tick.price, tick.price,
+                tick.price, tick.volume);
+    }
+
+    /**
+     * Completes the bar in progress, if any.
+     */
+    bool flush() {
+        bars.clear();
+        if (!open) return false;
+        bars.push_back(current);
+        last = current;
+        open = false;
+        return true;
+    }
+
+    Ohlcv get() const noexcept { return last; }
+
+    /**
+     * The bars completed by the last update or flush, oldest first.
+     */
+    const std::vector<Ohlcv>& completed() const noexcept { return bars; }
+
+    /**
+     * The bar in progress, e.g. to act before the period is over.
+     */
+    Ohlcv partial() const noexcept { return current; }
+
+    size_t dropped() const noexcept { return late; }
+};
+
+/**
+ * Aggregates bars or ticks into bars following the sessions of a
+ * calendar: one bar per session with a zero `timeframe`, or bars of
+ * `timeframe` seconds aligned to the opening of the session, the last
+ * one ending at its close. Unlike a `Resampler`, bars are aligned to
+ * the session whatever the time zone, DST or early closes, and daily
+ * bars don't mix sessions. Data outside the sessions, regular or
+ * `extended`, or older than the bar in progress is dropped and
+ * counted. A bar is completed when data from a later bar arrives, and
+ * it is stamped with its start; `flush` completes it at the close.
+ */
+class SessionBars {
+    Calendar calendar;
+    int64_t timeframe;
+    bool extended;
+    Ohlcv current;
+    Ohlcv last;
+    std::vector<Ohlcv> bars;
+    bool open = false;
+    size_t late = 0;
+
+    bool add(int64_t timestamp, double o, double h, double l, double c,
+            double v) {
+        int64_t start = 0, close = 0;
+        if (!calendar.session(calendar.trading_day(timestamp, extended),
+                    start, close, extended)
+                || timestamp < start || timestamp >= close) {
+            late++;
+            return false;
+        }
+        if (timeframe > 0)
+            start += (timestamp - start) / timeframe * timeframe;
+        if (open && start < current.timestamp) {
+            late++;
+            return false;
+        }
+        bars.clear();
+        if (open && start > current.timestamp) {
+            bars.push_back(current);
+            last = current;
+            open = false;
+        }
+        if (!open) {
+            current = Ohlcv(start, o, h, l, c, v);
+            open = true;
+            return !bars.empty();
+        }
+        if (h > current.high) current.high = h;
+        if (l < current.low) current.low = l;
+        current.close = c;
+        current.volume += v;
+        return false;
+    }
+
+public:
+    explicit SessionBars(const Calendar& calendar, int64_t timeframe = 0,
+            bool extended = false)
+        : calendar(calendar), timeframe(timeframe), extended(extended) {}
+
+    bool update(const Ohlcv& bar) {
+        return add(bar.timestamp, bar.open, bar.high, bar.low, bar.close,
+                bar.volume);
+    }
+
+    bool update(const Tick& tick) {
+        return add(tick.timestamp, tick.price, tick.price, tick.price,
+                tick.price, tick.volume);
+    }
+
+    /**
+     * Completes the bar in progress, if any, e.g. at the close.
+     */
+    bool flush() {
+        bars.clear();
+        if (!open) return false;
+        bars.push_back(current);
+        last = current;
+        open = false;
+        return true;
+    }
+
+    Ohlcv get() const noexcept { return last; }
+
+    /**
+     * The bar completed by the last update or flush, if any.
+     */
+    const std::vector<Ohlcv>& completed() const noexcept { return bars; }
+
+    Ohlcv partial() const noexcept { return current; }
+
+    size_t dropped() const noexcept { return late; }
+};
+
+/**
+ * Fills the gaps of a stream of bars expected every `interval`, e.g.
+ * daily bars missing weekends and holidays, or minute bars missing a
+ * feed outage. Each bar passes through, preceded by a filled bar for
+ * each missing interval since the previous one, according to `policy`;
+ * with `GapPolicy::SKIP`, or an `interval` that isn't positive, bars
+ * just pass through. Gaps longer than `max_fill` intervals, or than
+ * `MAX_GAP_FILL` when it is zero or larger, are not filled, e.g. to
+ * let a long halt show as such. Bars not after the previous one are
+ * dropped and counted. `update` returns whether the bar was accepted,
+ * and the bars to process, oldest first, are in `completed()`.
+ */
+class GapFiller {
+    int64_t interval;
+    GapPolicy policy;
+    size_t max_fill;
+    std::vector<Ohlcv> bars;
+    Ohlcv last;
+    bool started = false;
+    size_t filled_ = 0;
+    size_t late = 0;
+
+public:
+    explicit GapFiller(int64_t interval,
+            GapPolicy policy = GapPolicy::FORWARD_FILL, size_t max_fill = 0)
+        : interval(interval),
+          policy(interval > 0 ? policy : GapPolicy::SKIP),
+          max_fill(max_fill > 0 && max_fill < MAX_GAP_FILL
+                  ? max_fill : MAX_GAP_FILL) {}
+
+    bool update(const Ohlcv& bar) {
+        bars.clear();
+        if (started && bar.timestamp <= last.timestamp) {
+            late++;
+            return false;
+        }
+        if (started && policy != GapPolicy::SKIP) {
+            int64_t missing = (bar.timestamp - last.timestamp - 1) / interval;
+            if (missing <= static_cast<int64_t>(max_fill)) {
+                for (int64_t t = last.timestamp + interval; t < bar.timestamp;
+                        t += interval)
+                    bars.push_back(detail::fill_bar(last, t, policy));
+                filled_ += bars.size();
+            }
+        }
+        bars.push_back(bar);
+        last = bar;
+        started = true;
+        return true;
+    }
+
+    /**
+     * The bar of the last update, whether it was filled or not.
+     */
+    Ohlcv get() const noexcept { return last; }
+
+    /**
+     * The bars of the last update, filled ones first.
+     */
+    const std::vector<Ohlcv>& completed() const noexcept { return bars; }
+
+    size_t filled() const noexcept { return filled_; }
+
+    size_t dropped() const noexcept { return late; }
+};
+
+namespace detail {
+
+/**
+ * Likely price path within a bar: open, the nearest extreme, the other
+ * extreme and close.
+ */
+inline std::vector<double> bar_path(const Ohlcv& bar) {
+    if (bar.close >= bar.open)
+        return {bar.open, bar.low, bar.high, bar.close};
+    return {bar.open, bar.high, bar.low, bar.close};
+}
+
+} // namespace detail
+
+/**
+ * Builds Renko bricks from bars or ticks. A brick is completed each
+ * time the price moves `brick_size` beyond the close of the last brick
+ * in the same direction, or beyond its open in the opposite direction
+ * (a reversal takes two brick sizes). Bricks are stamped with the
+ * timestamp of the data completing them and carry the volume traded
+ * since the previous brick. Bars are walked through their open, high,
+ * low and close, so a single update can complete several bricks.
+ *
+ * With a positive `atr_period`, the brick size is `brick_size` times
+ * the ATR of the input over that period up to the previous update
+ * (ticks count as bars with a single price), so the data being walked
+ * doesn't size its own bricks, and no bricks are built until the ATR
+ * is available.
+ */
+class Renko {
+    double brick_size;
+    bool use_atr;
+    ATR atr;
+    std::vector<Ohlcv> bricks;
+    Ohlcv last;
+    bool started = false;
+    double open = 0.0;
+    double close = 0.0;
+    int direction = 0;
+    double volume = 0.0;
+
+    void add_brick(int64_t timestamp, double o, double c) {
+        bricks.push_back(Ohlcv(timestamp, o, std::fmax(o, c), std::fmin(o, c),
+                c, volume));
+        last = bricks.back();
+        volume = 0.0;
+        open = o;
+        close = c;
+        direction = c > o ? 1 : -1;
+    }
+
+    void add(int64_t timestamp, double price) {
+        double size = use_atr ? brick_size * atr.get() : brick_size;
+        if (std::isnan(size) || size <= 0.0) return;
+        if (!started) {
+            open = close = price;
+            started = true;
+            return;
+        }
+        while (true) {
+            if (direction >= 0 && price >= close + size)
+                add_brick(timestamp, close, close + size);
+            else if (direction <= 0 && price <= close - size)
+                add_brick(timestamp, close, close - size);
+            else if (direction > 0 && price <= open - size)
+                add_brick(timestamp, open, open - size);
+            else if (direction < 0 && price >= open + size)
+                add_brick(timestamp, open, open + size);
+            else
+                break;
+        }
+    }
+
+public:
+    explicit Renko(double brick_size, size_t atr_period = 0)
+        : brick_size(brick_size), use_atr(atr_period > 0),
+          atr(atr_period > 0 ? atr_period : 1) {}
+
+    bool update(const Ohlcv& bar) {
+        bricks.clear();
+        volume += bar.volume;
+        for (double price : detail::bar_path(bar))
+            add(bar.timestamp, price);
+        // after building, so the size doesn't depend on this bar
+        if (use_atr) atr.update(bar);
+        return !bricks.empty();
+    }
+
+    bool update(const Tick& tick) {
+        bricks.clear();
+        volume += tick.volume;
+        add(tick.timestamp, tick.price);
+        if (use_atr)
+            atr.update(Ohlcv(tick.timestamp, tick.price, tick.price,
+                        tick.price, tick.price, tick.volume));
+        return !bricks.empty();
+    }
+
+    /**
+     * Bricks are only built complete, so there is nothing to flush.
+     */
+    bool flush() { return false; }
+
+    Ohlcv get() const noexcept { return last; }
+
+    /**
+     * The bricks completed by the last update, oldest first.
+     */
+    const std::vector<Ohlcv>& completed() const noexcept { return bricks; }
+};
+
+/**
+ * Builds range bars from bars or ticks: each bar spans at most
+ * `range` from its low to its high. A bar is completed when the price
+ * moves beyond that range; it closes at the edge of the range and the
+ * next bar opens there, so price jumps produce several bars. Bars are
+ * stamped with the timestamp of the data opening them and carry the
+ * volume traded while they were in progress. Bars are walked through
+ * their open, high, low and close.
+ *
+ * With a positive `atr_period`, the range is `range` times the ATR of
+ * the input over that period up to the previous update, and no bars
+ * are built until the ATR is available.
+ */
+class RangeBars {
+    double range;
+    bool use_atr;
+    ATR atr;
+    std::vector<Ohlcv> bars;
+    Ohlcv last;
+    Ohlcv current;
+    bool open = false;
+    double volume = 0.0;
+
+    void complete(int64_t timestamp, double edge) {
+        current.close = edge;
+        current.volume = volume;
+        bars.push_back(current);
+        last = current;
+        volume = 0.0;
+        current = Ohlcv(timestamp, edge, edge, edge, edge, 0.0);
+    }
+
+    void add(int64_t timestamp, double price) {
+        double size = use_atr ? range * atr.get() : range;
+        if (std::isnan(size) || size <= 0.0) return;
+        if (!open) {
+            current = Ohlcv(timestamp, price, price, price, price, 0.0);
+            open = true;
+        }
+        while (true) {
+            if (price > current.low + size) {
+                current.high = current.low + size;
+                complete(timestamp, current.high);
+            } else if (price < current.high - size) {
+                current.low = current.high - size;
+                complete(timestamp, current.low);
+            } else {
+                current.high = std::fmax(current.high, price);
+                current.low = std::fmin(current.low, price);
+                current.close = price;
+                break;
+            }
+        }
+    }
+
+public:
+    explicit RangeBars(double range, size_t atr_period = 0)
+        : range(range), use_atr(atr_period > 0),
+          atr(atr_period > 0 ? atr_period : 1) {}
+
+    bool update(const Ohlcv& bar) {
+        bars.clear();
+        volume += bar.volume;
+        for (double price : detail::bar_path(bar))
+            add(bar.timestamp, price);
+        // after building, so the size doesn't depend on this bar
+        if (use_atr) atr.update(bar);
+        return !bars.empty();
+    }
+
+    bool update(const Tick& tick) {
+        bars.clear();
+        volume += tick.volume;
+        add(tick.timestamp, tick.price);
+        if (use_atr)
+            atr.update(Ohlcv(tick.timestamp, tick.price, tick.price,
+                        tick.price, tick.price, tick.volume));
+        return !bars.empty();
+    }
+
+    /**
+     * Completes the bar in progress, if any, with its partial range.
+     */
+    bool flush() {
+        bars.clear();
+        if (!open) return false;
+        current.volume = volume;
+        bars.push_back(current);
+        last = current;
+        volume = 0.0;
+        open = false;
+        return true;
+    }
+
+    Ohlcv get() const noexcept { return last; }
+
+    /**
+     * The bars completed by the last update or flush, oldest first.
+     */
+    const std::vector<Ohlcv>& completed() const noexcept { return bars; }
+
+    /**
+     * The bar in progress.
+     */
+    Ohlcv partial() const noexcept {
+        Ohlcv bar = current;
+        bar.volume = volume;
+        return bar;
+    }
+};
+
+namespace detail {
+
+/**
+ * Bars completed once the activity they accumulate, as measured by the
+ * derived builder, reaches a threshold.
+ */
+class ActivityBars {
+    double threshold;
+    std::vector<Ohlcv> bars;
+    Ohlcv last;
+    Ohlcv current;
+    bool open = false;
+    double amount = 0.0;
+
+protected:
+    explicit ActivityBars(double threshold) : threshold(threshold) {}
+
+    bool add(int64_t timestamp, double o, double h, double l, double c,
+            double v, double activity) {
+        bars.clear();
+        if (!open) {
+            current = Ohlcv(timestamp, o, h, l, c, v);
+            open = true;
+        } else {
+            current.high = std::fmax(current.high, h);
+            current.low = std::fmin(current.low, l);
+            current.close = c;
+            current.volume += v;
+        }
+        amount += activity;
+        if (amount < threshold) return false;
+        return flush();
+    }
+
+public:
+    /**
+     * Completes the bar in progress, if any, below the threshold.
+     */
+    bool flush() {
+        bars.clear();
+        if (!open) return false;
+        bars.push_back(current);
+        last = current;
+        open = false;
+        amount = 0.0;
+        return true;
+    }
+
+    Ohlcv get() const noexcept { return last; }
+
+    /**
+     * The bar completed by the last update or flush, if any.
+     */
+    const std::vector<Ohlcv>& completed() const noexcept { return bars; }
+
+    /**
+     * The bar in progress.
+     */
+    Ohlcv partial() const noexcept { return current; }
+
+    /**
+     * Activity accumulated by the bar in progress.
+     */
+    double accumulated() const noexcept { return amount; }
+};
+
+} // namespace detail
+
+/**
+ * Builds volume bars from bars or ticks: a bar is completed once the
+ * volume traded since the previous one reaches `threshold`, so bars
+ * are shorter when trading is busy and longer when it is quiet, and
+ * their returns are closer to normal than those of time bars. Input
+ * data is not split between bars, so the data completing a bar may
+ * take it beyond the threshold. Bars are stamped with the timestamp of
+ * the data opening them, as time bars are with the start of their
+ * period.
+ */
+class VolumeBars: public detail::ActivityBars {
+public:
+    explicit VolumeBars(double threshold) : ActivityBars(threshold) {}
+
+    bool update(const Ohlcv& bar) {
+        return add(bar.timestamp, bar.open, bar.high, bar.low, bar.close,
+                bar.volume, bar.volume);
+    }
+
+    bool update(const Tick& tick) {
+        return add(tick.timestamp, tick.price, tick.price, tick.price,
+                tick.price, tick.volume, tick.volume);
+    }
+};
+
+/**
+ * Builds dollar bars from bars or ticks, as `VolumeBars` does, but
+ * completing a bar once the value traded, price times volume, reaches
+ * `threshold`. Unlike volume bars, they are not distorted by splits or
+ * by large changes of the price over the history. The value of a bar
+ * of input is taken at its close.
+ */
+class DollarBars: public detail::ActivityBars {
+public:
+    explicit DollarBars(double threshold) : ActivityBars(threshold) {}
+
+    bool update(const Ohlcv& bar) {
+        return add(bar.timestamp, bar.open, bar.high, bar.low, bar.close,
+                bar.volume, bar.close * bar.volume);
+    }
+
+    bool update(const Tick& tick) {
+        return add(tick.timestamp, tick.price, tick.price, tick.price,
+                tick.price, tick.volume, tick.price * tick.volume);
+    }
+};
+
+} // namespace tzu
+
+#endif // BARS_H
@@ -0,0 +1,171 @@
+#ifndef BROKER_H
+#define BROKER_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <map>
+#include <ostream>
+#include <string>
+#include <vector>
+
+/**
+ * This header defines the account state shared by the trading layer
+ * and broker connectors, and the tools to keep both in sync. Fills can
+ * be missed and orders can be cancelled by the venue, so the internal
+ * view of the account drifts from the broker's one over time. The
+ * reconciliation compares both views and reports every difference.
+ */
+
+namespace tzu {
+
+/**
+ * An order still working at the venue. `quantity` is signed, positive
+ * to buy and negative to sell.
+ */
+struct OpenOrder {
+    std::string id;
+    std::string symbol;
+    double quantity = 0.0;
+    double price = 0.0;
+};
+
+/**
+ * A snapshot of an account: cash balance, positions per symbol, and
+ * open orders by id.
+ */
+struct AccountState {
+    double cash = 0.0;
+    std::map<std::string, double> positions;
+    std::map<std::string, OpenOrder> orders;
+};
+
+enum class DiscrepancyKind {
+    CASH,
+    POSITION,
+    MISSING_ORDER,  // known internally, not at the broker
+    UNKNOWN_ORDER   // at the broker, not known internally
+};
+
+/**
+ * A difference between the internal and the broker states. `key` is
+ * the symbol for positions and the order id for orders.
+ */
+struct Discrepancy {
+    int64_t timestamp;
+    DiscrepancyKind kind;
+    std::string key;
+    double internal;
+    double broker;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Discrepancy& d) {
+    os << "timestamp:" << d.timestamp << " kind:"
+       << (d.kind == DiscrepancyKind::CASH ? "CASH"
+           : d.kind == DiscrepancyKind::POSITION ? "POSITION"
+           : d.kind == DiscrepancyKind::MISSING_ORDER ? "MISSING_ORDER"
+           : "UNKNOWN_ORDER")
+       << " key:" << d.key
+       << " internal:" << d.internal
+       << " broker:" << d.broker;
+    return os;
+}
+
+/**
+ * Compares two account states. Cash and position differences smaller
+ * than `tolerance` (in absolute value) are ignored, so rounding in
+ * the broker's reports doesn't raise discrepancies.
+ */
+inline std::vector<Discrepancy> reconcile(int64_t timestamp,
+        const AccountState& internal, const AccountState& broker,
+        double tolerance = 1e-8) {
+    std::vector<Discrepancy> out;
+    if (std::fabs(internal.cash - broker.cash) > tolerance)
+        out.push_back({timestamp, DiscrepancyKind::CASH, "",
+                internal.cash, broker.cash});
+    std::map<std::string, double> symbols;
+    for (const auto& p : internal.positions) symbols[p.first] = 0.0;
+    for (const auto& p : broker.positions) symbols[p.first] = 0.0;
+    for (const auto& s : symbols) {
+        auto i = internal.positions.find(s.first);
+        auto b = broker.positions.find(s.first);
+        double qi = i == internal.positions.end() ? 0.0 : i->second;
+        double qb = b == broker.positions.end() ? 0.0 : b->second;
+        if (std::fabs(qi - qb) > tolerance)
+            out.push_back({timestamp, DiscrepancyKind::POSITION, s.first,
+                    qi, qb});
+    }
+    for (const auto& o : internal.orders)
+        if (broker.orders.find(o.first) == broker.orders.end())
+            out.push_back({timestamp, DiscrepancyKind::MISSING_ORDER,
+                    o.first, o.second.quantity, 0.0});
+    for (const auto& o : broker.orders)
+        if (internal.orders.find(o.first) == internal.orders.end())
+            out.push_back({timestamp, DiscrepancyKind::UNKNOWN_ORDER,
+                    o.first, 0.0, o.second.quantity});
+    return out;
+}
+
+/**
+ * What to do when a discrepancy is found. `REPORT` only records it,
+ * `ADOPT_BROKER` overwrites the internal state with the broker's one,
+ * the broker being the source of truth for what was actually executed.
+ */
+enum class ReconcilePolicy {
+    REPORT,
+    ADOPT_BROKER
+};
+
+/**
+ * Periodic reconciliation task. `fetch` is a callable returning the
+ * broker's `AccountState`, typically pulling positions, balances and
+ * open orders from a broker connector. `update` is meant to be called
+ * often, e.g. on every bar or timer tick, and reconciles only when
+ * `interval` time units have passed since the last time.
+ */
+template <typename Fetch>
+class Reconciler {
+    Fetch fetch;
+    int64_t interval;
+    ReconcilePolicy policy;
+    double tolerance;
+    int64_t last = INT64_MIN;
+    std::vector<Discrepancy> events;
+
+public:
+    Reconciler(Fetch fetch, int64_t interval,
+            ReconcilePolicy policy = ReconcilePolicy::REPORT,
+            double tolerance = 1e-8)
+        : fetch(fetch), interval(interval), policy(policy),
+          tolerance(tolerance) {}
+
+    /**
+     * Reconciles if due. Returns the number of discrepancies found, or
+     * zero if the reconciliation was not due.
+     */
+    size_t update(int64_t now, AccountState& internal) {
+        if (last != INT64_MIN && now - last < interval)
+            return 0;
+        last = now;
+        AccountState broker = fetch();
+        std::vector<Discrepancy> found = reconcile(now, internal, broker,
+                tolerance);
+        events.insert(events.end(), found.begin(), found.end());
+        if (!found.empty() && policy == ReconcilePolicy::ADOPT_BROKER)
+            internal = broker;
+        return found.size();
+    }
+
+    const std::vector<Discrepancy>& get_events() const noexcept { return events; }
+};
+
+template <typename Fetch>
+Reconciler<Fetch> make_reconciler(Fetch fetch, int64_t interval,
+        ReconcilePolicy policy = ReconcilePolicy::REPORT,
+        double tolerance = 1e-8) {
+    return Reconciler<Fetch>(fetch, interval, policy, tolerance);
+}
+
+} // namespace tzu
+
+#endif // BROKER_H
@@ -0,0 +1,268 @@
+#ifndef CALENDAR_H
+#define CALENDAR_H
+
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <map>
+#include <set>
+#include "datetime.h"
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines exchange calendars: the trading hours of a
+ * venue in its local time, with its weekends, holidays and early
+ * closes, so that bars and indicators can follow its sessions instead
+ * of UTC days. Timestamps are Unix times in seconds, as for the
+ * helpers of `datetime.h`. Daylight saving time follows the rules of
+ * the US or the EU; other rules can be handled with a calendar per
+ * period.
+ */
+
+namespace tzu {
+
+enum class DstRule {
+    NONE,
+    US,
+    EU
+};
+
+namespace detail {
+
+/**
+ * Day of the week of a number of days since 1970-01-01, from 0 for
+ * Sunday to 6 for Saturday.
+ */
+inline unsigned weekday(int64_t day) {
+    int64_t w = (day + 4) % 7;
+    return static_cast<unsigned>(w < 0 ? w + 7 : w);
+}
+
+/**
+ * Day of the `n`th Sunday of a month, or of the last one for zero.
+ */
+inline int64_t sunday_of(int64_t year, unsigned month, unsigned n) {
+    if (n == 0) {
+        int64_t last = month == 12 ? days_from_civil(year + 1, 1, 1) - 1
+            : days_from_civil(year, month + 1, 1) - 1;
+        return last - weekday(last);
+    }
+    int64_t first = days_from_civil(year, month, 1);
+    return first + (7 - weekday(first)) % 7 + 7 * (n - 1);
+}
+
+} // namespace detail
+
+/**
+ * Calendar
+ *
+ * Trading sessions of a venue. `utc_offset` is the standard offset of
+ * its local time to UTC, e.g. -18000 for New York, and `dst` the rule
+ * moving it forward one hour in summer. Hours are given in seconds
+ * since local midnight: a regular session (RTH) and, optionally, an
+ * extended one (ETH) including the pre and post market. A session
+ * opening at or after its close starts on the previous day, as futures
+ * sessions do, and belongs to the day it closes. Trading days are
+ * identified by their number of days since 1970-01-01, and weekdays
+ * from 0 for Sunday to 6 for Saturday. Early closes only shorten the
+ * regular session.
+ *
+ * By default, the calendar is open all the time every day, as crypto
+ * venues are, with sessions matching UTC days.
+ *
+ * Example:
+ *
+ *     Calendar nyse = Calendar::nyse()
+ *         .add_holiday(2024, 7, 4)
+ *         .add_early_close(2024, 7, 3, 13 * 3600);
+ *     if (nyse.is_open(now)) ...
+ */
+class Calendar {
+    int64_t offset;
+    DstRule dst;
+    int64_t open_time = 0;
+    int64_t close_time = 86400;
+    int64_t ext_open = 0;
+    int64_t ext_close = 86400;
+    bool closed[7] = {false, false, false, false, false, false, false};
+    std::set<int64_t> holidays;
+    std::map<int64_t, int64_t> early_closes;
+
+    // UTC time of a local time, near enough to any DST change
+    int64_t to_utc(int64_t local) const {
+        return local - utc_offset(local - offset);
+    }
+
+public:
+    explicit Calendar(int64_t utc_offset = 0, DstRule dst = DstRule::NONE)
+        : offset(utc_offset), dst(dst) {}
+
+    /**
+     * NYSE and Nasdaq: 9:30 to 16:00 New York time, 4:00 to 20:00 with
+     * the extended session, closed on weekends. Holidays and early
+     * closes are to be added.
+     */
+    static Calendar nyse() {
+        return Calendar(-5 * 3600, DstRule::US)
+            .set_hours(9 * 3600 + 1800, 16 * 3600)
+            .set_extended_hours(4 * 3600, 20 * 3600)
+            .close_weekday(0)
+            .close_weekday(6);
+    }
+
+    /**
+     * Sets the regular session. The extended session, if not set, is
+     * the same.
+     */
+    Calendar& set_hours(int64_t open, int64_t close) {
+        bool same = ext_open == open_time && ext_close == close_time;
+        open_time = open;
+        close_time = close;
+        if (same) set_extended_hours(open, close);
+        return *this;
+    }
+
+    Calendar& set_extended_hours(int64_t open, int64_t close) {
+        ext_open = open;
+        ext_close = close;
+        return *this;
+    }
+
+    Calendar& close_weekday(unsigned weekday) {
+        if (weekday < 7) closed[weekday] = true;
+        return *this;
+    }
+
+    Calendar& add_holiday(int64_t year, unsigned month, unsigned day) {
+        holidays.insert(days_from_civil(year, month, day));
+        return *this;
+    }
+
+    /**
+     * Closes the regular session of a day at `close`, in seconds since
+     * local midnight.
+     */
+    Calendar& add_early_close(int64_t year, unsigned month, unsigned day,
+            int64_t close) {
+        early_closes[days_from_civil(year, month, day)] = close;
+        return *this;
+    }
+
+    /**
+     * Offset of the local time to UTC at a given time, including DST.
+     */
+    int64_t utc_offset(int64_t timestamp) const {
+        if (dst == DstRule::NONE) return offset;
+        int64_t year;
+        unsigned month, day;
+        civil_from_days(detail::floor_div(timestamp + offset, 86400), year,
+                month, day);
+        int64_t start, end;
+        if (dst == DstRule::US) {
+            start = detail::sunday_of(year, 3, 2) * 86400 + 7200 - offset;
+            end = detail::sunday_of(year, 11, 1) * 86400 + 3600 - offset;
+        } else {
+            start = detail::sunday_of(year, 3, 0) * 86400 + 3600;
+            end = detail::sunday_of(year, 10, 0) * 86400 + 3600;
+        }
+        return timestamp >= start && timestamp < end ? offset + 3600 : offset;
+    }
+
+    /**
+     * Local time of a timestamp, as seconds since 1970-01-01 local.
+     */
+    int64_t local_time(int64_t timestamp) const {
+        return timestamp + utc_offset(timestamp);
+    }
+
+    /**
+     * Trading day a timestamp belongs to, whether the session is open
+     * at that time or not.
+     */
+    int64_t trading_day(int64_t timestamp, bool extended = false) const {
+        int64_t local = local_time(timestamp);
+        int64_t day = detail::floor_div(local, 86400);
+        int64_t open = extended ? ext_open : open_time;
+        int64_t close = extended ? ext_close : close_time;
+        if (open >= close && local - day * 86400 >= open) day++;
+        return day;
+    }
+
+    bool is_trading_day(int64_t day) const {
+        return !closed[detail::weekday(day)] && holidays.count(day) == 0;
+    }
+
+    /**
+     * Opening and closing times of the session of a trading day.
+     * Returns false if there is none.
+     */
+    bool session(int64_t day, int64_t& open, int64_t& close,
+            bool extended = false) const {
+        if (!is_trading_day(day)) return false;
+        int64_t o = extended ? ext_open : open_time;
+        int64_t c = extended ? ext_close : close_time;
+        int64_t start = day * 86400 + o - (o >= c ? 86400 : 0);
+        if (!extended) {
+            auto early = early_closes.find(day);
+            if (early != early_closes.end()) c = early->second;
+        }
+        open = to_utc(start);
+        close = to_utc(day * 86400 + c);
+        return true;
+    }
+
+    bool is_open(int64_t timestamp, bool extended = false) const {
+        int64_t open = 0, close = 0;
+        return session(trading_day(timestamp, extended), open, close, extended)
+            && timestamp >= open && timestamp < close;
+    }
+};
+
+/**
+ * Session Volume Weighted Average Price (SessionVWAP)
+ *
+ * Average of the typical prices (high + low + close) / 3 of the bars
+ * of the current trading day of a calendar, weighted by their volume.
+ * It restarts with the first bar of each trading day, including its
+ * pre market with `extended`. Returns NaN until some volume is traded.
+ */
+class SessionVWAP: public Indicator<SessionVWAP, Ohlcv, double> {
+    double data = std::nan("");
+    Calendar calendar;
+    bool extended;
+    int64_t day = INT64_MIN;
+    double pv = 0.0;
+    double volume = 0.0;
+public:
+    explicit SessionVWAP(const Calendar& calendar = Calendar(),
+            bool extended = false)
+        : calendar(calendar), extended(extended) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 0; }
+    double update(const Ohlcv& bar) {
+        int64_t d = calendar.trading_day(bar.timestamp, extended);
+        if (d != day) {
+            day = d;
+            pv = volume = 0.0;
+            data = std::nan("");
+        }
+        pv += (bar.high + bar.low + bar.close) / 3.0 * bar.volume;
+        volume += bar.volume;
+        if (volume > 0.0) data = pv / volume;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, day, pv, volume);
+    }
+    void reset_state() {
+        data = std::nan("");
+        day = INT64_MIN;
+        pv = volume = 0.0;
+    }
+};
+
+} // namespace tzu
+
+#endif // CALENDAR_H
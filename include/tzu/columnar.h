@@ -0,0 +1,290 @@
+#ifndef COLUMNAR_H
+#define COLUMNAR_H
+
+/**
+ * This header defines readers and writers of bars in Parquet files,
+ * which load multi-year intraday datasets much faster than CSV, as
+ * values are stored in binary columns. It is only available when
+ * building with `TZU_WITH_PARQUET` defined and linking against the
+ * Arrow and Parquet C++ libraries, which the CMake option of the same
+ * name does. Recent versions of Arrow require C++17.
+ *
+ * Files have one row per bar, with an integer (or timestamp) column
+ * for the time and double columns for the prices and the volume. The
+ * file is read one row group at a time, and bars are built directly
+ * from the column buffers in memory, without any conversion, so the
+ * memory used is bounded by the size of the row groups.
+ */
+
+#ifdef TZU_WITH_PARQUET
+
+#include <cmath>
+#include <cstdint>
+#include <memory>
+#include <string>
+#include <vector>
+#include <arrow/api.h>
+#include <arrow/io/file.h>
+#include <parquet/arrow/reader.h>
+#include <parquet/arrow/writer.h>
+#include "defs.h"
+
+namespace tzu {
+
+/**
+ * Names of the columns of a Parquet file with bars. `volume` can be
+ * empty if the file has no volumes. Integer timestamps are kept as
+ * they are, while Arrow timestamps are converted from their unit to
+ * seconds multiplied by `time_scale` (e.g. 1000 for milliseconds), as
+ * in `CsvLayout`.
+ */
+struct ColumnLayout {
+    std::string timestamp = "timestamp";
+    std::string open = "open";
+    std::string high = "high";
+    std::string low = "low";
+    std::string close = "close";
+    std::string volume = "volume";
+    int64_t time_scale = 1;
+};
+
+/**
+ * ParquetBars
+ *
+ * Iterable of the bars in a Parquet file, like `Csv<Ohlcv>`. If the
+ * file can't be opened, if columns are missing or of other types, or if
+ * the timestamp unit can't be converted to the one of the layout, `ok()`
+ * is false and there are no bars. Rows with a null timestamp are
+ * skipped, while null prices and volumes are yielded as NaN, so
+ * `transforms.h` can treat them as missing.
+ */
+class ParquetBars {
+    std::unique_ptr<parquet::arrow::FileReader> reader;
+    ColumnLayout layout;
+    bool ok_ = false;
+
+    struct Column {
+        std::shared_ptr<arrow::Array> array;
+        const double* values = nullptr;
+
+        double at(int64_t row) const {
+            return array->IsNull(row) ? std::nan("") : values[row];
+        }
+    };
+
+    // columns of the batch being read
+    struct Batch {
+        std::shared_ptr<arrow::RecordBatch> batch;
+        std::shared_ptr<arrow::Array> time_array;
+        const int64_t* timestamp = nullptr;
+        // conversion of the timestamps to the unit of the layout
+        int64_t multiply = 1;
+        int64_t divide = 1;
+        Column open;
+        Column high;
+        Column low;
+        Column close;
+        Column volume;
+        int64_t rows = 0;
+    };
+
+    static int64_t units_per_second(arrow::TimeUnit::type unit) {
+        switch (unit) {
+            case arrow::TimeUnit::SECOND: return 1;
+            case arrow::TimeUnit::MILLI: return 1000;
+            case arrow::TimeUnit::MICRO: return 1000000;
+            case arrow::TimeUnit::NANO: return 1000000000;
+        }
+        return 0;
+    }
+
+    // conversion of integer or Arrow timestamps to the unit of the layout
+    bool time_unit(const arrow::DataType& type, int64_t& multiply,
+            int64_t& divide) const {
+        multiply = divide = 1;
+        if (type.id() == arrow::Type::INT64) return true;
+        if (type.id() != arrow::Type::TIMESTAMP) return false;
+        int64_t from = units_per_second(
+                static_cast<const arrow::TimestampType&>(type).unit());
+        int64_t to = layout.time_scale;
+        if (from <= 0 || to <= 0) return false;
+        if (from % to == 0)
+            divide = from / to;
+        else if (to % from == 0)
+            multiply = to / from;
+        else
+            return false;
+        return true;
+    }
+
+    std::vector<std::string> price_columns() const {
+        std::vector<std::string> names = {layout.open, layout.high,
+            layout.low, layout.close};
+        if (!layout.volume.empty()) names.push_back(layout.volume);
+        return names;
+    }
+
+    bool valid(const arrow::Schema& schema) const {
+        int64_t multiply, divide;
+        std::shared_ptr<arrow::Field> field =
+            schema.GetFieldByName(layout.timestamp);
+        if (!field || !time_unit(*field->type(), multiply, divide))
+            return false;
+        for (const auto& name : price_columns()) {
+            field = schema.GetFieldByName(name);
+            if (!field || field->type()->id() != arrow::Type::DOUBLE)
+                return false;
+        }
+        return true;
+    }
+
+    static bool load_column(const std::shared_ptr<arrow::Array>& a,
+            Column& out) {
+        out.array = a;
+        if (!a || a->type_id() != arrow::Type::DOUBLE) return false;
+        out.values = a->data()->GetValues<double>(1);
+        return true;
+    }
+
+    bool load(const std::shared_ptr<arrow::RecordBatch>& batch, Batch& out) const {
+        out.batch = batch;
+        out.rows = batch->num_rows();
+        out.time_array = batch->GetColumnByName(layout.timestamp);
+        if (!out.time_array || !time_unit(*out.time_array->type(),
+                    out.multiply, out.divide))
+            return false;
+        out.timestamp = out.time_array->data()->GetValues<int64_t>(1);
+        out.volume = Column();
+        return load_column(batch->GetColumnByName(layout.open), out.open)
+            && load_column(batch->GetColumnByName(layout.high), out.high)
+            && load_column(batch->GetColumnByName(layout.low), out.low)
+            && load_column(batch->GetColumnByName(layout.close), out.close)
+            && (layout.volume.empty() || load_column(
+                        batch->GetColumnByName(layout.volume), out.volume));
+    }
+
+public:
+    class Iterator {
+        ParquetBars* source;
+        int group = 0;
+        std::shared_ptr<arrow::Table> table;
+        std::unique_ptr<arrow::TableBatchReader> batches;
+        Batch current;
+        int64_t row = 0;
+        Ohlcv bar;
+        bool end;
+
+        bool next_batch() {
+            while (true) {
+                if (batches) {
+                    std::shared_ptr<arrow::RecordBatch> batch;
+                    if (batches->ReadNext(&batch).ok() && batch) {
+                        if (!source->load(batch, current)) return false;
+                        row = 0;
+                        return true;
+                    }
+                    batches.reset();
+                }
+                if (group >= source->reader->num_row_groups()) return false;
+                if (!source->reader->ReadRowGroup(group++, &table).ok())
+                    return false;
+                batches.reset(new arrow::TableBatchReader(*table));
+            }
+        }
+
+    public:
+        Iterator(ParquetBars* source, bool end): source(source), end(end) {
+            if (!end) ++(*this);
+        }
+        Iterator& operator++() {
+            if (end) return *this;
+            // rows without a timestamp are skipped
+            while (row >= current.rows || current.time_array->IsNull(row)) {
+                if (row < current.rows) {
+                    row++;
+                } else if (!next_batch()) {
+                    end = true;
+                    return *this;
+                }
+            }
+            int64_t ts = current.timestamp[row] * current.multiply
+                / current.divide;
+            bar = Ohlcv(ts, current.open.at(row), current.high.at(row),
+                    current.low.at(row), current.close.at(row),
+                    current.volume.array ? current.volume.at(row) : 0.0);
+            row++;
+            return *this;
+        }
+        const Ohlcv& operator*() const { return bar; }
+        const Ohlcv* operator->() const { return &bar; }
+        bool operator==(const Iterator& other) const { return end == other.end; }
+        bool operator!=(const Iterator& other) const { return !(*this == other); }
+    };
+
+    explicit ParquetBars(const std::string& path,
+            const ColumnLayout& layout = ColumnLayout())
+        : layout(layout) {
+        auto file = arrow::io::ReadableFile::Open(path);
+        if (!file.ok()) return;
+        if (!parquet::arrow::OpenFile(*file, arrow::default_memory_pool(),
+                    &reader).ok())
+            return;
+        std::shared_ptr<arrow::Schema> schema;
+        ok_ = reader->GetSchema(&schema).ok() && valid(*schema);
+    }
+
+    bool ok() const noexcept { return ok_; }
+
+    Iterator begin() { return Iterator(this, !ok_); }
+    Iterator end() { return Iterator(this, true); }
+};
+
+/**
+ * Writes bars to a Parquet file, in row groups of `row_group_size`
+ * bars. Returns false on failure.
+ */
+inline bool write_parquet(const std::string& path,
+        const std::vector<Ohlcv>& bars,
+        const ColumnLayout& layout = ColumnLayout(),
+        int64_t row_group_size = 1 << 20) {
+    arrow::Int64Builder timestamp;
+    arrow::DoubleBuilder open, high, low, close, volume;
+    for (const auto& bar : bars) {
+        if (!timestamp.Append(bar.timestamp).ok()
+                || !open.Append(bar.open).ok()
+                || !high.Append(bar.high).ok()
+                || !low.Append(bar.low).ok()
+                || !close.Append(bar.close).ok()
+                || !volume.Append(bar.volume).ok())
+            return false;
+    }
+    std::vector<std::shared_ptr<arrow::Field>> fields = {
+        arrow::field(layout.timestamp, arrow::int64()),
+        arrow::field(layout.open, arrow::float64()),
+        arrow::field(layout.high, arrow::float64()),
+        arrow::field(layout.low, arrow::float64()),
+        arrow::field(layout.close, arrow::float64())
+    };
+    std::vector<std::shared_ptr<arrow::Array>> columns(5);
+    if (!timestamp.Finish(&columns[0]).ok() || !open.Finish(&columns[1]).ok()
+            || !high.Finish(&columns[2]).ok() || !low.Finish(&columns[3]).ok()
+            || !close.Finish(&columns[4]).ok())
+        return false;
+    if (!layout.volume.empty()) {
+        fields.push_back(arrow::field(layout.volume, arrow::float64()));
+        columns.push_back(nullptr);
+        if (!volume.Finish(&columns.back()).ok()) return false;
+    }
+    std::shared_ptr<arrow::Table> table =
+        arrow::Table::Make(arrow::schema(fields), columns);
+    auto file = arrow::io::FileOutputStream::Open(path);
+    if (!file.ok()) return false;
+    return parquet::arrow::WriteTable(*table, arrow::default_memory_pool(),
+            *file, row_group_size).ok();
+}
+
+} // namespace tzu
+
+#endif // TZU_WITH_PARQUET
+
+#endif // COLUMNAR_H
@@ -0,0 +1,260 @@
+#ifndef CONFIG_H
+#define CONFIG_H
+
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <istream>
+#include <string>
+#include <utility>
+#include <vector>
+#include "defs.h"
+#include "dynamic.h"
+#include "expr.h"
+#include "portfolios.h"
+#include "registry.h"
+#include "strategies.h"
+
+/**
+ * This header defines strategies declared in a configuration file
+ * instead of code, so they can be changed without recompiling. The
+ * file uses a subset of TOML: `key = value` lines, where values are
+ * numbers, double-quoted strings without escapes, or arrays of
+ * strings, grouped in `[sections]`, with `#` comments:
+ *
+ *     symbols = ["BTCUSD"]
+ *     timeframe = 3600
+ *
+ *     [indicators]
+ *     fast = "EMA(12)"
+ *     slow = "EMA(26)"
+ *
+ *     [rules]
+ *     entry = "fast crosses_above slow"
+ *     exit = "fast crosses_below slow"
+ *
+ *     [risk]
+ *     tx_cost = 0.001
+ *     stop_loss = 0.1
+ *
+ * Indicators are built from their specs by an `IndicatorRegistry`.
+ * Rules are expressions (see `expr.h`), which can use the indicators
+ * by name, e.g. `"fast > slow && rsi(14) < 70"`.
+ */
+
+namespace tzu {
+
+/**
+ * A strategy as declared in a configuration file. `symbols` and
+ * `timeframe` (in seconds, zero for the bars as they come) are not
+ * used by the strategy itself, but tell the program running it what
+ * data to feed.
+ */
+struct StrategyConfig {
+    std::vector<std::string> symbols;
+    int64_t timeframe = 0;
+    std::vector<std::pair<std::string, std::string>> indicators;
+    std::string entry;
+    std::string exit;
+    double init_cash = 100000.0;
+    double tx_cost = 0.0;
+    double stop_loss = std::nan("");
+    double take_profit = std::nan("");
+};
+
+namespace detail {
+
+inline bool parse_string(const std::string& s, std::string& out) {
+    if (s.size() < 2 || s.front() != '"' || s.back() != '"') return false;
+    out = s.substr(1, s.size() - 2);
+    return out.find('"') == std::string::npos;
+}
+
+inline bool parse_strings(const std::string& s, std::vector<std::string>& out) {
+    if (s.size() < 2 || s.front() != '[' || s.back() != ']') return false;
+    out.clear();
+    std::string inner = trim(s.substr(1, s.size() - 2));
+    size_t start = 0;
+    while (!inner.empty() && start <= inner.size()) {
+        size_t comma = inner.find(',', start);
+        if (comma == std::string::npos) comma = inner.size();
+        std::string item;
+        if (!parse_string(trim(inner.substr(start, comma - start)), item))
+            return false;
+        out.push_back(item);
+        start = comma + 1;
+    }
+    return true;
+}
+
+// the line without its comment, if any
+inline std::string strip_comment(const std::string& line) {
+    bool quoted = false;
+    for (size_t i = 0; i < line.size(); i++) {
+        if (line[i] == '"') quoted = !quoted;
+        else if (line[i] == '#' && !quoted) return line.substr(0, i);
+    }
+    return line;
+}
+
+} // namespace detail
+
+/**
+ * Reads a configuration. Returns false, with the line and the reason
+ * in `error`, for syntax errors, unknown sections or keys, and values
+ * of the wrong type. Indicator specs and rules are checked when the
+ * strategy is built.
+ */
+inline bool parse_config(std::istream& is, StrategyConfig& config,
+        std::string& error) {
+    std::string line, section;
+    size_t num = 0;
+    auto fail = [&](const std::string& message) {
+        error = "line " + std::to_string(num) + ": " + message;
+        return false;
+    };
+    while (std::getline(is, line)) {
+        num++;
+        line = detail::trim(detail::strip_comment(line));
+        if (line.empty()) continue;
+        if (line.front() == '[') {
+            if (line.back() != ']') return fail("invalid section");
+            section = detail::trim(line.substr(1, line.size() - 2));
+            if (section != "indicators" && section != "rules"
+                    && section != "risk")
+                return fail("unknown section '" + section + "'");
+            continue;
+        }
+        size_t eq = line.find('=');
+        if (eq == std::string::npos) return fail("expected key = value");
+        std::string key = detail::trim(line.substr(0, eq));
+        std::string value = detail::trim(line.substr(eq + 1));
+        std::string text;
+        double number;
+        if (section.empty()) {
+            if (key == "symbols") {
+                if (!detail::parse_strings(value, config.symbols))
+                    return fail("expected an array of strings");
+            } else if (key == "timeframe") {
+                if (!detail::parse_number(value, number) || number < 0.0)
+                    return fail("expected a number of seconds");
+                config.timeframe = static_cast<int64_t>(number);
+            } else {
+                return fail("unknown key '" + key + "'");
+            }
+        } else if (section == "indicators") {
+            if (!detail::parse_string(value, text))
+                return fail("expected an indicator spec");
+            for (const auto& ind : config.indicators)
+                if (ind.first == key)
+                    return fail("duplicate indicator '" + key + "'");
+            config.indicators.push_back(std::make_pair(key, text));
+        } else if (section == "rules") {
+            if (!detail::parse_string(value, text))
+                return fail("expected a rule");
+            if (key == "entry") config.entry = text;
+            else if (key == "exit") config.exit = text;
+            else return fail("unknown rule '" + key + "'");
+        } else {
+            if (!detail::parse_number(value, number))
+                return fail("expected a number");
+            if (key == "init_cash") config.init_cash = number;
+            else if (key == "tx_cost") config.tx_cost = number;
+            else if (key == "stop_loss") config.stop_loss = number;
+            else if (key == "take_profit") config.take_profit = number;
+            else return fail("unknown key '" + key + "'");
+        }
+    }
+    return true;
+}
+
+/**
+ * A strategy built from a `StrategyConfig`. At each bar it updates its
+ * indicators and evaluates the rules: the entry rule gives a buy
+ * signal, and the exit rule a sell signal, without repeating the last
+ * signal, as `RSIStrat` does. Without an exit rule, positions are only
+ * closed by the stop-loss and take-profit of the portfolio (see
+ * `make_portfolio`).
+ *
+ * Example:
+ *
+ *     StrategyConfig config;
+ *     std::string error;
+ *     ConfigStrat strat;
+ *     if (!parse_config(file, config, error)
+ *             || !strat.configure(config, error)) {
+ *         std::cerr << error << "\n";
+ *         return 1;
+ *     }
+ *     BasicPortfolio portfolio = make_portfolio(config);
+ */
+class ConfigStrat: public Strategy<ConfigStrat, Ohlcv> {
+    std::vector<DynIndicator> inds;
+    Expression entry;
+    Expression exit;
+    Side last_side = Side::NONE;
+public:
+    /**
+     * Builds the indicators and the rules of a configuration. Returns
+     * false, with the reason in `error`, if any of them is invalid.
+     */
+    bool configure(const StrategyConfig& config, std::string& error,
+            const IndicatorRegistry& registry = builtin_indicators()) {
+        std::vector<DynIndicator> built;
+        std::vector<std::string> names;
+        for (const auto& spec : config.indicators) {
+            std::unique_ptr<DynIndicator> ind = registry.make(spec.second,
+                    &error);
+            if (!ind) {
+                error = spec.first + ": " + error;
+                return false;
+            }
+            built.push_back(*ind);
+            names.push_back(spec.first);
+        }
+        if (config.entry.empty()) {
+            error = "missing entry rule";
+            return false;
+        }
+        Expression entry_rule, exit_rule;
+        if (!entry_rule.parse(config.entry, error, names, registry)) {
+            error = "entry: " + error;
+            return false;
+        }
+        if (!config.exit.empty()
+                && !exit_rule.parse(config.exit, error, names, registry)) {
+            error = "exit: " + error;
+            return false;
+        }
+        inds = built;
+        entry = entry_rule;
+        exit = exit_rule;
+        last_side = Side::NONE;
+        return true;
+    }
+
+    Signal update(const Ohlcv& bar) {
+        for (auto& ind : inds) ind.update(bar);
+        Signal signal = {bar.timestamp, Side::NONE, bar.close};
+        bool buy = entry.test(bar, inds);
+        bool sell = exit.test(bar, inds);
+        if (buy && last_side != Side::BUY)
+            last_side = signal.side = Side::BUY;
+        else if (sell && last_side != Side::SELL)
+            last_side = signal.side = Side::SELL;
+        return signal;
+    }
+};
+
+/**
+ * A portfolio with the cash, costs, stop-loss and take-profit of a
+ * configuration.
+ */
+inline BasicPortfolio make_portfolio(const StrategyConfig& config) {
+    return BasicPortfolio(config.init_cash, config.tx_cost, config.stop_loss,
+            config.take_profit);
+}
+
+} // namespace tzu
+
+#endif // CONFIG_H
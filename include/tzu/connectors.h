@@ -0,0 +1,483 @@
+#ifndef CONNECTORS_H
+#define CONNECTORS_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <functional>
+#include <map>
+#include <random>
+#include <string>
+#include <utility>
+#include <vector>
+
+/**
+ * This header defines building blocks for connectors to data feeds
+ * and brokers. They don't perform any I/O: time is given by the
+ * caller, or by injected clock and sleep callables, so they behave the
+ * same in live sessions, in simulations and in tests. Times are
+ * integers in the unit of the clock used, e.g. milliseconds.
+ */
+
+namespace tzu {
+
+/**
+ * A token bucket holding at most `capacity` tokens, refilled at a
+ * constant rate so that it goes from empty to full in `window` time
+ * units. Reservations can take the bucket below zero, which is how
+ * queued requests are accounted for. A non-positive window is taken
+ * as one time unit.
+ */
+class TokenBucket {
+    double capacity;
+    int64_t window;
+    double tokens;
+    int64_t last = INT64_MIN;
+
+    void refill(int64_t now) {
+        if (last != INT64_MIN && now > last) {
+            tokens += static_cast<double>(now - last) * capacity / window;
+            if (tokens > capacity) tokens = capacity;
+        }
+        if (last == INT64_MIN || now > last) last = now;
+    }
+
+public:
+    TokenBucket(double capacity, int64_t window)
+        : capacity(capacity), window(window > 0 ? window : 1),
+          tokens(capacity) {}
+
+    /**
+     * Time to wait from `now` until `weight` tokens are available.
+     */
+    int64_t delay(int64_t now, double weight) {
+        refill(now);
+        double missing = weight - tokens;
+        if (missing <= 0.0) return 0;
+        return static_cast<int64_t>(std::ceil(missing * window / capacity));
+    }
+
+    void take(int64_t now, double weight) {
+        refill(now);
+        tokens -= weight;
+    }
+
+    double available(int64_t now) {
+        refill(now);
+        return tokens;
+    }
+
+    double get_capacity() const noexcept { return capacity; }
+};
+
+/**
+ * Rate limiter for the requests sent to a venue. Several limits can be
+ * enforced at once (e.g. request weight per minute and orders per ten
+ * seconds, as many exchanges do), and each endpoint consumes a weight
+ * from every limit. A request is granted with the delay the caller has
+ * to wait before sending it. When that delay would exceed `max_delay`,
+ * the request is shed instead and nothing is consumed.
+ */
+class RateLimiter {
+    std::vector<TokenBucket> buckets;
+    std::map<std::string, double> weights;
+    double default_weight;
+    int64_t max_delay;
+    size_t shed = 0;
+
+public:
+    explicit RateLimiter(int64_t max_delay = INT64_MAX,
+            double default_weight = 1.0)
+        : default_weight(default_weight), max_delay(max_delay) {}
+
+    /**
+     * Adds a limit of `capacity` weight units per `window` time units.
+     */
+    RateLimiter& add_limit(double capacity, int64_t window) {
+        buckets.push_back(TokenBucket(capacity, window));
+        return *this;
+    }
+
+    RateLimiter& set_weight(const std::string& endpoint, double weight) {
+        weights[endpoint] = weight;
+        return *this;
+    }
+
+    double weight(const std::string& endpoint) const {
+        auto it = weights.find(endpoint);
+        return it == weights.end() ? default_weight : it->second;
+    }
+
+    /**
+     * Requests permission to call an endpoint at time `now`. Returns the
+     * time to wait before the call, or -1 if the request is shed.
+     */
+    int64_t acquire(int64_t now, const std::string& endpoint = "") {
+        double w = weight(endpoint);
+        int64_t wait = 0;
+        for (auto& bucket : buckets) {
+            if (w > bucket.get_capacity()) {
+                shed++;
+                return -1;
+            }
+            int64_t d = bucket.delay(now, w);
+            if (d > wait) wait = d;
+        }
+        if (wait > max_delay) {
+            shed++;
+            return -1;
+        }
+        for (auto& bucket : buckets)
+            bucket.take(now, w);
+        return wait;
+    }
+
+    size_t num_shed() const noexcept { return shed; }
+};
+
+/**
+ * Wraps calls to a connector with a rate limiter. `clock` returns the
+ * current time and `sleep` waits for a given time, both in the same
+ * unit as the limits.
+ */
+template <typename Clock, typename Sleep>
+class Throttle {
+    RateLimiter limiter;
+    Clock clock;
+    Sleep sleep;
+
+public:
+    Throttle(const RateLimiter& limiter, Clock clock, Sleep sleep)
+        : limiter(limiter), clock(clock), sleep(sleep) {}
+
+    /**
+     * Calls `f` once the limits allow it. Returns false, without
+     * calling it, if the request was shed.
+     */
+    template <typename F>
+    bool call(const std::string& endpoint, F f) {
+        int64_t wait = limiter.acquire(clock(), endpoint);
+        if (wait < 0) return false;
+        if (wait > 0) sleep(wait);
+        f();
+        return true;
+    }
+
+    const RateLimiter& get_limiter() const noexcept { return limiter; }
+};
+
+template <typename Clock, typename Sleep>
+Throttle<Clock, Sleep> make_throttle(const RateLimiter& limiter, Clock clock,
+        Sleep sleep) {
+    return Throttle<Clock, Sleep>(limiter, clock, sleep);
+}
+
+/**
+ * Retry settings. The delay before retry number n (starting at 1) is
+ * `initial_delay * multiplier^(n-1)`, capped at `max_delay`, and then
+ * reduced by a random fraction of up to `jitter` of its value, so
+ * clients that failed together don't retry together.
+ */
+struct RetryPolicy {
+    size_t max_attempts = 5;
+    int64_t initial_delay = 100;
+    double multiplier = 2.0;
+    int64_t max_delay = 10000;
+    double jitter = 0.5;
+};
+
+/**
+ * Delay before a given retry, with `u` a random number in [0, 1).
+ */
+inline int64_t backoff_delay(const RetryPolicy& policy, size_t retry,
+        double u = 0.0) {
+    double d = static_cast<double>(policy.initial_delay)
+        * std::pow(policy.multiplier, static_cast<double>(retry - 1));
+    if (d > policy.max_delay) d = static_cast<double>(policy.max_delay);
+    d *= 1.0 - policy.jitter * u;
+    return static_cast<int64_t>(d);
+}
+
+/**
+ * A call that failed in all of its attempts.
+ */
+struct GiveUp {
+    std::string key;
+    size_t attempts;
+};
+
+namespace detail {
+
+/**
+ * A random prefix for idempotency keys, e.g. "tzu-3f9a0c12", different
+ * in every session.
+ */
+inline std::string session_prefix() {
+    static const char digits[] = "0123456789abcdef";
+    std::random_device device;
+    uint32_t bits = device();
+    std::string out = "tzu-";
+    for (int i = 28; i >= 0; i -= 4) out += digits[(bits >> i) & 0xf];
+    return out;
+}
+
+} // namespace detail
+
+/**
+ * Retries failing connector calls with exponential backoff. The call
+ * receives an idempotency key, the same in every attempt, and returns
+ * true on success. Passing the key to the venue, e.g. as the client
+ * order id, ensures a retried order submission whose first attempt
+ * actually reached the venue is not executed twice. `sleep` waits for
+ * a given time. Calls that are given up are recorded.
+ *
+ * Keys are the `prefix` followed by a counter, so the prefix must be
+ * unique to the session: venues reject, or silently deduplicate, a
+ * client order id already used by a previous run. Without one, a
+ * random prefix is generated.
+ */
+template <typename Sleep>
+class Retry {
+    RetryPolicy policy;
+    Sleep sleep;
+    std::string prefix;
+    uint64_t counter = 0;
+    std::mt19937 rng;
+    std::uniform_real_distribution<double> uniform;
+    std::vector<GiveUp> events;
+
+public:
+    Retry(const RetryPolicy& policy, Sleep sleep,
+            const std::string& prefix = std::string(), unsigned seed = 0)
+        : policy(policy), sleep(sleep),
+          prefix(prefix.empty() ? detail::session_prefix() : prefix),
+          rng(seed), uniform(0.0, 1.0) {}
+
+    /**
+     * Generates a new idempotency key.
+     */
+    std::string next_key() {
+        return prefix + "-" + std::to_string(++counter);
+    }
+
+    /**
+     * Runs `f` with a new idempotency key until it succeeds.
+     */
+    template <typename F>
+    bool call(F f) { return call(next_key(), f); }
+
+    /**
+     * Runs `f` with the given idempotency key until it succeeds or the
+     * attempts are exhausted.
+     */
+    template <typename F>
+    bool call(const std::string& key, F f) {
+        for (size_t attempt = 1; attempt <= policy.max_attempts; attempt++) {
+            if (f(key)) return true;
+            if (attempt < policy.max_attempts)
+                sleep(backoff_delay(policy, attempt, uniform(rng)));
+        }
+        events.push_back(GiveUp{key, policy.max_attempts});
+        return false;
+    }
+
+    const std::vector<GiveUp>& get_events() const noexcept { return events; }
+};
+
+template <typename Sleep>
+Retry<Sleep> make_retry(const RetryPolicy& policy, Sleep sleep,
+        const std::string& prefix = std::string(), unsigned seed = 0) {
+    return Retry<Sleep>(policy, sleep, prefix, seed);
+}
+
+enum class FeedStatus {
+    OK,
+    STALE,
+    OUT_OF_ORDER,
+    DUPLICATE
+};
+
+/**
+ * Checks the timestamps of a live feed against the local clock. The
+ * offset between both clocks is observed as `local - exchange`, which
+ * is the skew plus the (positive) delivery latency, so the skew is
+ * estimated as the smallest offset among the last `window` messages.
+ * A message is stale when its latency, once corrected for the skew,
+ * exceeds `max_latency`, and out of order when it is older than the
+ * newest message seen. Boundary decisions, such as when a bar or a
+ * session is over, should be taken on exchange time with `to_exchange`
+ * or `boundary`, rather than on the local clock.
+ */
+class ClockSkew {
+    size_t window;
+    int64_t max_latency;
+    std::vector<int64_t> offsets;
+    size_t pos = 0;
+    int64_t last = INT64_MIN;
+    int64_t skew = 0;
+
+public:
+    ClockSkew(size_t window = 100, int64_t max_latency = 5000)
+        : window(window), max_latency(max_latency) {}
+
+    /**
+     * Registers a message stamped `exchange_time` by the venue and
+     * received at `local_time`.
+     */
+    FeedStatus observe(int64_t exchange_time, int64_t local_time) {
+        int64_t offset = local_time - exchange_time;
+        if (offsets.size() < window) {
+            offsets.push_back(offset);
+        } else {
+            offsets[pos] = offset;
+            pos = (pos + 1) % window;
+        }
+        skew = offsets[0];
+        for (int64_t o : offsets)
+            if (o < skew) skew = o;
+        if (exchange_time == last)
+            return FeedStatus::DUPLICATE;
+        if (last != INT64_MIN && exchange_time < last)
+            return FeedStatus::OUT_OF_ORDER;
+        last = exchange_time;
+        if (offset - skew > max_latency)
+            return FeedStatus::STALE;
+        return FeedStatus::OK;
+    }
+
+    /**
+     * Estimated difference between the local and the exchange clocks.
+     */
+    int64_t get() const noexcept { return skew; }
+
+    int64_t to_exchange(int64_t local_time) const noexcept {
+        return local_time - skew;
+    }
+
+    /**
+     * Start, in exchange time, of the period of length `timeframe` in
+     * progress at `local_time`.
+     */
+    int64_t boundary(int64_t local_time, int64_t timeframe) const noexcept {
+        int64_t t = to_exchange(local_time);
+        int64_t r = t % timeframe;
+        return r < 0 ? t - r - timeframe : t - r;
+    }
+};
+
+enum class Liveness {
+    ALIVE,
+    DEGRADED,
+    STALE,
+    DEAD
+};
+
+/**
+ * A change in the liveness of a feed.
+ */
+struct LivenessEvent {
+    int64_t timestamp;
+    std::string key;
+    Liveness status;
+};
+
+/**
+ * Time without messages after which a feed is considered degraded,
+ * stale and dead.
+ */
+struct WatchdogConfig {
+    int64_t degraded = 5000;
+    int64_t stale = 15000;
+    int64_t dead = 60000;
+};
+
+/**
+ * Liveness monitor for feeds. Each feed, or symbol within a feed, is
+ * identified by a key and reports its messages with `heartbeat`.
+ * `check` is called periodically with the current time and returns the
+ * status changes since the previous call, which the caller can use to
+ * pause trading, reconnect a dead feed, or trip a `CircuitBreaker`.
+ */
+class Watchdog {
+    struct Feed {
+        int64_t last;
+        Liveness status;
+    };
+    WatchdogConfig config;
+    std::map<std::string, Feed> feeds;
+    std::vector<LivenessEvent> pending;
+
+    Liveness classify(int64_t silence) const {
+        if (silence >= config.dead) return Liveness::DEAD;
+        if (silence >= config.stale) return Liveness::STALE;
+        if (silence >= config.degraded) return Liveness::DEGRADED;
+        return Liveness::ALIVE;
+    }
+
+public:
+    explicit Watchdog(const WatchdogConfig& config = WatchdogConfig())
+        : config(config) {}
+
+    void heartbeat(const std::string& key, int64_t now) {
+        auto it = feeds.find(key);
+        if (it == feeds.end()) {
+            feeds[key] = Feed{now, Liveness::ALIVE};
+            return;
+        }
+        if (now > it->second.last) it->second.last = now;
+        if (it->second.status != Liveness::ALIVE) {
+            it->second.status = Liveness::ALIVE;
+            pending.push_back(LivenessEvent{now, key, Liveness::ALIVE});
+        }
+    }
+
+    std::vector<LivenessEvent> check(int64_t now) {
+        for (auto& f : feeds) {
+            Liveness status = classify(now - f.second.last);
+            if (status != f.second.status) {
+                f.second.status = status;
+                pending.push_back(LivenessEvent{now, f.first, status});
+            }
+        }
+        std::vector<LivenessEvent> out;
+        out.swap(pending);
+        return out;
+    }
+
+    Liveness status(const std::string& key) const {
+        auto it = feeds.find(key);
+        return it == feeds.end() ? Liveness::DEAD : it->second.status;
+    }
+
+    bool all_alive() const {
+        for (const auto& f : feeds)
+            if (f.second.status != Liveness::ALIVE) return false;
+        return true;
+    }
+};
+
+/**
+ * An HTTP request built by a REST connector, to be sent by the HTTP
+ * client of the caller.
+ */
+struct HttpRequest {
+    std::string method;
+    std::string url;
+    std::vector<std::pair<std::string, std::string>> headers;
+    std::string body;
+};
+
+struct HttpResponse {
+    int status = 0;
+    std::string body;
+};
+
+/**
+ * Sends a request and sets the response. Returns false if there was
+ * no response, e.g. because the connection failed.
+ */
+using HttpClient = std::function<bool(const HttpRequest&, HttpResponse&)>;
+
+} // namespace tzu
+
+#endif // CONNECTORS_H
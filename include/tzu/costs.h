@@ -0,0 +1,136 @@
+#ifndef COSTS_H
+#define COSTS_H
+
+#include <cstddef>
+#include <cmath>
+#include <functional>
+#include <utility>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines trading cost models for simulated execution.
+ * A commission model returns the fee of a fill from its signed quantity
+ * and price. A slippage model returns the price actually obtained by an
+ * order taking liquidity, given its signed quantity, the reference
+ * price (e.g. the open) and the bar where it executes; the difference
+ * is always against the order. Any callable with those signatures can
+ * be used, and the classes below cover the common cases.
+ */
+
+namespace tzu {
+
+using CommissionModel = std::function<double(double quantity, double price)>;
+using SlippageModel = std::function<double(double quantity, double price,
+        const Ohlcv& bar)>;
+
+/**
+ * Fixed fee per unit traded, with an optional minimum per fill and an
+ * optional cap as a fraction of the traded value.
+ */
+class PerShareCommission {
+    double per_share;
+    double minimum;
+    double max_rate;
+public:
+    PerShareCommission(double per_share, double minimum = 0.0,
+            double max_rate = std::nan(""))
+        : per_share(per_share), minimum(minimum), max_rate(max_rate) {}
+    double operator()(double quantity, double price) const {
+        double fee = std::fmax(std::fabs(quantity) * per_share, minimum);
+        if (!std::isnan(max_rate))
+            fee = std::fmin(fee, std::fabs(quantity) * price * max_rate);
+        return fee;
+    }
+};
+
+/**
+ * Fee as a fraction of the traded value (e.g. 0.001 == 0.1%), with an
+ * optional minimum per fill.
+ */
+class PercentageCommission {
+    double rate;
+    double minimum;
+public:
+    PercentageCommission(double rate, double minimum = 0.0)
+        : rate(rate), minimum(minimum) {}
+    double operator()(double quantity, double price) const {
+        double value = std::fabs(quantity) * price;
+        return value > 0.0 ? std::fmax(value * rate, minimum) : 0.0;
+    }
+};
+
+/**
+ * Fee rate decreasing with the cumulative traded value, as with volume
+ * discounts. `tiers` are pairs of (traded value from which the tier
+ * applies, rate), sorted by value, the first one starting at zero. The
+ * whole fill pays the rate of the tier reached before it.
+ */
+class TieredCommission {
+    std::vector<std::pair<double, double>> tiers;
+    double traded = 0.0;
+public:
+    explicit TieredCommission(const std::vector<std::pair<double, double>>& tiers)
+        : tiers(tiers) {}
+    double operator()(double quantity, double price) {
+        double value = std::fabs(quantity) * price;
+        double rate = 0.0;
+        for (const auto& tier : tiers)
+            if (traded >= tier.first) rate = tier.second;
+        traded += value;
+        return value * rate;
+    }
+};
+
+/**
+ * Slippage as a fixed fraction of the price.
+ */
+class FixedSlippage {
+    double rate;
+public:
+    explicit FixedSlippage(double rate): rate(rate) {}
+    double operator()(double quantity, double price, const Ohlcv&) const {
+        return quantity > 0.0 ? price * (1.0 + rate) : price * (1.0 - rate);
+    }
+};
+
+/**
+ * Crossing the bid-ask spread: with bar prices at the mid, orders pay
+ * half the spread, given as a fraction of the price.
+ */
+class SpreadSlippage {
+    double spread;
+public:
+    explicit SpreadSlippage(double spread): spread(spread) {}
+    double operator()(double quantity, double price, const Ohlcv&) const {
+        double half = spread / 2.0;
+        return quantity > 0.0 ? price * (1.0 + half) : price * (1.0 - half);
+    }
+};
+
+/**
+ * Market impact growing with the size of the order relative to the
+ * volume of the bar:
+ *
+ * impact = coefficient * (|quantity| / volume) ^ exponent
+ *
+ * as a fraction of the price. The default exponent of 0.5 is the usual
+ * square-root law. Bars without volume get the impact of an order as
+ * large as the bar.
+ */
+class VolumeImpactSlippage {
+    double coefficient;
+    double exponent;
+public:
+    VolumeImpactSlippage(double coefficient, double exponent = 0.5)
+        : coefficient(coefficient), exponent(exponent) {}
+    double operator()(double quantity, double price, const Ohlcv& bar) const {
+        double share = bar.volume > 0.0 ? std::fabs(quantity) / bar.volume : 1.0;
+        double impact = coefficient * std::pow(share, exponent);
+        return quantity > 0.0 ? price * (1.0 + impact) : price * (1.0 - impact);
+    }
+};
+
+} // namespace tzu
+
+#endif // COSTS_H
@@ -0,0 +1,253 @@
+#ifndef CURRENCY_H
+#define CURRENCY_H
+
+#include <cstdint>
+#include <cmath>
+#include <map>
+#include <string>
+#include <vector>
+#include <algorithm>
+#include <iterator>
+#include <ostream>
+#include "defs.h"
+#include "indicators.h"
+#include "ledger.h"
+#include "portfolios.h"
+
+/**
+ * This header defines a currency layer for multi-currency accounts.
+ * Prices quoted in a foreign currency are converted into the account's
+ * base currency using a supplied stream of FX rates, so that the
+ * portfolio, its performance metrics, and its reports are all expressed
+ * in the base currency. Rates are looked up as of the time of each
+ * value (the last known rate at or before its timestamp), so no future
+ * rate is ever used.
+ */
+
+namespace tzu {
+
+/**
+ * A table of FX rate series. Each rate is expressed as units of the
+ * base currency per one unit of the given currency, e.g. with base
+ * currency "USD", a rate of 1.08 for "EUR" means 1 EUR = 1.08 USD.
+ */
+class FxRates {
+    std::string base;
+    std::map<std::string, std::vector<SingleValue>> series;
+public:
+    explicit FxRates(const std::string& base_currency = "USD")
+        : base(base_currency) {}
+
+    const std::string& base_currency() const noexcept { return base; }
+
+    /**
+     * Adds a rate for a currency. Rates may be added in any order.
+     */
+    void add(const std::string& currency, const SingleValue& rate) {
+        std::vector<SingleValue>& s = series[currency];
+        if (s.empty() || s.back().timestamp < rate.timestamp) {
+            s.push_back(rate);
+            return;
+        }
+        auto it = std::lower_bound(s.begin(), s.end(), rate,
+            [](const SingleValue& a, const SingleValue& b) {
+                return a.timestamp < b.timestamp;
+            });
+        if (it != s.end() && it->timestamp == rate.timestamp)
+            *it = rate;
+        else
+            s.insert(it, rate);
+    }
+
+    /**
+     * Adds all the rates provided by a streamer, e.g.
+     * `Csv<SingleValue>`.
+     */
+    template <typename Streamer>
+    void load(const std::string& currency, Streamer& streamer) {
+        for (const auto& rate : streamer)
+            add(currency, rate);
+    }
+
+    /**
+     * Returns the rate of a currency at the given time, which is the
+     * last rate known at or before the timestamp. The rate of the base
+     * currency is always 1. Returns NaN if no rate is known yet.
+     */
+    double rate(const std::string& currency, int64_t timestamp) const {
+        if (currency == base)
+            return 1.0;
+        auto it = series.find(currency);
+        if (it == series.end())
+            return std::nan("");
+        const std::vector<SingleValue>& s = it->second;
+        auto pos = std::upper_bound(s.begin(), s.end(), timestamp,
+            [](int64_t ts, const SingleValue& v) { return ts < v.timestamp; });
+        if (pos == s.begin())
+            return std::nan("");
+        return std::prev(pos)->value;
+    }
+
+    /**
+     * Converts an amount between two currencies at the given time,
+     * crossing through the base currency. Converts into the base
+     * currency if `to` is empty.
+     */
+    double convert(double amount, const std::string& from, int64_t timestamp,
+            const std::string& to = "") const {
+        double value = amount * rate(from, timestamp);
+        if (to.empty() || to == base)
+            return value;
+        return value / rate(to, timestamp);
+    }
+};
+
+/**
+ * FX Conversion (FxConvert)
+ *
+ * Converts `Ohlcv` bars quoted in a foreign currency into the base
+ * currency. Prices are multiplied by the rate at the bar's timestamp,
+ * while the volume (in units of the asset) is left unchanged. Returns a
+ * bar with NaN prices while no rate is known.
+ */
+class FxConvert: public Indicator<FxConvert, Ohlcv, Ohlcv> {
+    Ohlcv data = Ohlcv(0, std::nan(""), std::nan(""), std::nan(""),
+            std::nan(""), 0.0);
+    FxRates rates;
+    std::string currency;
+public:
+    FxConvert(const FxRates& rates, const std::string& currency)
+        : rates(rates), currency(currency) {}
+    Ohlcv get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    Ohlcv update(const Ohlcv& value) {
+        double fx = rates.rate(currency, value.timestamp);
+        data = Ohlcv(value.timestamp, value.open * fx, value.high * fx,
+                value.low * fx, value.close * fx, value.volume);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data);
+    }
+    void reset_state() {
+        data = Ohlcv(0, std::nan(""), std::nan(""), std::nan(""), std::nan(""),
+            0.0);
+    }
+};
+
+/**
+ * FxPortfolio wraps a portfolio managed in the base currency and
+ * trades an asset quoted in a foreign currency. Signal prices are
+ * converted before being forwarded, so cash, holdings, costs, profits
+ * and all the metrics reported by the wrapped portfolio are expressed
+ * in the base currency, and include the effect of FX moves. Signals
+ * received before a rate is known are ignored.
+ */
+template <class P>
+class FxPortfolio: public Portfolio<FxPortfolio<P>> {
+    P portfolio;
+    FxRates rates;
+    std::string currency;
+public:
+    FxPortfolio(const P& portfolio, const FxRates& rates,
+            const std::string& currency)
+        : portfolio(portfolio), rates(rates), currency(currency) {}
+
+    void update(const Signal& signal) {
+        double fx = rates.rate(currency, signal.timestamp);
+        if (std::isnan(fx))
+            return;
+        Signal converted = signal;
+        converted.price = signal.price * fx;
+        portfolio.update(converted);
+    }
+
+    /**
+     * Gives access to the wrapped portfolio.
+     */
+    const P& get() const noexcept { return portfolio; }
+
+    friend std::ostream& operator<<(std::ostream& os,
+            const FxPortfolio& fx_portfolio) {
+        return os << fx_portfolio.portfolio;
+    }
+};
+
+/**
+ * FxLedger keeps a `Ledger` in the base currency for symbols quoted in
+ * several currencies. Each symbol is assigned its quote currency, the
+ * base currency by default. Fill prices and commissions are converted
+ * at the rate of the fill, and marks at the rate of their time, so
+ * positions are valued and profits realized in the base currency,
+ * including the effect of FX moves. The trades recorded, and so their
+ * statistics, are in the base currency too. Fills and marks are
+ * ignored, returning false, while the rate of their currency is
+ * unknown.
+ */
+class FxLedger {
+    Ledger ledger;
+    FxRates rates;
+    std::map<std::string, std::string> currencies;
+
+    double rate(const std::string& symbol, int64_t timestamp) const {
+        return rates.rate(currency(symbol), timestamp);
+    }
+
+public:
+    explicit FxLedger(const FxRates& rates, double cash = 0.0)
+        : ledger(cash), rates(rates) {}
+
+    void set_currency(const std::string& symbol, const std::string& currency) {
+        currencies[symbol] = currency;
+    }
+
+    const std::string& currency(const std::string& symbol) const {
+        auto it = currencies.find(symbol);
+        return it != currencies.end() ? it->second : rates.base_currency();
+    }
+
+    /**
+     * Applies an execution with its price and commission in the quote
+     * currency of the symbol.
+     */
+    bool on_fill(int64_t timestamp, const std::string& symbol,
+            double quantity, double price, double commission = 0.0) {
+        double fx = rate(symbol, timestamp);
+        if (std::isnan(fx)) return false;
+        ledger.on_fill(timestamp, symbol, quantity, price * fx,
+                commission * fx);
+        return true;
+    }
+
+    /**
+     * Updates the price, in the quote currency, used to value a symbol.
+     */
+    bool mark(const std::string& symbol, double price, int64_t timestamp) {
+        double fx = rate(symbol, timestamp);
+        if (std::isnan(fx)) return false;
+        ledger.mark(symbol, price * fx);
+        return true;
+    }
+
+    bool mark(const std::string& symbol, const Ohlcv& bar) {
+        return mark(symbol, bar.close, bar.timestamp);
+    }
+
+    /**
+     * The accounts, in the base currency.
+     */
+    const Ledger& account() const noexcept { return ledger; }
+
+    const std::string& base_currency() const noexcept {
+        return rates.base_currency();
+    }
+
+    friend std::ostream& operator<<(std::ostream& os, const FxLedger& fx) {
+        return os << "currency:" << fx.base_currency() << " " << fx.ledger;
+    }
+};
+
+} // namespace tzu
+
+#endif // CURRENCY_H
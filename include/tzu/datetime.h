@@ -0,0 +1,273 @@
+#ifndef DATETIME_H
+#define DATETIME_H
+
+#include <chrono>
+#include <cstdint>
+#include <cctype>
+#include <cstdio>
+#include <string>
+
+/**
+ * This header defines date and time helpers. Timestamps in tzutrader
+ * are integers whose unit is chosen by the user; these helpers work
+ * with Unix timestamps in seconds, in UTC, and use the proleptic
+ * Gregorian calendar. Timestamps in other units are converted to and
+ * from `std::chrono` time points by giving their unit as a duration.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+inline int64_t floor_div(int64_t a, int64_t b) {
+    int64_t q = a / b;
+    return a % b < 0 ? q - 1 : q;
+}
+
+} // namespace detail
+
+/**
+ * Number of days since 1970-01-01 for a civil date (H. Hinnant's
+ * algorithm). `month` is 1 to 12 and `day` is 1 to 31.
+ */
+inline int64_t days_from_civil(int64_t year, unsigned month, unsigned day) {
+    year -= month <= 2 ? 1 : 0;
+    int64_t era = (year >= 0 ? year : year - 399) / 400;
+    unsigned yoe = static_cast<unsigned>(year - era * 400);
+    unsigned doy = (153 * (month > 2 ? month - 3 : month + 9) + 2) / 5 + day - 1;
+    unsigned doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    return era * 146097 + static_cast<int64_t>(doe) - 719468;
+}
+
+/**
+ * Civil date of a number of days since 1970-01-01, the inverse of
+ * `days_from_civil`.
+ */
+inline void civil_from_days(int64_t days, int64_t& year, unsigned& month,
+        unsigned& day) {
+    days += 719468;
+    int64_t era = (days >= 0 ? days : days - 146096) / 146097;
+    unsigned doe = static_cast<unsigned>(days - era * 146097);
+    unsigned yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    unsigned doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    unsigned mp = (5 * doy + 2) / 153;
+    day = doy - (153 * mp + 2) / 5 + 1;
+    month = mp < 10 ? mp + 3 : mp - 9;
+    year = static_cast<int64_t>(yoe) + era * 400 + (month <= 2 ? 1 : 0);
+}
+
+/**
+ * Number of days of a month, `month` being 1 to 12.
+ */
+inline unsigned days_in_month(int64_t year, unsigned month) {
+    static const unsigned days[12] = {31, 28, 31, 30, 31, 30, 31, 31, 30,
+        31, 30, 31};
+    bool leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    return month == 2 && leap ? 29 : days[month - 1];
+}
+
+/**
+ * Unix timestamp, in seconds, of a civil date and time in UTC.
+ */
+inline int64_t to_unix(int64_t year, unsigned month, unsigned day,
+        unsigned hour = 0, unsigned minute = 0, unsigned second = 0) {
+    return days_from_civil(year, month, day) * 86400
+        + hour * 3600 + minute * 60 + second;
+}
+
+/**
+ * Start of the period of length `timeframe` containing `timestamp`, for
+ * periods aligned to `offset`.
+ */
+inline int64_t bucket_start(int64_t timestamp, int64_t timeframe,
+        int64_t offset = 0) {
+    return detail::floor_div(timestamp - offset, timeframe) * timeframe
+        + offset;
+}
+
+/**
+ * Parses a timeframe like `30s`, `5m`, `4h`, `1d` or `1w` into seconds.
+ * A number without unit is taken as seconds. Returns false if the text
+ * is not a positive timeframe.
+ */
+inline bool parse_timeframe(const std::string& text, int64_t& out) {
+    size_t i = 0;
+    int64_t value = 0;
+    while (i < text.size() && i < 12
+            && std::isdigit(static_cast<unsigned char>(text[i])))
+        value = value * 10 + (text[i++] - '0');
+    if (i == 0 || value == 0) return false;
+    int64_t unit = 1;
+    if (i < text.size()) {
+        if (i + 1 != text.size()) return false;
+        switch (text[i]) {
+            case 's': unit = 1; break;
+            case 'm': unit = 60; break;
+            case 'h': unit = 3600; break;
+            case 'd': unit = 86400; break;
+            case 'w': unit = 7 * 86400; break;
+            default: return false;
+        }
+    }
+    out = value * unit;
+    return true;
+}
+
+/**
+ * Parses a date/time with a `strptime`-like format into a Unix
+ * timestamp in seconds, in UTC. Supported directives are `%Y`, `%m`,
+ * `%d`, `%H`, `%M`, `%S` (with optional fractional seconds, which are
+ * ignored), `%s` (Unix seconds) and `%%`; other characters must match
+ * literally. Returns false if the text doesn't match the format, or
+ * the date doesn't exist.
+ */
+inline bool parse_time(const char* text, const char* format, int64_t& out) {
+    int64_t year = 1970;
+    unsigned month = 1, day = 1, hour = 0, minute = 0, second = 0;
+    int64_t unix_seconds = 0;
+    bool has_unix = false;
+    const char* t = text;
+    for (const char* f = format; *f != '\0'; f++) {
+        if (*f != '%') {
+            if (*t != *f) return false;
+            t++;
+            continue;
+        }
+        f++;
+        if (*f == '%') {
+            if (*t++ != '%') return false;
+            continue;
+        }
+        bool negative = false;
+        if (*f == 's' && *t == '-') {
+            negative = true;
+            t++;
+        }
+        size_t max_digits = *f == 'Y' ? 4 : *f == 's' ? 19 : 2;
+        int64_t value = 0;
+        size_t n = 0;
+        while (n < max_digits && std::isdigit(static_cast<unsigned char>(*t))) {
+            value = value * 10 + (*t - '0');
+            t++;
+            n++;
+        }
+        if (n == 0) return false;
+        switch (*f) {
+            case 'Y': year = value; break;
+            case 'm': month = static_cast<unsigned>(value); break;
+            case 'd': day = static_cast<unsigned>(value); break;
+            case 'H': hour = static_cast<unsigned>(value); break;
+            case 'M': minute = static_cast<unsigned>(value); break;
+            case 'S':
+                second = static_cast<unsigned>(value);
+                if (*t == '.')
+                    for (t++; std::isdigit(static_cast<unsigned char>(*t)); t++) {}
+                break;
+            case 's':
+                unix_seconds = negative ? -value : value;
+                has_unix = true;
+                break;
+            default: return false;
+        }
+    }
+    if (*t != '\0') return false;
+    if (has_unix) {
+        out = unix_seconds;
+        return true;
+    }
+    if (month < 1 || month > 12 || day < 1
+            || day > days_in_month(year, month) || hour > 23
+            || minute > 59 || second > 60)
+        return false;
+    out = to_unix(year, month, day, hour, minute, second);
+    return true;
+}
+
+/**
+ * Formats a Unix timestamp in seconds with the directives of
+ * `parse_time`, in UTC or, with `utc_offset` in seconds, in local time
+ * (see `Calendar::utc_offset`). Other directives are copied as they
+ * are.
+ */
+inline std::string format_time(int64_t timestamp, const char* format,
+        int64_t utc_offset = 0) {
+    int64_t local = timestamp + utc_offset;
+    int64_t days = detail::floor_div(local, 86400);
+    int64_t secs = local - days * 86400;
+    int64_t year;
+    unsigned month, day;
+    civil_from_days(days, year, month, day);
+    std::string out;
+    char buf[24];
+    for (const char* f = format; *f != '\0'; f++) {
+        if (*f != '%' || f[1] == '\0') {
+            out += *f;
+            continue;
+        }
+        f++;
+        switch (*f) {
+            case 'Y':
+                std::snprintf(buf, sizeof(buf), "%04lld",
+                        static_cast<long long>(year));
+                break;
+            case 'm': std::snprintf(buf, sizeof(buf), "%02u", month); break;
+            case 'd': std::snprintf(buf, sizeof(buf), "%02u", day); break;
+            case 'H':
+                std::snprintf(buf, sizeof(buf), "%02d",
+                        static_cast<int>(secs / 3600));
+                break;
+            case 'M':
+                std::snprintf(buf, sizeof(buf), "%02d",
+                        static_cast<int>(secs / 60 % 60));
+                break;
+            case 'S':
+                std::snprintf(buf, sizeof(buf), "%02d",
+                        static_cast<int>(secs % 60));
+                break;
+            case 's':
+                std::snprintf(buf, sizeof(buf), "%lld",
+                        static_cast<long long>(timestamp));
+                break;
+            case '%': std::snprintf(buf, sizeof(buf), "%%"); break;
+            default: std::snprintf(buf, sizeof(buf), "%%%c", *f); break;
+        }
+        out += buf;
+    }
+    return out;
+}
+
+/**
+ * Time point of a timestamp counting `Duration` units since the Unix
+ * epoch, e.g. `to_time_point<std::chrono::milliseconds>(ts)` for
+ * timestamps in milliseconds.
+ */
+template <class Duration = std::chrono::seconds>
+std::chrono::system_clock::time_point to_time_point(int64_t timestamp) {
+    return std::chrono::system_clock::time_point(
+            std::chrono::duration_cast<std::chrono::system_clock::duration>(
+                Duration(timestamp)));
+}
+
+/**
+ * Timestamp in `Duration` units of a time point, rounded down, e.g. to
+ * build bars from `std::chrono::system_clock::now()`.
+ */
+template <class Duration = std::chrono::seconds>
+int64_t to_timestamp(std::chrono::system_clock::time_point time) {
+    Duration d = std::chrono::duration_cast<Duration>(time.time_since_epoch());
+    if (d > time.time_since_epoch()) d -= Duration(1);
+    return static_cast<int64_t>(d.count());
+}
+
+/**
+ * Time point of any data with a timestamp, e.g. an `Ohlcv` or a `Tick`,
+ * whose timestamps count `Duration` units.
+ */
+template <class Duration = std::chrono::seconds, class T>
+std::chrono::system_clock::time_point time_of(const T& data) {
+    return to_time_point<Duration>(data.timestamp);
+}
+
+} // namespace tzu
+
+#endif // DATETIME_H
@@ -106,6 +106,22 @@ struct SingleValue {
     SingleValue(int64_t ts, double v) : timestamp(ts), value(v) {}
 };
 
+/**
+ * Represents a corporate action taking effect at `timestamp` (the
+ * ex-date). `split` is the number of new shares per old share (e.g.
+ * 2.0 for a 2-for-1 split, 1.0 for no split), and `dividend` is the
+ * cash amount paid per share, in the prices of the bar before the
+ * ex-date (0.0 for no dividend).
+ */
+struct CorporateAction {
+    int64_t timestamp;
+    double split = 1.0;
+    double dividend = 0.0;
+    CorporateAction() = default;
+    CorporateAction(int64_t ts, double s, double d)
+        : timestamp(ts), split(s), dividend(d) {}
+};
+
 /**
  * This enum is intended to communicate the type of data being
  * processed, to enable components to handle different data types
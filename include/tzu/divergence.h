@@ -0,0 +1,155 @@
+#ifndef DIVERGENCE_H
+#define DIVERGENCE_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <ostream>
+#include <vector>
+#include "defs.h"
+#include "swings.h"
+
+/**
+ * This header defines the detection of divergences between the price
+ * and an oscillator (e.g. RSI, the MACD histogram or CCI). A divergence
+ * happens when two consecutive swing highs, or swing lows, of the price
+ * move in one direction while the oscillator values at those bars move
+ * in the other, which is read as a weakening of the trend (regular
+ * divergence) or as a continuation (hidden divergence).
+ */
+
+namespace tzu {
+
+enum class DivergenceKind {
+    REGULAR_BULLISH,  // price lower low, oscillator higher low
+    REGULAR_BEARISH,  // price higher high, oscillator lower high
+    HIDDEN_BULLISH,   // price higher low, oscillator lower low
+    HIDDEN_BEARISH    // price lower high, oscillator higher high
+};
+
+inline const char* to_string(DivergenceKind kind) {
+    switch (kind) {
+        case DivergenceKind::REGULAR_BULLISH: return "REGULAR_BULLISH";
+        case DivergenceKind::REGULAR_BEARISH: return "REGULAR_BEARISH";
+        case DivergenceKind::HIDDEN_BULLISH: return "HIDDEN_BULLISH";
+        case DivergenceKind::HIDDEN_BEARISH: return "HIDDEN_BEARISH";
+    }
+    return "UNKNOWN";
+}
+
+/**
+ * A detected divergence. `timestamp` is the bar where it was confirmed,
+ * `lookback` bars after the second swing point, and `first` and
+ * `second` are the timestamps of the swing points compared.
+ */
+struct Divergence {
+    int64_t timestamp;
+    DivergenceKind kind;
+    int64_t first;
+    int64_t second;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Divergence& d) {
+    os << "timestamp:" << d.timestamp
+       << " divergence:" << to_string(d.kind)
+       << " first:" << d.first
+       << " second:" << d.second;
+    return os;
+}
+
+/**
+ * Detects divergences from a stream of prices and oscillator values.
+ * Swing points are found by a `SwingDetector`, by default with
+ * `lookback` bars on each side, so they are confirmed `lookback` bars
+ * late. Each swing point is compared with the previous one of the same
+ * type, using the oscillator values at those bars. Oscillator values
+ * that are NaN (warmup) skip the comparison.
+ */
+class DivergenceDetector {
+    struct Point {
+        int64_t timestamp;
+        double price;
+        double osc;
+    };
+    SwingDetector swings;
+    // oscillator values of the bars not yet confirmed as swing points
+    std::vector<double> oscs;
+    size_t pos = 0;
+    Point last_high;
+    Point last_low;
+    bool has_high = false;
+    bool has_low = false;
+    std::vector<Divergence> found;
+
+    void swing_high(int64_t now, const Point& p) {
+        if (has_high && !std::isnan(p.osc) && !std::isnan(last_high.osc)) {
+            if (p.price > last_high.price && p.osc < last_high.osc)
+                found.push_back({now, DivergenceKind::REGULAR_BEARISH,
+                        last_high.timestamp, p.timestamp});
+            else if (p.price < last_high.price && p.osc > last_high.osc)
+                found.push_back({now, DivergenceKind::HIDDEN_BEARISH,
+                        last_high.timestamp, p.timestamp});
+        }
+        last_high = p;
+        has_high = true;
+    }
+
+    void swing_low(int64_t now, const Point& p) {
+        if (has_low && !std::isnan(p.osc) && !std::isnan(last_low.osc)) {
+            if (p.price < last_low.price && p.osc > last_low.osc)
+                found.push_back({now, DivergenceKind::REGULAR_BULLISH,
+                        last_low.timestamp, p.timestamp});
+            else if (p.price > last_low.price && p.osc < last_low.osc)
+                found.push_back({now, DivergenceKind::HIDDEN_BULLISH,
+                        last_low.timestamp, p.timestamp});
+        }
+        last_low = p;
+        has_low = true;
+    }
+
+    bool add(bool confirmed, double osc) {
+        found.clear();
+        oscs[pos] = osc;
+        pos = (pos + 1) % oscs.size();
+        if (!confirmed) return false;
+        // `pos` is now the value of the bar `right` bars ago
+        for (const auto& s : swings.get()) {
+            Point p = {s.timestamp, s.price, oscs[pos]};
+            if (s.type == SwingType::HIGH) swing_high(s.confirmed, p);
+            else swing_low(s.confirmed, p);
+        }
+        return !found.empty();
+    }
+
+public:
+    explicit DivergenceDetector(size_t lookback)
+        : DivergenceDetector(SwingDetector(lookback, lookback)) {}
+
+    /**
+     * Detects the divergences at the swing points of `swings`, e.g.
+     * Bill Williams' fractals.
+     */
+    explicit DivergenceDetector(const SwingDetector& swings)
+        : swings(swings), oscs(swings.right() + 1, std::nan("")) {}
+
+    /**
+     * Adds a bar and the oscillator value at that bar. Returns true if
+     * divergences were confirmed, available with `get()`.
+     */
+    bool update(const Ohlcv& bar, double osc) {
+        return add(swings.update(bar), osc);
+    }
+
+    bool update(int64_t timestamp, double price, double osc) {
+        return add(swings.update(timestamp, price), osc);
+    }
+
+    /**
+     * The divergences confirmed by the last update.
+     */
+    const std::vector<Divergence>& get() const noexcept { return found; }
+};
+
+} // namespace tzu
+
+#endif // DIVERGENCE_H
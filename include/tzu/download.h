@@ -0,0 +1,501 @@
+#ifndef DOWNLOAD_H
+#define DOWNLOAD_H
+
+#include <algorithm>
+#include <chrono>
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <cstdlib>
+#include <functional>
+#include <sstream>
+#include <string>
+#include <thread>
+#include <vector>
+#include "defs.h"
+#include "connectors.h"
+#include "datetime.h"
+#include "feed.h"
+#include "transforms.h"
+
+/**
+ * This header defines downloaders of historical data from free
+ * sources, to get from zero to a backtest without external tools. As
+ * for the connectors, no network I/O is done here: requests are sent by
+ * the `HttpClient` given by the caller. Downloads taking many requests
+ * are paced with a `RateLimiter`, so they stay within the limits of
+ * the source.
+ *
+ * Daily bars are stamped at midnight UTC of their date, whatever the
+ * exchange, so series from different sources line up.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+/**
+ * The elements of a flat JSON array, without the quotes of strings.
+ */
+inline std::vector<std::string> json_values(const std::string& array) {
+    std::vector<std::string> out;
+    std::string current;
+    bool quoted = false;
+    for (char c : array) {
+        if (c == '"') {
+            quoted = !quoted;
+        } else if (quoted) {
+            current += c;
+        } else if (c == ',') {
+            out.push_back(current);
+            current.clear();
+        } else if (c != '[' && c != ']' && c != ' ' && c != '\n') {
+            current += c;
+        }
+    }
+    if (!current.empty() || !out.empty()) out.push_back(current);
+    return out;
+}
+
+/**
+ * Parses a response of the Binance klines endpoint, an array of arrays
+ * starting with the open time (in milliseconds), open, high, low,
+ * close and volume. Returns false if it is malformed.
+ */
+inline bool parse_binance_klines(const std::string& body,
+        std::vector<Ohlcv>& out, std::vector<int64_t>& open_times) {
+    size_t pos = body.find('[');
+    if (pos == std::string::npos) return false;
+    while (true) {
+        size_t start = body.find('[', pos + 1);
+        if (start == std::string::npos) return true;
+        size_t end = body.find(']', start);
+        if (end == std::string::npos) return false;
+        std::vector<std::string> v = json_values(body.substr(start,
+                    end - start + 1));
+        if (v.size() < 6) return false;
+        char* rest;
+        int64_t t = std::strtoll(v[0].c_str(), &rest, 10);
+        if (*rest != '\0') return false;
+        double fields[5];
+        for (size_t i = 0; i < 5; i++) {
+            fields[i] = std::strtod(v[i + 1].c_str(), &rest);
+            if (*rest != '\0') return false;
+        }
+        out.push_back(Ohlcv(t / 1000, fields[0], fields[1], fields[2],
+                    fields[3], fields[4]));
+        open_times.push_back(t);
+        pos = end;
+    }
+}
+
+/**
+ * The values of the flat array following `key`, starting the search at
+ * `from`, skipping arrays of objects. Returns false if there is none.
+ */
+inline bool json_array(const std::string& msg, const std::string& key,
+        std::vector<std::string>& out, size_t from = 0) {
+    std::string pattern = "\"" + key + "\"";
+    size_t pos = msg.find(pattern, from);
+    while (pos != std::string::npos) {
+        size_t p = msg.find_first_not_of(" \t\r\n", pos + pattern.size());
+        if (p != std::string::npos && msg[p] == ':') {
+            p = msg.find_first_not_of(" \t\r\n", p + 1);
+            size_t q = p == std::string::npos ? p
+                : msg.find_first_not_of(" \t\r\n", p + 1);
+            if (p != std::string::npos && msg[p] == '['
+                    && q != std::string::npos && msg[q] != '{') {
+                size_t end = msg.find(']', p);
+                if (end == std::string::npos) return false;
+                out = json_values(msg.substr(p, end - p + 1));
+                return true;
+            }
+        }
+        pos = msg.find(pattern, pos + pattern.size());
+    }
+    return false;
+}
+
+/**
+ * The object following `key`, as raw text, or an empty string.
+ */
+inline std::string json_object(const std::string& msg,
+        const std::string& key) {
+    size_t pos = msg.find("\"" + key + "\"");
+    if (pos == std::string::npos) return "";
+    size_t start = msg.find_first_not_of(" \t\r\n:",
+            pos + key.size() + 2);
+    if (start == std::string::npos || msg[start] != '{') return "";
+    std::vector<std::string> objects = json_objects(msg.substr(start));
+    return objects.empty() ? "" : objects[0];
+}
+
+inline bool to_double(const std::string& s, double& out) {
+    if (s.empty()) return false;
+    char* end;
+    out = std::strtod(s.c_str(), &end);
+    return *end == '\0' && std::isfinite(out);
+}
+
+inline int64_t day_of(int64_t timestamp) {
+    return bucket_start(timestamp, 86400);
+}
+
+inline int64_t steady_millis() {
+    return std::chrono::duration_cast<std::chrono::milliseconds>(
+            std::chrono::steady_clock::now().time_since_epoch()).count();
+}
+
+inline void sleep_millis(int64_t ms) {
+    std::this_thread::sleep_for(std::chrono::milliseconds(ms));
+}
+
+} // namespace detail
+
+/**
+ * The request weight limit of the Binance spot API: 6000 per minute,
+ * with 2 per klines request of up to 1000 klines, in milliseconds.
+ */
+inline RateLimiter binance_rate_limiter() {
+    RateLimiter limiter(INT64_MAX, 2.0);
+    limiter.add_limit(6000.0, 60000);
+    return limiter;
+}
+
+/**
+ * BinanceDownloader
+ *
+ * Downloads the klines of a symbol and interval (`1m`, `1h`, `1d`,
+ * etc.) from the Binance spot API, requesting them in pages of up to
+ * `page_size` klines, from 1 to the 1000 the API allows, paced by a
+ * rate limiter using `clock` and `sleep`, in milliseconds. Timestamps
+ * are in seconds, as with `BinanceKlineParser`. If the range reaches
+ * the present, the last kline may still be in progress.
+ *
+ * Example:
+ *
+ *     BinanceDownloader binance(http);
+ *     std::vector<Ohlcv> bars;
+ *     if (!binance.download("BTCUSDT", "1h", to_unix(2024, 1, 1),
+ *                 to_unix(2024, 7, 1), bars))
+ *         std::cerr << binance.last_error() << "\n";
+ */
+class BinanceDownloader {
+public:
+    using Clock = std::function<int64_t()>;
+    using Sleep = std::function<void(int64_t)>;
+
+private:
+    HttpClient http;
+    RateLimiter limiter;
+    Clock clock;
+    Sleep sleep;
+    std::string base_url;
+    size_t page_size;
+    size_t requests = 0;
+    std::string error;
+
+public:
+    explicit BinanceDownloader(HttpClient http,
+            const RateLimiter& limiter = binance_rate_limiter(),
+            Clock clock = detail::steady_millis,
+            Sleep sleep = detail::sleep_millis,
+            const std::string& base_url = "https://api.binance.com",
+            size_t page_size = 1000)
+        : http(http), limiter(limiter), clock(clock), sleep(sleep),
+          base_url(base_url),
+          page_size(std::min<size_t>(std::max<size_t>(page_size, 1), 1000)) {}
+
+    /**
+     * Appends to `out` the klines opening from `start` to before `end`,
+     * Unix times in seconds. Returns false, with the reason in
+     * `last_error`, if a request fails; the klines of the pages already
+     * received are kept.
+     */
+    bool download(const std::string& symbol, const std::string& interval,
+            int64_t start, int64_t end, std::vector<Ohlcv>& out) {
+        error.clear();
+        int64_t from = start * 1000;
+        int64_t to = end * 1000 - 1;
+        while (from <= to) {
+            int64_t wait = limiter.acquire(clock(), "klines");
+            if (wait < 0) {
+                error = "request shed by the rate limiter";
+                return false;
+            }
+            if (wait > 0) sleep(wait);
+            HttpRequest req;
+            req.method = "GET";
+            req.url = base_url + "/api/v3/klines?symbol=" + symbol
+                + "&interval=" + interval
+                + "&startTime=" + std::to_string(from)
+                + "&endTime=" + std::to_string(to)
+                + "&limit=" + std::to_string(page_size);
+            HttpResponse res;
+            requests++;
+            if (!http(req, res)) {
+                error = "GET " + req.url + ": no response";
+                return false;
+            }
+            if (res.status != 200) {
+                std::string message;
+                detail::json_field(res.body, "msg", message);
+                error = "GET " + req.url + ": HTTP "
+                    + std::to_string(res.status) + " " + message;
+                return false;
+            }
+            std::vector<Ohlcv> page;
+            std::vector<int64_t> open_times;
+            if (!detail::parse_binance_klines(res.body, page, open_times)) {
+                error = "GET " + req.url + ": malformed response";
+                return false;
+            }
+            out.insert(out.end(), page.begin(), page.end());
+            if (page.size() < page_size || open_times.back() < from) break;
+            from = open_times.back() + 1;
+        }
+        return true;
+    }
+
+    /**
+     * Requests sent so far.
+     */
+    size_t num_requests() const noexcept { return requests; }
+
+    /**
+     * Reason of the last failed download.
+     */
+    const std::string& last_error() const noexcept { return error; }
+};
+
+/**
+ * Which corporate actions the prices of a download are adjusted for:
+ * none (the prices actually traded), splits, or splits and dividends,
+ * as `back_adjust` does.
+ */
+enum class PriceAdjustment {
+    NONE,
+    SPLITS,
+    ALL
+};
+
+/**
+ * YahooDownloader
+ *
+ * Downloads the daily bars of a symbol from the chart API of Yahoo
+ * Finance, with its splits and dividends. Yahoo reports prices adjusted
+ * for splits; they are converted to the prices actually traded and
+ * then adjusted as asked, so the adjustment is the same as with
+ * `back_adjust`. The actions can also be retrieved, with dividends in
+ * the prices actually traded, e.g. to adjust unadjusted bars as they
+ * are replayed with `ForwardAdjust`. Days without prices are skipped.
+ *
+ * Example:
+ *
+ *     YahooDownloader yahoo(http);
+ *     std::vector<Ohlcv> bars;
+ *     if (!yahoo.download("AAPL", to_unix(2020, 1, 1), to_unix(2024, 1, 1),
+ *                 bars, PriceAdjustment::ALL))
+ *         std::cerr << yahoo.last_error() << "\n";
+ */
+class YahooDownloader {
+    HttpClient http;
+    std::string base_url;
+    std::string error;
+
+    static void parse_actions(const std::string& body, const char* key,
+            std::vector<CorporateAction>& out) {
+        std::string events = detail::json_object(body, key);
+        if (events.size() < 2) return;
+        for (const auto& e : detail::json_objects(
+                    events.substr(1, events.size() - 2))) {
+            int64_t date;
+            double a = 0.0, b = 0.0;
+            if (!detail::json_integer(e, "date", date)) continue;
+            CorporateAction action(detail::day_of(date), 1.0, 0.0);
+            if (detail::json_number(e, "numerator", a)
+                    && detail::json_number(e, "denominator", b) && b > 0.0)
+                action.split = a / b;
+            else if (detail::json_number(e, "amount", a))
+                action.dividend = a;
+            else
+                continue;
+            out.push_back(action);
+        }
+    }
+
+public:
+    explicit YahooDownloader(HttpClient http,
+            const std::string& base_url = "https://query1.finance.yahoo.com")
+        : http(http), base_url(base_url) {}
+
+    /**
+     * Appends to `out` the bars from `start` to before `end`, Unix times
+     * in seconds, and to `actions`, if given, the splits and dividends
+     * of the period, sorted by date. Returns false, with the reason in
+     * `last_error`, if the request fails.
+     */
+    bool download(const std::string& symbol, int64_t start, int64_t end,
+            std::vector<Ohlcv>& out,
+            PriceAdjustment adjustment = PriceAdjustment::ALL,
+            std::vector<CorporateAction>* actions = nullptr) {
+        error.clear();
+        HttpRequest req;
+        req.method = "GET";
+        req.url = base_url + "/v8/finance/chart/" + symbol
+            + "?period1=" + std::to_string(start)
+            + "&period2=" + std::to_string(end)
+            + "&interval=1d&events=div%7Csplit";
+        req.headers.push_back(std::make_pair("User-Agent", "tzutrader"));
+        HttpResponse res;
+        if (!http(req, res)) {
+            error = "GET " + req.url + ": no response";
+            return false;
+        }
+        if (res.status != 200) {
+            std::string message;
+            detail::json_field(res.body, "description", message);
+            error = "GET " + req.url + ": HTTP " + std::to_string(res.status)
+                + " " + message;
+            return false;
+        }
+        std::vector<std::string> t, o, h, l, c, v;
+        size_t quote = res.body.find("\"quote\"");
+        if (!detail::json_array(res.body, "timestamp", t)
+                || quote == std::string::npos
+                || !detail::json_array(res.body, "open", o, quote)
+                || !detail::json_array(res.body, "high", h, quote)
+                || !detail::json_array(res.body, "low", l, quote)
+                || !detail::json_array(res.body, "close", c, quote)
+                || !detail::json_array(res.body, "volume", v, quote)
+                || o.size() != t.size() || h.size() != t.size()
+                || l.size() != t.size() || c.size() != t.size()
+                || v.size() != t.size()) {
+            std::string message;
+            detail::json_field(res.body, "description", message);
+            error = "GET " + req.url + ": no prices " + message;
+            return false;
+        }
+        std::vector<CorporateAction> events;
+        parse_actions(res.body, "splits", events);
+        parse_actions(res.body, "dividends", events);
+        sort_actions(events);
+        // Yahoo's prices and dividends are adjusted for later splits
+        auto later_splits = [&events](int64_t ts) {
+            double factor = 1.0;
+            for (const auto& e : events)
+                if (e.timestamp > ts) factor *= e.split;
+            return factor;
+        };
+        for (auto& e : events)
+            e.dividend *= later_splits(e.timestamp);
+        std::vector<Ohlcv> bars;
+        for (size_t i = 0; i < t.size(); i++) {
+            double ts, open, high, low, close, volume;
+            if (!detail::to_double(t[i], ts) || !detail::to_double(o[i], open)
+                    || !detail::to_double(h[i], high)
+                    || !detail::to_double(l[i], low)
+                    || !detail::to_double(c[i], close))
+                continue;
+            if (!detail::to_double(v[i], volume)) volume = 0.0;
+            int64_t day = detail::day_of(static_cast<int64_t>(ts));
+            double f = later_splits(day);
+            bars.push_back(Ohlcv(day, open * f, high * f, low * f, close * f,
+                        volume / f));
+        }
+        if (adjustment != PriceAdjustment::NONE) {
+            std::vector<CorporateAction> applied;
+            for (const auto& e : events)
+                if (adjustment == PriceAdjustment::ALL || e.split != 1.0)
+                    applied.push_back(CorporateAction(e.timestamp, e.split,
+                                adjustment == PriceAdjustment::ALL
+                                ? e.dividend : 0.0));
+            back_adjust(bars, applied);
+        }
+        out.insert(out.end(), bars.begin(), bars.end());
+        if (actions)
+            actions->insert(actions->end(), events.begin(), events.end());
+        return true;
+    }
+
+    /**
+     * Reason of the last failed download.
+     */
+    const std::string& last_error() const noexcept { return error; }
+};
+
+/**
+ * StooqDownloader
+ *
+ * Downloads the daily bars of a symbol from Stooq, as CSV. Symbols
+ * have a market suffix, e.g. `aapl.us`. Stooq prices are adjusted for
+ * splits and dividends.
+ */
+class StooqDownloader {
+    HttpClient http;
+    std::string base_url;
+    std::string error;
+
+public:
+    explicit StooqDownloader(HttpClient http,
+            const std::string& base_url = "https://stooq.com")
+        : http(http), base_url(base_url) {}
+
+    /**
+     * Appends to `out` the bars from `start` to before `end`, Unix times
+     * in seconds. Returns false, with the reason in `last_error`, if the
+     * request fails or returns no data.
+     */
+    bool download(const std::string& symbol, int64_t start, int64_t end,
+            std::vector<Ohlcv>& out) {
+        error.clear();
+        HttpRequest req;
+        req.method = "GET";
+        req.url = base_url + "/q/d/l/?s=" + symbol
+            + "&d1=" + format_time(start, "%Y%m%d")
+            + "&d2=" + format_time(end - 1, "%Y%m%d") + "&i=d";
+        HttpResponse res;
+        if (!http(req, res)) {
+            error = "GET " + req.url + ": no response";
+            return false;
+        }
+        if (res.status != 200) {
+            error = "GET " + req.url + ": HTTP " + std::to_string(res.status);
+            return false;
+        }
+        std::istringstream is(res.body);
+        std::string line;
+        if (!std::getline(is, line) || line.compare(0, 4, "Date") != 0) {
+            error = "GET " + req.url + ": no data";
+            return false;
+        }
+        while (std::getline(is, line)) {
+            if (!line.empty() && line.back() == '\r') line.pop_back();
+            std::vector<std::string> v;
+            std::stringstream fields(line);
+            std::string field;
+            while (std::getline(fields, field, ',')) v.push_back(field);
+            int64_t ts;
+            double open, high, low, close, volume = 0.0;
+            if (v.size() < 5 || !parse_time(v[0].c_str(), "%Y-%m-%d", ts)
+                    || !detail::to_double(v[1], open)
+                    || !detail::to_double(v[2], high)
+                    || !detail::to_double(v[3], low)
+                    || !detail::to_double(v[4], close))
+                continue;
+            if (v.size() > 5) detail::to_double(v[5], volume);
+            if (ts >= start && ts < end)
+                out.push_back(Ohlcv(ts, open, high, low, close, volume));
+        }
+        return true;
+    }
+
+    /**
+     * Reason of the last failed download.
+     */
+    const std::string& last_error() const noexcept { return error; }
+};
+
+} // namespace tzu
+
+#endif // DOWNLOAD_H
@@ -0,0 +1,169 @@
+#ifndef DYNAMIC_H
+#define DYNAMIC_H
+
+#include <cmath>
+#include <cstddef>
+#include <istream>
+#include <memory>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "ehlers.h"
+#include "indicators.h"
+
+/**
+ * This header defines `DynIndicator`, which hides the type of an
+ * indicator behind a common one, so indicators of different kinds,
+ * chosen at run time (e.g. from a configuration file), can be kept in
+ * the same container. The price is a virtual call per update, which
+ * the indicators used directly don't pay.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+/**
+ * Main value of the output of an indicator, as reported by
+ * `DynIndicator`.
+ */
+inline double main_output(double value) { return value; }
+inline double main_output(float value) { return value; }
+template <int Decimals>
+double main_output(Fixed<Decimals> value) {
+    return NumericTraits<Fixed<Decimals>>::is_missing(value)
+        ? std::nan("") : static_cast<double>(value);
+}
+inline double main_output(const Ohlcv& bar) { return bar.close; }
+inline double main_output(const MACDResult& r) { return r.macd; }
+inline double main_output(const LinRegResult& r) { return r.value; }
+inline double main_output(const ADXResult& r) { return r.adx; }
+inline double main_output(const ElderRayResult& r) { return r.bull; }
+inline double main_output(const AroonResult& r) { return r.oscillator; }
+inline double main_output(const StochRSIResult& r) { return r.k; }
+inline double main_output(const PivotLevels& r) { return r.pp; }
+inline double main_output(const MAMAResult& r) { return r.mama; }
+
+} // namespace detail
+
+/**
+ * DynIndicator
+ *
+ * Holds any indicator and feeds it with bars: indicators taking bars
+ * get the whole bar, and the others the given field. `update` and
+ * `get` return the main value of the output, e.g. the MACD line or the
+ * ADX. The other outputs are available by name with `get_output`, or
+ * by asking for the type of the indicator with `target`, as with
+ * `std::function`. Copies are deep.
+ *
+ * The indicator held must define `required_warmup` and `serialize`,
+ * as the indicators of the library do, since they back
+ * `required_warmup`, `save_state` and `load_state`. A moved-from
+ * `DynIndicator` holds nothing: it returns NaN, is never ready and
+ * fails to save or load its state.
+ *
+ * Example:
+ *
+ *     std::vector<DynIndicator> inds;
+ *     inds.push_back(DynIndicator(RSI(14)));
+ *     inds.push_back(DynIndicator(MACD(12, 26, 9)));
+ *     for (auto& ind : inds) ind.update(bar);
+ */
+class DynIndicator {
+    struct Concept {
+        virtual ~Concept() {}
+        virtual Concept* clone() const = 0;
+        virtual double update(const Ohlcv& bar) = 0;
+        virtual double get() const = 0;
+        virtual void reset() = 0;
+        virtual bool is_ready() const = 0;
+        virtual size_t required_warmup() const = 0;
+        virtual bool save_state(std::ostream& os) const = 0;
+        virtual bool load_state(std::istream& is) = 0;
+        virtual std::vector<std::string> outputs() const = 0;
+        virtual bool get_output(const std::string& name,
+                double& value) const = 0;
+    };
+
+    template <class Ind>
+    struct Model: Concept {
+        Ind ind;
+        OhlcvField field;
+        Model(const Ind& ind, OhlcvField field): ind(ind), field(field) {}
+        Concept* clone() const override { return new Model(*this); }
+        double update(const Ohlcv& bar) override {
+            return detail::main_output(ind.update(
+                    detail::from_bar<typename Ind::input_type>(bar, field)));
+        }
+        double get() const override { return detail::main_output(ind.get()); }
+        void reset() override { ind.reset(); }
+        bool is_ready() const override { return ind.is_ready(); }
+        size_t required_warmup() const override {
+            return ind.required_warmup();
+        }
+        bool save_state(std::ostream& os) const override {
+            return ind.save_state(os);
+        }
+        bool load_state(std::istream& is) override {
+            return ind.load_state(is);
+        }
+        std::vector<std::string> outputs() const override {
+            return ind.outputs();
+        }
+        bool get_output(const std::string& name,
+                double& value) const override {
+            return ind.get_output(name, value);
+        }
+    };
+
+    std::unique_ptr<Concept> impl;
+
+public:
+    template <class Ind>
+    explicit DynIndicator(const Ind& ind, OhlcvField field = OhlcvField::CLOSE)
+        : impl(new Model<Ind>(ind, field)) {}
+
+    DynIndicator(const DynIndicator& other)
+        : impl(other.impl ? other.impl->clone() : nullptr) {}
+    DynIndicator(DynIndicator&& other) = default;
+    DynIndicator& operator=(const DynIndicator& other) {
+        if (this != &other)
+            impl.reset(other.impl ? other.impl->clone() : nullptr);
+        return *this;
+    }
+    DynIndicator& operator=(DynIndicator&& other) = default;
+
+    double update(const Ohlcv& bar) {
+        return impl ? impl->update(bar) : std::nan("");
+    }
+    double get() const { return impl ? impl->get() : std::nan(""); }
+    void reset() { if (impl) impl->reset(); }
+    bool is_ready() const { return impl && impl->is_ready(); }
+    size_t required_warmup() const {
+        return impl ? impl->required_warmup() : 0;
+    }
+    bool save_state(std::ostream& os) const {
+        return impl && impl->save_state(os);
+    }
+    bool load_state(std::istream& is) { return impl && impl->load_state(is); }
+    std::vector<std::string> outputs() const {
+        return impl ? impl->outputs() : std::vector<std::string>();
+    }
+    bool get_output(const std::string& name, double& value) const {
+        return impl && impl->get_output(name, value);
+    }
+
+    /**
+     * The indicator held, or null if it is not an `Ind`.
+     */
+    template <class Ind>
+    const Ind* target() const noexcept {
+        const Model<Ind>* model = dynamic_cast<const Model<Ind>*>(impl.get());
+        return model ? &model->ind : nullptr;
+    }
+};
+
+} // namespace tzu
+
+#endif // DYNAMIC_H
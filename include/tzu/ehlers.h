@@ -0,0 +1,393 @@
+#ifndef EHLERS_H
+#define EHLERS_H
+
+#include <algorithm>
+#include <cmath>
+#include <cstddef>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines John Ehlers' indicators, which apply signal
+ * processing to prices: filters removing the noise or the trend with
+ * less lag than moving averages, the MESA adaptive moving average,
+ * following the dominant cycle, and the Fisher transform. Like `KAMA`,
+ * they keep a few previous values and outputs as state.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+constexpr double pi = 3.14159265358979323846;
+
+/**
+ * Two-pole high-pass filter, removing the cycles longer than `period`.
+ * Its output is zero for the first two values.
+ */
+class HighPass {
+    double c0;
+    double c1;
+    double c2;
+    double x1 = 0.0;
+    double x2 = 0.0;
+    double hp1 = 0.0;
+    double hp2 = 0.0;
+    size_t count = 0;
+public:
+    explicit HighPass(size_t period) {
+        double w = 0.707 * 2.0 * pi / static_cast<double>(period);
+        double alpha = (std::cos(w) + std::sin(w) - 1.0) / std::cos(w);
+        c0 = (1.0 - alpha / 2.0) * (1.0 - alpha / 2.0);
+        c1 = 2.0 * (1.0 - alpha);
+        c2 = -(1.0 - alpha) * (1.0 - alpha);
+    }
+    double update(double x) {
+        double hp = count < 2 ? 0.0
+            : c0 * (x - 2.0 * x1 + x2) + c1 * hp1 + c2 * hp2;
+        if (count < 2) count++;
+        x2 = x1;
+        x1 = x;
+        hp2 = hp1;
+        hp1 = hp;
+        return hp;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(x1, x2, hp1, hp2, count);
+    }
+    void reset() {
+        x1 = x2 = hp1 = hp2 = 0.0;
+        count = 0;
+    }
+};
+
+} // namespace detail
+
+/**
+ * Super Smoother (SuperSmoother)
+ *
+ * Two-pole Butterworth low-pass filter removing the cycles shorter
+ * than `period`, with much less lag than a moving average smoothing
+ * as much:
+ *
+ * a = exp(-1.414 * pi / period)
+ * c2 = 2 * a * cos(1.414 * pi / period)
+ * c3 = -a^2
+ * c1 = 1 - c2 - c3
+ * SS = c1 * (Value + Value_prev) / 2 + c2 * SS_prev + c3 * SS_prev2
+ *
+ * The first two outputs are the values themselves.
+ */
+class SuperSmoother: public Indicator<SuperSmoother, double, double> {
+    double data = std::nan("");
+    double c1;
+    double c2;
+    double c3;
+    double x1 = 0.0;
+    double prev = 0.0;
+    size_t count = 0;
+public:
+    explicit SuperSmoother(size_t period) {
+        double w = 1.414 * detail::pi / static_cast<double>(period);
+        double a = std::exp(-w);
+        c2 = 2.0 * a * std::cos(w);
+        c3 = -a * a;
+        c1 = 1.0 - c2 - c3;
+    }
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(double value) {
+        double out = count < 2 ? value
+            : c1 * (value + x1) / 2.0 + c2 * data + c3 * prev;
+        if (count < 2) count++;
+        x1 = value;
+        prev = std::isnan(data) ? value : data;
+        data = out;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, x1, prev, count);
+    }
+    void reset_state() {
+        data = std::nan("");
+        x1 = prev = 0.0;
+        count = 0;
+    }
+};
+
+/**
+ * Roofing Filter (Roofing)
+ *
+ * Band-pass filter keeping the cycles between `lp_period` and
+ * `hp_period` bars: a two-pole high-pass filter removes the trend, and
+ * a `SuperSmoother` the noise. The output oscillates around zero, as
+ * an input to oscillators such as the stochastic or the RSI free of
+ * both. It is zero for the first two values.
+ */
+class Roofing: public Indicator<Roofing, double, double> {
+    double data = std::nan("");
+    detail::HighPass highpass;
+    SuperSmoother smoother;
+public:
+    explicit Roofing(size_t hp_period = 48, size_t lp_period = 10)
+        : highpass(hp_period), smoother(lp_period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(double value) {
+        data = smoother.update(highpass.update(value));
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, highpass, smoother);
+    }
+    void reset_state() {
+        data = std::nan("");
+        highpass.reset();
+        smoother.reset();
+    }
+};
+
+/**
+ * Decycler (Decycler)
+ *
+ * The value minus its two-pole high-pass filter, which leaves the
+ * trend once the cycles shorter than `period` bars are removed, with
+ * almost no lag. Prices above the decycler are in an uptrend. The
+ * first two outputs are the values themselves.
+ */
+class Decycler: public Indicator<Decycler, double, double> {
+    double data = std::nan("");
+    detail::HighPass highpass;
+public:
+    explicit Decycler(size_t period = 125) : highpass(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(double value) {
+        data = value - highpass.update(value);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, highpass);
+    }
+    void reset_state() {
+        data = std::nan("");
+        highpass.reset();
+    }
+};
+
+/**
+ * Fisher Transform (FisherTransform)
+ *
+ * Turns the position of the median price (high + low) / 2 within its
+ * range over the last `period` bars into a nearly Gaussian variable,
+ * so turning points show as sharp peaks:
+ *
+ * v = 0.66 * ((mid - lowest) / (highest - lowest) - 0.5) + 0.67 * v_prev
+ * Fisher = 0.5 * ln((1 + v) / (1 - v)) + 0.5 * Fisher_prev
+ *
+ * with v capped to +/-0.999. Its previous value, `trigger()`, is the
+ * usual signal line. Returns NaN until `period` bars have been added.
+ */
+class FisherTransform: public Indicator<FisherTransform, Ohlcv, double> {
+    double data = std::nan("");
+    double trigger_ = std::nan("");
+    detail::RollingMax highest;
+    detail::RollingMin lowest;
+    double v = 0.0;
+public:
+    explicit FisherTransform(size_t period = 10)
+        : highest(period), lowest(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return highest.period(); }
+    double update(const Ohlcv& bar) {
+        double mid = (bar.high + bar.low) / 2.0;
+        highest.push(mid);
+        lowest.push(mid);
+        if (!highest.full()) return data;
+        double range = highest.value() - lowest.value();
+        double x = range > 0.0 ? (mid - lowest.value()) / range - 0.5 : 0.0;
+        v = 0.66 * x + 0.67 * v;
+        if (v > 0.999) v = 0.999;
+        if (v < -0.999) v = -0.999;
+        double prev = std::isnan(data) ? 0.0 : data;
+        trigger_ = data;
+        data = 0.5 * std::log((1.0 + v) / (1.0 - v)) + 0.5 * prev;
+        return data;
+    }
+    /**
+     * The previous value, NaN before the second one.
+     */
+    double trigger() const noexcept { return trigger_; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, trigger_, highest, lowest, v);
+    }
+    void reset_state() {
+        data = trigger_ = std::nan("");
+        highest.clear();
+        lowest.clear();
+        v = 0.0;
+    }
+};
+
+/**
+ * MESA Adaptive Moving Average Result (MAMAResult)
+ *
+ * Holds the MAMA and the FAMA, following it.
+ */
+struct MAMAResult {
+    double mama;
+    double fama;
+};
+
+/**
+ * MESA Adaptive Moving Average (MAMA)
+ *
+ * EMA whose smoothing factor follows the rate of change of the phase
+ * of the dominant cycle, measured with a Hilbert transform: fast, up
+ * to `fast_limit`, when the phase jumps at the start of a move, and
+ * slow, down to `slow_limit`, while it rotates steadily in cycles. The
+ * Following Adaptive Moving Average (FAMA) is an EMA of the MAMA with
+ * half its factor, so their crossings signal trend changes.
+ *
+ * alpha = fast_limit / delta phase, within [slow_limit, fast_limit]
+ * MAMA = alpha * Value + (1 - alpha) * MAMA_prev
+ * FAMA = alpha / 2 * MAMA + (1 - alpha / 2) * FAMA_prev
+ *
+ * It is usually fed with the median price (high + low) / 2. The
+ * dominant cycle, from 6 to 50 bars, is available with `period()`.
+ * Returns NaN until 32 values have been added, as the transform needs
+ * them to settle.
+ */
+class MAMA: public Indicator<MAMA, double, MAMAResult> {
+    MAMAResult data = {std::nan(""), std::nan("")};
+    double fast;
+    double slow;
+    // previous values, the latest first
+    std::vector<double> price;
+    std::vector<double> smooth;
+    std::vector<double> detrender;
+    std::vector<double> q1;
+    std::vector<double> i1;
+    double i2 = 0.0;
+    double q2 = 0.0;
+    double re = 0.0;
+    double im = 0.0;
+    double cycle = 0.0;
+    double phase = 0.0;
+    double mama = 0.0;
+    double fama = 0.0;
+    size_t count = 0;
+
+    static void push(std::vector<double>& values, double value) {
+        for (size_t i = values.size() - 1; i > 0; i--)
+            values[i] = values[i - 1];
+        values[0] = value;
+    }
+
+    double hilbert(const std::vector<double>& h) const {
+        return (0.0962 * h[0] + 0.5769 * h[2] - 0.5769 * h[4]
+                - 0.0962 * h[6]) * (0.075 * cycle + 0.54);
+    }
+
+public:
+    explicit MAMA(double fast_limit = 0.5, double slow_limit = 0.05)
+        : fast(fast_limit), slow(slow_limit), price(4, 0.0),
+          smooth(7, 0.0), detrender(7, 0.0), q1(7, 0.0), i1(7, 0.0) {}
+    MAMAResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 32; }
+    MAMAResult update(double value) {
+        count++;
+        push(price, value);
+        push(smooth, (4.0 * price[0] + 3.0 * price[1] + 2.0 * price[2]
+                    + price[3]) / 10.0);
+        push(detrender, hilbert(smooth));
+        push(q1, hilbert(detrender));
+        push(i1, detrender[3]);
+        // advance the phase of the in-phase and quadrature components
+        double ji = hilbert(i1);
+        double jq = hilbert(q1);
+        double i2n = 0.2 * (i1[0] - jq) + 0.8 * i2;
+        double q2n = 0.2 * (q1[0] + ji) + 0.8 * q2;
+        re = 0.2 * (i2n * i2 + q2n * q2) + 0.8 * re;
+        im = 0.2 * (i2n * q2 - q2n * i2) + 0.8 * im;
+        i2 = i2n;
+        q2 = q2n;
+        double p = cycle;
+        if (im != 0.0 && re != 0.0) p = 2.0 * detail::pi / std::atan(im / re);
+        p = std::fmin(p, 1.5 * cycle);
+        p = std::fmax(p, 0.67 * cycle);
+        p = std::fmin(std::fmax(p, 6.0), 50.0);
+        cycle = 0.2 * p + 0.8 * cycle;
+        double ph = i1[0] != 0.0 ? std::atan(q1[0] / i1[0]) * 180.0 / detail::pi
+            : phase;
+        double delta = std::fmax(phase - ph, 1.0);
+        phase = ph;
+        double alpha = std::fmin(std::fmax(fast / delta, slow), fast);
+        if (count == 1) {
+            mama = fama = value;
+        } else {
+            mama = alpha * value + (1.0 - alpha) * mama;
+            fama = 0.5 * alpha * mama + (1.0 - 0.5 * alpha) * fama;
+        }
+        if (count >= required_warmup()) data = {mama, fama};
+        return data;
+    }
+    /**
+     * Period of the dominant cycle, in bars.
+     */
+    double period() const noexcept { return cycle; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, price, smooth, detrender, q1, i1, i2, q2, re, im, cycle,
+                phase, mama, fama, count);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan("")};
+        std::fill(price.begin(), price.end(), 0.0);
+        std::fill(smooth.begin(), smooth.end(), 0.0);
+        std::fill(detrender.begin(), detrender.end(), 0.0);
+        std::fill(q1.begin(), q1.end(), 0.0);
+        std::fill(i1.begin(), i1.end(), 0.0);
+        i2 = q2 = re = im = cycle = phase = mama = fama = 0.0;
+        count = 0;
+    }
+};
+
+template <>
+struct OutputCheck<MAMAResult> {
+    static IndicatorStatus check(const MAMAResult& r) {
+        return OutputCheck<double>::check(r.mama);
+    }
+};
+
+template <>
+struct Outputs<MAMAResult> {
+    static std::vector<std::string> names() {
+        return {"mama", "fama"};
+    }
+    static std::vector<double> values(const MAMAResult& r) {
+        return {r.mama, r.fama};
+    }
+};
+
+inline std::ostream& operator<<(std::ostream& os, const MAMAResult& result) {
+    os << "mama:" << result.mama << " fama:" << result.fama;
+    return os;
+}
+
+template <class Archive>
+void serialize(Archive& ar, MAMAResult& r) {
+    ar(r.mama, r.fama);
+}
+
+} // namespace tzu
+
+#endif // EHLERS_H
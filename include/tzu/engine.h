@@ -0,0 +1,257 @@
+#ifndef ENGINE_H
+#define ENGINE_H
+
+#include <atomic>
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <map>
+#include <ostream>
+#include <string>
+#include <thread>
+#include <utility>
+#include <vector>
+#include "defs.h"
+#include "dynamic.h"
+
+/**
+ * This header defines an engine keeping the same set of indicators for
+ * each symbol of a universe, e.g. to scan hundreds of tickers in real
+ * time. Bars are routed by symbol to the indicators of that symbol,
+ * and the latest values across the universe are available as
+ * snapshots. Symbols are independent, so a batch of bars can be
+ * processed in parallel, one symbol per thread at a time, keeping the
+ * order of the bars of each symbol.
+ */
+
+namespace tzu {
+
+/**
+ * A bar of a symbol, as routed by the `IndicatorEngine`.
+ */
+struct SymbolBar {
+    std::string symbol;
+    Ohlcv bar;
+};
+
+/**
+ * Latest values of the indicators of a symbol, by indicator name, and
+ * the timestamp of the last bar received.
+ */
+struct SymbolSnapshot {
+    std::string symbol;
+    int64_t timestamp = 0;
+    std::map<std::string, double> values;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const SymbolSnapshot& s) {
+    os << "symbol:" << s.symbol << " timestamp:" << s.timestamp;
+    for (const auto& v : s.values)
+        os << " " << v.first << ":" << v.second;
+    return os;
+}
+
+/**
+ * IndicatorEngine
+ *
+ * Keeps a copy of each indicator added with `add` for every symbol,
+ * created when its first bar arrives. Indicators are type-erased with
+ * `DynIndicator`, so the set is built at run time, and each one is
+ * fed with the whole bar or one of its fields. Bars not newer than the
+ * previous bar of their symbol are rejected, so a replayed or out of
+ * order message doesn't corrupt the indicators.
+ *
+ * Example:
+ *
+ *     IndicatorEngine engine;
+ *     engine.add("rsi", RSI(14));
+ *     engine.add("sma", SMA(200));
+ *     engine.update("AAPL", bar);
+ *     for (const auto& s : engine.snapshot())
+ *         if (s.values.at("rsi") < 30.0) std::cout << s << "\n";
+ */
+class IndicatorEngine {
+    struct SymbolState {
+        std::vector<DynIndicator> inds;
+        int64_t timestamp = INT64_MIN;
+    };
+    std::vector<std::string> names;
+    std::vector<DynIndicator> prototypes;
+    std::map<std::string, SymbolState> states;
+
+    SymbolState& state(const std::string& symbol) {
+        auto it = states.find(symbol);
+        if (it == states.end()) {
+            it = states.emplace(symbol, SymbolState()).first;
+            it->second.inds = prototypes;
+        }
+        return it->second;
+    }
+
+    static bool feed(SymbolState& st, const Ohlcv& bar) {
+        if (bar.timestamp <= st.timestamp) return false;
+        st.timestamp = bar.timestamp;
+        for (auto& ind : st.inds) ind.update(bar);
+        return true;
+    }
+
+    const DynIndicator* find(const std::string& symbol,
+            const std::string& name) const {
+        auto it = states.find(symbol);
+        if (it == states.end()) return nullptr;
+        for (size_t i = 0; i < names.size(); i++)
+            if (names[i] == name) return &it->second.inds[i];
+        return nullptr;
+    }
+
+public:
+    /**
+     * Adds an indicator for every symbol, including those already
+     * seen, which get a fresh copy. Returns false if the name is
+     * already taken.
+     */
+    bool add(const std::string& name, const DynIndicator& ind) {
+        for (const auto& n : names)
+            if (n == name) return false;
+        names.push_back(name);
+        prototypes.push_back(ind);
+        for (auto& st : states) st.second.inds.push_back(ind);
+        return true;
+    }
+
+    template <class Ind>
+    bool add(const std::string& name, const Ind& ind,
+            OhlcvField field = OhlcvField::CLOSE) {
+        return add(name, DynIndicator(ind, field));
+    }
+
+    /**
+     * Feeds a bar to the indicators of its symbol. Returns false if it
+     * was rejected for not being newer than the previous one.
+     */
+    bool update(const std::string& symbol, const Ohlcv& bar) {
+        return feed(state(symbol), bar);
+    }
+
+    /**
+     * Feeds a batch of bars, in order within each symbol, using up to
+     * `threads` threads. Returns the number of bars accepted.
+     */
+    size_t update(const std::vector<SymbolBar>& bars, size_t threads = 1) {
+        // the states are created first, so workers only touch their own
+        std::vector<std::pair<SymbolState*, std::vector<const Ohlcv*>>> work;
+        std::map<std::string, size_t> index;
+        for (const auto& b : bars) {
+            auto it = index.find(b.symbol);
+            if (it == index.end()) {
+                it = index.emplace(b.symbol, work.size()).first;
+                work.push_back(std::make_pair(&state(b.symbol),
+                            std::vector<const Ohlcv*>()));
+            }
+            work[it->second].second.push_back(&b.bar);
+        }
+        std::atomic<size_t> next(0);
+        std::atomic<size_t> accepted(0);
+        auto run = [&]() {
+            for (size_t i = next++; i < work.size(); i = next++) {
+                size_t n = 0;
+                for (const Ohlcv* bar : work[i].second)
+                    if (feed(*work[i].first, *bar)) n++;
+                accepted += n;
+            }
+        };
+        if (threads <= 1 || work.size() <= 1) {
+            run();
+        } else {
+            std::vector<std::thread> pool;
+            for (size_t t = 0; t < threads && t < work.size(); t++)
+                pool.push_back(std::thread(run));
+            for (auto& thread : pool)
+                thread.join();
+        }
+        return accepted;
+    }
+
+    /**
+     * Main value of an indicator of a symbol, or NaN if either is
+     * unknown.
+     */
+    double get(const std::string& symbol, const std::string& name) const {
+        const DynIndicator* ind = find(symbol, name);
+        return ind ? ind->get() : std::nan("");
+    }
+
+    /**
+     * One of the outputs of an indicator of a symbol, e.g. `signal` for
+     * MACD. Returns false if any of them is unknown.
+     */
+    bool get_output(const std::string& symbol, const std::string& name,
+            const std::string& output, double& value) const {
+        const DynIndicator* ind = find(symbol, name);
+        return ind && ind->get_output(output, value);
+    }
+
+    /**
+     * The indicator of a symbol, or null if either is unknown.
+     */
+    const DynIndicator* indicator(const std::string& symbol,
+            const std::string& name) const {
+        return find(symbol, name);
+    }
+
+    /**
+     * The indicators of a symbol, in the order they were added, or null
+     * if the symbol is unknown.
+     */
+    const std::vector<DynIndicator>* indicators(
+            const std::string& symbol) const {
+        auto it = states.find(symbol);
+        return it == states.end() ? nullptr : &it->second.inds;
+    }
+
+    /**
+     * Names of the indicators, in the order they were added.
+     */
+    const std::vector<std::string>& indicator_names() const noexcept {
+        return names;
+    }
+
+    /**
+     * Latest values of a symbol; without values if it is unknown.
+     */
+    SymbolSnapshot snapshot(const std::string& symbol) const {
+        SymbolSnapshot s;
+        s.symbol = symbol;
+        auto it = states.find(symbol);
+        if (it == states.end()) return s;
+        s.timestamp = it->second.timestamp;
+        for (size_t i = 0; i < names.size(); i++)
+            s.values[names[i]] = it->second.inds[i].get();
+        return s;
+    }
+
+    /**
+     * Latest values of all the symbols, sorted by symbol.
+     */
+    std::vector<SymbolSnapshot> snapshot() const {
+        std::vector<SymbolSnapshot> all;
+        for (const auto& st : states)
+            all.push_back(snapshot(st.first));
+        return all;
+    }
+
+    std::vector<std::string> symbols() const {
+        std::vector<std::string> all;
+        for (const auto& st : states) all.push_back(st.first);
+        return all;
+    }
+
+    /**
+     * Number of symbols seen so far.
+     */
+    size_t size() const noexcept { return states.size(); }
+};
+
+} // namespace tzu
+
+#endif // ENGINE_H
@@ -0,0 +1,276 @@
+#ifndef EVENTS_H
+#define EVENTS_H
+
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <deque>
+#include <functional>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "orders.h"
+#include "risk.h"
+#include "serialize.h"
+
+/**
+ * This header defines an event-driven design for trading systems. The
+ * components don't call each other, but publish and handle events on
+ * an `EventBus`:
+ *
+ *     feed      -- MarketEvent -->  strategy, execution
+ *     strategy  -- SignalEvent -->  risk
+ *     risk      -- OrderEvent  -->  execution
+ *     execution -- FillEvent   -->  risk
+ *
+ * so the same strategy and risk components run in a backtest, with
+ * bars replayed from a streamer and a `SimulatedBroker`, and in a live
+ * session, with bars from a feed and a real broker publishing the
+ * fills. Custom components subscribe to the events they need.
+ */
+
+namespace tzu {
+
+/**
+ * A new bar of a symbol.
+ */
+struct MarketEvent {
+    std::string symbol;
+    Ohlcv bar;
+};
+
+/**
+ * A signal of a strategy for a symbol.
+ */
+struct SignalEvent {
+    std::string symbol;
+    Signal signal;
+};
+
+/**
+ * An order to be executed, after risk checks.
+ */
+struct OrderEvent {
+    Order order;
+};
+
+/**
+ * An execution of an order.
+ */
+struct FillEvent {
+    Fill fill;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const MarketEvent& e) {
+    os << "market symbol:" << e.symbol << " " << e.bar;
+    return os;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const SignalEvent& e) {
+    os << "signal symbol:" << e.symbol
+       << " timestamp:" << e.signal.timestamp
+       << " side:" << (e.signal.side == Side::BUY ? "BUY"
+               : e.signal.side == Side::SELL ? "SELL" : "NONE")
+       << " price:" << e.signal.price
+       << " volume:" << e.signal.volume;
+    return os;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const OrderEvent& e) {
+    os << "order " << e.order;
+    return os;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const FillEvent& e) {
+    os << "fill " << e.fill;
+    return os;
+}
+
+/**
+ * EventBus
+ *
+ * Delivers each event published to the handlers subscribed to its
+ * type, in the order they subscribed. Events are queued and delivered
+ * in the order they were published: an event published by a handler
+ * is delivered after the current one has reached all its handlers, so
+ * every component sees the events in the same order and the call stack
+ * doesn't grow with the chain of events. `publish` returns once the
+ * queue is empty. Handlers are called on the thread publishing; the
+ * bus is not thread-safe.
+ *
+ * Example:
+ *
+ *     EventBus bus;
+ *     bus.subscribe<FillEvent>([](const FillEvent& e) {
+ *         std::cout << e << "\n";
+ *     });
+ *     connect_strategy(bus, "BTCUSD", strat);
+ *     connect_risk(bus, fixed_size(1.0));
+ *     connect_broker(bus, broker);
+ *     for (const auto& bar : csv) bus.publish(MarketEvent{"BTCUSD", bar});
+ */
+class EventBus {
+    template <class E>
+    using Handlers = std::vector<std::function<void(const E&)>>;
+
+    Handlers<MarketEvent> market;
+    Handlers<SignalEvent> signals;
+    Handlers<OrderEvent> orders;
+    Handlers<FillEvent> fills;
+    std::deque<std::function<void()>> queue;
+    bool dispatching = false;
+    size_t delivered = 0;
+
+    Handlers<MarketEvent>& handlers(const MarketEvent*) { return market; }
+    Handlers<SignalEvent>& handlers(const SignalEvent*) { return signals; }
+    Handlers<OrderEvent>& handlers(const OrderEvent*) { return orders; }
+    Handlers<FillEvent>& handlers(const FillEvent*) { return fills; }
+
+    template <class E>
+    void deliver(const E& event) {
+        Handlers<E>& list = handlers(static_cast<const E*>(nullptr));
+        // handlers subscribed meanwhile get the next events
+        size_t n = list.size();
+        for (size_t i = 0; i < n; i++) list[i](event);
+        delivered++;
+    }
+
+public:
+    template <class E>
+    void subscribe(std::function<void(const E&)> handler) {
+        handlers(static_cast<const E*>(nullptr)).push_back(handler);
+    }
+
+    template <class E>
+    void publish(const E& event) {
+        queue.push_back([this, event] { deliver(event); });
+        if (dispatching) return;
+        dispatching = true;
+        while (!queue.empty()) {
+            std::function<void()> next = queue.front();
+            queue.pop_front();
+            next();
+        }
+        dispatching = false;
+    }
+
+    /**
+     * Number of events delivered so far.
+     */
+    size_t num_events() const noexcept { return delivered; }
+};
+
+/**
+ * Feeds the bars of a symbol to a strategy, which must outlive the
+ * bus, and publishes its signals, leaving out those without a side.
+ */
+template <class Strat>
+void connect_strategy(EventBus& bus, const std::string& symbol,
+        Strat& strat) {
+    Strat* s = &strat;
+    EventBus* b = &bus;
+    bus.subscribe<MarketEvent>([s, b, symbol](const MarketEvent& e) {
+        if (e.symbol != symbol) return;
+        Signal signal = s->update(e.bar);
+        if (signal.side != Side::NONE) b->publish(SignalEvent{symbol, signal});
+    });
+}
+
+/**
+ * Signed quantity to order for a signal, zero for none.
+ */
+using Sizer = std::function<double(const SignalEvent&)>;
+
+/**
+ * Sizer buying `quantity` units at buy signals, scaled by the volume of
+ * the signal, and selling as many at sell signals.
+ */
+inline Sizer fixed_size(double quantity) {
+    return [quantity](const SignalEvent& e) {
+        double q = quantity * e.signal.volume;
+        return e.signal.side == Side::BUY ? q
+            : e.signal.side == Side::SELL ? -q : 0.0;
+    };
+}
+
+/**
+ * Turns signals into market orders sized by `sizer` and, with a risk
+ * checker, which must outlive the bus, checks them before publishing
+ * them: they may be resized or rejected, as recorded by the checker.
+ * The checker is kept up to date with the bars and the fills.
+ */
+inline void connect_risk(EventBus& bus, Sizer sizer,
+        RiskChecker* checker = nullptr) {
+    EventBus* b = &bus;
+    bus.subscribe<SignalEvent>([b, sizer, checker](const SignalEvent& e) {
+        double quantity = sizer(e);
+        if (quantity == 0.0 || std::isnan(quantity)) return;
+        if (checker) {
+            RiskDecision decision = checker->check(e.signal.timestamp,
+                    e.symbol, quantity, e.signal.price);
+            if (decision.action == RiskAction::REJECT) return;
+            quantity = decision.quantity;
+        }
+        OrderEvent order;
+        order.order = Order::market(e.symbol, quantity);
+        order.order.timestamp = e.signal.timestamp;
+        b->publish(order);
+    });
+    if (!checker) return;
+    bus.subscribe<MarketEvent>([checker](const MarketEvent& e) {
+        checker->mark(e.symbol, e.bar.close);
+    });
+    bus.subscribe<FillEvent>([checker](const FillEvent& e) {
+        checker->on_fill(e.fill.symbol, e.fill.quantity, e.fill.price);
+    });
+}
+
+/**
+ * Submits the orders to a simulated broker, which must outlive the bus,
+ * matches them against the bars, and publishes the fills. Risk checks
+ * are left to `connect_risk`, so the broker shouldn't have a checker
+ * of its own.
+ */
+inline void connect_broker(EventBus& bus, SimulatedBroker& broker) {
+    SimulatedBroker* br = &broker;
+    EventBus* b = &bus;
+    bus.subscribe<OrderEvent>([br](const OrderEvent& e) {
+        br->submit(e.order.timestamp, e.order);
+    });
+    bus.subscribe<MarketEvent>([br, b](const MarketEvent& e) {
+        if (!br->update(e.symbol, e.bar)) return;
+        for (const auto& fill : br->fills()) b->publish(FillEvent{fill});
+    });
+}
+
+/**
+ * Submits the orders to a connection to a venue, which must outlive the
+ * bus. Its fills are published by the connection itself, e.g. from the
+ * order updates streamed by the venue.
+ */
+inline void connect_broker(EventBus& bus, Broker& broker) {
+    Broker* br = &broker;
+    bus.subscribe<OrderEvent>([br](const OrderEvent& e) {
+        br->submit(e.order.timestamp, e.order);
+    });
+}
+
+/**
+ * Publishes the bars of a streamer as market events of a symbol, e.g.
+ * to backtest the components connected to the bus. Returns the number
+ * of bars.
+ */
+template <class Streamer>
+size_t replay(EventBus& bus, const std::string& symbol, Streamer& streamer) {
+    size_t count = 0;
+    for (const auto& bar : streamer) {
+        bus.publish(MarketEvent{symbol, bar});
+        count++;
+    }
+    return count;
+}
+
+} // namespace tzu
+
+#endif // EVENTS_H
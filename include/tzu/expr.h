@@ -0,0 +1,479 @@
+#ifndef EXPR_H
+#define EXPR_H
+
+#include <algorithm>
+#include <cctype>
+#include <cmath>
+#include <cstddef>
+#include <cstdlib>
+#include <map>
+#include <memory>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "dynamic.h"
+#include "registry.h"
+
+/**
+ * This header defines an evaluator of signal rules written as text,
+ * such as `"rsi(14) < 30 && close > ema(50)"`, for configuration files,
+ * screeners and alerts. An expression is parsed once, building the
+ * indicators it calls, and then updated with each bar, like an
+ * indicator.
+ *
+ * The operands are numbers, bar fields (`open`, `high`, `low`, `close`,
+ * `volume`), indicator calls, with the names of a registry in any case
+ * and a bar field as an optional last argument (`highest(20, high)`),
+ * and names of indicators kept outside the expression. Other outputs
+ * follow a dot, as in `macd(12, 26, 9).signal`; those of indicator
+ * calls are checked when parsing, while unknown outputs of named
+ * indicators, only known when updating, give NaN. From the lowest to
+ * the highest precedence, the operators are:
+ *
+ *     ||  or
+ *     &&  and
+ *     !   not
+ *     <  <=  >  >=  ==  !=  crosses_above  crosses_below
+ *     +  -
+ *     *  /
+ *     -  (negation)
+ *
+ * Comparisons give 1 or 0, and are false when an operand is NaN, e.g.
+ * while an indicator warms up. Crosses compare with the values of the
+ * previous bar.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+struct ExprToken {
+    enum Kind { END, NUMBER, NAME, SYMBOL };
+    Kind kind;
+    std::string text;
+    double number;
+};
+
+inline std::string to_lower(std::string s) {
+    for (auto& c : s)
+        c = static_cast<char>(std::tolower(static_cast<unsigned char>(c)));
+    return s;
+}
+
+inline bool tokenize(const std::string& text, std::vector<ExprToken>& tokens,
+        std::string& error) {
+    static const char* symbols[] = {"&&", "||", "<=", ">=", "==", "!=",
+        "<", ">", "!", "+", "-", "*", "/", "(", ")", ",", "."};
+    size_t i = 0;
+    while (i < text.size()) {
+        unsigned char c = static_cast<unsigned char>(text[i]);
+        if (std::isspace(c)) {
+            i++;
+        } else if (std::isdigit(c) || (c == '.' && i + 1 < text.size()
+                    && std::isdigit(static_cast<unsigned char>(text[i + 1])))) {
+            char* end;
+            double value = std::strtod(text.c_str() + i, &end);
+            size_t n = static_cast<size_t>(end - (text.c_str() + i));
+            tokens.push_back(ExprToken{ExprToken::NUMBER, text.substr(i, n),
+                    value});
+            i += n;
+        } else if (std::isalpha(c) || c == '_') {
+            size_t start = i;
+            while (i < text.size() && (std::isalnum(
+                            static_cast<unsigned char>(text[i]))
+                        || text[i] == '_'))
+                i++;
+            tokens.push_back(ExprToken{ExprToken::NAME,
+                    text.substr(start, i - start), 0.0});
+        } else {
+            bool found = false;
+            for (const char* s : symbols) {
+                std::string sym(s);
+                if (text.compare(i, sym.size(), sym) == 0) {
+                    tokens.push_back(ExprToken{ExprToken::SYMBOL, sym, 0.0});
+                    i += sym.size();
+                    found = true;
+                    break;
+                }
+            }
+            if (!found) {
+                error = "unexpected '" + text.substr(i, 1) + "'";
+                return false;
+            }
+        }
+    }
+    tokens.push_back(ExprToken{ExprToken::END, "", 0.0});
+    return true;
+}
+
+} // namespace detail
+
+/**
+ * Expression
+ *
+ * A parsed rule. `parse` builds it, resolving the names of the
+ * indicators kept outside the expression, which are passed to
+ * `update` in the same order. `update` feeds the bar to the indicators
+ * the expression calls, and returns its value; `test` tells whether it
+ * is true (non-zero and not NaN). Both sides of `&&` and `||` are
+ * always evaluated, so crosses see every bar.
+ *
+ * Example:
+ *
+ *     Expression rule;
+ *     std::string error;
+ *     if (!rule.parse("rsi(14) < 30 && close > ema(50)", error))
+ *         std::cerr << error << "\n";
+ *     for (const auto& bar : bars)
+ *         if (rule.test(bar)) ...
+ */
+class Expression {
+    enum class Op {
+        NUMBER, FIELD, OWN, NAMED, NEG, NOT, ADD, SUB, MUL, DIV,
+        LT, LE, GT, GE, EQ, NE, CROSS_ABOVE, CROSS_BELOW, AND, OR
+    };
+    struct Node {
+        Op op;
+        double number;
+        OhlcvField field;
+        size_t index;
+        std::string output;
+        size_t lhs;
+        size_t rhs;
+        double prev_lhs;
+        double prev_rhs;
+    };
+    std::vector<Node> nodes;
+    size_t root = 0;
+    std::vector<DynIndicator> own;
+    // the calls of the own indicators, in lower case
+    std::vector<std::string> calls;
+
+    // parser state, only used while parsing
+    std::vector<detail::ExprToken> tokens;
+    size_t pos = 0;
+    const std::vector<std::string>* names = nullptr;
+    const IndicatorRegistry* registry = nullptr;
+    std::string* error = nullptr;
+
+    const detail::ExprToken& peek() const { return tokens[pos]; }
+
+    bool accept(const std::string& symbol) {
+        const detail::ExprToken& t = peek();
+        if ((t.kind == detail::ExprToken::SYMBOL
+                    || t.kind == detail::ExprToken::NAME)
+                && t.text == symbol) {
+            pos++;
+            return true;
+        }
+        return false;
+    }
+
+    bool fail(const std::string& message) {
+        if (error->empty()) *error = message;
+        return false;
+    }
+
+    size_t add(Op op, size_t lhs = 0, size_t rhs = 0) {
+        Node n;
+        n.op = op;
+        n.number = 0.0;
+        n.field = OhlcvField::CLOSE;
+        n.index = 0;
+        n.lhs = lhs;
+        n.rhs = rhs;
+        n.prev_lhs = n.prev_rhs = std::nan("");
+        nodes.push_back(n);
+        return nodes.size() - 1;
+    }
+
+    bool parse_or(size_t& out) {
+        if (!parse_and(out)) return false;
+        while (accept("||") || accept("or")) {
+            size_t rhs;
+            if (!parse_and(rhs)) return false;
+            out = add(Op::OR, out, rhs);
+        }
+        return true;
+    }
+
+    bool parse_and(size_t& out) {
+        if (!parse_not(out)) return false;
+        while (accept("&&") || accept("and")) {
+            size_t rhs;
+            if (!parse_not(rhs)) return false;
+            out = add(Op::AND, out, rhs);
+        }
+        return true;
+    }
+
+    bool parse_not(size_t& out) {
+        if (accept("!") || accept("not")) {
+            if (!parse_not(out)) return false;
+            out = add(Op::NOT, out);
+            return true;
+        }
+        return parse_comparison(out);
+    }
+
+    bool parse_comparison(size_t& out) {
+        if (!parse_sum(out)) return false;
+        static const struct { const char* text; Op op; } ops[] = {
+            {"<=", Op::LE}, {">=", Op::GE}, {"<", Op::LT}, {">", Op::GT},
+            {"==", Op::EQ}, {"!=", Op::NE}, {"crosses_above", Op::CROSS_ABOVE},
+            {"crosses_below", Op::CROSS_BELOW}};
+        for (const auto& o : ops) {
+            if (accept(o.text)) {
+                size_t rhs;
+                if (!parse_sum(rhs)) return false;
+                out = add(o.op, out, rhs);
+                return true;
+            }
+        }
+        return true;
+    }
+
+    bool parse_sum(size_t& out) {
+        if (!parse_product(out)) return false;
+        for (;;) {
+            Op op;
+            if (accept("+")) op = Op::ADD;
+            else if (accept("-")) op = Op::SUB;
+            else return true;
+            size_t rhs;
+            if (!parse_product(rhs)) return false;
+            out = add(op, out, rhs);
+        }
+    }
+
+    bool parse_product(size_t& out) {
+        if (!parse_unary(out)) return false;
+        for (;;) {
+            Op op;
+            if (accept("*")) op = Op::MUL;
+            else if (accept("/")) op = Op::DIV;
+            else return true;
+            size_t rhs;
+            if (!parse_unary(rhs)) return false;
+            out = add(op, out, rhs);
+        }
+    }
+
+    bool parse_unary(size_t& out) {
+        if (accept("-")) {
+            if (!parse_unary(out)) return false;
+            out = add(Op::NEG, out);
+            return true;
+        }
+        return parse_primary(out);
+    }
+
+    bool parse_primary(size_t& out) {
+        detail::ExprToken t = peek();
+        if (t.kind == detail::ExprToken::NUMBER) {
+            pos++;
+            out = add(Op::NUMBER);
+            nodes[out].number = t.number;
+            return true;
+        }
+        if (accept("(")) {
+            if (!parse_or(out)) return false;
+            return accept(")") || fail("expected ')'");
+        }
+        if (t.kind != detail::ExprToken::NAME)
+            return fail(t.kind == detail::ExprToken::END
+                    ? "unexpected end of expression"
+                    : "unexpected '" + t.text + "'");
+        pos++;
+        if (accept("(")) {
+            if (!parse_call(t.text, out)) return false;
+        } else {
+            OhlcvField field;
+            if (detail::parse_field(t.text, field)) {
+                out = add(Op::FIELD);
+                nodes[out].field = field;
+                return true;
+            }
+            size_t i = 0;
+            while (i < names->size() && (*names)[i] != t.text) i++;
+            if (i == names->size())
+                return fail("unknown name '" + t.text + "'");
+            out = add(Op::NAMED);
+            nodes[out].index = i;
+        }
+        if (accept(".")) {
+            if (peek().kind != detail::ExprToken::NAME)
+                return fail("expected an output after '.'");
+            std::string output = peek().text;
+            if (nodes[out].op == Op::OWN) {
+                std::vector<std::string> known =
+                    own[nodes[out].index].outputs();
+                if (std::find(known.begin(), known.end(), output)
+                        == known.end())
+                    return fail("unknown output '" + output + "'");
+            }
+            nodes[out].output = output;
+            pos++;
+        }
+        return true;
+    }
+
+    // an indicator call, after its opening parenthesis
+    bool parse_call(const std::string& name, size_t& out) {
+        std::string canonical;
+        for (const auto& n : registry->names())
+            if (detail::to_lower(n) == detail::to_lower(name)) canonical = n;
+        if (canonical.empty())
+            return fail("unknown indicator '" + name + "'");
+        std::string args, field;
+        if (!accept(")")) {
+            do {
+                bool negative = accept("-");
+                detail::ExprToken t = peek();
+                if (t.kind == detail::ExprToken::NUMBER && field.empty()) {
+                    if (!args.empty()) args += ", ";
+                    args += (negative ? "-" : "") + t.text;
+                } else if (t.kind == detail::ExprToken::NAME && !negative
+                        && field.empty()) {
+                    field = t.text;
+                } else {
+                    return fail("invalid argument to '" + name + "'");
+                }
+                pos++;
+            } while (accept(","));
+            if (!accept(")")) return fail("expected ')'");
+        }
+        std::string spec = canonical + "(" + args + ")";
+        if (!field.empty()) spec += " of " + field;
+        std::unique_ptr<DynIndicator> ind = registry->make(spec, error);
+        if (!ind) return false;
+        own.push_back(*ind);
+        calls.push_back(detail::to_lower(name) + "(" + args
+                + (args.empty() || field.empty() ? "" : ", ") + field + ")");
+        out = add(Op::OWN);
+        nodes[out].index = own.size() - 1;
+        return true;
+    }
+
+    static double get(const DynIndicator& ind, const std::string& output) {
+        if (output.empty()) return ind.get();
+        double value = std::nan("");
+        ind.get_output(output, value);
+        return value;
+    }
+
+    double eval(size_t i, const Ohlcv& bar,
+            const std::vector<DynIndicator>& named) {
+        Node& n = nodes[i];
+        switch (n.op) {
+            case Op::NUMBER: return n.number;
+            case Op::FIELD: return bar.getFieldValue(n.field);
+            case Op::OWN: return get(own[n.index], n.output);
+            case Op::NAMED:
+                return n.index < named.size()
+                    ? get(named[n.index], n.output) : std::nan("");
+            case Op::NEG: return -eval(n.lhs, bar, named);
+            case Op::NOT: return truth(eval(n.lhs, bar, named)) ? 0.0 : 1.0;
+            default: break;
+        }
+        double a = eval(n.lhs, bar, named);
+        double b = eval(n.rhs, bar, named);
+        double result = 0.0;
+        switch (n.op) {
+            case Op::ADD: result = a + b; break;
+            case Op::SUB: result = a - b; break;
+            case Op::MUL: result = a * b; break;
+            case Op::DIV: result = a / b; break;
+            case Op::LT: result = a < b; break;
+            case Op::LE: result = a <= b; break;
+            case Op::GT: result = a > b; break;
+            case Op::GE: result = a >= b; break;
+            case Op::EQ: result = a == b; break;
+            case Op::NE: result = a != b; break;
+            case Op::CROSS_ABOVE:
+                result = n.prev_lhs <= n.prev_rhs && a > b;
+                break;
+            case Op::CROSS_BELOW:
+                result = n.prev_lhs >= n.prev_rhs && a < b;
+                break;
+            case Op::AND: result = truth(a) && truth(b); break;
+            case Op::OR: result = truth(a) || truth(b); break;
+            default: break;
+        }
+        n.prev_lhs = a;
+        n.prev_rhs = b;
+        return result;
+    }
+
+    static bool truth(double value) {
+        return !std::isnan(value) && value != 0.0;
+    }
+
+public:
+    /**
+     * Parses an expression, where `names` are the names of the
+     * indicators passed to `update`. Returns false, with the reason in
+     * `error`, for syntax errors, unknown names and invalid indicator
+     * calls; the expression is then left unchanged.
+     */
+    bool parse(const std::string& text, std::string& error,
+            const std::vector<std::string>& names = std::vector<std::string>(),
+            const IndicatorRegistry& registry = builtin_indicators()) {
+        Expression e;
+        e.names = &names;
+        e.registry = &registry;
+        e.error = &error;
+        error.clear();
+        if (!detail::tokenize(text, e.tokens, error)) return false;
+        if (!e.parse_or(e.root)) return false;
+        if (e.peek().kind != detail::ExprToken::END)
+            return e.fail("unexpected '" + e.peek().text + "'");
+        e.tokens.clear();
+        e.names = nullptr;
+        e.registry = nullptr;
+        e.error = nullptr;
+        *this = e;
+        return true;
+    }
+
+    bool empty() const noexcept { return nodes.empty(); }
+
+    /**
+     * Feeds a bar to the indicators called by the expression and
+     * returns its value, NaN if it was never parsed.
+     */
+    double update(const Ohlcv& bar,
+            const std::vector<DynIndicator>& named = std::vector<DynIndicator>()) {
+        for (auto& ind : own) ind.update(bar);
+        if (nodes.empty()) return std::nan("");
+        return eval(root, bar, named);
+    }
+
+    bool test(const Ohlcv& bar,
+            const std::vector<DynIndicator>& named = std::vector<DynIndicator>()) {
+        return truth(update(bar, named));
+    }
+
+    /**
+     * The current values of the indicators called by the expression,
+     * by call in lower case, e.g. `rsi(14)`, and for several outputs
+     * by call and output, e.g. `macd(12, 26, 9).signal`.
+     */
+    std::map<std::string, double> values() const {
+        std::map<std::string, double> all;
+        for (size_t i = 0; i < own.size(); i++) {
+            std::vector<std::string> outputs = own[i].outputs();
+            if (outputs.size() == 1) {
+                all[calls[i]] = own[i].get();
+                continue;
+            }
+            for (const auto& output : outputs)
+                all[calls[i] + "." + output] = get(own[i], output);
+        }
+        return all;
+    }
+};
+
+} // namespace tzu
+
+#endif // EXPR_H
@@ -0,0 +1,324 @@
+#ifndef FEED_H
+#define FEED_H
+
+#include <condition_variable>
+#include <cstddef>
+#include <cstdint>
+#include <cstdio>
+#include <cstdlib>
+#include <deque>
+#include <mutex>
+#include <string>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines live data feeds. A feed receives messages from a
+ * venue, parses the ones carrying data, and yields the values through
+ * an input iterator, so it can be consumed like a `Csv` streamer, e.g.
+ * by a runner or a loop updating indicators. As in `connectors.h`, no
+ * network I/O is done here: messages come from a `receive` callable,
+ * which blocks until a message arrives and returns false when the
+ * connection is over. Any WebSocket or HTTP client can provide it; for
+ * clients delivering messages from their own thread through callbacks,
+ * a `MessageQueue` bridges both sides.
+ *
+ * Parsers follow the `CsvParseTraits` convention: a static `parse`
+ * function returning false for messages to be skipped (subscription
+ * acknowledgments, bars still in progress, etc.).
+ *
+ * Note the units of the timestamps of the Binance parsers: bars from
+ * `BinanceKlineParser` are stamped in seconds, like the CSV data and
+ * `BinanceDownloader`, while ticks from `BinanceTradeParser` are in
+ * milliseconds, since many trades share a second. Convert one of them
+ * before mixing both, e.g. when building bars from trades to compare
+ * with downloaded ones.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+/**
+ * Raw value of a field in a flat JSON object, without the quotes of
+ * strings, searching from position `from`. Nested objects and escaped
+ * quotes within strings are not supported, which is enough for the
+ * stream messages of most venues. Returns false if the key is missing.
+ */
+inline bool json_field(const std::string& msg, const std::string& key,
+        std::string& out, size_t from = 0) {
+    std::string pattern = "\"" + key + "\"";
+    size_t pos = msg.find(pattern, from);
+    while (pos != std::string::npos) {
+        size_t p = pos + pattern.size();
+        while (p < msg.size() && (msg[p] == ' ' || msg[p] == '\t')) p++;
+        if (p < msg.size() && msg[p] == ':') {
+            p++;
+            while (p < msg.size() && (msg[p] == ' ' || msg[p] == '\t')) p++;
+            if (p >= msg.size()) return false;
+            if (msg[p] == '"') {
+                size_t end = msg.find('"', p + 1);
+                if (end == std::string::npos) return false;
+                out = msg.substr(p + 1, end - p - 1);
+            } else {
+                size_t end = msg.find_first_of(",}] \t\r\n", p);
+                out = msg.substr(p, end == std::string::npos ? end : end - p);
+            }
+            return true;
+        }
+        // the key was a string value; keep looking
+        pos = msg.find(pattern, pos + pattern.size());
+    }
+    return false;
+}
+
+inline bool json_number(const std::string& msg, const std::string& key,
+        double& out, size_t from = 0) {
+    std::string raw;
+    if (!json_field(msg, key, raw, from) || raw.empty()) return false;
+    char* end;
+    out = std::strtod(raw.c_str(), &end);
+    return *end == '\0';
+}
+
+inline bool json_integer(const std::string& msg, const std::string& key,
+        int64_t& out, size_t from = 0) {
+    std::string raw;
+    if (!json_field(msg, key, raw, from) || raw.empty()) return false;
+    char* end;
+    out = std::strtoll(raw.c_str(), &end, 10);
+    return *end == '\0';
+}
+
+/**
+ * The objects of a JSON array, as raw text, for messages listing
+ * several records. Objects may be nested; their contents are not
+ * checked.
+ */
+inline std::vector<std::string> json_objects(const std::string& msg) {
+    std::vector<std::string> out;
+    size_t depth = 0, start = 0;
+    bool quoted = false;
+    for (size_t i = 0; i < msg.size(); i++) {
+        char c = msg[i];
+        if (quoted) {
+            if (c == '\\') i++;
+            else if (c == '"') quoted = false;
+        } else if (c == '"') {
+            quoted = true;
+        } else if (c == '{') {
+            if (depth++ == 0) start = i;
+        } else if (c == '}' && depth > 0) {
+            if (--depth == 0) out.push_back(msg.substr(start, i - start + 1));
+        }
+    }
+    return out;
+}
+
+/**
+ * Escapes a string to be written within the quotes of a JSON string.
+ */
+inline std::string json_escape(const std::string& s) {
+    std::string out;
+    for (char c : s) {
+        switch (c) {
+        case '"': out += "\\\""; break;
+        case '\\': out += "\\\\"; break;
+        case '\n': out += "\\n"; break;
+        case '\r': out += "\\r"; break;
+        case '\t': out += "\\t"; break;
+        default:
+            if (static_cast<unsigned char>(c) < 0x20) {
+                char buf[8];
+                std::snprintf(buf, sizeof(buf), "\\u%04x", c);
+                out += buf;
+            } else {
+                out += c;
+            }
+        }
+    }
+    return out;
+}
+
+} // namespace detail
+
+/**
+ * Parses Binance kline stream messages (`<symbol>@kline_<interval>`)
+ * into bars. Only closed klines are yielded, so each bar is delivered
+ * once and final. Timestamps are the open time of the kline, in
+ * seconds (see the note on units at the top of this file).
+ */
+struct BinanceKlineParser {
+    static bool parse(const std::string& msg, Ohlcv& out) {
+        size_t k = msg.find("\"k\"");
+        if (k == std::string::npos) return false;
+        std::string closed;
+        if (!detail::json_field(msg, "x", closed, k) || closed != "true")
+            return false;
+        int64_t ts = 0;
+        double o, h, l, c, v;
+        if (!detail::json_integer(msg, "t", ts, k)
+                || !detail::json_number(msg, "o", o, k)
+                || !detail::json_number(msg, "h", h, k)
+                || !detail::json_number(msg, "l", l, k)
+                || !detail::json_number(msg, "c", c, k)
+                || !detail::json_number(msg, "v", v, k))
+            return false;
+        out = Ohlcv(ts / 1000, o, h, l, c, v);
+        return true;
+    }
+};
+
+/**
+ * Parses Binance trade stream messages (`<symbol>@trade`) into ticks.
+ * The side is the one of the aggressor: a trade where the buyer is the
+ * maker was initiated by a seller. Timestamps are the trade time, in
+ * milliseconds (see the note on units at the top of this file).
+ */
+struct BinanceTradeParser {
+    static bool parse(const std::string& msg, Tick& out) {
+        std::string event, maker;
+        if (!detail::json_field(msg, "e", event) || event != "trade")
+            return false;
+        int64_t ts = 0;
+        double price, quantity;
+        if (!detail::json_integer(msg, "T", ts)
+                || !detail::json_number(msg, "p", price)
+                || !detail::json_number(msg, "q", quantity))
+            return false;
+        Side side = Side::NONE;
+        if (detail::json_field(msg, "m", maker))
+            side = maker == "true" ? Side::SELL : Side::BUY;
+        out = Tick(ts, price, quantity, side);
+        return true;
+    }
+};
+
+/**
+ * A thread-safe queue connecting producers running in their own
+ * threads (`push`, and `close` when done) with a consumer waiting for
+ * the values (`pop`), e.g. a client receiving messages through
+ * callbacks and a feed reading them, or alerts sent to a monitor.
+ */
+template <typename T>
+class Channel {
+    std::deque<T> values;
+    std::mutex mutex;
+    std::condition_variable ready;
+    bool closed = false;
+
+public:
+    void push(const T& value) {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            if (closed) return;
+            values.push_back(value);
+        }
+        ready.notify_one();
+    }
+
+    void close() {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            closed = true;
+        }
+        ready.notify_all();
+    }
+
+    /**
+     * Waits for the next value. Returns false once the channel is
+     * closed and drained.
+     */
+    bool pop(T& value) {
+        std::unique_lock<std::mutex> lock(mutex);
+        ready.wait(lock, [this] { return closed || !values.empty(); });
+        if (values.empty()) return false;
+        value = values.front();
+        values.pop_front();
+        return true;
+    }
+
+    /**
+     * Takes the next value without waiting. Returns false if there is
+     * none.
+     */
+    bool try_pop(T& value) {
+        std::lock_guard<std::mutex> lock(mutex);
+        if (values.empty()) return false;
+        value = values.front();
+        values.pop_front();
+        return true;
+    }
+
+    /**
+     * Callable to be used as the `receive` of a feed.
+     */
+    struct Receiver {
+        Channel* channel;
+        bool operator()(T& value) { return channel->pop(value); }
+    };
+
+    Receiver receiver() { return Receiver{this}; }
+};
+
+/**
+ * A channel of raw messages, as read by a `LiveFeed`.
+ */
+using MessageQueue = Channel<std::string>;
+
+/**
+ * LiveFeed
+ *
+ * Iterable of the values of type `T` parsed by `Parser` from the
+ * messages returned by `receive`. Iteration blocks while waiting for
+ * messages, and ends when `receive` returns false. Messages that don't
+ * parse are skipped and counted.
+ */
+template <typename T, typename Parser, typename Receive>
+class LiveFeed {
+    Receive receive;
+    size_t skipped = 0;
+
+public:
+    class Iterator {
+        LiveFeed* feed;
+        T current;
+        bool end;
+    public:
+        Iterator(LiveFeed* feed, bool end): feed(feed), end(end) {
+            if (!end) ++(*this);
+        }
+        Iterator& operator++() {
+            std::string message;
+            while (!end && feed->receive(message)) {
+                if (Parser::parse(message, current)) return *this;
+                feed->skipped++;
+            }
+            end = true;
+            return *this;
+        }
+        const T& operator*() const { return current; }
+        const T* operator->() const { return &current; }
+        bool operator==(const Iterator& other) const { return end == other.end; }
+        bool operator!=(const Iterator& other) const { return !(*this == other); }
+    };
+
+    explicit LiveFeed(Receive receive): receive(receive) {}
+
+    Iterator begin() { return Iterator(this, false); }
+    Iterator end() { return Iterator(this, true); }
+
+    /**
+     * Messages received that were not data.
+     */
+    size_t num_skipped() const noexcept { return skipped; }
+};
+
+template <typename T, typename Parser, typename Receive>
+LiveFeed<T, Parser, Receive> make_feed(Receive receive) {
+    return LiveFeed<T, Parser, Receive>(receive);
+}
+
+} // namespace tzu
+
+#endif // FEED_H
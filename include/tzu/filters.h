@@ -0,0 +1,122 @@
+#ifndef FILTERS_H
+#define FILTERS_H
+
+#include <cmath>
+#include <cstddef>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines state-space filters: smoothers whose lag adapts
+ * to how noisy the series is relative to how much its underlying level
+ * moves, set explicitly by the variances of both, instead of by a
+ * period as for moving averages.
+ */
+
+namespace tzu {
+
+/**
+ * Kalman Filter (Kalman)
+ *
+ * Estimate of the level of a series observed with noise. The local
+ * level model lets the level drift as a random walk:
+ *
+ * level = level_prev + level noise
+ * value = level + measurement noise
+ *
+ * and the local linear trend model, built with `trend`, adds a slope
+ * that drifts as well:
+ *
+ * level = level_prev + slope_prev + level noise
+ * slope = slope_prev + slope noise
+ *
+ * The noises have the given variances. Each value corrects the
+ * prediction of the level by a gain from 0 to 1, which settles at a
+ * value set by the ratio of the noises: the larger the level noise
+ * relative to the measurement noise, the faster the filter follows the
+ * values. With the local level model, it settles to an EMA. The level
+ * starts at the first value, the slope at zero.
+ *
+ * Example:
+ *
+ *     Kalman level(0.01, 1.0);                  // smooth, EMA-like
+ *     Kalman trend = Kalman::trend(0.01, 1e-4, 1.0);
+ *     trend.update(bar.close);
+ *     if (trend.slope() > 0.0) ...
+ */
+class Kalman: public Indicator<Kalman, double, double> {
+    double data = std::nan("");
+    double q_level;
+    double q_slope;
+    double r;
+    bool with_slope = false;
+    double slope_ = 0.0;
+    // covariance of the errors of the level and the slope
+    double p00 = 0.0;
+    double p01 = 0.0;
+    double p11 = 0.0;
+    double k = std::nan("");
+public:
+    explicit Kalman(double level_noise, double measurement_noise = 1.0)
+        : q_level(level_noise), q_slope(0.0), r(measurement_noise) {}
+
+    static Kalman trend(double level_noise, double slope_noise,
+            double measurement_noise = 1.0) {
+        Kalman kalman(level_noise, measurement_noise);
+        kalman.q_slope = slope_noise;
+        kalman.with_slope = true;
+        return kalman;
+    }
+
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(double value) {
+        if (std::isnan(data)) {
+            data = value;
+            p00 = r;
+            // the slope is unknown until a few values are seen
+            p11 = with_slope ? r : 0.0;
+            return data;
+        }
+        double level = data + slope_;
+        double a = p00 + 2.0 * p01 + p11 + q_level;
+        double b = p01 + p11;
+        double c = p11 + q_slope;
+        double s = a + r;
+        k = a / s;
+        double k1 = b / s;
+        double error = value - level;
+        data = level + k * error;
+        slope_ += k1 * error;
+        p00 = (1.0 - k) * a;
+        p01 = (1.0 - k) * b;
+        p11 = c - k1 * b;
+        return data;
+    }
+    /**
+     * Estimated change of the level per value, zero for the local
+     * level model.
+     */
+    double slope() const noexcept { return slope_; }
+    /**
+     * Variance of the error of the estimated level.
+     */
+    double variance() const noexcept { return p00; }
+    /**
+     * Weight of the latest value in the level, NaN before the second
+     * value.
+     */
+    double gain() const noexcept { return k; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, slope_, p00, p01, p11, k);
+    }
+    void reset_state() {
+        data = k = std::nan("");
+        slope_ = p00 = p01 = p11 = 0.0;
+    }
+};
+
+} // namespace tzu
+
+#endif // FILTERS_H
@@ -0,0 +1,251 @@
+#ifndef FIXTURES_H
+#define FIXTURES_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <cstdlib>
+#include <functional>
+#include <iomanip>
+#include <istream>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines golden fixtures: the outputs of a set of
+ * indicators over a dataset, saved to a file so later runs can be
+ * compared against them. After upgrading the library, or changing an
+ * indicator, a comparison shows whether any value changed beyond a
+ * tolerance.
+ *
+ * Fixture files are CSV files with a first comment line holding the
+ * format version and a label (e.g. the library version or a commit
+ * id), a header line with the column names, and one row per bar with
+ * the timestamp and the value of each indicator. NaN values are
+ * written as `nan`.
+ */
+
+namespace tzu {
+
+const int FIXTURE_FORMAT_VERSION = 1;
+
+/**
+ * Indicator outputs over a dataset. `values[i][j]` is the value of
+ * column `j` at the bar with timestamp `timestamps[i]`.
+ */
+struct Fixture {
+    std::string label;
+    std::vector<std::string> columns;
+    std::vector<int64_t> timestamps;
+    std::vector<std::vector<double>> values;
+};
+
+/**
+ * A set of named indicators to generate fixtures. Indicators are
+ * copied when added, and each run starts from those copies.
+ */
+class FixtureSet {
+    std::vector<std::string> names;
+    std::vector<std::function<double(const Ohlcv&)>> indicators;
+
+public:
+    template <typename Ind>
+    FixtureSet& add(const std::string& name, Ind indicator,
+            OhlcvField field = OhlcvField::CLOSE) {
+        names.push_back(name);
+        indicators.push_back(bar_indicator(indicator, field));
+        return *this;
+    }
+
+    /**
+     * Adds an indicator from a specification like `sma:20`. Supported
+     * names are sma, ema, rsi and mvar (sample variance). Returns false
+     * if the specification is not valid.
+     */
+    bool add(const std::string& spec) {
+        size_t sep = spec.find(':');
+        if (sep == std::string::npos) return false;
+        std::string kind = spec.substr(0, sep);
+        char* end = nullptr;
+        long period = std::strtol(spec.c_str() + sep + 1, &end, 10);
+        if (period <= 0 || *end != '\0') return false;
+        size_t p = static_cast<size_t>(period);
+        if (kind == "sma") add(spec, SMA(p));
+        else if (kind == "ema") add(spec, EMA(p));
+        else if (kind == "rsi") add(spec, RSI(p));
+        else if (kind == "mvar") {
+            MVar mvar(p, 1);
+            names.push_back(spec);
+            indicators.push_back([mvar](const Ohlcv& bar) mutable {
+                return mvar.update(bar.close);
+            });
+        }
+        else return false;
+        return true;
+    }
+
+    size_t size() const noexcept { return names.size(); }
+
+    Fixture run(const std::vector<Ohlcv>& bars,
+            const std::string& label = "") const {
+        Fixture fixture;
+        fixture.label = label;
+        fixture.columns = names;
+        std::vector<std::function<double(const Ohlcv&)>> run = indicators;
+        for (const auto& bar : bars) {
+            std::vector<double> row;
+            row.reserve(run.size());
+            for (auto& ind : run)
+                row.push_back(ind(bar));
+            fixture.timestamps.push_back(bar.timestamp);
+            fixture.values.push_back(row);
+        }
+        return fixture;
+    }
+};
+
+inline void write_fixture(std::ostream& os, const Fixture& fixture) {
+    os << "# tzutrader-fixture " << FIXTURE_FORMAT_VERSION;
+    if (!fixture.label.empty()) os << " " << fixture.label;
+    os << "\ntimestamp";
+    for (const auto& c : fixture.columns) os << "," << c;
+    os << "\n" << std::setprecision(17);
+    for (size_t i = 0; i < fixture.timestamps.size(); i++) {
+        os << fixture.timestamps[i];
+        for (double v : fixture.values[i]) {
+            if (std::isnan(v)) os << ",nan";
+            else os << "," << v;
+        }
+        os << "\n";
+    }
+}
+
+/**
+ * Reads a fixture written by `write_fixture`. Returns false if the
+ * input is not a fixture of a supported format version.
+ */
+inline bool read_fixture(std::istream& is, Fixture& fixture) {
+    fixture = Fixture();
+    std::string line;
+    if (!std::getline(is, line)) return false;
+    std::istringstream head(line);
+    std::string hash, magic;
+    int version = 0;
+    if (!(head >> hash >> magic >> version) || hash != "#"
+            || magic != "tzutrader-fixture" || version != FIXTURE_FORMAT_VERSION)
+        return false;
+    std::getline(head >> std::ws, fixture.label);
+    if (!std::getline(is, line)) return false;
+    std::istringstream header(line);
+    std::string cell;
+    std::getline(header, cell, ',');
+    while (std::getline(header, cell, ','))
+        fixture.columns.push_back(cell);
+    while (std::getline(is, line)) {
+        if (line.empty()) continue;
+        std::istringstream row(line);
+        std::getline(row, cell, ',');
+        fixture.timestamps.push_back(std::strtoll(cell.c_str(), nullptr, 10));
+        std::vector<double> values;
+        while (std::getline(row, cell, ','))
+            values.push_back(std::strtod(cell.c_str(), nullptr));
+        if (values.size() != fixture.columns.size()) return false;
+        fixture.values.push_back(values);
+    }
+    return true;
+}
+
+/**
+ * A value that differs from the golden fixture.
+ */
+struct FixtureMismatch {
+    size_t row;
+    int64_t timestamp;
+    std::string column;
+    double expected;
+    double actual;
+};
+
+/**
+ * Result of comparing a run against a golden fixture. Columns present
+ * in only one of them are listed apart, and only shared columns are
+ * compared value by value.
+ */
+struct FixtureReport {
+    std::vector<std::string> missing_columns;
+    std::vector<std::string> new_columns;
+    bool rows_match = true;
+    size_t compared = 0;
+    std::vector<FixtureMismatch> mismatches;
+    bool ok() const noexcept {
+        return missing_columns.empty() && new_columns.empty() && rows_match
+            && mismatches.empty();
+    }
+};
+
+/**
+ * Compares a fixture against a golden one. Values match when both are
+ * NaN, or when their difference is within `tolerance` relative to the
+ * magnitude of the expected value (absolute for values below one).
+ */
+inline FixtureReport compare_fixtures(const Fixture& expected,
+        const Fixture& actual, double tolerance = 1e-9) {
+    FixtureReport report;
+    std::vector<std::pair<size_t, size_t>> shared;
+    for (size_t j = 0; j < expected.columns.size(); j++) {
+        size_t k = 0;
+        while (k < actual.columns.size()
+                && actual.columns[k] != expected.columns[j])
+            k++;
+        if (k == actual.columns.size())
+            report.missing_columns.push_back(expected.columns[j]);
+        else
+            shared.push_back(std::make_pair(j, k));
+    }
+    for (const auto& c : actual.columns) {
+        bool found = false;
+        for (const auto& e : expected.columns)
+            if (e == c) found = true;
+        if (!found) report.new_columns.push_back(c);
+    }
+    report.rows_match = expected.timestamps == actual.timestamps;
+    size_t rows = expected.timestamps.size() < actual.timestamps.size()
+        ? expected.timestamps.size() : actual.timestamps.size();
+    for (size_t i = 0; i < rows; i++) {
+        for (const auto& s : shared) {
+            double e = expected.values[i][s.first];
+            double a = actual.values[i][s.second];
+            report.compared++;
+            if (std::isnan(e) && std::isnan(a)) continue;
+            double scale = std::fabs(e) > 1.0 ? std::fabs(e) : 1.0;
+            if (std::isnan(e) || std::isnan(a)
+                    || std::fabs(e - a) > tolerance * scale)
+                report.mismatches.push_back({i, expected.timestamps[i],
+                        expected.columns[s.first], e, a});
+        }
+    }
+    return report;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const FixtureReport& report) {
+    os << "fixture_compared:" << report.compared
+       << " fixture_mismatches:" << report.mismatches.size()
+       << " fixture_rows_match:" << (report.rows_match ? "true" : "false");
+    for (const auto& c : report.missing_columns)
+        os << "\nmissing_column:" << c;
+    for (const auto& c : report.new_columns)
+        os << "\nnew_column:" << c;
+    for (const auto& m : report.mismatches)
+        os << "\nrow:" << m.row << " timestamp:" << m.timestamp
+           << " column:" << m.column << " expected:" << m.expected
+           << " actual:" << m.actual;
+    return os;
+}
+
+} // namespace tzu
+
+#endif // FIXTURES_H
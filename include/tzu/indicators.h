@@ -1,9 +1,22 @@
 #ifndef INDICATORS_H
 #define INDICATORS_H
 
+#include <algorithm>
+#include <cmath>
 #include <cstddef>
+#include <cstdint>
+#include <deque>
+#include <functional>
+#include <istream>
+#include <iterator>
+#include <ostream>
+#include <string>
+#include <type_traits>
+#include <utility>
 #include <vector>
 #include "defs.h"
+#include "numeric.h"
+#include "state.h"
 
 /**
  * This header defines several technical indicators commonly used in
@@ -17,17 +30,551 @@
 
 namespace tzu {
 
+/**
+ * Outcome of `try_update`. Invalid inputs are non-finite prices,
+ * negative or non-finite volumes (or non-positive for ticks), and
+ * timestamps older than the previous input. A zero divisor is reported
+ * when an indicator produced an infinite value.
+ */
+enum class IndicatorStatus {
+    OK,
+    WARMING_UP,
+    INVALID_PRICE,
+    INVALID_VOLUME,
+    NON_MONOTONIC_TIME,
+    ZERO_DIVISOR
+};
+
+inline const char* to_string(IndicatorStatus status) {
+    switch (status) {
+        case IndicatorStatus::OK: return "ok";
+        case IndicatorStatus::WARMING_UP: return "warming_up";
+        case IndicatorStatus::INVALID_PRICE: return "invalid_price";
+        case IndicatorStatus::INVALID_VOLUME: return "invalid_volume";
+        case IndicatorStatus::NON_MONOTONIC_TIME: return "non_monotonic_time";
+        case IndicatorStatus::ZERO_DIVISOR: return "zero_divisor";
+    }
+    return "";
+}
+
+namespace detail {
+
+inline IndicatorStatus check_input(double value) {
+    return std::isfinite(value) ? IndicatorStatus::OK
+        : IndicatorStatus::INVALID_PRICE;
+}
+
+inline IndicatorStatus check_input(const Ohlcv& bar) {
+    if (!std::isfinite(bar.open) || !std::isfinite(bar.high)
+            || !std::isfinite(bar.low) || !std::isfinite(bar.close))
+        return IndicatorStatus::INVALID_PRICE;
+    if (!std::isfinite(bar.volume) || bar.volume < 0.0)
+        return IndicatorStatus::INVALID_VOLUME;
+    return IndicatorStatus::OK;
+}
+
+inline IndicatorStatus check_input(const SingleValue& value) {
+    return check_input(value.value);
+}
+
+inline IndicatorStatus check_input(const Tick& tick) {
+    if (!std::isfinite(tick.price)) return IndicatorStatus::INVALID_PRICE;
+    if (!std::isfinite(tick.volume) || tick.volume <= 0.0)
+        return IndicatorStatus::INVALID_VOLUME;
+    return IndicatorStatus::OK;
+}
+
+template <typename In>
+IndicatorStatus check_input(const In&) { return IndicatorStatus::OK; }
+
+// inputs with a timestamp
+template <typename In>
+auto input_time(const In& value, int) -> decltype(value.timestamp, int64_t()) {
+    return value.timestamp;
+}
+
+template <typename In>
+int64_t input_time(const In&, long) { return INT64_MIN; }
+
+/**
+ * Input of an indicator taken from a bar: the bar itself, or one of its
+ * fields, the close by default.
+ */
+template <typename In>
+In from_bar(const Ohlcv& bar, OhlcvField field = OhlcvField::CLOSE) {
+    return In(bar.getFieldValue(field));
+}
+
+template <>
+inline Ohlcv from_bar<Ohlcv>(const Ohlcv& bar, OhlcvField) {
+    return bar;
+}
+
+template <>
+inline SingleValue from_bar<SingleValue>(const Ohlcv& bar, OhlcvField field) {
+    return SingleValue(bar.timestamp, bar.getFieldValue(field));
+}
+
+} // namespace detail
+
+/**
+ * Tells whether an output is still missing (warming up) or was computed
+ * with a zero divisor. Indicators with several outputs specialize it,
+ * checking their main output.
+ */
+template <typename Out>
+struct OutputCheck {
+    static IndicatorStatus check(const Out&) { return IndicatorStatus::OK; }
+};
+
+template <>
+struct OutputCheck<double> {
+    static IndicatorStatus check(double value) {
+        if (std::isnan(value)) return IndicatorStatus::WARMING_UP;
+        if (std::isinf(value)) return IndicatorStatus::ZERO_DIVISOR;
+        return IndicatorStatus::OK;
+    }
+};
+
+template <>
+struct OutputCheck<Ohlcv> {
+    static IndicatorStatus check(const Ohlcv& bar) {
+        return OutputCheck<double>::check(bar.close);
+    }
+};
+
+template <>
+struct OutputCheck<float> {
+    static IndicatorStatus check(float value) {
+        return OutputCheck<double>::check(value);
+    }
+};
+
+template <int Decimals>
+struct OutputCheck<Fixed<Decimals>> {
+    static IndicatorStatus check(Fixed<Decimals> value) {
+        return NumericTraits<Fixed<Decimals>>::is_missing(value)
+            ? IndicatorStatus::WARMING_UP : IndicatorStatus::OK;
+    }
+};
+
+/**
+ * Names and values of the outputs of an indicator, so generic code
+ * (exports, plots, optimizers) can enumerate them. Single outputs are
+ * named `value`, and indicators with several outputs specialize it
+ * with the fields of their result.
+ */
+template <typename Out>
+struct Outputs {
+    static std::vector<std::string> names() { return {"value"}; }
+    static std::vector<double> values(const Out& value) {
+        return {static_cast<double>(value)};
+    }
+};
+
+template <int Decimals>
+struct Outputs<Fixed<Decimals>> {
+    static std::vector<std::string> names() { return {"value"}; }
+    static std::vector<double> values(Fixed<Decimals> value) {
+        return {NumericTraits<Fixed<Decimals>>::is_missing(value)
+            ? std::nan("") : static_cast<double>(value)};
+    }
+};
+
+template <>
+struct Outputs<Ohlcv> {
+    static std::vector<std::string> names() {
+        return {"open", "high", "low", "close", "volume"};
+    }
+    static std::vector<double> values(const Ohlcv& bar) {
+        return {bar.open, bar.high, bar.low, bar.close, bar.volume};
+    }
+};
+
 template <class T, typename In, typename Out>
 class Indicator {
+    int64_t last_time = INT64_MIN;
 public:
+    using input_type = In;
+    using output_type = Out;
     Out get() const noexcept {
-        return static_cast<T*>(this)->get();
+        return static_cast<const T*>(this)->get();
     }
     Out update(In value) {
         return static_cast<T*>(this)->update(value);
     }
+    /**
+     * Number of inputs consumed before the first complete output, i.e.
+     * its index in the outputs of a fresh indicator. Uses the
+     * `required_warmup()` of the indicator: the number of inputs needed
+     * before all its outputs are available, e.g. to fetch just enough
+     * history before going live, or zero when the warmup depends on
+     * time rather than on a number of inputs. Indicators built on
+     * recursive averages, like the EMA, keep depending on older inputs,
+     * so more history brings their values closer to those of a longer
+     * run. There is no default, so indicators without one don't compile
+     * here.
+     */
+    size_t lookback() const noexcept {
+        size_t n = static_cast<const T*>(this)->required_warmup();
+        return n > 0 ? n - 1 : 0;
+    }
+    /**
+     * Tells whether the outputs are complete, from the current output.
+     * Indicators whose outputs don't tell it provide their own.
+     */
+    bool is_ready() const noexcept {
+        return OutputCheck<Out>::check(static_cast<const T*>(this)->get())
+            != IndicatorStatus::WARMING_UP;
+    }
+    /**
+     * Warms up the indicator with historical data, in chronological
+     * order, and returns the resulting value. Indicators that only
+     * depend on the latest values provide faster versions.
+     */
+    template <typename Container>
+    Out init_from(const Container& history) {
+        T& self = *static_cast<T*>(this);
+        for (const auto& value : history)
+            self.update(value);
+        return self.get();
+    }
+    /**
+     * Warms up the indicator with historical bars, whatever its input:
+     * indicators taking single values are fed the close prices. Uses
+     * the faster `init_from` of the indicator if it has one.
+     */
+    template <typename Container>
+    Out warmup(const Container& bars) {
+        std::vector<In> inputs;
+        inputs.reserve(std::distance(std::begin(bars), std::end(bars)));
+        for (const Ohlcv& bar : bars)
+            inputs.push_back(detail::from_bar<In>(bar));
+        return static_cast<T*>(this)->init_from(inputs);
+    }
+    /**
+     * Updates the indicator with a series of values and returns all the
+     * outputs, with NaN during the warmup as with `update`.
+     */
+    template <typename Container>
+    std::vector<Out> compute_batch(const Container& inputs) {
+        T& self = *static_cast<T*>(this);
+        std::vector<Out> out;
+        out.reserve(std::distance(std::begin(inputs), std::end(inputs)));
+        for (const auto& value : inputs)
+            out.push_back(self.update(value));
+        return out;
+    }
+    /**
+     * Returns an independent copy of the indicator state, to evaluate
+     * hypothetical data without modifying the original.
+     */
+    T fork() const {
+        return *static_cast<const T*>(this);
+    }
+    /**
+     * Returns the value the indicator would have after `value`, e.g.
+     * if the current bar closed at a given price, leaving the state
+     * untouched. The cost is a copy of the state.
+     */
+    Out peek(In value) const {
+        T branch = fork();
+        return branch.update(value);
+    }
+    /**
+     * Updates the indicator after checking the input, so that data
+     * problems are told apart from the warmup instead of silently
+     * producing NaN. Invalid inputs are rejected, leaving the state and
+     * `out` untouched. Otherwise `out` receives the result of `update`,
+     * and the status tells whether it is valid yet. Timestamps are only
+     * compared between calls to `try_update`.
+     */
+    IndicatorStatus try_update(In value, Out& out) {
+        IndicatorStatus status = detail::check_input(value);
+        if (status != IndicatorStatus::OK) return status;
+        int64_t time = detail::input_time(value, 0);
+        if (time != INT64_MIN) {
+            if (last_time != INT64_MIN && time < last_time)
+                return IndicatorStatus::NON_MONOTONIC_TIME;
+            last_time = time;
+        }
+        out = static_cast<T*>(this)->update(value);
+        return OutputCheck<Out>::check(out);
+    }
+    /**
+     * Names of the outputs, e.g. `macd`, `signal` and `histogram` for
+     * MACD, or `value` for indicators with a single output.
+     */
+    std::vector<std::string> outputs() const { return Outputs<Out>::names(); }
+    /**
+     * Current value of the output `name`. Returns false, leaving
+     * `value` untouched, if there is no such output.
+     */
+    bool get_output(const std::string& name, double& value) const {
+        std::vector<std::string> names = Outputs<Out>::names();
+        for (size_t i = 0; i < names.size(); i++) {
+            if (names[i] == name) {
+                value = Outputs<Out>::values(
+                        static_cast<const T*>(this)->get())[i];
+                return true;
+            }
+        }
+        return false;
+    }
+    /**
+     * Clears the state, including the last timestamp seen by
+     * `try_update`, as if the indicator had just been built with the
+     * same parameters. Indicators clear their own state in
+     * `reset_state`.
+     */
+    void reset() {
+        static_cast<T*>(this)->reset_state();
+        last_time = INT64_MIN;
+    }
+    /**
+     * Writes the internal state of the indicator, so that a restarted
+     * process can restore it with `load_state` instead of replaying the
+     * history. See `state.h` for the format.
+     */
+    bool save_state(std::ostream& os) const {
+        T& self = const_cast<T&>(*static_cast<const T*>(this));
+        int64_t time = last_time;
+        StateWriter ar(os);
+        ar(time);
+        self.serialize(ar);
+        return ar.finish();
+    }
+    /**
+     * Restores a state written by `save_state` from an indicator built
+     * with the same parameters. On failure, e.g. with a state written
+     * by another kind of indicator or with other parameters, the
+     * indicator is left untouched and false is returned.
+     */
+    bool load_state(std::istream& is) {
+        T copy = *static_cast<T*>(this);
+        int64_t time = INT64_MIN;
+        StateReader ar(is);
+        ar(time);
+        copy.serialize(ar);
+        if (!ar.finish()) return false;
+        *static_cast<T*>(this) = copy;
+        last_time = time;
+        return true;
+    }
+};
+
+namespace detail {
+
+template <typename Ind>
+double feed_bar(Ind& ind, const Ohlcv& bar, OhlcvField, std::true_type) {
+    return ind.update(bar);
+}
+
+template <typename Ind>
+double feed_bar(Ind& ind, const Ohlcv& bar, OhlcvField field, std::false_type) {
+    return ind.update(bar.getFieldValue(field));
+}
+
+template <typename Ind, typename E>
+typename Ind::output_type feed_input(Ind& ind, const E& value) {
+    return ind.update(value);
+}
+
+template <typename Ind>
+typename Ind::output_type feed_input(Ind& ind, const Ohlcv& bar) {
+    return ind.update(from_bar<typename Ind::input_type>(bar));
+}
+
+} // namespace detail
+
+/**
+ * Wraps an indicator with a `double` output into a callable that takes
+ * bars. Indicators taking bars are fed with the whole bar, and the
+ * others with the given field. Useful to handle indicators of different
+ * types together, e.g. in a container.
+ */
+template <typename Ind>
+std::function<double(const Ohlcv&)> bar_indicator(Ind indicator,
+        OhlcvField field = OhlcvField::CLOSE) {
+    return [indicator, field](const Ohlcv& bar) mutable {
+        return detail::feed_bar(indicator, bar, field,
+            std::is_same<typename Ind::input_type, Ohlcv>());
+    };
+}
+
+/**
+ * Price of a bar to feed indicators taking single values: one of the
+ * bar prices or a combination of them.
+ */
+enum class PriceSource {
+    OPEN,
+    HIGH,
+    LOW,
+    CLOSE,
+    MEDIAN,    // (high + low) / 2
+    TYPICAL,   // (high + low + close) / 3
+    WEIGHTED   // (high + low + 2 * close) / 4
+};
+
+inline double bar_price(const Ohlcv& bar, PriceSource source) {
+    switch (source) {
+        case PriceSource::OPEN: return bar.open;
+        case PriceSource::HIGH: return bar.high;
+        case PriceSource::LOW: return bar.low;
+        case PriceSource::CLOSE: return bar.close;
+        case PriceSource::MEDIAN: return (bar.high + bar.low) / 2.0;
+        case PriceSource::TYPICAL:
+            return (bar.high + bar.low + bar.close) / 3.0;
+        case PriceSource::WEIGHTED:
+            return (bar.high + bar.low + 2.0 * bar.close) / 4.0;
+    }
+    return std::nan(""); // Should never reach here
+}
+
+/**
+ * Applied
+ *
+ * Adapts an indicator taking single values to take bars, feeding it
+ * with the selected price of each bar, e.g. an RSI over the typical
+ * price. The wrapped indicator is available with `inner()`.
+ *
+ * Example: `Applied<SMA> sma(SMA(20), PriceSource::TYPICAL);`
+ */
+template <class Ind>
+class Applied: public Indicator<Applied<Ind>, Ohlcv,
+        typename Ind::output_type> {
+    Ind ind;
+    PriceSource source;
+public:
+    Applied(const Ind& ind, PriceSource source = PriceSource::CLOSE)
+        : ind(ind), source(source) {}
+    typename Ind::output_type get() const noexcept { return ind.get(); }
+    size_t required_warmup() const noexcept { return ind.required_warmup(); }
+    typename Ind::output_type update(const Ohlcv& value) {
+        return ind.update(bar_price(value, source));
+    }
+    const Ind& inner() const noexcept { return ind; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(ind);
+    }
+    void reset_state() {
+        ind.reset();
+    }
+};
+
+template <class Ind>
+Applied<Ind> make_applied(const Ind& ind,
+        PriceSource source = PriceSource::CLOSE) {
+    return Applied<Ind>(ind, source);
+}
+
+/**
+ * Converted
+ *
+ * Adapts an indicator taking doubles to take values of another numeric
+ * type, e.g. `Decimal` prices kept by exact accounting code, converting
+ * each one to `double`.
+ *
+ * Example: `auto ema = make_converted<Decimal>(EMA(14));`
+ */
+template <typename V, class Ind>
+class Converted: public Indicator<Converted<V, Ind>, V,
+        typename Ind::output_type> {
+    Ind ind;
+public:
+    explicit Converted(const Ind& ind): ind(ind) {}
+    typename Ind::output_type get() const noexcept { return ind.get(); }
+    size_t required_warmup() const noexcept { return ind.required_warmup(); }
+    typename Ind::output_type update(V value) {
+        return ind.update(static_cast<double>(value));
+    }
+    const Ind& inner() const noexcept { return ind; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(ind);
+    }
+    void reset_state() {
+        ind.reset();
+    }
+};
+
+template <typename V, class Ind>
+Converted<V, Ind> make_converted(const Ind& ind) {
+    return Converted<V, Ind>(ind);
+}
+
+/**
+ * Indicated
+ *
+ * A range over the outputs of an indicator fed with the elements of
+ * another range, e.g. a vector of bars or a `Csv` streamer, with NaN
+ * during the warmup. Bars are fed whole to indicators taking bars, and
+ * by their close price to the others. The outputs are computed as the
+ * range is walked, so it can only be walked once; the indicator is
+ * available afterwards with `indicator()`.
+ *
+ * Example: `for (double rsi : make_indicated(csv, RSI(14)))`
+ */
+template <typename Range, class Ind>
+class Indicated {
+    using input_iterator = decltype(std::begin(std::declval<Range&>()));
+    Range& range;
+    Ind ind;
+
+public:
+    using output_type = typename Ind::output_type;
+
+    class Iterator {
+        Indicated* owner;
+        input_iterator it;
+        input_iterator last;
+        output_type current;
+
+        void compute() {
+            if (it != last) current = detail::feed_input(owner->ind, *it);
+        }
+
+    public:
+        Iterator(Indicated* owner, input_iterator it, input_iterator last)
+            : owner(owner), it(it), last(last), current() {
+            compute();
+        }
+        Iterator& operator++() {
+            ++it;
+            compute();
+            return *this;
+        }
+        const output_type& operator*() const { return current; }
+        const output_type* operator->() const { return &current; }
+        bool operator==(const Iterator& other) const { return it == other.it; }
+        bool operator!=(const Iterator& other) const { return !(*this == other); }
+    };
+
+    Indicated(Range& range, const Ind& ind): range(range), ind(ind) {}
+
+    Iterator begin() { return Iterator(this, std::begin(range), std::end(range)); }
+    Iterator end() { return Iterator(this, std::end(range), std::end(range)); }
+
+    const Ind& indicator() const noexcept { return ind; }
 };
 
+template <typename Range, class Ind>
+Indicated<Range, Ind> make_indicated(Range& range, const Ind& ind) {
+    return Indicated<Range, Ind>(range, ind);
+}
+
+namespace detail {
+
+template <typename V>
+V window_sum(const std::vector<V>& values) {
+    V sum = V(0.0);
+    for (const V& value : values) sum += value;
+    return sum;
+}
+
+} // namespace detail
+
 /**
  * Simple Moving Average (SMA)
  *
@@ -35,20 +582,27 @@ public:
  * calculates the average. Returns NaN until enough values have been
  * added to fill the window. Once the window is full, it updates the
  * average efficiently by keeping a running sum and subtracting the
- * value that falls out of the window.
+ * value that falls out of the window. The sum is recomputed from the
+ * window each time it wraps around, so rounding errors don't build up
+ * over long sessions.
  *
- * Template parameter `window_size` specifies the size of the window.
+ * `window_size` specifies the size of the window. `BasicSMA` works with
+ * other value types, such as `float` or `Fixed` (see `numeric.h`),
+ * returning their missing value instead of NaN.
  */
-class SMA: public Indicator<SMA, double, double> {
-    double data = std::nan("");
-    std::vector<double> prev;
+template <typename V>
+class BasicSMA: public Indicator<BasicSMA<V>, V, V> {
+    using Traits = NumericTraits<V>;
+    V data = Traits::missing();
+    std::vector<V> prev;
     size_t pos = 0;
     size_t len = 0;
-    double sum = 0.0;
+    V sum = V(0.0);
 public:
-    SMA(size_t window_size): prev(window_size, std::nan("")) {}
-    double get() const noexcept { return data; }
-    double update(double value) {
+    BasicSMA(size_t window_size): prev(window_size, V(0.0)) {}
+    V get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    V update(V value) {
         if (len < prev.size())
             len++;
         else
@@ -56,13 +610,71 @@ public:
         sum += value;
         prev[pos] = value;
         pos = (pos + 1) % prev.size();
+        if (pos == 0) sum = detail::window_sum(prev);
         data = len < prev.size()
-                ? std::nan("") 
-                : sum / static_cast<double>(prev.size());
+                ? Traits::missing()
+                : sum / V(static_cast<double>(prev.size()));
+        return data;
+    }
+    template <typename Container>
+    V init_from(const Container& history) {
+        auto first = std::begin(history);
+        auto last = std::end(history);
+        auto n = std::distance(first, last);
+        // older values would be dropped from the window anyway
+        if (n > static_cast<decltype(n)>(prev.size())) {
+            std::advance(first, n - prev.size());
+            sum = V(0.0);
+            len = 0;
+            pos = 0;
+        }
+        for (; first != last; ++first)
+            update(*first);
         return data;
     }
+    template <typename Container>
+    std::vector<V> compute_batch(const Container& inputs) {
+        std::vector<V> in(std::begin(inputs), std::end(inputs));
+        if (len > 0)
+            return Indicator<BasicSMA<V>, V, V>::compute_batch(in);
+        // a fresh indicator can slide over the input directly
+        std::vector<V> out(in.size(), Traits::missing());
+        size_t n = prev.size();
+        V acc = V(0.0);
+        for (size_t i = 0; i < in.size(); i++) {
+            if (i >= n) acc -= in[i - n];
+            acc += in[i];
+            // as in `update`, where the window wraps around
+            if ((i + 1) % n == 0) {
+                acc = V(0.0);
+                for (size_t k = i + 1 - n; k <= i; k++) acc += in[k];
+            }
+            if (i + 1 >= n) out[i] = acc / V(static_cast<double>(n));
+        }
+        size_t start = in.size() > n ? in.size() - n : 0;
+        for (size_t i = start; i < in.size(); i++) {
+            prev[pos] = in[i];
+            pos = (pos + 1) % n;
+            len++;
+        }
+        sum = acc;
+        if (!out.empty()) data = out.back();
+        return out;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len, sum);
+    }
+    void reset_state() {
+        data = Traits::missing();
+        std::fill(prev.begin(), prev.end(), V(0.0));
+        pos = len = 0;
+        sum = V(0.0);
+    }
 };
 
+using SMA = BasicSMA<double>;
+
 /**
  * Exponential Moving Average (EMA)
  *
@@ -78,143 +690,578 @@ public:
  * alpha = smoothing / (period + 1)
  *
  * The default smoothing factor is 2.0, which is commonly used in
- * financial applications.
+ * financial applications. `BasicEMA` works with other value types, as
+ * `BasicSMA`.
  */
-class EMA: public Indicator<EMA, double, double> {
-    double data = std::nan("");
-    double alpha;
-    double prev = 0.0;
+template <typename V>
+class BasicEMA: public Indicator<BasicEMA<V>, V, V> {
+    using Traits = NumericTraits<V>;
+    V data = Traits::missing();
+    V alpha;
+    V prev = V(0.0);
     size_t len = 0;
     size_t period;
 public:
-    EMA(size_t period, double smoothing = 2.0)
+    BasicEMA(size_t period, double smoothing = 2.0)
         : alpha(smoothing / (period + 1.0)), period(period) {}
-    double get() const noexcept { return data; }
-    double update(double value) {
+    V get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return period; }
+    V update(V value) {
         len++;
         if (len < period) {
             prev += value;
-            data = std::nan("");
+            data = Traits::missing();
         } else if (len == period) {
             prev += value;
-            prev /= period;
+            prev /= V(static_cast<double>(period));
             data = prev;
         } else {
-            prev = (value * alpha) + (prev * (1.0 - alpha));
+            prev = (value * alpha) + (prev * (V(1.0) - alpha));
             data = prev;
         }
         return data;
     }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, len);
+    }
+    void reset_state() {
+        data = Traits::missing();
+        prev = V(0.0);
+        len = 0;
+    }
 };
 
+using EMA = BasicEMA<double>;
+
 /**
- * Moving Variance (MVar)
- * 
- * Calculates the variance of the most recent N values. Returns NaN
- * until enough values have been added to fill the window. Once the
- * window is full, it calculates the variance using the formula:
+ * Weighted Moving Average (WMA)
  *
- * Variance = (1 / (N - dof)) * sum((x_i - mean)^2)
+ * Averages the most recent `period` values with linearly increasing
+ * weights, from 1 for the oldest to `period` for the latest. Returns
+ * NaN until the window is full. The weighted sum is updated in constant
+ * time by subtracting the plain sum of the previous window.
+ * `BasicWMA` works with other value types, as `BasicSMA`.
+ */
+template <typename V>
+class BasicWMA: public Indicator<BasicWMA<V>, V, V> {
+    using Traits = NumericTraits<V>;
+    V data = Traits::missing();
+    std::vector<V> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    V sum = V(0.0);
+    V weighted = V(0.0);
+public:
+    BasicWMA(size_t period): prev(period, V(0.0)) {}
+    V get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    V update(V value) {
+        size_t n = prev.size();
+        if (len < n) {
+            len++;
+            weighted += V(static_cast<double>(len)) * value;
+        } else {
+            weighted += V(static_cast<double>(n)) * value - sum;
+            sum -= prev[pos];
+        }
+        sum += value;
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (pos == 0) {
+            // the oldest value is first when the window wraps around
+            sum = weighted = V(0.0);
+            for (size_t i = 0; i < n; i++) {
+                sum += prev[i];
+                weighted += V(static_cast<double>(i + 1)) * prev[i];
+            }
+        }
+        data = len < n ? Traits::missing()
+            : weighted / V(n * (n + 1) / 2.0);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len, sum, weighted);
+    }
+    void reset_state() {
+        data = Traits::missing();
+        std::fill(prev.begin(), prev.end(), V(0.0));
+        pos = len = 0;
+        sum = weighted = V(0.0);
+    }
+};
+
+using WMA = BasicWMA<double>;
+
+/**
+ * Hull Moving Average (HMA)
  *
- * where dof is the degrees of freedom, which is typically 1 for sample
- * variance and 0 for population variance. The N template parameter
- * specifies the size of the window.
+ * Alan Hull's low-lag moving average:
  *
- * The standard deviation can be obtained by taking the square root of
- * the variance.
+ * HMA = WMA(2 * WMA(period / 2) - WMA(period), sqrt(period))
+ *
+ * Returns NaN until `period + sqrt(period) - 1` values have been added.
+ */
+class HMA: public Indicator<HMA, double, double> {
+    double data = std::nan("");
+    WMA half;
+    WMA full;
+    WMA smooth;
+public:
+    HMA(size_t period)
+        : half(period / 2 > 0 ? period / 2 : 1), full(period),
+          smooth(std::sqrt(period) >= 1.0
+                ? static_cast<size_t>(std::sqrt(period)) : 1) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return full.required_warmup() + smooth.required_warmup() - 1;
+    }
+    double update(double value) {
+        double h = half.update(value);
+        double f = full.update(value);
+        if (std::isnan(f))
+            return data;
+        data = smooth.update(2.0 * h - f);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, half, full, smooth);
+    }
+    void reset_state() {
+        data = std::nan("");
+        half.reset();
+        full.reset();
+        smooth.reset();
+    }
+};
+
+/**
+ * Zero-Lag Exponential Moving Average (ZLEMA)
+ *
+ * An EMA of the values corrected by their momentum over the lag of the
+ * EMA, which is `(period - 1) / 2`:
+ *
+ * ZLEMA = EMA(2 * Value_today - Value_lag_ago)
+ *
+ * Returns NaN until `lag + period` values have been added.
  */
-class MVar {
+class ZLEMA: public Indicator<ZLEMA, double, double> {
     double data = std::nan("");
-    SMA sma;
+    EMA ema;
     std::vector<double> prev;
     size_t pos = 0;
     size_t len = 0;
-    // double sum = 0.0;
-    size_t dof;
 public:
-    MVar(size_t window_size, size_t dof)
-        : sma(window_size), prev(window_size, std::nan("")), dof(dof) {}
+    ZLEMA(size_t period, double smoothing = 2.0)
+        : ema(period, smoothing), prev((period > 0 ? period - 1 : 0) / 2 + 1) {}
     double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return prev.size() + ema.required_warmup() - 1;
+    }
     double update(double value) {
-        if (len < prev.size()) len++;
+        // the oldest value in the buffer is `lag` updates ago
         prev[pos] = value;
         pos = (pos + 1) % prev.size();
-        sma.update(value);
+        if (len < prev.size()) len++;
         if (len < prev.size())
-            return std::nan("");
-        double accum = 0.0;
-        for (size_t prev_value : prev) {
-            if (std::isnan(prev_value))
-                return std::nan("");
-            double diff = prev_value - sma.get();
-            accum += diff * diff;
-        }
-        data = accum / (prev.size() - dof);
+            return data;
+        data = ema.update(2.0 * value - prev[pos]);
         return data;
     }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, ema, prev, pos, len);
+    }
+    void reset_state() {
+        data = std::nan("");
+        ema.reset();
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+    }
 };
 
 /**
- * Relative Strength Index (RSI)
+ * Tillson T3 Moving Average (T3)
  *
- * Calculates the RSI based on the average gains and losses over a
- * specified period. Returns NaN until enough values have been added to
- * fill the window. Once the window is full, it calculates the RSI using
- * the formula:
+ * Six EMAs chained, each over the previous one, combined to reduce the
+ * lag of the result with the volume factor `v` (0 behaves as a triple
+ * EMA, 1 as a DEMA of DEMAs):
  *
- * RSI = 100 - (100 / (1 + (Average Gain / Average Loss)))
+ * T3 = c1 * e6 + c2 * e5 + c3 * e4 + c4 * e3
  *
- * where Average Gain and Average Loss are calculated using the SMA of
- * the gains and losses over the specified period. The N template
- * parameter specifies the size of the window for calculating the
- * average gains and losses.
+ * with c1 = -v^3, c2 = 3v^2 + 3v^3, c3 = -6v^2 - 3v - 3v^3 and
+ * c4 = 1 + 3v + v^3 + 3v^2. Returns NaN until `6 * (period - 1) + 1`
+ * values have been added.
  */
-class RSI: public Indicator<RSI, Ohlcv, double> {
+class T3: public Indicator<T3, double, double> {
     double data = std::nan("");
-    SMA gains;
-    SMA losses;
+    std::vector<EMA> emas;
+    double c1, c2, c3, c4;
 public:
-    RSI(size_t period): gains(period), losses(period) {}
+    T3(size_t period, double v = 0.7)
+        : emas(6, EMA(period)),
+          c1(-v * v * v),
+          c2(3.0 * v * v + 3.0 * v * v * v),
+          c3(-6.0 * v * v - 3.0 * v - 3.0 * v * v * v),
+          c4(1.0 + 3.0 * v + v * v * v + 3.0 * v * v) {}
     double get() const noexcept { return data; }
-    double update(Ohlcv value) {
-        double diff = value.close - value.open;
-        gains.update(diff >= 0.0 ? diff : 0.0);
-        losses.update(diff < 0 ? -diff : 0.0);
-        if (std::isnan(losses.get()))
-            return std::nan("");
-        data = 100.0 - 100.0 / (1.0 + gains.get() / losses.get());
+    size_t required_warmup() const noexcept {
+        return 6 * (emas[0].required_warmup() - 1) + 1;
+    }
+    double update(double value) {
+        for (auto& ema : emas) {
+            value = ema.update(value);
+            if (std::isnan(value))
+                return data;
+        }
+        data = c1 * emas[5].get() + c2 * emas[4].get()
+            + c3 * emas[3].get() + c4 * emas[2].get();
         return data;
     }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, emas);
+    }
+    void reset_state() {
+        data = std::nan("");
+        for (auto& ema : emas) ema.reset();
+    }
 };
 
 /**
- * Moving Average Convergence Divergence Result (MACDResult)
+ * Kaufman Adaptive Moving Average (KAMA)
  *
- * Holds the current values of the MACD line, signal line, and
- * histogram. The MACD line is the difference between the short-term EMA
- * and the long-term EMA. The signal line is the EMA of the MACD line,
- * and the histogram is the difference between the MACD line and the
- * signal line.
+ * An EMA whose smoothing adapts to the efficiency ratio of the last
+ * `period` changes, the net change divided by the sum of the absolute
+ * changes: close to 1 in a steady trend, close to 0 in noise.
+ *
+ * SC = (ER * (2 / (fast + 1) - 2 / (slow + 1)) + 2 / (slow + 1))^2
+ * KAMA = KAMA_prev + SC * (Value - KAMA_prev)
+ *
+ * The window is allocated once, at construction, and both sums are
+ * updated in constant time. Returns NaN until `period + 1` values have
+ * been added; the first KAMA starts from the previous value.
  */
-struct MACDResult {
-    double macd;
-    double signal;
-    double histogram;
+class KAMA: public Indicator<KAMA, double, double> {
+    double data = std::nan("");
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    double volatility = 0.0;
+    double fast_sc;
+    double slow_sc;
+public:
+    KAMA(size_t period, size_t fast = 2, size_t slow = 30)
+        : prev(period + 1, 0.0),
+          fast_sc(2.0 / (fast + 1.0)),
+          slow_sc(2.0 / (slow + 1.0)) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    double update(double value) {
+        size_t n = prev.size();
+        size_t last = (pos + n - 1) % n;
+        if (len > 0) volatility += std::fabs(value - prev[last]);
+        if (len == n) {
+            // the oldest change leaves the window
+            volatility -= std::fabs(prev[(pos + 1) % n] - prev[pos]);
+        } else {
+            len++;
+        }
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (len < n)
+            return std::nan("");
+        double change = std::fabs(value - prev[pos]);
+        double er = volatility > 0.0 ? change / volatility : 0.0;
+        if (er > 1.0) er = 1.0;
+        double sc = er * (fast_sc - slow_sc) + slow_sc;
+        sc *= sc;
+        double base = std::isnan(data) ? prev[last] : data;
+        data = base + sc * (value - base);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len, volatility);
+    }
+    void reset_state() {
+        data = std::nan("");
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+        volatility = 0.0;
+    }
 };
 
 /**
- * Moving Average Convergence Divergence (MACD)
+ * Moving Variance (MVar)
+ * 
+ * Calculates the variance of the most recent N values. Returns NaN
+ * until enough values have been added to fill the window. Once the
+ * window is full, it calculates the variance using the formula:
  *
- * Combines multiple EMAs to calculate the MACD line, signal line, and
- * histogram. Returns NaN for all values until enough data has been
- * added to fill the initial periods for both EMAs. Once the initial
+ * Variance = (1 / (N - dof)) * sum((x_i - mean)^2)
+ *
+ * where dof is the degrees of freedom, which is typically 1 for sample
+ * variance and 0 for population variance, and N is `window_size`.
+ *
+ * The standard deviation can be obtained by taking the square root of
+ * the variance.
+ *
+ * The mean and the sum of squared deviations are updated with
+ * Welford's method as values enter and leave the window, so updates
+ * take constant time, and they are recomputed from the window each
+ * time it wraps around, so rounding errors don't build up. Deviations
+ * are taken from the mean, not from zero, which keeps small variances
+ * accurate at large price levels.
+ */
+class MVar: public Indicator<MVar, double, double> {
+    double data = std::nan("");
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    double mean = 0.0;
+    double m2 = 0.0;
+    size_t dof;
+
+    void resum() {
+        double sum = 0.0;
+        for (double x : prev) sum += x;
+        mean = sum / static_cast<double>(prev.size());
+        m2 = 0.0;
+        for (double x : prev) m2 += (x - mean) * (x - mean);
+    }
+public:
+    MVar(size_t window_size, size_t dof)
+        : prev(window_size, 0.0), dof(dof) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    double update(double value) {
+        size_t n = prev.size();
+        if (len < n) {
+            len++;
+            double delta = value - mean;
+            mean += delta / static_cast<double>(len);
+            m2 += delta * (value - mean);
+        } else {
+            double old = prev[pos];
+            double old_mean = mean;
+            mean += (value - old) / static_cast<double>(n);
+            m2 += (value - old) * (value - mean + old - old_mean);
+        }
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (len < n)
+            return data;
+        if (pos == 0) resum();
+        data = std::max(m2, 0.0) / static_cast<double>(n - dof);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len, mean, m2);
+    }
+    void reset_state() {
+        data = std::nan("");
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+        mean = m2 = 0.0;
+    }
+};
+
+/**
+ * Rolling Sharpe Ratio (Sharpe)
+ *
+ * Sharpe ratio of the most recent `period` returns, e.g. the daily
+ * returns of a strategy, with a zero risk-free rate:
+ *
+ * Sharpe = mean / stddev * sqrt(periods_per_year)
+ *
+ * using the sample standard deviation. Pass 1 as `periods_per_year`
+ * for a ratio per period. Returns NaN until the window is full, and
+ * zero if the returns have no dispersion.
+ */
+class Sharpe: public Indicator<Sharpe, double, double> {
+    double data = std::nan("");
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    double sum = 0.0;
+    double sum_sq = 0.0;
+    double scale;
+public:
+    Sharpe(size_t period, double periods_per_year = 1.0)
+        : prev(period, 0.0), scale(std::sqrt(periods_per_year)) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    double update(double value) {
+        size_t n = prev.size();
+        if (len < n) {
+            len++;
+        } else {
+            sum -= prev[pos];
+            sum_sq -= prev[pos] * prev[pos];
+        }
+        sum += value;
+        sum_sq += value * value;
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (pos == 0) {
+            sum = sum_sq = 0.0;
+            for (double x : prev) {
+                sum += x;
+                sum_sq += x * x;
+            }
+        }
+        if (len < n || n < 2)
+            return data;
+        double mean = sum / n;
+        double var = (sum_sq - n * mean * mean) / (n - 1);
+        data = var > 1e-18 ? mean / std::sqrt(var) * scale : 0.0;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len, sum, sum_sq);
+    }
+    void reset_state() {
+        data = std::nan("");
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+        sum = sum_sq = 0.0;
+    }
+};
+
+/**
+ * Rolling Sortino Ratio (Sortino)
+ *
+ * Like the Sharpe ratio, but only penalizes losses: the mean of the
+ * most recent `period` returns is divided by their downside deviation,
+ * sqrt(sum(min(r, 0)^2) / period), and annualized with
+ * `periods_per_year`. Returns NaN until the window is full, and zero
+ * if there are no negative returns in the window.
+ */
+class Sortino: public Indicator<Sortino, double, double> {
+    double data = std::nan("");
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    double sum = 0.0;
+    double down_sq = 0.0;
+    double scale;
+public:
+    Sortino(size_t period, double periods_per_year = 1.0)
+        : prev(period, 0.0), scale(std::sqrt(periods_per_year)) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    double update(double value) {
+        size_t n = prev.size();
+        if (len < n) {
+            len++;
+        } else {
+            double old = prev[pos];
+            sum -= old;
+            if (old < 0.0) down_sq -= old * old;
+        }
+        sum += value;
+        if (value < 0.0) down_sq += value * value;
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (pos == 0) {
+            sum = down_sq = 0.0;
+            for (double x : prev) {
+                sum += x;
+                if (x < 0.0) down_sq += x * x;
+            }
+        }
+        if (len < n)
+            return data;
+        double downdev = std::sqrt(std::fmax(down_sq, 0.0) / n);
+        data = downdev > 1e-9 ? sum / n / downdev * scale : 0.0;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len, sum, down_sq);
+    }
+    void reset_state() {
+        data = std::nan("");
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+        sum = down_sq = 0.0;
+    }
+};
+
+/**
+ * Relative Strength Index (RSI)
+ *
+ * Calculates the RSI based on the average gains and losses over a
+ * specified period. Returns NaN until enough values have been added to
+ * fill the window. Once the window is full, it calculates the RSI using
+ * the formula:
+ *
+ * RSI = 100 - (100 / (1 + (Average Gain / Average Loss)))
+ *
+ * where Average Gain and Average Loss are calculated using the SMA of
+ * the gains and losses over the specified period.
+ */
+class RSI: public Indicator<RSI, Ohlcv, double> {
+    double data = std::nan("");
+    SMA gains;
+    SMA losses;
+public:
+    RSI(size_t period): gains(period), losses(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return gains.required_warmup(); }
+    double update(Ohlcv value) {
+        double diff = value.close - value.open;
+        gains.update(diff >= 0.0 ? diff : 0.0);
+        losses.update(diff < 0 ? -diff : 0.0);
+        if (std::isnan(losses.get()))
+            return std::nan("");
+        data = 100.0 - 100.0 / (1.0 + gains.get() / losses.get());
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, gains, losses);
+    }
+    void reset_state() {
+        data = std::nan("");
+        gains.reset();
+        losses.reset();
+    }
+};
+
+/**
+ * Moving Average Convergence Divergence Result (MACDResult)
+ *
+ * Holds the current values of the MACD line, signal line, and
+ * histogram. The MACD line is the difference between the short-term EMA
+ * and the long-term EMA. The signal line is the EMA of the MACD line,
+ * and the histogram is the difference between the MACD line and the
+ * signal line.
+ */
+struct MACDResult {
+    double macd;
+    double signal;
+    double histogram;
+};
+
+/**
+ * Moving Average Convergence Divergence (MACD)
+ *
+ * Combines multiple EMAs to calculate the MACD line, signal line, and
+ * histogram. Returns NaN for all values until enough data has been
+ * added to fill the initial periods for both EMAs. Once the initial
  * periods are filled, it calculates the MACD line as the difference
  * between the short-term EMA and the long-term EMA. The signal line is
  * calculated as the EMA of the MACD line, and the histogram is the
  * difference between the MACD line and the signal line.
  */
 class MACD: public Indicator<MACD, double, MACDResult> {
-    MACDResult data;
+    MACDResult data = {std::nan(""), std::nan(""), std::nan("")};
     EMA short_ema;
     EMA long_ema;
     EMA signal_ema;
@@ -228,19 +1275,896 @@ public:
           signal_ema(signal_period, smoothing),
           start(std::fmax(short_period, long_period)) {}
     MACDResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return start + signal_ema.required_warmup();
+    }
+    /**
+     * The MACD line is available before the signal line, which
+     * completes the outputs.
+     */
+    bool is_ready() const noexcept { return !std::isnan(data.signal); }
     MACDResult update(double value) {
         len++;
         short_ema.update(value);
         long_ema.update(value);
         if (len <= start)
-            return {std::nan(""), std::nan(""), std::nan("")};
+            return data;
         double diff = short_ema.get() - long_ema.get();
         signal_ema.update(diff);
         data = {diff, signal_ema.get(), diff - signal_ema.get()};
         return data;
     }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, short_ema, long_ema, signal_ema, len);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan(""), std::nan("")};
+        short_ema.reset();
+        long_ema.reset();
+        signal_ema.reset();
+        len = 0;
+    }
+};
+
+/**
+ * Linear Regression Result (LinRegResult)
+ *
+ * Holds the least-squares line fitted to the window, with x from 0 for
+ * the oldest value to `period - 1` for the latest: its slope and
+ * intercept, its value at the latest point, its forecast for the next
+ * one, the coefficient of determination R², and a channel of `k`
+ * standard errors around the value.
+ */
+struct LinRegResult {
+    double slope;
+    double intercept;
+    double value;
+    double forecast;
+    double r2;
+    double upper;
+    double lower;
+};
+
+/**
+ * Linear Regression (LinReg)
+ *
+ * Fits a line by least squares to the most recent `period` values.
+ * Returns NaN fields until the window is full. The standard error is
+ * sqrt(SSE / (period - 2)), zero for windows of two values, and a
+ * window of constant values has an R² of one.
+ */
+class LinReg: public Indicator<LinReg, double, LinRegResult> {
+    LinRegResult data = {std::nan(""), std::nan(""), std::nan(""),
+        std::nan(""), std::nan(""), std::nan(""), std::nan("")};
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    double k;
+public:
+    LinReg(size_t period, double k = 2.0): prev(period, 0.0), k(k) {}
+    LinRegResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    LinRegResult update(double value) {
+        size_t n = prev.size();
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (len < n) len++;
+        if (len < n)
+            return data;
+        double x_mean = (n - 1) / 2.0;
+        double y_mean = 0.0;
+        for (double y : prev) y_mean += y;
+        y_mean /= n;
+        double sxy = 0.0, sxx = 0.0, syy = 0.0;
+        for (size_t i = 0; i < n; i++) {
+            // `pos` is the oldest value in the circular buffer
+            double dx = i - x_mean;
+            double dy = prev[(pos + i) % n] - y_mean;
+            sxy += dx * dy;
+            sxx += dx * dx;
+            syy += dy * dy;
+        }
+        double slope = sxx > 0.0 ? sxy / sxx : 0.0;
+        double intercept = y_mean - slope * x_mean;
+        double sse = syy - slope * sxy;
+        if (sse < 0.0) sse = 0.0;
+        double stderr_ = n > 2 ? std::sqrt(sse / (n - 2)) : 0.0;
+        double fitted = intercept + slope * (n - 1);
+        data = {slope, intercept, fitted, fitted + slope,
+            syy > 0.0 ? 1.0 - sse / syy : 1.0,
+            fitted + k * stderr_, fitted - k * stderr_};
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan(""), std::nan(""), std::nan(""),
+            std::nan(""), std::nan(""), std::nan("")};
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+    }
+};
+
+/**
+ * True range of a bar: the largest of its high-low range and the
+ * distances from the previous close to its high and low. With a NaN
+ * previous close, it is the high-low range.
+ */
+inline double true_range(const Ohlcv& bar, double prev_close) {
+    double tr = bar.high - bar.low;
+    if (!std::isnan(prev_close)) {
+        tr = std::fmax(tr, std::fabs(bar.high - prev_close));
+        tr = std::fmax(tr, std::fabs(bar.low - prev_close));
+    }
+    return tr;
+}
+
+/**
+ * Average True Range (ATR)
+ *
+ * Wilder's average of the true range. The first value is the mean of
+ * the true ranges of the first `period` bars, and later values are
+ * smoothed as:
+ *
+ * ATR = (ATR_prev * (period - 1) + TR) / period
+ *
+ * Returns NaN until `period` bars have been added.
+ */
+class ATR: public Indicator<ATR, Ohlcv, double> {
+    double data = std::nan("");
+    size_t period;
+    size_t len = 0;
+    double sum = 0.0;
+    double prev_close = std::nan("");
+public:
+    ATR(size_t period): period(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return period; }
+    double update(const Ohlcv& value) {
+        double tr = true_range(value, prev_close);
+        prev_close = value.close;
+        len++;
+        if (len < period) {
+            sum += tr;
+        } else if (len == period) {
+            data = (sum + tr) / period;
+        } else {
+            data = (data * (period - 1) + tr) / period;
+        }
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, len, sum, prev_close);
+    }
+    void reset_state() {
+        data = std::nan("");
+        len = 0;
+        sum = 0.0;
+        prev_close = std::nan("");
+    }
+};
+
+/**
+ * Average Directional Index Result (ADXResult)
+ *
+ * Holds the ADX, which measures the strength of the trend regardless
+ * of its direction, and the positive and negative directional
+ * indicators (+DI and -DI), which measure upward and downward
+ * movement.
+ */
+struct ADXResult {
+    double adx;
+    double plus_di;
+    double minus_di;
+};
+
+/**
+ * Average Directional Index (ADX)
+ *
+ * Welles Wilder's directional movement system. For each bar, the
+ * directional movements are +DM = high - previous high and -DM =
+ * previous low - low, keeping only the larger one if positive, and the
+ * true range TR is computed. The three series are smoothed with
+ * Wilder's method over `period` bars, and:
+ *
+ * +DI = 100 * +DM_smoothed / TR_smoothed
+ * -DI = 100 * -DM_smoothed / TR_smoothed
+ * DX = 100 * |+DI - -DI| / (+DI + -DI)
+ *
+ * ADX is the Wilder average of DX. The directional indicators are NaN
+ * until `period + 1` bars have been added, and ADX until `2 * period`
+ * bars have been added.
+ */
+class ADX: public Indicator<ADX, Ohlcv, ADXResult> {
+    ADXResult data = {std::nan(""), std::nan(""), std::nan("")};
+    size_t period;
+    Ohlcv prev;
+    size_t len = 0;
+    double tr_sum = 0.0;
+    double plus_sum = 0.0;
+    double minus_sum = 0.0;
+    double dx_sum = 0.0;
+    double adx = std::nan("");
+public:
+    ADX(size_t period): period(period) {}
+    ADXResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 2 * period; }
+    ADXResult update(const Ohlcv& value) {
+        len++;
+        if (len == 1) {
+            prev = value;
+            return data;
+        }
+        double up = value.high - prev.high;
+        double down = prev.low - value.low;
+        double plus_dm = up > down && up > 0.0 ? up : 0.0;
+        double minus_dm = down > up && down > 0.0 ? down : 0.0;
+        double tr = true_range(value, prev.close);
+        prev = value;
+        if (len <= period + 1) {
+            tr_sum += tr;
+            plus_sum += plus_dm;
+            minus_sum += minus_dm;
+        } else {
+            tr_sum = tr_sum - tr_sum / period + tr;
+            plus_sum = plus_sum - plus_sum / period + plus_dm;
+            minus_sum = minus_sum - minus_sum / period + minus_dm;
+        }
+        if (len < period + 1)
+            return data;
+        double plus_di = tr_sum > 0.0 ? 100.0 * plus_sum / tr_sum : 0.0;
+        double minus_di = tr_sum > 0.0 ? 100.0 * minus_sum / tr_sum : 0.0;
+        double di_sum = plus_di + minus_di;
+        double dx = di_sum > 0.0
+            ? 100.0 * std::fabs(plus_di - minus_di) / di_sum : 0.0;
+        if (len < 2 * period) {
+            dx_sum += dx;
+        } else if (len == 2 * period) {
+            dx_sum += dx;
+            adx = dx_sum / period;
+        } else {
+            adx = (adx * (period - 1) + dx) / period;
+        }
+        data = {adx, plus_di, minus_di};
+        return data;
+    }
+    void reset_state() {
+        *this = ADX(period);
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, len, tr_sum, plus_sum, minus_sum, dx_sum, adx);
+    }
+};
+
+/**
+ * Elder Ray Result (ElderRayResult)
+ *
+ * Holds the bull power, how far buyers pushed the high above the
+ * consensus value (the EMA), and the bear power, how far sellers pushed
+ * the low below it.
+ */
+struct ElderRayResult {
+    double bull;
+    double bear;
+};
+
+/**
+ * Elder Ray (ElderRay)
+ *
+ * Alexander Elder's bull and bear power, computed from an EMA of the
+ * close price:
+ *
+ * Bull Power = High - EMA
+ * Bear Power = Low - EMA
+ *
+ * Both are NaN until the EMA has `period` values.
+ */
+class ElderRay: public Indicator<ElderRay, Ohlcv, ElderRayResult> {
+    ElderRayResult data = {std::nan(""), std::nan("")};
+    EMA ema;
+public:
+    ElderRay(size_t period, double smoothing = 2.0): ema(period, smoothing) {}
+    ElderRayResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return ema.required_warmup(); }
+    ElderRayResult update(const Ohlcv& value) {
+        double avg = ema.update(value.close);
+        data = {value.high - avg, value.low - avg};
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, ema);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan("")};
+        ema.reset();
+    }
+};
+
+namespace detail {
+
+/**
+ * Extreme of the last `period` values, the highest with `std::greater`
+ * and the lowest with `std::less`, in constant amortized time instead
+ * of scanning the window. A deque keeps, in order, the values not
+ * beaten by a newer one, so the extreme is the oldest of them. Ties go
+ * to the most recent value.
+ */
+template <typename Compare>
+class RollingExtreme {
+    struct Entry {
+        size_t index;
+        double value;
+        template <class Archive>
+        void serialize(Archive& ar) { ar(index, value); }
+    };
+    std::deque<Entry> items;
+    size_t period_;
+    size_t count = 0;
+public:
+    explicit RollingExtreme(size_t period): period_(period) {}
+    void push(double value) {
+        while (!items.empty() && !Compare()(items.back().value, value))
+            items.pop_back();
+        items.push_back(Entry{count, value});
+        count++;
+        if (items.front().index + period_ < count)
+            items.pop_front();
+    }
+    double value() const {
+        return items.empty() ? std::nan("") : items.front().value;
+    }
+    /**
+     * Number of values added after the extreme.
+     */
+    size_t age() const {
+        return items.empty() ? 0 : count - 1 - items.front().index;
+    }
+    bool full() const noexcept { return count >= period_; }
+    size_t period() const noexcept { return period_; }
+    template <class Archive>
+    void serialize(Archive& ar) { ar(items, count); }
+    void clear() {
+        items.clear();
+        count = 0;
+    }
+};
+
+using RollingMax = RollingExtreme<std::greater<double>>;
+using RollingMin = RollingExtreme<std::less<double>>;
+
+} // namespace detail
+
+/**
+ * Highest and Lowest Values (Highest, Lowest)
+ *
+ * The highest or the lowest of the last `period` values, e.g. the
+ * highest high when fed with the highs (see `Applied`), as used by the
+ * Donchian channels or the stochastic oscillator. Updates take
+ * constant amortized time. `age()` tells how many values were added
+ * after the extreme, the most recent winning ties. Returns NaN until
+ * `period` values have been added.
+ */
+template <typename Window>
+class Extreme: public Indicator<Extreme<Window>, double, double> {
+    double data = std::nan("");
+    Window window;
+public:
+    Extreme(size_t period): window(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.period(); }
+    size_t age() const { return window.age(); }
+    double update(double value) {
+        window.push(value);
+        data = window.full() ? window.value() : std::nan("");
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.clear();
+    }
+};
+
+using Highest = Extreme<detail::RollingMax>;
+using Lowest = Extreme<detail::RollingMin>;
+
+/**
+ * Aroon Result (AroonResult)
+ *
+ * Aroon Up and Down, from 0 to 100, and the oscillator, their
+ * difference.
+ */
+struct AroonResult {
+    double up;
+    double down;
+    double oscillator;
+};
+
+/**
+ * Aroon
+ *
+ * Measures how recently the highest high and the lowest low of the
+ * last `period + 1` bars occurred:
+ *
+ * Up = 100 * (period - bars since the highest high) / period
+ * Down = 100 * (period - bars since the lowest low) / period
+ *
+ * Ties go to the most recent bar. Returns NaN values until
+ * `period + 1` bars have been added.
+ */
+class Aroon: public Indicator<Aroon, Ohlcv, AroonResult> {
+    AroonResult data = {std::nan(""), std::nan(""), std::nan("")};
+    detail::RollingMax highs;
+    detail::RollingMin lows;
+public:
+    Aroon(size_t period): highs(period + 1), lows(period + 1) {}
+    AroonResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return highs.period(); }
+    AroonResult update(const Ohlcv& value) {
+        highs.push(value.high);
+        lows.push(value.low);
+        if (!highs.full())
+            return data;
+        double period = static_cast<double>(highs.period() - 1);
+        double up = 100.0 * (period - highs.age()) / period;
+        double down = 100.0 * (period - lows.age()) / period;
+        data = {up, down, up - down};
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, highs, lows);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan(""), std::nan("")};
+        highs.clear();
+        lows.clear();
+    }
+};
+
+/**
+ * Stochastic RSI Result (StochRSIResult)
+ *
+ * Holds %K, the smoothed position of the RSI within its recent range,
+ * from 0 to 100, and %D, its moving average.
+ */
+struct StochRSIResult {
+    double k;
+    double d;
+};
+
+/**
+ * Stochastic RSI (StochRSI)
+ *
+ * The stochastic oscillator applied to the RSI instead of the price:
+ *
+ * StochRSI = 100 * (RSI - min(RSI)) / (max(RSI) - min(RSI))
+ *
+ * over the last `stoch_period` RSI values, zero if they are all equal.
+ * %K is its SMA over `k_period` and %D the SMA of %K over `d_period`.
+ * Only complete RSI values enter the window. %K is NaN until
+ * `rsi_period + stoch_period + k_period - 2` bars have been added, and
+ * %D until `d_period - 1` more.
+ */
+class StochRSI: public Indicator<StochRSI, Ohlcv, StochRSIResult> {
+    StochRSIResult data = {std::nan(""), std::nan("")};
+    RSI rsi;
+    detail::RollingMax highs;
+    detail::RollingMin lows;
+    SMA k_sma;
+    SMA d_sma;
+public:
+    StochRSI(size_t rsi_period = 14, size_t stoch_period = 14,
+            size_t k_period = 3, size_t d_period = 3)
+        : rsi(rsi_period), highs(stoch_period), lows(stoch_period),
+          k_sma(k_period), d_sma(d_period) {}
+    StochRSIResult get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return rsi.required_warmup() + highs.period() + k_sma.required_warmup()
+            + d_sma.required_warmup() - 3;
+    }
+    /**
+     * %K is available before %D, which completes the outputs.
+     */
+    bool is_ready() const noexcept { return !std::isnan(data.d); }
+    StochRSIResult update(const Ohlcv& value) {
+        double r = rsi.update(value);
+        if (std::isnan(r))
+            return data;
+        highs.push(r);
+        lows.push(r);
+        if (!highs.full())
+            return data;
+        double high = highs.value(), low = lows.value();
+        double stoch = high > low ? 100.0 * (r - low) / (high - low) : 0.0;
+        double k = k_sma.update(stoch);
+        if (std::isnan(k))
+            return data;
+        data = {k, d_sma.update(k)};
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, rsi, highs, lows, k_sma, d_sma);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan("")};
+        rsi.reset();
+        highs.clear();
+        lows.clear();
+        k_sma.reset();
+        d_sma.reset();
+    }
+};
+
+/**
+ * Pivot point calculation methods.
+ */
+enum class PivotScheme {
+    CLASSIC,
+    FIBONACCI,
+    CAMARILLA
+};
+
+/**
+ * Pivot Levels (PivotLevels)
+ *
+ * Holds the pivot point and three resistance and support levels.
+ */
+struct PivotLevels {
+    double pp;
+    double r1;
+    double r2;
+    double r3;
+    double s1;
+    double s2;
+    double s3;
+};
+
+/**
+ * Pivot levels for the period after `bar`. All the schemes share the
+ * pivot point PP = (H + L + C) / 3, and with R = H - L:
+ *
+ * Classic: R1 = 2PP - L, S1 = 2PP - H, R2/S2 = PP +/- R,
+ *          R3 = H + 2(PP - L), S3 = L - 2(H - PP)
+ * Fibonacci: PP +/- 0.382R, 0.618R and R
+ * Camarilla: C +/- 1.1R/12, 1.1R/6 and 1.1R/4
+ */
+inline PivotLevels pivot_levels(const Ohlcv& bar,
+        PivotScheme scheme = PivotScheme::CLASSIC) {
+    double pp = (bar.high + bar.low + bar.close) / 3.0;
+    double range = bar.high - bar.low;
+    switch (scheme) {
+        case PivotScheme::FIBONACCI:
+            return {pp, pp + 0.382 * range, pp + 0.618 * range, pp + range,
+                pp - 0.382 * range, pp - 0.618 * range, pp - range};
+        case PivotScheme::CAMARILLA: {
+            double c = bar.close, d = 1.1 * range;
+            return {pp, c + d / 12.0, c + d / 6.0, c + d / 4.0,
+                c - d / 12.0, c - d / 6.0, c - d / 4.0};
+        }
+        case PivotScheme::CLASSIC:
+            break;
+    }
+    return {pp, 2.0 * pp - bar.low, pp + range,
+        bar.high + 2.0 * (pp - bar.low), 2.0 * pp - bar.high, pp - range,
+        bar.low - 2.0 * (bar.high - pp)};
+}
+
+/**
+ * Pivot Points (Pivots)
+ *
+ * Support and resistance levels from the last completed bar of a
+ * higher timeframe, e.g. the previous day. Feed it with the completed
+ * bars of a `Resampler` and query `get()` from the intraday bars.
+ * Returns NaN levels until a bar has been added.
+ */
+class Pivots: public Indicator<Pivots, Ohlcv, PivotLevels> {
+    PivotLevels data = {std::nan(""), std::nan(""), std::nan(""),
+        std::nan(""), std::nan(""), std::nan(""), std::nan("")};
+    PivotScheme scheme;
+public:
+    Pivots(PivotScheme scheme = PivotScheme::CLASSIC): scheme(scheme) {}
+    PivotLevels get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    PivotLevels update(const Ohlcv& value) {
+        data = pivot_levels(value, scheme);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data);
+    }
+    void reset_state() {
+        data = {std::nan(""), std::nan(""), std::nan(""), std::nan(""),
+            std::nan(""), std::nan(""), std::nan("")};
+    }
+};
+
+/**
+ * Time-based Simple Moving Average (TimeSMA)
+ *
+ * Averages the values whose timestamps fall within the trailing
+ * `duration` (in the same unit as the timestamps), regardless of how
+ * many values that is, which is useful with irregular bars. Returns NaN
+ * until the values seen span at least `duration`, which is at least 1.
+ * Bars are averaged by their close price.
+ */
+class TimeSMA: public Indicator<TimeSMA, SingleValue, double> {
+    double data = std::nan("");
+    int64_t duration;
+    std::deque<SingleValue> prev;
+    int64_t first = 0;
+    bool started = false;
+    double sum = 0.0;
+public:
+    TimeSMA(int64_t duration): duration(duration > 0 ? duration : 1) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 0; }
+    double update(SingleValue value) {
+        if (!started) {
+            first = value.timestamp;
+            started = true;
+        }
+        prev.push_back(value);
+        sum += value.value;
+        while (!prev.empty()
+                && prev.front().timestamp <= value.timestamp - duration) {
+            sum -= prev.front().value;
+            prev.pop_front();
+        }
+        data = value.timestamp - first < duration
+            ? std::nan("")
+            : sum / static_cast<double>(prev.size());
+        return data;
+    }
+    double update(const Ohlcv& bar) {
+        return update(SingleValue(bar.timestamp, bar.close));
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, first, started, sum);
+    }
+    void reset_state() {
+        data = std::nan("");
+        prev.clear();
+        first = 0;
+        started = false;
+        sum = 0.0;
+    }
+};
+
+/**
+ * Time-based Moving Variance (TimeMVar)
+ *
+ * Variance of the values within the trailing `duration`, at least 1,
+ * with `dof` degrees of freedom as in `MVar`. Returns NaN until the
+ * values seen span at least `duration`, or if the window holds no more
+ * than `dof` values. For the volatility over the last 24 hours of
+ * returns, feed it with returns and take the square root.
+ */
+class TimeMVar: public Indicator<TimeMVar, SingleValue, double> {
+    double data = std::nan("");
+    int64_t duration;
+    size_t dof;
+    std::deque<SingleValue> prev;
+    int64_t first = 0;
+    bool started = false;
+    double shift = 0.0;
+    double sum = 0.0;
+    double sum2 = 0.0;
+public:
+    TimeMVar(int64_t duration, size_t dof)
+        : duration(duration > 0 ? duration : 1), dof(dof) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 0; }
+    double update(SingleValue value) {
+        if (!started) {
+            // sums are taken around the first value to limit cancellation
+            first = value.timestamp;
+            shift = value.value;
+            started = true;
+        }
+        prev.push_back(value);
+        double x = value.value - shift;
+        sum += x;
+        sum2 += x * x;
+        while (!prev.empty()
+                && prev.front().timestamp <= value.timestamp - duration) {
+            double y = prev.front().value - shift;
+            sum -= y;
+            sum2 -= y * y;
+            prev.pop_front();
+        }
+        double n = static_cast<double>(prev.size());
+        if (value.timestamp - first < duration || prev.size() <= dof) {
+            data = std::nan("");
+        } else {
+            double var = (sum2 - sum * sum / n) / (n - dof);
+            data = var > 0.0 ? var : 0.0;
+        }
+        return data;
+    }
+    double update(const Ohlcv& bar) {
+        return update(SingleValue(bar.timestamp, bar.close));
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, first, started, shift, sum, sum2);
+    }
+    void reset_state() {
+        data = std::nan("");
+        prev.clear();
+        first = 0;
+        started = false;
+        shift = sum = sum2 = 0.0;
+    }
+};
+
+template <>
+struct OutputCheck<MACDResult> {
+    static IndicatorStatus check(const MACDResult& r) {
+        return OutputCheck<double>::check(r.macd);
+    }
+};
+
+template <>
+struct OutputCheck<LinRegResult> {
+    static IndicatorStatus check(const LinRegResult& r) {
+        return OutputCheck<double>::check(r.value);
+    }
 };
 
+template <>
+struct OutputCheck<ADXResult> {
+    static IndicatorStatus check(const ADXResult& r) {
+        return OutputCheck<double>::check(r.adx);
+    }
+};
+
+template <>
+struct OutputCheck<ElderRayResult> {
+    static IndicatorStatus check(const ElderRayResult& r) {
+        return OutputCheck<double>::check(r.bull);
+    }
+};
+
+template <>
+struct OutputCheck<AroonResult> {
+    static IndicatorStatus check(const AroonResult& r) {
+        return OutputCheck<double>::check(r.up);
+    }
+};
+
+template <>
+struct OutputCheck<StochRSIResult> {
+    static IndicatorStatus check(const StochRSIResult& r) {
+        return OutputCheck<double>::check(r.k);
+    }
+};
+
+template <>
+struct OutputCheck<PivotLevels> {
+    static IndicatorStatus check(const PivotLevels& r) {
+        return OutputCheck<double>::check(r.pp);
+    }
+};
+
+template <>
+struct Outputs<MACDResult> {
+    static std::vector<std::string> names() {
+        return {"macd", "signal", "histogram"};
+    }
+    static std::vector<double> values(const MACDResult& r) {
+        return {r.macd, r.signal, r.histogram};
+    }
+};
+
+template <>
+struct Outputs<LinRegResult> {
+    static std::vector<std::string> names() {
+        return {"slope", "intercept", "value", "forecast", "r2", "upper",
+            "lower"};
+    }
+    static std::vector<double> values(const LinRegResult& r) {
+        return {r.slope, r.intercept, r.value, r.forecast, r.r2, r.upper,
+            r.lower};
+    }
+};
+
+template <>
+struct Outputs<ADXResult> {
+    static std::vector<std::string> names() {
+        return {"adx", "plus_di", "minus_di"};
+    }
+    static std::vector<double> values(const ADXResult& r) {
+        return {r.adx, r.plus_di, r.minus_di};
+    }
+};
+
+template <>
+struct Outputs<ElderRayResult> {
+    static std::vector<std::string> names() { return {"bull", "bear"}; }
+    static std::vector<double> values(const ElderRayResult& r) {
+        return {r.bull, r.bear};
+    }
+};
+
+template <>
+struct Outputs<AroonResult> {
+    static std::vector<std::string> names() {
+        return {"up", "down", "oscillator"};
+    }
+    static std::vector<double> values(const AroonResult& r) {
+        return {r.up, r.down, r.oscillator};
+    }
+};
+
+template <>
+struct Outputs<StochRSIResult> {
+    static std::vector<std::string> names() { return {"k", "d"}; }
+    static std::vector<double> values(const StochRSIResult& r) {
+        return {r.k, r.d};
+    }
+};
+
+template <>
+struct Outputs<PivotLevels> {
+    static std::vector<std::string> names() {
+        return {"pp", "r1", "r2", "r3", "s1", "s2", "s3"};
+    }
+    static std::vector<double> values(const PivotLevels& r) {
+        return {r.pp, r.r1, r.r2, r.r3, r.s1, r.s2, r.s3};
+    }
+};
+
+/**
+ * State of the results kept by the indicators, for `save_state`.
+ */
+template <class Archive>
+void serialize(Archive& ar, MACDResult& r) {
+    ar(r.macd, r.signal, r.histogram);
+}
+
+template <class Archive>
+void serialize(Archive& ar, LinRegResult& r) {
+    ar(r.slope, r.intercept, r.value, r.forecast, r.r2, r.upper, r.lower);
+}
+
+template <class Archive>
+void serialize(Archive& ar, ADXResult& r) {
+    ar(r.adx, r.plus_di, r.minus_di);
+}
+
+template <class Archive>
+void serialize(Archive& ar, ElderRayResult& r) {
+    ar(r.bull, r.bear);
+}
+
+template <class Archive>
+void serialize(Archive& ar, AroonResult& r) {
+    ar(r.up, r.down, r.oscillator);
+}
+
+template <class Archive>
+void serialize(Archive& ar, StochRSIResult& r) {
+    ar(r.k, r.d);
+}
+
+template <class Archive>
+void serialize(Archive& ar, PivotLevels& r) {
+    ar(r.pp, r.r1, r.r2, r.r3, r.s1, r.s2, r.s3);
+}
+
 } // namespace tzu
 
 #endif // INDICATORS_H
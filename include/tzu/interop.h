@@ -0,0 +1,144 @@
+#ifndef INTEROP_H
+#define INTEROP_H
+
+#include <cstddef>
+#include <cstdint>
+#include <vector>
+#include "defs.h"
+#ifdef TZU_WITH_EIGEN
+#include <Eigen/Dense>
+#endif
+
+/**
+ * This header converts between the row-oriented data used by the
+ * library (vectors of bars, of indicator results) and the column
+ * arrays used by dataframe and array libraries, so streaming
+ * indicators can be mixed with research code based on them. Columns
+ * are plain vectors, or raw pointers to memory owned by someone else
+ * (e.g. a NumPy array or a dataframe column exposed through bindings).
+ * When building with `TZU_WITH_EIGEN` defined and Eigen 3 in the
+ * include path, Eigen vectors are supported too.
+ */
+
+namespace tzu {
+
+/**
+ * Bars as columns.
+ */
+struct OhlcvColumns {
+    std::vector<int64_t> timestamp;
+    std::vector<double> open;
+    std::vector<double> high;
+    std::vector<double> low;
+    std::vector<double> close;
+    std::vector<double> volume;
+
+    size_t size() const noexcept { return timestamp.size(); }
+};
+
+inline OhlcvColumns to_columns(const std::vector<Ohlcv>& bars) {
+    OhlcvColumns cols;
+    cols.timestamp.reserve(bars.size());
+    cols.open.reserve(bars.size());
+    cols.high.reserve(bars.size());
+    cols.low.reserve(bars.size());
+    cols.close.reserve(bars.size());
+    cols.volume.reserve(bars.size());
+    for (const auto& bar : bars) {
+        cols.timestamp.push_back(bar.timestamp);
+        cols.open.push_back(bar.open);
+        cols.high.push_back(bar.high);
+        cols.low.push_back(bar.low);
+        cols.close.push_back(bar.close);
+        cols.volume.push_back(bar.volume);
+    }
+    return cols;
+}
+
+/**
+ * Bars from columns of `n` values each. `volume` can be null if there
+ * are no volumes.
+ */
+inline std::vector<Ohlcv> from_columns(const int64_t* timestamp,
+        const double* open, const double* high, const double* low,
+        const double* close, const double* volume, size_t n) {
+    std::vector<Ohlcv> bars;
+    bars.reserve(n);
+    for (size_t i = 0; i < n; i++)
+        bars.push_back(Ohlcv(timestamp[i], open[i], high[i], low[i], close[i],
+                    volume ? volume[i] : 0.0));
+    return bars;
+}
+
+inline std::vector<Ohlcv> from_columns(const OhlcvColumns& cols) {
+    return from_columns(cols.timestamp.data(), cols.open.data(),
+            cols.high.data(), cols.low.data(), cols.close.data(),
+            cols.volume.empty() ? nullptr : cols.volume.data(), cols.size());
+}
+
+/**
+ * One field of the bars as a column.
+ */
+inline std::vector<double> column(const std::vector<Ohlcv>& bars,
+        OhlcvField field) {
+    std::vector<double> out;
+    out.reserve(bars.size());
+    for (const auto& bar : bars)
+        out.push_back(bar.getFieldValue(field));
+    return out;
+}
+
+/**
+ * One member of the results of an indicator with several outputs as a
+ * column, e.g. `column(results, &ADXResult::adx)`.
+ */
+template <typename R>
+std::vector<double> column(const std::vector<R>& results, double R::*member) {
+    std::vector<double> out;
+    out.reserve(results.size());
+    for (const auto& r : results)
+        out.push_back(r.*member);
+    return out;
+}
+
+/**
+ * Updates an indicator with `n` values read from `in`, and writes its
+ * outputs to `out`, which must have room for `n` values. Neither
+ * buffer is copied, so they can be memory owned by another library.
+ */
+template <typename Ind>
+void compute_into(Ind& indicator, const typename Ind::input_type* in,
+        size_t n, typename Ind::output_type* out) {
+    for (size_t i = 0; i < n; i++)
+        out[i] = indicator.update(in[i]);
+}
+
+#ifdef TZU_WITH_EIGEN
+
+inline Eigen::VectorXd to_eigen(const std::vector<double>& values) {
+    return Eigen::Map<const Eigen::VectorXd>(values.data(),
+            static_cast<Eigen::Index>(values.size()));
+}
+
+inline std::vector<double> from_eigen(const Eigen::Ref<const Eigen::VectorXd>& v) {
+    return std::vector<double>(v.data(), v.data() + v.size());
+}
+
+/**
+ * Outputs of an indicator, with a single value per input, over an Eigen
+ * vector.
+ */
+template <typename Ind>
+Eigen::VectorXd compute_eigen(Ind& indicator,
+        const Eigen::Ref<const Eigen::VectorXd>& in) {
+    Eigen::VectorXd out(in.size());
+    for (Eigen::Index i = 0; i < in.size(); i++)
+        out[i] = indicator.update(in[i]);
+    return out;
+}
+
+#endif // TZU_WITH_EIGEN
+
+} // namespace tzu
+
+#endif // INTEROP_H
@@ -0,0 +1,193 @@
+#ifndef LABELING_H
+#define LABELING_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <vector>
+#include "defs.h"
+#include "validation.h"
+#include "volatility.h"
+
+/**
+ * This header defines utilities to generate targets for machine
+ * learning models from bar data. Labels are computed from prices that
+ * follow the event being labeled, so they use future data by
+ * definition: they are meant to build training sets, never to be fed
+ * back into a strategy at run time. The span of each label can be
+ * passed to `purged_kfold` to avoid leakage between folds.
+ */
+
+namespace tzu {
+
+/**
+ * Parameters of the triple-barrier method. The horizontal barriers are
+ * placed at `profit_mult` and `stop_mult` times the volatility of
+ * returns away from the entry price (a non-positive multiplier
+ * disables the barrier). The vertical barrier is placed `max_bars`
+ * bars after the event. Volatility is the exponentially weighted
+ * standard deviation of close-to-close returns with the given span.
+ */
+struct TripleBarrier {
+    double profit_mult = 1.0;
+    double stop_mult = 1.0;
+    size_t max_bars = 10;
+    size_t vol_span = 20;
+};
+
+/**
+ * The result of labeling an event. `label` is +1 if the profit barrier
+ * was touched first, -1 for the stop barrier, and 0 if the time limit
+ * was reached. `ret` is the return from the entry price to the price
+ * where the first barrier was touched.
+ */
+struct BarrierLabel {
+    size_t index = 0;
+    int64_t start = 0;
+    int64_t touch = 0;
+    int label = 0;
+    double ret = 0.0;
+    double volatility = 0.0;
+    Span span() const { return Span(start, touch); }
+};
+
+/**
+ * Exponentially weighted volatility of close-to-close returns at each
+ * bar, using only data up to that bar. NaN for the first bar.
+ */
+inline std::vector<double> ewm_volatility(const std::vector<Ohlcv>& bars,
+        size_t span) {
+    std::vector<double> vol;
+    vol.reserve(bars.size());
+    EWMAVol estimator(span);
+    for (const auto& bar : bars)
+        vol.push_back(estimator.update(bar.close));
+    return vol;
+}
+
+/**
+ * Labels a single event at bar `index` with the given volatility. For a
+ * `SELL` side, the trade is short: the profit barrier is below the
+ * entry price, the stop barrier above it, and `ret` is the return of
+ * the short position. A bar touching both barriers is labeled as a
+ * stop, which is the conservative choice since the order within the
+ * bar is unknown.
+ */
+inline BarrierLabel barrier_label(const std::vector<Ohlcv>& bars, size_t index,
+        double vol, const TripleBarrier& config, Side side = Side::BUY) {
+    BarrierLabel out;
+    out.index = index;
+    out.start = bars[index].timestamp;
+    out.volatility = vol;
+    double dir = side == Side::SELL ? -1.0 : 1.0;
+    double entry = bars[index].close;
+    double profit = config.profit_mult > 0.0
+        ? entry * (1.0 + dir * config.profit_mult * vol) : std::nan("");
+    double stop = config.stop_mult > 0.0
+        ? entry * (1.0 - dir * config.stop_mult * vol) : std::nan("");
+    size_t last = index + config.max_bars < bars.size()
+        ? index + config.max_bars : bars.size() - 1;
+    for (size_t j = index + 1; j <= last; j++) {
+        double adverse = dir > 0.0 ? bars[j].low : bars[j].high;
+        double favorable = dir > 0.0 ? bars[j].high : bars[j].low;
+        if (!std::isnan(stop) && dir * (adverse - stop) <= 0.0) {
+            out.touch = bars[j].timestamp;
+            out.label = -1;
+            out.ret = dir * (stop / entry - 1.0);
+            return out;
+        }
+        if (!std::isnan(profit) && dir * (favorable - profit) >= 0.0) {
+            out.touch = bars[j].timestamp;
+            out.label = 1;
+            out.ret = dir * (profit / entry - 1.0);
+            return out;
+        }
+    }
+    out.touch = bars[last].timestamp;
+    out.label = 0;
+    out.ret = dir * (bars[last].close / entry - 1.0);
+    return out;
+}
+
+/**
+ * Applies the triple-barrier method to the bars at the given event
+ * indices, or to every bar if no events are given. Events without a
+ * volatility estimate yet, or without any later bar, are skipped.
+ */
+inline std::vector<BarrierLabel> triple_barrier_labels(
+        const std::vector<Ohlcv>& bars, const TripleBarrier& config,
+        const std::vector<size_t>& events = std::vector<size_t>()) {
+    std::vector<BarrierLabel> labels;
+    std::vector<double> vol = ewm_volatility(bars, config.vol_span);
+    size_t n = events.empty() ? bars.size() : events.size();
+    for (size_t e = 0; e < n; e++) {
+        size_t i = events.empty() ? e : events[e];
+        if (i + 1 >= bars.size() || std::isnan(vol[i]) || vol[i] <= 0.0)
+            continue;
+        labels.push_back(barrier_label(bars, i, vol[i], config));
+    }
+    return labels;
+}
+
+/**
+ * Extracts the spans of a set of labels, e.g. for `purged_kfold`.
+ */
+inline std::vector<Span> label_spans(const std::vector<BarrierLabel>& labels) {
+    std::vector<Span> spans;
+    spans.reserve(labels.size());
+    for (const auto& label : labels)
+        spans.push_back(label.span());
+    return spans;
+}
+
+/**
+ * A meta-labeling sample: a buy signal of the primary strategy, the
+ * features known when it was generated, and whether it would have won
+ * (`label` is 1 if the trade return at the first touched barrier is
+ * positive, 0 otherwise).
+ */
+struct MetaSample {
+    Signal signal;
+    std::vector<double> features;
+    BarrierLabel barrier;
+    int label = 0;
+};
+
+/**
+ * Runs a copy of a primary strategy over the bars and labels each of
+ * its buy signals with the triple-barrier method, as a long trade.
+ * Sell signals only close positions, and are always taken by
+ * `MetaLabelStrat`, so they are not labeled. The feature builder is
+ * called with every bar, in order, and returns the features known at
+ * that bar as a `std::vector<double>`; it is copied, so it can keep
+ * state such as indicators. The resulting feature/label pairs can be
+ * used to train a secondary model that decides whether to take, or how
+ * to size, each primary buy signal.
+ */
+template <typename Strat, typename Features>
+std::vector<MetaSample> meta_labels(const Strat& primary,
+        const std::vector<Ohlcv>& bars, const Features& features,
+        const TripleBarrier& config) {
+    std::vector<MetaSample> samples;
+    std::vector<double> vol = ewm_volatility(bars, config.vol_span);
+    Strat strat = primary;
+    Features builder = features;
+    for (size_t i = 0; i < bars.size(); i++) {
+        Signal signal = strat.update(bars[i]);
+        std::vector<double> x = builder(bars[i]);
+        if (signal.side != Side::BUY || i + 1 >= bars.size()
+                || std::isnan(vol[i]) || vol[i] <= 0.0)
+            continue;
+        MetaSample sample;
+        sample.signal = signal;
+        sample.features = x;
+        sample.barrier = barrier_label(bars, i, vol[i], config, signal.side);
+        sample.label = sample.barrier.ret > 0.0 ? 1 : 0;
+        samples.push_back(sample);
+    }
+    return samples;
+}
+
+} // namespace tzu
+
+#endif // LABELING_H
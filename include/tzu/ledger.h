@@ -0,0 +1,212 @@
+#ifndef LEDGER_H
+#define LEDGER_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <map>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "numeric.h"
+#include "stats.h"
+
+/**
+ * This header defines the accounting of a multi-asset portfolio: cash,
+ * positions per symbol with their average cost, realized and
+ * unrealized profit and loss, and exposure. The ledger is updated from
+ * fills, whether simulated in a backtest or reported by a broker, and
+ * from market prices. It doesn't decide what to trade; strategies and
+ * executors use it to know where the account stands.
+ */
+
+namespace tzu {
+
+/**
+ * Position in a symbol. The quantity is negative for short positions,
+ * and `avg_price` is the average entry price of the open quantity.
+ * `realized` accumulates the profit closed in this symbol, without
+ * commissions.
+ */
+template <typename V>
+struct BasicHolding {
+    std::string symbol;
+    V quantity = V(0.0);
+    V avg_price = V(0.0);
+    V last_price = NumericTraits<V>::missing();
+    V realized = V(0.0);
+    int64_t open_time = 0;
+
+    V market_value() const noexcept {
+        return quantity == V(0.0) ? V(0.0) : quantity * last_price;
+    }
+
+    V unrealized() const noexcept {
+        return quantity == V(0.0) ? V(0.0) : (last_price - avg_price) * quantity;
+    }
+};
+
+using Holding = BasicHolding<double>;
+
+/**
+ * Ledger
+ *
+ * Tracks cash and positions from fills. Buying adds to a long position,
+ * or covers a short one, and selling the opposite; fills reducing a
+ * position realize profit against the average entry price and record a
+ * closed `Trade` (with the signed quantity closed), and fills crossing
+ * zero open a new position at the fill price for the remainder.
+ * Commissions are deducted from the cash and totalled apart. Positions
+ * are valued at the last price seen, from `mark` or from fills.
+ *
+ * `BasicLedger<Decimal>` keeps the accounts in fixed-point decimals
+ * (see `numeric.h`), so cash, fees and profits add up exactly; only
+ * products and average prices are rounded, to 8 decimals. The trades
+ * recorded are converted to `double` for the statistics.
+ */
+template <typename V>
+class BasicLedger {
+    V cash_;
+    V commissions_ = V(0.0);
+    std::map<std::string, BasicHolding<V>> positions;
+    std::vector<Trade> closed;
+
+    static V abs(V value) { return value < V(0.0) ? -value : value; }
+
+public:
+    explicit BasicLedger(V cash = V(0.0)): cash_(cash) {}
+
+    /**
+     * Applies an execution: a signed quantity (positive to buy) at a
+     * price, with an optional commission.
+     */
+    void on_fill(int64_t timestamp, const std::string& symbol,
+            V quantity, V price, V commission = V(0.0)) {
+        if (quantity == V(0.0)) return;
+        BasicHolding<V>& h = positions[symbol];
+        h.symbol = symbol;
+        h.last_price = price;
+        cash_ -= quantity * price + commission;
+        commissions_ += commission;
+        if (h.quantity == V(0.0) || (h.quantity > V(0.0)) == (quantity > V(0.0))) {
+            V total = abs(h.quantity) + abs(quantity);
+            h.avg_price = (h.avg_price * abs(h.quantity)
+                    + price * abs(quantity)) / total;
+            if (h.quantity == V(0.0)) h.open_time = timestamp;
+            h.quantity += quantity;
+            return;
+        }
+        V closing = abs(quantity) < abs(h.quantity) ? -quantity : h.quantity;
+        V profit = (price - h.avg_price) * closing;
+        h.realized += profit;
+        closed.push_back(Trade(h.open_time, timestamp,
+                    static_cast<double>(h.avg_price), static_cast<double>(price),
+                    static_cast<double>(closing), static_cast<double>(profit),
+                    true, symbol));
+        h.quantity += quantity;
+        if (abs(h.quantity) <= V(1e-12)) {
+            h.quantity = V(0.0);
+            h.avg_price = V(0.0);
+        } else if ((h.quantity > V(0.0)) != (closing > V(0.0))) {
+            // crossed zero: the remainder opens a new position
+            h.avg_price = price;
+            h.open_time = timestamp;
+        }
+    }
+
+    /**
+     * Updates the price used to value a symbol.
+     */
+    void mark(const std::string& symbol, V price) {
+        auto it = positions.find(symbol);
+        if (it != positions.end()) it->second.last_price = price;
+    }
+
+    void mark(const std::string& symbol, const Ohlcv& bar) {
+        mark(symbol, V(bar.close));
+    }
+
+    V cash() const noexcept { return cash_; }
+
+    V commissions() const noexcept { return commissions_; }
+
+    /**
+     * Position in a symbol, flat if it was never traded.
+     */
+    BasicHolding<V> holding(const std::string& symbol) const {
+        auto it = positions.find(symbol);
+        if (it != positions.end()) return it->second;
+        BasicHolding<V> h;
+        h.symbol = symbol;
+        return h;
+    }
+
+    /**
+     * All the symbols traded, including the ones now flat.
+     */
+    const std::map<std::string, BasicHolding<V>>& holdings() const noexcept {
+        return positions;
+    }
+
+    const std::vector<Trade>& trades() const noexcept { return closed; }
+
+    V realized() const {
+        V total = V(0.0);
+        for (const auto& p : positions) total += p.second.realized;
+        return total;
+    }
+
+    V unrealized() const {
+        V total = V(0.0);
+        for (const auto& p : positions) total += p.second.unrealized();
+        return total;
+    }
+
+    V gross_exposure() const {
+        V total = V(0.0);
+        for (const auto& p : positions) total += abs(p.second.market_value());
+        return total;
+    }
+
+    V net_exposure() const {
+        V total = V(0.0);
+        for (const auto& p : positions) total += p.second.market_value();
+        return total;
+    }
+
+    /**
+     * Cash plus the market value of the positions.
+     */
+    V equity() const { return cash_ + net_exposure(); }
+};
+
+using Ledger = BasicLedger<double>;
+
+template <typename V>
+std::ostream& operator<<(std::ostream& os, const BasicHolding<V>& h) {
+    os << "symbol:" << h.symbol
+       << " quantity:" << h.quantity
+       << " avg_price:" << h.avg_price
+       << " last_price:" << h.last_price
+       << " realized:" << h.realized
+       << " unrealized:" << h.unrealized();
+    return os;
+}
+
+template <typename V>
+std::ostream& operator<<(std::ostream& os, const BasicLedger<V>& ledger) {
+    os << "cash:" << ledger.cash()
+       << " equity:" << ledger.equity()
+       << " realized:" << ledger.realized()
+       << " unrealized:" << ledger.unrealized()
+       << " commissions:" << ledger.commissions()
+       << " gross_exposure:" << ledger.gross_exposure()
+       << " net_exposure:" << ledger.net_exposure()
+       << " num_trades:" << ledger.trades().size();
+    return os;
+}
+
+} // namespace tzu
+
+#endif // LEDGER_H
@@ -0,0 +1,178 @@
+#ifndef LEVELS_H
+#define LEVELS_H
+
+#include <algorithm>
+#include <cstddef>
+#include <cstdint>
+#include <deque>
+#include <ostream>
+#include <vector>
+#include "defs.h"
+#include "swings.h"
+
+/**
+ * This header defines support and resistance levels found from the
+ * recent swing points of the price: swing highs and lows at about the
+ * same price are grouped into a zone, which is stronger the more often
+ * and the more recently the price turned there. Strategies can use
+ * them as filters, e.g. not buying right below a resistance, or to
+ * place stops and targets.
+ */
+
+namespace tzu {
+
+/**
+ * A support or resistance zone, from `low` to `high`, around `price`,
+ * the mean of its swing points. `touches` is their number, `first` and
+ * `last` the times of the oldest and the latest, and `strength` the sum
+ * of their weights, which grow with recency.
+ */
+struct Level {
+    double price;
+    double low;
+    double high;
+    size_t touches;
+    int64_t first;
+    int64_t last;
+    double strength;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Level& l) {
+    os << "price:" << l.price
+       << " low:" << l.low
+       << " high:" << l.high
+       << " touches:" << l.touches
+       << " strength:" << l.strength;
+    return os;
+}
+
+/**
+ * SupportResistance
+ *
+ * Keeps the last `max_swings` swing points, either found from the bars
+ * by a `SwingDetector` or added directly, and groups them into levels:
+ * sorted by price, a swing point joins the current zone while it is
+ * within `tolerance`, a fraction of the price, from the bottom of the
+ * zone. Swing highs and lows are grouped alike, since a broken
+ * resistance often becomes a support. The weight of a swing point goes
+ * from 1 for the latest one down to 1 / n for the oldest of the n kept.
+ * Levels are computed when asked for, in time linear in the number of
+ * swing points, after sorting them.
+ *
+ * Example:
+ *
+ *     SupportResistance levels(SwingDetector(5, 5), 0.005);
+ *     levels.update(bar);
+ *     Level above;
+ *     if (levels.nearest_above(bar.close, above, 2)
+ *             && above.price < bar.close * 1.01)
+ *         ...  // resistance within 1%, don't buy
+ */
+class SupportResistance {
+    SwingDetector detector;
+    double tolerance;
+    size_t max_swings;
+    std::deque<Swing> swings;
+
+public:
+    explicit SupportResistance(
+            const SwingDetector& finder = SwingDetector(5, 5),
+            double tolerance = 0.005, size_t max_swings = 50)
+        : detector(finder), tolerance(tolerance),
+          max_swings(max_swings > 0 ? max_swings : 1) {}
+
+    /**
+     * Adds a bar. Returns true if it confirmed swing points.
+     */
+    bool update(const Ohlcv& bar) {
+        if (!detector.update(bar)) return false;
+        for (const auto& s : detector.get()) add(s);
+        return true;
+    }
+
+    /**
+     * Adds a swing point, e.g. from another detector.
+     */
+    void add(const Swing& swing) {
+        swings.push_back(swing);
+        if (swings.size() > max_swings) swings.pop_front();
+    }
+
+    /**
+     * The levels, from the lowest price up.
+     */
+    std::vector<Level> levels() const {
+        struct Point {
+            double price;
+            double weight;
+            int64_t timestamp;
+        };
+        std::vector<Point> points;
+        double n = static_cast<double>(swings.size());
+        for (size_t i = 0; i < swings.size(); i++)
+            points.push_back({swings[i].price, (i + 1) / n,
+                    swings[i].timestamp});
+        std::sort(points.begin(), points.end(),
+                [](const Point& a, const Point& b) {
+                    return a.price < b.price;
+                });
+        std::vector<Level> out;
+        double sum = 0.0;
+        for (const auto& p : points) {
+            if (out.empty() || p.price > out.back().low * (1.0 + tolerance)) {
+                sum = 0.0;
+                out.push_back({p.price, p.price, p.price, 0, p.timestamp,
+                        p.timestamp, 0.0});
+            }
+            Level& level = out.back();
+            sum += p.price;
+            level.touches++;
+            level.price = sum / level.touches;
+            level.high = p.price;
+            level.first = std::min(level.first, p.timestamp);
+            level.last = std::max(level.last, p.timestamp);
+            level.strength += p.weight;
+        }
+        return out;
+    }
+
+    /**
+     * Finds the closest level above `price` with at least `min_touches`
+     * swing points. Returns false if there is none.
+     */
+    bool nearest_above(double price, Level& level,
+            size_t min_touches = 1) const {
+        for (const auto& l : levels()) {
+            if (l.price > price && l.touches >= min_touches) {
+                level = l;
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /**
+     * Finds the closest level below `price` with at least `min_touches`
+     * swing points. Returns false if there is none.
+     */
+    bool nearest_below(double price, Level& level,
+            size_t min_touches = 1) const {
+        std::vector<Level> all = levels();
+        for (auto it = all.rbegin(); it != all.rend(); ++it) {
+            if (it->price < price && it->touches >= min_touches) {
+                level = *it;
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /**
+     * The swing points kept, the oldest first.
+     */
+    const std::deque<Swing>& points() const noexcept { return swings; }
+};
+
+} // namespace tzu
+
+#endif // LEVELS_H
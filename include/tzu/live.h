@@ -0,0 +1,500 @@
+#ifndef LIVE_H
+#define LIVE_H
+
+#include <atomic>
+#include <chrono>
+#include <cmath>
+#include <condition_variable>
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <future>
+#include <memory>
+#include <mutex>
+#include <random>
+#include <string>
+#include <thread>
+#include <vector>
+#include "defs.h"
+#include "connectors.h"
+#include "engine.h"
+#include "feed.h"
+#include "orders.h"
+
+/**
+ * This header defines a runner for live trading loops. Real feeds and
+ * broker APIs are asynchronous: connections drop and have to be
+ * reopened, and the loop has to stop cleanly when asked to, from
+ * another thread or a signal handler. As in `feed.h`, no network I/O
+ * is done here: the runner is given callables to connect, receive
+ * messages and disconnect, which any WebSocket or HTTP client can
+ * provide, and it parses the messages and passes the values to a
+ * handler, e.g. publishing them on an `EventBus`.
+ *
+ * It also defines asynchronous variants of the feed, engine and broker
+ * interfaces: `PushFeed` for clients delivering messages through
+ * callbacks, and `AsyncEngine` and `AsyncBroker`, whose calls return
+ * futures, so the loop doesn't block on a slow venue.
+ */
+
+namespace tzu {
+
+enum class LiveStatus {
+    IDLE,
+    RUNNING,
+    STOPPED,
+    FAILED
+};
+
+inline const char* to_string(LiveStatus status) {
+    switch (status) {
+        case LiveStatus::IDLE: return "IDLE";
+        case LiveStatus::RUNNING: return "RUNNING";
+        case LiveStatus::STOPPED: return "STOPPED";
+        case LiveStatus::FAILED: return "FAILED";
+    }
+    return "UNKNOWN";
+}
+
+/**
+ * LiveRunner
+ *
+ * Connects with `connect`, which returns false if it failed, and
+ * passes the values of type `T` parsed by `Parser` (as for `LiveFeed`)
+ * from the messages returned by `receive` to the handler, until
+ * `receive` returns false because the connection dropped. It then
+ * reconnects. Failed connections are retried with the backoff of the
+ * retry policy, giving up after `max_attempts` consecutive failures,
+ * and dropped ones are reopened after the backoff as well, which grows
+ * while connections drop without new values. Values older than the
+ * previous one are dropped. Values with the same timestamp are kept, as
+ * trades can share a millisecond, except right after reconnecting,
+ * since venues often send the last value again then.
+ *
+ * `run` blocks until the runner stops or gives up; `start` runs it in
+ * a thread of its own instead, where the handler is then called.
+ * `stop` can be called from any thread: it interrupts the backoff and
+ * calls `disconnect`, which must make a blocked `receive` return. It
+ * is not safe in a signal handler, where `request_stop` is to be used
+ * instead. A runner runs once, and is stopped when destroyed.
+ *
+ * Example:
+ *
+ *     LiveRunner<Ohlcv, BinanceKlineParser> runner(connect, receive,
+ *             [&bus](const Ohlcv& bar) {
+ *                 bus.publish(MarketEvent{"BTCUSDT", bar});
+ *             }, disconnect);
+ *     runner.start();
+ *     ...
+ *     runner.stop();
+ *     runner.join();
+ */
+template <typename T, typename Parser>
+class LiveRunner {
+public:
+    using Connect = std::function<bool()>;
+    using Receive = std::function<bool(std::string&)>;
+    using Disconnect = std::function<void()>;
+    using Handler = std::function<void(const T&)>;
+
+private:
+    Connect connect;
+    Receive receive;
+    Handler handler;
+    Disconnect disconnect;
+    RetryPolicy policy;
+    std::mt19937 rng;
+    std::uniform_real_distribution<double> uniform;
+    std::atomic<bool> stopping;
+    std::atomic<LiveStatus> status;
+    std::atomic<size_t> reconnects;
+    std::atomic<size_t> skipped;
+    std::atomic<size_t> duplicates;
+    int64_t last = INT64_MIN;
+    // set by `stop`, which `request_stop` doesn't
+    bool stopped = false;
+    std::mutex mutex;
+    std::condition_variable wake;
+    std::thread worker;
+
+    // Waits for `delay` milliseconds; returns false if stopped meanwhile.
+    bool wait(int64_t delay) {
+        std::unique_lock<std::mutex> lock(mutex);
+        wake.wait_for(lock, std::chrono::milliseconds(delay),
+                [this] { return stopping.load(); });
+        return !stopping;
+    }
+
+public:
+    LiveRunner(Connect connect, Receive receive, Handler handler,
+            Disconnect disconnect = Disconnect(),
+            const RetryPolicy& policy = RetryPolicy(), unsigned seed = 0)
+        : connect(connect), receive(receive), handler(handler),
+          disconnect(disconnect), policy(policy), rng(seed),
+          uniform(0.0, 1.0), stopping(false), status(LiveStatus::IDLE),
+          reconnects(0), skipped(0), duplicates(0) {}
+
+    LiveRunner(const LiveRunner&) = delete;
+    LiveRunner& operator=(const LiveRunner&) = delete;
+
+    ~LiveRunner() {
+        stop();
+        join();
+    }
+
+    /**
+     * Runs the loop in the calling thread. Returns STOPPED after
+     * `stop`, or FAILED after giving up connecting.
+     */
+    LiveStatus run() {
+        status = LiveStatus::RUNNING;
+        size_t failures = 0;
+        size_t drops = 0;
+        bool connected = false;
+        while (!stopping) {
+            if (!connect()) {
+                if (++failures >= policy.max_attempts) {
+                    status = LiveStatus::FAILED;
+                    return status;
+                }
+                if (!wait(backoff_delay(policy, failures, uniform(rng))))
+                    break;
+                continue;
+            }
+            if (connected) reconnects++;
+            bool replay = connected;
+            bool fresh = false;
+            connected = true;
+            failures = 0;
+            std::string message;
+            T value;
+            while (!stopping && receive(message)) {
+                if (!Parser::parse(message, value)) {
+                    skipped++;
+                } else if (value.timestamp < last
+                        || (replay && value.timestamp == last)) {
+                    duplicates++;
+                } else {
+                    replay = false;
+                    fresh = true;
+                    last = value.timestamp;
+                    handler(value);
+                }
+            }
+            drops = fresh ? 1 : drops + 1;
+            if (!stopping && !wait(backoff_delay(policy, drops, uniform(rng))))
+                break;
+        }
+        status = LiveStatus::STOPPED;
+        return status;
+    }
+
+    /**
+     * Runs the loop in a new thread.
+     */
+    void start() {
+        if (!worker.joinable() && status == LiveStatus::IDLE)
+            worker = std::thread([this] { run(); });
+    }
+
+    /**
+     * Asks the loop to stop, interrupting the backoff and the
+     * connection.
+     */
+    void stop() {
+        {
+            std::lock_guard<std::mutex> lock(mutex);
+            if (stopped) return;
+            stopped = stopping = true;
+        }
+        wake.notify_all();
+        if (disconnect) disconnect();
+    }
+
+    /**
+     * Asks the loop to stop, only setting a flag, so it can be called
+     * from a signal handler. The loop stops at the end of the current
+     * backoff or once `receive` returns.
+     */
+    void request_stop() noexcept {
+        stopping = true;
+    }
+
+    /**
+     * Waits for the thread started by `start` to end.
+     */
+    void join() {
+        if (worker.joinable()) worker.join();
+    }
+
+    LiveStatus get_status() const noexcept { return status; }
+
+    /**
+     * Connections reopened after dropping.
+     */
+    size_t num_reconnects() const noexcept { return reconnects; }
+
+    /**
+     * Messages received that were not data.
+     */
+    size_t num_skipped() const noexcept { return skipped; }
+
+    /**
+     * Values dropped for being older than the previous one, or replayed
+     * on reconnection.
+     */
+    size_t num_duplicates() const noexcept { return duplicates; }
+};
+
+/**
+ * PushFeed
+ *
+ * Callback-based counterpart of `LiveRunner`, for clients that keep
+ * the connection themselves and deliver the messages through callbacks.
+ * `on_message` parses a message and passes the value to the handler,
+ * with the same rules as `LiveRunner` for older and replayed values,
+ * and `on_reconnect` tells the feed that the connection was reopened.
+ * They can be called from any thread: calls are serialized, and the
+ * handler runs in the thread calling `on_message`.
+ *
+ * Example:
+ *
+ *     PushFeed<Ohlcv, BinanceKlineParser> feed([&engine](const Ohlcv& bar) {
+ *         engine.update("BTCUSDT", bar);
+ *     });
+ *     client.on_message([&feed](const std::string& msg) {
+ *         feed.on_message(msg);
+ *     });
+ *     client.on_open([&feed] { feed.on_reconnect(); });
+ */
+template <typename T, typename Parser>
+class PushFeed {
+public:
+    using Handler = std::function<void(const T&)>;
+
+private:
+    Handler handler;
+    int64_t last = INT64_MIN;
+    bool replay = false;
+    size_t skipped = 0;
+    size_t duplicates = 0;
+    mutable std::mutex mutex;
+
+public:
+    explicit PushFeed(Handler handler): handler(handler) {}
+
+    /**
+     * Handles a message. Returns true if it carried a new value, which
+     * was passed to the handler.
+     */
+    bool on_message(const std::string& message) {
+        std::lock_guard<std::mutex> lock(mutex);
+        T value;
+        if (!Parser::parse(message, value)) {
+            skipped++;
+            return false;
+        }
+        if (value.timestamp < last || (replay && value.timestamp == last)) {
+            duplicates++;
+            return false;
+        }
+        replay = false;
+        last = value.timestamp;
+        handler(value);
+        return true;
+    }
+
+    /**
+     * Drops a value with the timestamp of the previous one if it comes
+     * next, since venues often send it again after reconnecting.
+     */
+    void on_reconnect() {
+        std::lock_guard<std::mutex> lock(mutex);
+        replay = last != INT64_MIN;
+    }
+
+    size_t num_skipped() const {
+        std::lock_guard<std::mutex> lock(mutex);
+        return skipped;
+    }
+
+    size_t num_duplicates() const {
+        std::lock_guard<std::mutex> lock(mutex);
+        return duplicates;
+    }
+};
+
+/**
+ * Runs tasks one after another in a thread of its own. `post` returns
+ * a future with the result of the task. Tasks still queued when the
+ * executor is destroyed are run before it returns.
+ */
+class SerialExecutor {
+    Channel<std::function<void()>> tasks;
+    std::thread worker;
+
+public:
+    SerialExecutor(): worker([this] {
+        std::function<void()> task;
+        while (tasks.pop(task)) task();
+    }) {}
+
+    SerialExecutor(const SerialExecutor&) = delete;
+    SerialExecutor& operator=(const SerialExecutor&) = delete;
+
+    ~SerialExecutor() {
+        tasks.close();
+        worker.join();
+    }
+
+    template <typename F>
+    auto post(F f) -> std::future<decltype(f())> {
+        using R = decltype(f());
+        auto task = std::make_shared<std::packaged_task<R()>>(f);
+        std::future<R> result = task->get_future();
+        tasks.push([task] { (*task)(); });
+        return result;
+    }
+};
+
+/**
+ * AsyncEngine
+ *
+ * Future-based variant of `IndicatorEngine`. It owns an engine, set up
+ * beforehand, and runs its calls in order in a thread of its own, so
+ * bars can be handed over from a feed callback without waiting for the
+ * indicators to be updated.
+ *
+ * Example:
+ *
+ *     IndicatorEngine engine;
+ *     engine.add("rsi", RSI(14));
+ *     AsyncEngine async(engine);
+ *     async.update("AAPL", bar);
+ *     std::vector<SymbolSnapshot> values = async.snapshot().get();
+ */
+class AsyncEngine {
+    IndicatorEngine engine;
+    SerialExecutor executor;
+
+public:
+    explicit AsyncEngine(const IndicatorEngine& engine): engine(engine) {}
+
+    /**
+     * Feeds a bar to the indicators of its symbol. The result tells
+     * whether it was accepted, as in `IndicatorEngine::update`.
+     */
+    std::future<bool> update(const std::string& symbol, const Ohlcv& bar) {
+        IndicatorEngine* e = &engine;
+        return executor.post([e, symbol, bar] {
+            return e->update(symbol, bar);
+        });
+    }
+
+    std::future<double> get(const std::string& symbol,
+            const std::string& name) {
+        IndicatorEngine* e = &engine;
+        return executor.post([e, symbol, name] {
+            return e->get(symbol, name);
+        });
+    }
+
+    std::future<SymbolSnapshot> snapshot(const std::string& symbol) {
+        IndicatorEngine* e = &engine;
+        return executor.post([e, symbol] { return e->snapshot(symbol); });
+    }
+
+    std::future<std::vector<SymbolSnapshot>> snapshot() {
+        IndicatorEngine* e = &engine;
+        return executor.post([e] { return e->snapshot(); });
+    }
+};
+
+/**
+ * AsyncBroker
+ *
+ * Future-based variant of the `Broker` interface, for connectors whose
+ * requests complete asynchronously. The results are those of the
+ * corresponding `Broker` calls.
+ */
+class AsyncBroker {
+public:
+    virtual ~AsyncBroker() {}
+
+    virtual std::future<std::string> submit(int64_t timestamp,
+            const Order& order) = 0;
+    virtual std::future<bool> cancel(const std::string& id) = 0;
+    virtual std::future<std::string> replace(int64_t timestamp,
+            const std::string& id, double quantity,
+            double limit_price = std::nan(""),
+            double stop_price = std::nan("")) = 0;
+    virtual std::future<Order> order(const std::string& id) = 0;
+    virtual std::future<std::vector<Order>> open_orders() = 0;
+    virtual std::future<AccountState> state() = 0;
+};
+
+/**
+ * ThreadedBroker
+ *
+ * `AsyncBroker` running the calls of a synchronous `Broker`, e.g. one
+ * doing blocking HTTP requests, in order in a thread of its own. The
+ * broker must outlive it, and must not be called directly meanwhile.
+ *
+ * Example:
+ *
+ *     AlpacaBroker alpaca(url, key, secret, http);
+ *     ThreadedBroker broker(alpaca);
+ *     std::future<std::string> id =
+ *         broker.submit(now, Order::market("AAPL", 10.0));
+ *     ...
+ *     if (id.get().empty()) std::cerr << "order not sent\n";
+ */
+class ThreadedBroker: public AsyncBroker {
+    Broker& broker;
+    SerialExecutor executor;
+
+public:
+    explicit ThreadedBroker(Broker& broker): broker(broker) {}
+
+    std::future<std::string> submit(int64_t timestamp,
+            const Order& order) override {
+        Broker* b = &broker;
+        return executor.post([b, timestamp, order] {
+            return b->submit(timestamp, order);
+        });
+    }
+
+    std::future<bool> cancel(const std::string& id) override {
+        Broker* b = &broker;
+        return executor.post([b, id] { return b->cancel(id); });
+    }
+
+    std::future<std::string> replace(int64_t timestamp,
+            const std::string& id, double quantity,
+            double limit_price = std::nan(""),
+            double stop_price = std::nan("")) override {
+        Broker* b = &broker;
+        return executor.post([=] {
+            return b->replace(timestamp, id, quantity, limit_price, stop_price);
+        });
+    }
+
+    std::future<Order> order(const std::string& id) override {
+        Broker* b = &broker;
+        return executor.post([b, id] { return b->order(id); });
+    }
+
+    std::future<std::vector<Order>> open_orders() override {
+        Broker* b = &broker;
+        return executor.post([b] { return b->open_orders(); });
+    }
+
+    std::future<AccountState> state() override {
+        Broker* b = &broker;
+        return executor.post([b] { return b->state(); });
+    }
+};
+
+} // namespace tzu
+
+#endif // LIVE_H
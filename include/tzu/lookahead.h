@@ -0,0 +1,143 @@
+#ifndef LOOKAHEAD_H
+#define LOOKAHEAD_H
+
+#include <algorithm>
+#include <cstddef>
+#include <cstdint>
+#include <ostream>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines an instrumentation pass that detects common
+ * forms of lookahead bias in strategies. Streaming already prevents a
+ * strategy from reading future rows of the input, but it is still
+ * easy to produce signals that could not have been acted upon:
+ *
+ * - A signal stamped with a time other than the one of the data point
+ *   that produced it, which misaligns decisions and executions.
+ * - A signal price outside of the bar's low-high range, which can only
+ *   come from data of another bar.
+ * - A signal executed at the bar's open whose decision depends on the
+ *   bar's high, low, close or volume, which are only known after the
+ *   open. This is detected by replaying the strategy with the bar
+ *   truncated to its open and checking whether the decision changes.
+ */
+
+namespace tzu {
+
+enum class LookaheadIssue {
+    TIMESTAMP_MISMATCH,
+    PRICE_OUTSIDE_BAR,
+    USES_DATA_AFTER_DECISION
+};
+
+/**
+ * A detected issue, with the index and timestamp of the data point
+ * where it happened.
+ */
+struct LookaheadFinding {
+    size_t index;
+    int64_t timestamp;
+    LookaheadIssue issue;
+};
+
+struct LookaheadReport {
+    std::vector<LookaheadFinding> findings;
+    size_t num_signals = 0;
+    bool ok() const noexcept { return findings.empty(); }
+};
+
+inline int64_t data_timestamp(const Ohlcv& data) { return data.timestamp; }
+inline int64_t data_timestamp(const Tick& data) { return data.timestamp; }
+inline int64_t data_timestamp(const SingleValue& data) { return data.timestamp; }
+
+/**
+ * Checks that a price could have been traded within the data point.
+ * Only bars have a range, other data types are always accepted.
+ */
+inline bool price_within(const Ohlcv& data, double price) {
+    const double eps = 1e-9 * (data.high > 0.0 ? data.high : 1.0);
+    return price >= data.low - eps && price <= data.high + eps;
+}
+template <typename In>
+bool price_within(const In&, double) { return true; }
+
+/**
+ * Replaces a data point with what was known at its open. Returns false
+ * for data types where that doesn't apply.
+ */
+inline bool truncate_to_open(Ohlcv& data) {
+    data = Ohlcv(data.timestamp, data.open, data.open, data.open,
+            data.open, 0.0);
+    return true;
+}
+template <typename In>
+bool truncate_to_open(In&) { return false; }
+
+inline bool is_open_price(const Ohlcv& data, double price) {
+    return price == data.open;
+}
+template <typename In>
+bool is_open_price(const In&, double) { return false; }
+
+/**
+ * Runs copies of a strategy over a dataset and reports lookahead
+ * issues. The truncation replay is O(N) per checked signal, so at most
+ * `max_replays` signals executed at the open are checked, evenly spread
+ * over the dataset. The strategy given is not modified.
+ */
+template <typename Strat, typename In>
+LookaheadReport check_lookahead(const Strat& prototype,
+        const std::vector<In>& data, size_t max_replays = 20) {
+    LookaheadReport report;
+    Strat strat = prototype;
+    std::vector<size_t> at_open;
+    std::vector<Side> sides(data.size(), Side::NONE);
+    for (size_t i = 0; i < data.size(); i++) {
+        Signal signal = strat.update(data[i]);
+        sides[i] = signal.side;
+        if (signal.side == Side::NONE)
+            continue;
+        report.num_signals++;
+        int64_t ts = data_timestamp(data[i]);
+        if (signal.timestamp != ts)
+            report.findings.push_back({i, ts, LookaheadIssue::TIMESTAMP_MISMATCH});
+        if (!price_within(data[i], signal.price))
+            report.findings.push_back({i, ts, LookaheadIssue::PRICE_OUTSIDE_BAR});
+        if (is_open_price(data[i], signal.price))
+            at_open.push_back(i);
+    }
+    size_t replays = std::min(at_open.size(), max_replays);
+    for (size_t r = 0; r < replays; r++) {
+        size_t k = at_open[r * at_open.size() / replays];
+        Strat replay = prototype;
+        for (size_t i = 0; i < k; i++)
+            replay.update(data[i]);
+        In truncated = data[k];
+        if (!truncate_to_open(truncated))
+            continue;
+        if (replay.update(truncated).side != sides[k])
+            report.findings.push_back({k, data_timestamp(data[k]),
+                    LookaheadIssue::USES_DATA_AFTER_DECISION});
+    }
+    return report;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const LookaheadReport& report) {
+    os << "lookahead_signals:" << report.num_signals
+       << " lookahead_issues:" << report.findings.size();
+    for (const auto& f : report.findings) {
+        os << "\nindex:" << f.index << " timestamp:" << f.timestamp
+           << " issue:"
+           << (f.issue == LookaheadIssue::TIMESTAMP_MISMATCH
+                   ? "TIMESTAMP_MISMATCH"
+                   : f.issue == LookaheadIssue::PRICE_OUTSIDE_BAR
+                    ? "PRICE_OUTSIDE_BAR" : "USES_DATA_AFTER_DECISION");
+    }
+    return os;
+}
+
+} // namespace tzu
+
+#endif // LOOKAHEAD_H
@@ -0,0 +1,200 @@
+#ifndef METRICS_H
+#define METRICS_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <ostream>
+#include <utility>
+#include <vector>
+#include "stats.h"
+
+/**
+ * This header defines performance metrics of a strategy, computed from
+ * its equity curve and its closed trades. `MetricsTracker` updates them
+ * incrementally, e.g. to monitor a live strategy, and
+ * `compute_metrics` evaluates a whole backtest at once. Returns are
+ * the simple returns between consecutive equity values, and annualized
+ * figures assume timestamps in seconds unless another year length is
+ * given.
+ */
+
+namespace tzu {
+
+const int64_t SECONDS_PER_YEAR = 365 * 24 * 3600;
+
+/**
+ * Performance metrics. Ratios are annualized and assume a zero
+ * risk-free rate; they are zero when they can't be computed (e.g. no
+ * volatility, or less than 30 days elapsed for the CAGR).
+ * Drawdowns are fractions of the previous peak, and the drawdown
+ * duration is the longest time spent below a peak, in timestamp units.
+ * Exposure is the fraction of the periods with an open position.
+ */
+struct Metrics {
+    double total_return = 0.0;
+    double cagr = 0.0;
+    double volatility = 0.0;
+    double sharpe = 0.0;
+    double sortino = 0.0;
+    double calmar = 0.0;
+    double max_drawdown = 0.0;
+    int64_t max_drawdown_duration = 0;
+    size_t num_trades = 0;
+    double win_rate = 0.0;
+    double profit_factor = 0.0;
+    double expectancy = 0.0;
+    double exposure = 0.0;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Metrics& m) {
+    os << "total_return:" << m.total_return
+       << " cagr:" << m.cagr
+       << " volatility:" << m.volatility
+       << " sharpe:" << m.sharpe
+       << " sortino:" << m.sortino
+       << " calmar:" << m.calmar
+       << " max_drawdown:" << m.max_drawdown
+       << " max_drawdown_duration:" << m.max_drawdown_duration
+       << " num_trades:" << m.num_trades
+       << " win_rate:" << m.win_rate
+       << " profit_factor:" << m.profit_factor
+       << " expectancy:" << m.expectancy
+       << " exposure:" << m.exposure;
+    return os;
+}
+
+/**
+ * Incremental performance metrics. Add equity values in chronological
+ * order with `update`, and closed trades with `add_trade`; `get`
+ * computes the metrics so far in constant time.
+ */
+class MetricsTracker {
+    int64_t year_length;
+    size_t points = 0;
+    int64_t first_time = 0;
+    int64_t last_time = 0;
+    double first_equity = 0.0;
+    double last_equity = 0.0;
+    // returns, with Welford's running variance
+    size_t n = 0;
+    double mean = 0.0;
+    double m2 = 0.0;
+    double downside = 0.0;
+    size_t exposed = 0;
+    double peak = 0.0;
+    int64_t peak_time = 0;
+    double max_dd = 0.0;
+    int64_t max_dd_duration = 0;
+    bool last_below = false;
+    size_t trades = 0;
+    size_t wins = 0;
+    double gross_profit = 0.0;
+    double gross_loss = 0.0;
+
+public:
+    explicit MetricsTracker(int64_t year_length = SECONDS_PER_YEAR)
+        : year_length(year_length) {}
+
+    /**
+     * Adds the account equity at a time, and whether a position was
+     * held during the period ending then.
+     */
+    void update(int64_t timestamp, double equity, bool in_market = false) {
+        if (points == 0) {
+            first_time = timestamp;
+            first_equity = equity;
+            peak = equity;
+            peak_time = timestamp;
+        } else {
+            double r = last_equity != 0.0 ? equity / last_equity - 1.0 : 0.0;
+            n++;
+            double delta = r - mean;
+            mean += delta / n;
+            m2 += delta * (r - mean);
+            if (r < 0.0) downside += r * r;
+            if (in_market) exposed++;
+        }
+        points++;
+        last_time = timestamp;
+        last_equity = equity;
+        // the time to recover a peak counts as drawdown
+        bool below = equity < peak;
+        if (below || last_below) {
+            if (timestamp - peak_time > max_dd_duration)
+                max_dd_duration = timestamp - peak_time;
+        }
+        if (below) {
+            double dd = (peak - equity) / peak;
+            if (dd > max_dd) max_dd = dd;
+        } else {
+            peak = equity;
+            peak_time = timestamp;
+        }
+        last_below = below;
+    }
+
+    void add_trade(double profit) {
+        trades++;
+        if (profit > 0.0) {
+            wins++;
+            gross_profit += profit;
+        } else {
+            gross_loss -= profit;
+        }
+    }
+
+    void add_trade(const Trade& trade) { add_trade(trade.profit); }
+
+    Metrics get() const {
+        Metrics m;
+        if (points > 0 && first_equity != 0.0)
+            m.total_return = last_equity / first_equity - 1.0;
+        double years = static_cast<double>(last_time - first_time) / year_length;
+        const double min_period_years = 30.0 / 365.0;
+        if (years >= min_period_years && first_equity > 0.0 && last_equity > 0.0)
+            m.cagr = std::pow(last_equity / first_equity, 1.0 / years) - 1.0;
+        if (n >= 2 && years > 0.0) {
+            double per_year = n / years;
+            double stddev = std::sqrt(m2 / (n - 1));
+            double downdev = std::sqrt(downside / n);
+            m.volatility = stddev * std::sqrt(per_year);
+            if (stddev > 0.0) m.sharpe = mean / stddev * std::sqrt(per_year);
+            if (downdev > 0.0) m.sortino = mean / downdev * std::sqrt(per_year);
+        }
+        m.max_drawdown = max_dd;
+        m.max_drawdown_duration = max_dd_duration;
+        if (max_dd > 0.0) m.calmar = m.cagr / max_dd;
+        m.num_trades = trades;
+        if (trades > 0) {
+            m.win_rate = static_cast<double>(wins) / trades;
+            m.expectancy = (gross_profit - gross_loss) / trades;
+        }
+        if (gross_loss > 0.0) m.profit_factor = gross_profit / gross_loss;
+        if (n > 0) m.exposure = static_cast<double>(exposed) / n;
+        return m;
+    }
+};
+
+/**
+ * Metrics of a whole equity curve and its closed trades. `in_market`,
+ * if not empty, tells for each point of the curve whether a position
+ * was held.
+ */
+inline Metrics compute_metrics(
+        const std::vector<std::pair<int64_t, double>>& equity_curve,
+        const std::vector<Trade>& trades = std::vector<Trade>(),
+        const std::vector<bool>& in_market = std::vector<bool>(),
+        int64_t year_length = SECONDS_PER_YEAR) {
+    MetricsTracker tracker(year_length);
+    for (size_t i = 0; i < equity_curve.size(); i++)
+        tracker.update(equity_curve[i].first, equity_curve[i].second,
+                i < in_market.size() && in_market[i]);
+    for (const auto& trade : trades)
+        if (trade.closed) tracker.add_trade(trade);
+    return tracker.get();
+}
+
+} // namespace tzu
+
+#endif // METRICS_H
@@ -0,0 +1,175 @@
+#ifndef NUMERIC_H
+#define NUMERIC_H
+
+#include <cmath>
+#include <cstdint>
+#include <limits>
+#include <ostream>
+
+/**
+ * This header defines the value types indicators can work with besides
+ * `double`. Indicators generic over their value type (e.g.
+ * `BasicSMA<V>`) only require the arithmetic operators, construction
+ * from a `double`, and a `NumericTraits` specialization telling how a
+ * missing value (NaN for floating point) is represented.
+ *
+ * `Fixed` is a decimal fixed-point number, for deterministic results
+ * across platforms, or for prices quoted in integer ticks.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+constexpr int64_t pow10(int n) {
+    return n == 0 ? 1 : 10 * pow10(n - 1);
+}
+
+/**
+ * Computes a * b / d, rounded half away from zero, with the product
+ * kept in 128 bits as two 64-bit halves. Returns false if d is zero or
+ * the result doesn't fit in an `int64_t`.
+ */
+inline bool mul_div(uint64_t a, uint64_t b, uint64_t d, uint64_t& out) {
+    if (d == 0) return false;
+    const uint64_t mask = 0xffffffffu;
+    uint64_t a0 = a & mask, a1 = a >> 32;
+    uint64_t b0 = b & mask, b1 = b >> 32;
+    uint64_t p00 = a0 * b0, p01 = a0 * b1, p10 = a1 * b0, p11 = a1 * b1;
+    uint64_t mid = (p00 >> 32) + (p01 & mask) + (p10 & mask);
+    uint64_t lo = (mid << 32) | (p00 & mask);
+    uint64_t hi = p11 + (p01 >> 32) + (p10 >> 32) + (mid >> 32);
+    if (hi >= d) return false;
+    // long division, a bit at a time, keeping the remainder below d
+    uint64_t q = 0, rem = hi;
+    for (int i = 63; i >= 0; i--) {
+        bool carry = (rem >> 63) != 0;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        q <<= 1;
+        if (carry || rem >= d) {
+            rem -= d;
+            q |= 1;
+        }
+    }
+    uint64_t round = rem >= d - rem ? 1 : 0;
+    if (q > static_cast<uint64_t>(std::numeric_limits<int64_t>::max())
+            - round)
+        return false;
+    out = q + round;
+    return true;
+}
+
+inline uint64_t magnitude(int64_t value) {
+    return value < 0 ? 0 - static_cast<uint64_t>(value)
+        : static_cast<uint64_t>(value);
+}
+
+} // namespace detail
+
+/**
+ * Representation of missing values, for floating-point types.
+ */
+template <typename V>
+struct NumericTraits {
+    static V missing() { return std::numeric_limits<V>::quiet_NaN(); }
+    static bool is_missing(V value) { return std::isnan(value); }
+};
+
+/**
+ * Fixed-point number with `Decimals` decimal digits, stored as an
+ * integer count of units of 10^-Decimals. Additions and subtractions
+ * are exact; products and quotients are rounded to the nearest unit,
+ * computed in integers so they are exact as well. The range is about
+ * ±9.2e18 units, e.g. ±9.2e10 with 8 decimals. Products and quotients
+ * out of range, of missing values, or by zero are missing.
+ */
+template <int Decimals>
+class Fixed {
+    int64_t raw_ = 0;
+
+    // a * b / d, missing on overflow, division by zero or if the
+    // other operand is missing
+    Fixed scaled(int64_t a, int64_t b, int64_t d, int64_t other) const {
+        const int64_t nan = std::numeric_limits<int64_t>::min();
+        uint64_t q;
+        if (raw_ == nan || other == nan
+                || !detail::mul_div(detail::magnitude(a),
+                    detail::magnitude(b), detail::magnitude(d), q))
+            return from_raw(nan);
+        bool negative = ((a < 0) != (b < 0)) != (d < 0);
+        int64_t value = static_cast<int64_t>(q);
+        return from_raw(negative ? -value : value);
+    }
+
+public:
+    static constexpr int64_t SCALE = detail::pow10(Decimals);
+
+    Fixed() = default;
+    explicit Fixed(double value)
+        : raw_(static_cast<int64_t>(std::llround(value * SCALE))) {}
+
+    static Fixed from_raw(int64_t raw) {
+        Fixed f;
+        f.raw_ = raw;
+        return f;
+    }
+
+    int64_t raw() const noexcept { return raw_; }
+    explicit operator double() const noexcept {
+        return static_cast<double>(raw_) / SCALE;
+    }
+
+    Fixed operator-() const { return from_raw(-raw_); }
+    Fixed operator+(Fixed other) const { return from_raw(raw_ + other.raw_); }
+    Fixed operator-(Fixed other) const { return from_raw(raw_ - other.raw_); }
+    Fixed operator*(Fixed other) const {
+        return scaled(raw_, other.raw_, SCALE, other.raw_);
+    }
+    Fixed operator/(Fixed other) const {
+        return scaled(raw_, SCALE, other.raw_, other.raw_);
+    }
+    Fixed& operator+=(Fixed other) { raw_ += other.raw_; return *this; }
+    Fixed& operator-=(Fixed other) { raw_ -= other.raw_; return *this; }
+    Fixed& operator*=(Fixed other) { return *this = *this * other; }
+    Fixed& operator/=(Fixed other) { return *this = *this / other; }
+
+    bool operator==(Fixed other) const { return raw_ == other.raw_; }
+    bool operator!=(Fixed other) const { return raw_ != other.raw_; }
+    bool operator<(Fixed other) const { return raw_ < other.raw_; }
+    bool operator<=(Fixed other) const { return raw_ <= other.raw_; }
+    bool operator>(Fixed other) const { return raw_ > other.raw_; }
+    bool operator>=(Fixed other) const { return raw_ >= other.raw_; }
+};
+
+template <int Decimals>
+constexpr int64_t Fixed<Decimals>::SCALE;
+
+/**
+ * Missing fixed-point values are the lowest integer.
+ */
+template <int Decimals>
+struct NumericTraits<Fixed<Decimals>> {
+    static Fixed<Decimals> missing() {
+        return Fixed<Decimals>::from_raw(std::numeric_limits<int64_t>::min());
+    }
+    static bool is_missing(Fixed<Decimals> value) {
+        return value.raw() == std::numeric_limits<int64_t>::min();
+    }
+};
+
+/**
+ * Decimal type for exact accounting, with 8 decimals as crypto
+ * exchanges quote (e.g. `BasicLedger<Decimal>`).
+ */
+using Decimal = Fixed<8>;
+
+template <int Decimals>
+std::ostream& operator<<(std::ostream& os, Fixed<Decimals> value) {
+    if (NumericTraits<Fixed<Decimals>>::is_missing(value))
+        return os << "nan";
+    return os << static_cast<double>(value);
+}
+
+} // namespace tzu
+
+#endif // NUMERIC_H
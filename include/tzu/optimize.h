@@ -0,0 +1,170 @@
+#ifndef OPTIMIZE_H
+#define OPTIMIZE_H
+
+#include <algorithm>
+#include <atomic>
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <map>
+#include <ostream>
+#include <random>
+#include <string>
+#include <thread>
+#include <vector>
+
+/**
+ * This header defines parameter searches. A search generates candidate
+ * parameter sets (a full grid, random samples or a latin hypercube
+ * sample), runs a user evaluation for each one, typically a backtest
+ * returning a metric such as the Sharpe ratio, and ranks the results.
+ * Indicator periods are constructor arguments, so the evaluation can
+ * build its indicators and strategies from each parameter set.
+ *
+ * Beware that the best parameters over a dataset are likely overfit to
+ * it; validate them on data not used in the search (see
+ * `validation.h`).
+ */
+
+namespace tzu {
+
+/**
+ * A parameter and the values to try.
+ */
+struct Param {
+    std::string name;
+    std::vector<double> values;
+};
+
+/**
+ * A parameter taking the values from `start` to `stop`, both included,
+ * every `step`.
+ */
+inline Param param_range(const std::string& name, double start, double stop,
+        double step = 1.0) {
+    Param param;
+    param.name = name;
+    if (step <= 0.0) return param;
+    // tolerate rounding errors at the end of the range
+    for (size_t i = 0; start + i * step <= stop + step * 1e-9; i++)
+        param.values.push_back(start + i * step);
+    return param;
+}
+
+using ParamSet = std::map<std::string, double>;
+
+/**
+ * All the combinations of the parameter values.
+ */
+inline std::vector<ParamSet> grid(const std::vector<Param>& params) {
+    std::vector<ParamSet> sets(1);
+    for (const auto& param : params) {
+        std::vector<ParamSet> next;
+        next.reserve(sets.size() * param.values.size());
+        for (const auto& set : sets)
+            for (double value : param.values) {
+                ParamSet extended = set;
+                extended[param.name] = value;
+                next.push_back(extended);
+            }
+        sets.swap(next);
+    }
+    return sets;
+}
+
+/**
+ * `n` parameter sets with each value drawn at random from its
+ * parameter values. Sets may repeat.
+ */
+inline std::vector<ParamSet> random_samples(const std::vector<Param>& params,
+        size_t n, uint32_t seed = 0) {
+    std::mt19937 rng(seed);
+    std::vector<ParamSet> sets(n);
+    for (const auto& param : params) {
+        if (param.values.empty()) continue;
+        std::uniform_int_distribution<size_t> pick(0, param.values.size() - 1);
+        for (auto& set : sets)
+            set[param.name] = param.values[pick(rng)];
+    }
+    return sets;
+}
+
+/**
+ * `n` parameter sets from a latin hypercube sample: the values of each
+ * parameter are split into `n` strata, and each stratum is used by
+ * exactly one set, so every parameter is evenly covered with few sets.
+ */
+inline std::vector<ParamSet> latin_hypercube(const std::vector<Param>& params,
+        size_t n, uint32_t seed = 0) {
+    std::mt19937 rng(seed);
+    std::uniform_real_distribution<double> unit(0.0, 1.0);
+    std::vector<ParamSet> sets(n);
+    std::vector<size_t> strata(n);
+    for (const auto& param : params) {
+        if (param.values.empty()) continue;
+        for (size_t i = 0; i < n; i++) strata[i] = i;
+        std::shuffle(strata.begin(), strata.end(), rng);
+        for (size_t i = 0; i < n; i++) {
+            double u = (strata[i] + unit(rng)) / n;
+            size_t k = static_cast<size_t>(u * param.values.size());
+            if (k >= param.values.size()) k = param.values.size() - 1;
+            sets[i][param.name] = param.values[k];
+        }
+    }
+    return sets;
+}
+
+/**
+ * Score of a parameter set.
+ */
+struct OptimizationResult {
+    ParamSet params;
+    double score;
+};
+
+inline std::ostream& operator<<(std::ostream& os,
+        const OptimizationResult& result) {
+    os << "score:" << result.score;
+    for (const auto& p : result.params)
+        os << " " << p.first << ":" << p.second;
+    return os;
+}
+
+/**
+ * Evaluates each parameter set with `evaluate`, a callable taking a
+ * `ParamSet` and returning a score, and returns the results from the
+ * best to the worst score (highest first, or lowest if `maximize` is
+ * false); NaN scores go last. With more than one thread, the sets are
+ * evaluated in parallel, so `evaluate` must not share mutable state
+ * between calls.
+ */
+template <typename Evaluate>
+std::vector<OptimizationResult> optimize(const std::vector<ParamSet>& candidates,
+        Evaluate evaluate, size_t threads = 1, bool maximize = true) {
+    std::vector<OptimizationResult> results(candidates.size());
+    std::atomic<size_t> next(0);
+    auto work = [&]() {
+        for (size_t i = next++; i < candidates.size(); i = next++)
+            results[i] = OptimizationResult{candidates[i], evaluate(candidates[i])};
+    };
+    if (threads <= 1) {
+        work();
+    } else {
+        std::vector<std::thread> pool;
+        for (size_t t = 0; t < threads; t++)
+            pool.push_back(std::thread(work));
+        for (auto& thread : pool)
+            thread.join();
+    }
+    std::stable_sort(results.begin(), results.end(),
+        [maximize](const OptimizationResult& a, const OptimizationResult& b) {
+            if (std::isnan(b.score)) return !std::isnan(a.score);
+            if (std::isnan(a.score)) return false;
+            return maximize ? a.score > b.score : a.score < b.score;
+        });
+    return results;
+}
+
+} // namespace tzu
+
+#endif // OPTIMIZE_H
@@ -0,0 +1,291 @@
+#ifndef ORDERBOOK_H
+#define ORDERBOOK_H
+
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <map>
+#include <ostream>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines order book (level 2) data and microstructure
+ * indicators computed from it. Venues stream depth either as snapshots
+ * of the best levels or as deltas updating single levels; `OrderBook`
+ * keeps the book up to date from both, and its snapshots are the input
+ * of the indicators, which follow the interface of those in
+ * `indicators.h`.
+ */
+
+namespace tzu {
+
+/**
+ * A price level of the book, with the size resting at it.
+ */
+struct BookLevel {
+    double price = 0.0;
+    double size = 0.0;
+    BookLevel() = default;
+    BookLevel(double p, double s) : price(p), size(s) {}
+};
+
+/**
+ * The best levels of a book: bids from the highest price down, asks
+ * from the lowest price up.
+ */
+struct BookSnapshot {
+    int64_t timestamp = 0;
+    std::vector<BookLevel> bids;
+    std::vector<BookLevel> asks;
+};
+
+/**
+ * A change of a level of the book: `size` is the new size resting at
+ * `price` on the side of the bids (`BUY`) or of the asks (`SELL`), zero
+ * to remove the level.
+ */
+struct BookDelta {
+    int64_t timestamp = 0;
+    Side side = Side::NONE;
+    double price = 0.0;
+    double size = 0.0;
+    BookDelta() = default;
+    BookDelta(int64_t ts, Side s, double p, double q)
+        : timestamp(ts), side(s), price(p), size(q) {}
+};
+
+inline std::ostream& operator<<(std::ostream& os, const BookSnapshot& book) {
+    os << "timestamp:" << book.timestamp;
+    if (!book.bids.empty())
+        os << " bid:" << book.bids[0].price << " bid_size:" << book.bids[0].size;
+    if (!book.asks.empty())
+        os << " ask:" << book.asks[0].price << " ask_size:" << book.asks[0].size;
+    return os;
+}
+
+/**
+ * OrderBook
+ *
+ * Book of a symbol built from snapshots and deltas. A snapshot
+ * replaces the whole book, as venues send one to start or resync a
+ * stream of deltas. Snapshot levels with a non-finite price or size, or
+ * an empty size, are dropped. Deltas for a side other than `BUY` or
+ * `SELL`, or with a negative or non-finite size, are ignored. The book doesn't
+ * check that it isn't crossed, as some feeds are briefly between
+ * updates.
+ *
+ * Example:
+ *
+ *     OrderBook book;
+ *     Microprice micro;
+ *     book.update(BookDelta(ts, Side::BUY, 100.0, 3.0));
+ *     micro.update(book.snapshot(1));
+ */
+class OrderBook {
+    std::map<double, double, std::greater<double>> bid_levels;
+    std::map<double, double> ask_levels;
+    int64_t time = 0;
+
+    template <class Levels>
+    static void set(Levels& levels, double price, double size) {
+        if (size == 0.0) levels.erase(price);
+        else levels[price] = size;
+    }
+
+    template <class Levels>
+    static std::vector<BookLevel> top(const Levels& levels, size_t depth) {
+        std::vector<BookLevel> out;
+        for (const auto& level : levels) {
+            if (depth > 0 && out.size() == depth) break;
+            out.push_back(BookLevel(level.first, level.second));
+        }
+        return out;
+    }
+
+    static bool valid(const BookLevel& level) {
+        return std::isfinite(level.price) && std::isfinite(level.size)
+            && level.size > 0.0;
+    }
+
+public:
+    void update(const BookSnapshot& snapshot) {
+        bid_levels.clear();
+        ask_levels.clear();
+        for (const auto& level : snapshot.bids)
+            if (valid(level)) bid_levels[level.price] = level.size;
+        for (const auto& level : snapshot.asks)
+            if (valid(level)) ask_levels[level.price] = level.size;
+        time = snapshot.timestamp;
+    }
+
+    /**
+     * Applies a delta. Returns false if it was ignored.
+     */
+    bool update(const BookDelta& delta) {
+        if (!std::isfinite(delta.price) || !std::isfinite(delta.size)
+                || delta.size < 0.0)
+            return false;
+        if (delta.side == Side::BUY)
+            set(bid_levels, delta.price, delta.size);
+        else if (delta.side == Side::SELL)
+            set(ask_levels, delta.price, delta.size);
+        else
+            return false;
+        time = delta.timestamp;
+        return true;
+    }
+
+    /**
+     * The best `depth` levels of each side, all of them for zero.
+     */
+    BookSnapshot snapshot(size_t depth = 0) const {
+        BookSnapshot book;
+        book.timestamp = time;
+        book.bids = top(bid_levels, depth);
+        book.asks = top(ask_levels, depth);
+        return book;
+    }
+
+    /**
+     * Highest bid, NaN without bids.
+     */
+    double best_bid() const {
+        return bid_levels.empty() ? std::nan("") : bid_levels.begin()->first;
+    }
+
+    /**
+     * Lowest ask, NaN without asks.
+     */
+    double best_ask() const {
+        return ask_levels.empty() ? std::nan("") : ask_levels.begin()->first;
+    }
+
+    /**
+     * Number of levels on the side of the bids (`BUY`) or of the asks.
+     */
+    size_t levels(Side side) const {
+        return side == Side::BUY ? bid_levels.size() : ask_levels.size();
+    }
+
+    int64_t timestamp() const noexcept { return time; }
+};
+
+/**
+ * Bid-Ask Spread (BidAskSpread)
+ *
+ * Difference between the best ask and the best bid. If `relative`, it
+ * is divided by the midprice, as a fraction of the price. Returns NaN
+ * while a side of the book is empty.
+ */
+class BidAskSpread: public Indicator<BidAskSpread, BookSnapshot, double> {
+    double data = std::nan("");
+    bool relative;
+public:
+    explicit BidAskSpread(bool relative = false) : relative(relative) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(const BookSnapshot& book) {
+        if (book.bids.empty() || book.asks.empty()) {
+            data = std::nan("");
+            return data;
+        }
+        double bid = book.bids[0].price;
+        double ask = book.asks[0].price;
+        data = ask - bid;
+        if (relative) data /= (ask + bid) / 2.0;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data);
+    }
+    void reset_state() {
+        data = std::nan("");
+    }
+};
+
+/**
+ * Order Book Imbalance (BookImbalance)
+ *
+ * Balance of the sizes resting on the best `levels` levels of each
+ * side:
+ *
+ * imbalance = (bid size - ask size) / (bid size + ask size)
+ *
+ * from -1, only asks, to 1, only bids. Buying pressure shows as a
+ * positive imbalance, which tends to precede upticks. Returns NaN for
+ * an empty book.
+ */
+class BookImbalance: public Indicator<BookImbalance, BookSnapshot, double> {
+    double data = std::nan("");
+    size_t depth;
+
+    static double total(const std::vector<BookLevel>& levels, size_t n) {
+        double sum = 0.0;
+        for (size_t i = 0; i < levels.size() && i < n; i++)
+            sum += levels[i].size;
+        return sum;
+    }
+public:
+    explicit BookImbalance(size_t levels = 1) : depth(levels) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(const BookSnapshot& book) {
+        double bids = total(book.bids, depth);
+        double asks = total(book.asks, depth);
+        data = bids + asks > 0.0 ? (bids - asks) / (bids + asks)
+            : std::nan("");
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data);
+    }
+    void reset_state() {
+        data = std::nan("");
+    }
+};
+
+/**
+ * Microprice
+ *
+ * Midprice weighted by the sizes at the best levels:
+ *
+ * microprice = (ask * bid size + bid * ask size) / (bid size + ask size)
+ *
+ * It leans towards the side with less size, where the price is more
+ * likely to move, so it estimates the fair price better than the
+ * midprice. Returns NaN while a side of the book is empty.
+ */
+class Microprice: public Indicator<Microprice, BookSnapshot, double> {
+    double data = std::nan("");
+public:
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    double update(const BookSnapshot& book) {
+        if (book.bids.empty() || book.asks.empty()) {
+            data = std::nan("");
+            return data;
+        }
+        const BookLevel& bid = book.bids[0];
+        const BookLevel& ask = book.asks[0];
+        double size = bid.size + ask.size;
+        data = size > 0.0 ? (ask.price * bid.size + bid.price * ask.size) / size
+            : (ask.price + bid.price) / 2.0;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data);
+    }
+    void reset_state() {
+        data = std::nan("");
+    }
+};
+
+} // namespace tzu
+
+#endif // ORDERBOOK_H
@@ -0,0 +1,504 @@
+#ifndef ORDERS_H
+#define ORDERS_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <cstdlib>
+#include <map>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "broker.h"
+#include "costs.h"
+#include "ledger.h"
+#include "risk.h"
+
+/**
+ * This header defines orders and a simulated broker that executes them
+ * against bars. Strategies that send orders to the simulated broker and
+ * read their fills and the account back from it can later be connected
+ * to a live broker with the same flow.
+ */
+
+namespace tzu {
+
+enum class OrderType {
+    MARKET,
+    LIMIT,
+    STOP,
+    STOP_LIMIT
+};
+
+enum class OrderStatus {
+    NEW,
+    PARTIAL,
+    FILLED,
+    CANCELED,
+    REJECTED
+};
+
+inline const char* to_string(OrderType type) {
+    switch (type) {
+        case OrderType::MARKET: return "MARKET";
+        case OrderType::LIMIT: return "LIMIT";
+        case OrderType::STOP: return "STOP";
+        case OrderType::STOP_LIMIT: return "STOP_LIMIT";
+    }
+    return "UNKNOWN";
+}
+
+inline const char* to_string(OrderStatus status) {
+    switch (status) {
+        case OrderStatus::NEW: return "NEW";
+        case OrderStatus::PARTIAL: return "PARTIAL";
+        case OrderStatus::FILLED: return "FILLED";
+        case OrderStatus::CANCELED: return "CANCELED";
+        case OrderStatus::REJECTED: return "REJECTED";
+    }
+    return "UNKNOWN";
+}
+
+/**
+ * An order. `quantity` is signed, positive to buy and negative to
+ * sell. Limit orders use `limit_price`, stop orders `stop_price`, and
+ * stop-limit orders both. The broker sets the id, status and fill
+ * fields.
+ */
+struct Order {
+    std::string id;
+    int64_t timestamp = 0;
+    std::string symbol;
+    OrderType type = OrderType::MARKET;
+    double quantity = 0.0;
+    double limit_price = std::nan("");
+    double stop_price = std::nan("");
+    OrderStatus status = OrderStatus::NEW;
+    double filled = 0.0;
+    double avg_fill_price = 0.0;
+    bool triggered = false;
+
+    double remaining() const noexcept { return quantity - filled; }
+
+    bool is_open() const noexcept {
+        return status == OrderStatus::NEW || status == OrderStatus::PARTIAL;
+    }
+
+    static Order market(const std::string& symbol, double quantity) {
+        Order order;
+        order.symbol = symbol;
+        order.quantity = quantity;
+        return order;
+    }
+
+    static Order limit(const std::string& symbol, double quantity,
+            double price) {
+        Order order = market(symbol, quantity);
+        order.type = OrderType::LIMIT;
+        order.limit_price = price;
+        return order;
+    }
+
+    static Order stop(const std::string& symbol, double quantity,
+            double price) {
+        Order order = market(symbol, quantity);
+        order.type = OrderType::STOP;
+        order.stop_price = price;
+        return order;
+    }
+
+    static Order stop_limit(const std::string& symbol, double quantity,
+            double stop, double limit) {
+        Order order = market(symbol, quantity);
+        order.type = OrderType::STOP_LIMIT;
+        order.stop_price = stop;
+        order.limit_price = limit;
+        return order;
+    }
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Order& order) {
+    os << "id:" << order.id
+       << " timestamp:" << order.timestamp
+       << " symbol:" << order.symbol
+       << " type:" << to_string(order.type)
+       << " quantity:" << order.quantity
+       << " limit_price:" << order.limit_price
+       << " stop_price:" << order.stop_price
+       << " status:" << to_string(order.status)
+       << " filled:" << order.filled
+       << " avg_fill_price:" << order.avg_fill_price;
+    return os;
+}
+
+/**
+ * An execution of (part of) an order.
+ */
+struct Fill {
+    int64_t timestamp;
+    std::string order_id;
+    std::string symbol;
+    double quantity;
+    double price;
+    double commission;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Fill& fill) {
+    os << "timestamp:" << fill.timestamp
+       << " order_id:" << fill.order_id
+       << " symbol:" << fill.symbol
+       << " quantity:" << fill.quantity
+       << " price:" << fill.price
+       << " commission:" << fill.commission;
+    return os;
+}
+
+/**
+ * Broker
+ *
+ * Interface of the brokers orders are routed to: `SimulatedBroker` for
+ * backtests and paper trading, and connectors to real venues, such as
+ * `AlpacaBroker`. Orders are identified by the id returned when they
+ * are submitted, which is empty if the order couldn't be sent; the
+ * status of the order tells whether it was accepted. Replacing an
+ * order may give it a new id, as most venues cancel and resubmit it.
+ */
+class Broker {
+public:
+    virtual ~Broker() {}
+
+    virtual std::string submit(int64_t timestamp, Order order) = 0;
+
+    /**
+     * Cancels the unfilled part of an order. Returns false if the order
+     * is not open or the request failed.
+     */
+    virtual bool cancel(const std::string& id) = 0;
+
+    /**
+     * Changes the quantity, as a new total including the part already
+     * filled, and the prices that are not NaN, of an open order.
+     * Returns the id of the order replacing it, or an empty one if it
+     * couldn't be replaced.
+     */
+    virtual std::string replace(int64_t timestamp, const std::string& id,
+            double quantity, double limit_price = std::nan(""),
+            double stop_price = std::nan("")) = 0;
+
+    /**
+     * An order by id, with a REJECTED status if it is unknown.
+     */
+    virtual Order order(const std::string& id) const = 0;
+
+    virtual std::vector<Order> open_orders() const = 0;
+
+    /**
+     * Cash balance, positions and open orders.
+     */
+    virtual AccountState state() const = 0;
+};
+
+/**
+ * Simulated Broker (SimulatedBroker)
+ *
+ * Executes orders against the bars of each symbol and keeps the account
+ * in a `Ledger`. Orders are only matched against bars later than their
+ * submission time, so an order sent after seeing a bar is filled in the
+ * next one. The fill rules are conservative:
+ *
+ * - Market orders fill at the open.
+ * - Limit orders fill at the open if it is already at or better than
+ *   the limit, or at the limit if the price goes strictly beyond it
+ *   (touching the limit is not enough).
+ * - Stop orders trigger when the price reaches the stop, and fill at
+ *   the stop, or at the open if it gapped beyond it.
+ * - Stop-limit orders become limit orders once triggered. On the bar
+ *   that triggers them, they only fill if it opens beyond the stop and
+ *   within the limit, since the path within the bar is unknown.
+ *
+ * Market and stop fills, which take liquidity, pay slippage, by default
+ * `slippage` as a fraction of the price. Commissions are, by default,
+ * `commission` times the traded value. Other cost models from
+ * `costs.h`, or custom ones, can be set instead. With a
+ * `max_participation` fraction, each bar fills at most that fraction
+ * of its volume per order, leaving the rest for later bars. If a
+ * `RiskChecker` is set, orders are checked on submission and may be
+ * resized or rejected, and fills are reported to it.
+ */
+class SimulatedBroker: public Broker {
+    Ledger ledger;
+    SlippageModel slippage;
+    CommissionModel commission;
+    double max_participation;
+    RiskChecker* risk = nullptr;
+    std::map<std::string, Order> orders;
+    std::vector<std::string> open;
+    std::vector<Fill> last_fills;
+    std::map<std::string, double> last_price;
+    size_t next_id = 1;
+
+    void execute(Order& order, const Ohlcv& bar, double price, bool taker) {
+        double qty = order.remaining();
+        if (!std::isnan(max_participation) && max_participation > 0.0) {
+            double cap = max_participation * bar.volume;
+            if (std::fabs(qty) > cap) qty = std::copysign(cap, qty);
+        }
+        if (qty == 0.0) return;
+        if (taker && slippage) price = slippage(qty, price, bar);
+        double fee = commission ? commission(qty, price) : 0.0;
+        double total = order.filled + qty;
+        order.avg_fill_price = (order.avg_fill_price * order.filled
+                + price * qty) / total;
+        order.filled = total;
+        order.status = std::fabs(order.remaining()) < 1e-12
+            ? OrderStatus::FILLED : OrderStatus::PARTIAL;
+        ledger.on_fill(bar.timestamp, order.symbol, qty, price, fee);
+        if (risk) risk->on_fill(order.symbol, qty, price);
+        last_fills.push_back(Fill{bar.timestamp, order.id, order.symbol,
+                qty, price, fee});
+    }
+
+    // Returns true, setting `price` before slippage, if the order
+    // executes in the bar. `taker` tells if it takes liquidity.
+    static bool match(Order& order, const Ohlcv& bar, double& price,
+            bool& taker) {
+        bool buy = order.quantity > 0.0;
+        taker = order.type == OrderType::MARKET || order.type == OrderType::STOP;
+        switch (order.type) {
+            case OrderType::MARKET:
+                price = bar.open;
+                return true;
+            case OrderType::STOP:
+                if (buy ? bar.high < order.stop_price
+                        : bar.low > order.stop_price)
+                    return false;
+                price = buy ? std::fmax(bar.open, order.stop_price)
+                    : std::fmin(bar.open, order.stop_price);
+                return true;
+            case OrderType::STOP_LIMIT:
+                if (!order.triggered) {
+                    if (buy ? bar.high < order.stop_price
+                            : bar.low > order.stop_price)
+                        return false;
+                    order.triggered = true;
+                    bool gapped = buy ? bar.open >= order.stop_price
+                        : bar.open <= order.stop_price;
+                    bool within = buy ? bar.open <= order.limit_price
+                        : bar.open >= order.limit_price;
+                    price = bar.open;
+                    return gapped && within;
+                }
+                return match_limit(order, bar, price);
+            case OrderType::LIMIT:
+                return match_limit(order, bar, price);
+        }
+        return false;
+    }
+
+    static bool match_limit(const Order& order, const Ohlcv& bar,
+            double& price) {
+        bool buy = order.quantity > 0.0;
+        if (buy ? bar.open <= order.limit_price
+                : bar.open >= order.limit_price) {
+            price = bar.open;
+            return true;
+        }
+        if (buy ? bar.low < order.limit_price
+                : bar.high > order.limit_price) {
+            price = order.limit_price;
+            return true;
+        }
+        return false;
+    }
+
+public:
+    explicit SimulatedBroker(double cash, double slippage = 0.0,
+            double commission = 0.0,
+            double max_participation = std::nan(""))
+        : ledger(cash), slippage(FixedSlippage(slippage)),
+          commission(PercentageCommission(commission)),
+          max_participation(max_participation) {}
+
+    /**
+     * Replaces the commission model, e.g. with a `PerShareCommission`.
+     */
+    void set_commission(const CommissionModel& model) { commission = model; }
+
+    /**
+     * Replaces the slippage model, e.g. with a `VolumeImpactSlippage`.
+     */
+    void set_slippage(const SlippageModel& model) { slippage = model; }
+
+    /**
+     * Checks the orders and fills with a risk checker, which must
+     * outlive the broker. Pass nullptr to stop checking.
+     */
+    void set_risk_checker(RiskChecker* checker) { risk = checker; }
+
+    /**
+     * Accepts an order at a time and returns its id. Orders with a zero
+     * quantity, missing their prices, or resized to nothing by the risk
+     * checker are rejected; their status tells it.
+     */
+    std::string submit(int64_t timestamp, Order order) override {
+        order.id = std::to_string(next_id++);
+        order.timestamp = timestamp;
+        order.status = OrderStatus::NEW;
+        order.filled = 0.0;
+        order.avg_fill_price = 0.0;
+        order.triggered = false;
+        bool needs_limit = order.type == OrderType::LIMIT
+            || order.type == OrderType::STOP_LIMIT;
+        bool needs_stop = order.type == OrderType::STOP
+            || order.type == OrderType::STOP_LIMIT;
+        if (order.quantity == 0.0 || (needs_limit && std::isnan(order.limit_price))
+                || (needs_stop && std::isnan(order.stop_price)))
+            order.status = OrderStatus::REJECTED;
+        if (risk && order.status == OrderStatus::NEW) {
+            double price = needs_limit ? order.limit_price
+                : needs_stop ? order.stop_price : std::nan("");
+            if (std::isnan(price)) {
+                auto it = last_price.find(order.symbol);
+                price = it != last_price.end() ? it->second : 0.0;
+            }
+            RiskDecision decision = risk->check(timestamp, order.symbol,
+                    order.quantity, price);
+            if (decision.action == RiskAction::REJECT
+                    || decision.quantity == 0.0)
+                order.status = OrderStatus::REJECTED;
+            else
+                order.quantity = decision.quantity;
+        }
+        orders[order.id] = order;
+        if (order.status == OrderStatus::NEW)
+            open.push_back(order.id);
+        return order.id;
+    }
+
+    /**
+     * Cancels the unfilled part of an order. Returns false if the order
+     * is not open.
+     */
+    bool cancel(const std::string& id) override {
+        auto it = orders.find(id);
+        if (it == orders.end() || !it->second.is_open()) return false;
+        it->second.status = OrderStatus::CANCELED;
+        for (size_t i = 0; i < open.size(); i++)
+            if (open[i] == id) {
+                open.erase(open.begin() + i);
+                break;
+            }
+        return true;
+    }
+
+    /**
+     * Cancels an open order and submits a copy of it with the new
+     * quantity and prices, keeping the part already filled.
+     */
+    std::string replace(int64_t timestamp, const std::string& id,
+            double quantity, double limit_price = std::nan(""),
+            double stop_price = std::nan("")) override {
+        Order old = order(id);
+        if (!cancel(id)) return "";
+        Order next = old;
+        next.quantity = quantity - old.filled;
+        if (!std::isnan(limit_price)) next.limit_price = limit_price;
+        if (!std::isnan(stop_price)) next.stop_price = stop_price;
+        return submit(timestamp, next);
+    }
+
+    /**
+     * Matches the open orders of a symbol against a new bar, in the
+     * order they were submitted, and values the account at its close.
+     * Returns true if there were fills, available with `fills()`.
+     */
+    bool update(const std::string& symbol, const Ohlcv& bar) {
+        last_fills.clear();
+        std::vector<std::string> still_open;
+        for (const auto& id : open) {
+            Order& order = orders[id];
+            double price = 0.0;
+            bool taker = false;
+            if (order.symbol == symbol && order.timestamp < bar.timestamp
+                    && match(order, bar, price, taker))
+                execute(order, bar, price, taker);
+            if (order.is_open()) still_open.push_back(id);
+        }
+        open.swap(still_open);
+        last_price[symbol] = bar.close;
+        ledger.mark(symbol, bar.close);
+        if (risk) {
+            risk->mark(symbol, bar.close);
+            risk->update_equity(bar.timestamp, ledger.equity());
+        }
+        return !last_fills.empty();
+    }
+
+    /**
+     * The fills of the last update.
+     */
+    const std::vector<Fill>& fills() const noexcept { return last_fills; }
+
+    /**
+     * An order by id, with a REJECTED default if it doesn't exist.
+     */
+    Order order(const std::string& id) const override {
+        auto it = orders.find(id);
+        if (it != orders.end()) return it->second;
+        Order missing;
+        missing.id = id;
+        missing.status = OrderStatus::REJECTED;
+        return missing;
+    }
+
+    std::vector<Order> open_orders() const override {
+        std::vector<Order> result;
+        for (const auto& id : open) result.push_back(orders.at(id));
+        return result;
+    }
+
+    const Ledger& account() const noexcept { return ledger; }
+
+    /**
+     * Applies a fill made before, e.g. read from a journal when a
+     * session restarts, to the account and the risk checker. Orders
+     * submitted later get ids after the numeric ids of the fills.
+     */
+    void restore(const Fill& fill) {
+        ledger.on_fill(fill.timestamp, fill.symbol, fill.quantity, fill.price,
+                fill.commission);
+        last_price[fill.symbol] = fill.price;
+        if (risk) risk->on_fill(fill.symbol, fill.quantity, fill.price);
+        char* end;
+        unsigned long long id = std::strtoull(fill.order_id.c_str(), &end, 10);
+        if (!fill.order_id.empty() && *end == '\0' && id >= next_id)
+            next_id = static_cast<size_t>(id) + 1;
+    }
+
+    /**
+     * The account in the format used for reconciliation.
+     */
+    AccountState state() const override {
+        AccountState s;
+        s.cash = ledger.cash();
+        for (const auto& h : ledger.holdings())
+            if (h.second.quantity != 0.0)
+                s.positions[h.first] = h.second.quantity;
+        for (const auto& id : open) {
+            const Order& o = orders.at(id);
+            double price = o.type == OrderType::STOP ? o.stop_price
+                : o.type == OrderType::MARKET ? 0.0 : o.limit_price;
+            OpenOrder& open_order = s.orders[id];
+            open_order.id = id;
+            open_order.symbol = o.symbol;
+            open_order.quantity = o.remaining();
+            open_order.price = price;
+        }
+        return s;
+    }
+};
+
+} // namespace tzu
+
+#endif // ORDERS_H
@@ -0,0 +1,226 @@
+#ifndef PAPER_H
+#define PAPER_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cstdlib>
+#include <fstream>
+#include <ostream>
+#include <sstream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "events.h"
+#include "orders.h"
+#include "risk.h"
+
+/**
+ * This header defines paper trading: a strategy runs on live bars, and
+ * its orders are executed by a `SimulatedBroker` instead of a real one,
+ * so it can be validated in real time without risking capital, before
+ * writing a broker connector. The components are connected with an
+ * `EventBus`, as they would be with a real broker. Fills and equity
+ * are written to CSV files as they happen, so a session can be
+ * followed while it runs and analyzed after it ends.
+ */
+
+namespace tzu {
+
+/**
+ * Writes fills and equity as CSV lines to two files, appending to them
+ * if they exist, so a restarted session continues the same journal.
+ * Headers are written to new files. Lines are flushed as they are
+ * written. The fills already written can be read back with
+ * `read_fills` to rebuild the account.
+ */
+class PaperJournal {
+    std::ofstream fills;
+    std::ofstream equity;
+
+    static bool open(std::ofstream& out, const std::string& path,
+            const char* header) {
+        bool exists = static_cast<bool>(std::ifstream(path));
+        out.open(path, std::ios::out | std::ios::app);
+        if (!out) return false;
+        out.precision(15);
+        if (!exists) out << header << "\n" << std::flush;
+        return true;
+    }
+
+    static bool parse(const std::string& s, double& value) {
+        char* end;
+        value = std::strtod(s.c_str(), &end);
+        return !s.empty() && *end == '\0';
+    }
+
+public:
+    /**
+     * Opens the files. Returns false, with the reason in `error`, if
+     * any of them can't be opened.
+     */
+    bool open(const std::string& fills_path, const std::string& equity_path,
+            std::string& error) {
+        if (!open(fills, fills_path,
+                    "timestamp,order_id,symbol,quantity,price,commission")) {
+            error = "cannot open '" + fills_path + "'";
+            return false;
+        }
+        if (!open(equity, equity_path, "timestamp,cash,equity")) {
+            error = "cannot open '" + equity_path + "'";
+            return false;
+        }
+        return true;
+    }
+
+    bool is_open() const { return fills.is_open() && equity.is_open(); }
+
+    /**
+     * Reads the fills of a journal file, if it exists. Returns false,
+     * with the reason in `error`, if a line is invalid.
+     */
+    static bool read_fills(const std::string& path, std::vector<Fill>& out,
+            std::string& error) {
+        std::ifstream in(path);
+        std::string line;
+        size_t num = 0;
+        while (std::getline(in, line)) {
+            // the first line is the header
+            if (num++ == 0 || line.empty()) continue;
+            std::istringstream fields(line);
+            std::string timestamp, quantity, price, commission;
+            Fill f;
+            double ts = 0.0;
+            if (!std::getline(fields, timestamp, ',')
+                    || !std::getline(fields, f.order_id, ',')
+                    || !std::getline(fields, f.symbol, ',')
+                    || !std::getline(fields, quantity, ',')
+                    || !std::getline(fields, price, ',')
+                    || !std::getline(fields, commission)
+                    || !parse(timestamp, ts) || !parse(quantity, f.quantity)
+                    || !parse(price, f.price)
+                    || !parse(commission, f.commission)) {
+                error = "invalid fill at line " + std::to_string(num)
+                    + " of '" + path + "'";
+                return false;
+            }
+            f.timestamp = static_cast<int64_t>(ts);
+            out.push_back(f);
+        }
+        return true;
+    }
+
+    void write(const Fill& f) {
+        if (!fills.is_open()) return;
+        fills << f.timestamp << "," << f.order_id << "," << f.symbol << ","
+            << f.quantity << "," << f.price << "," << f.commission
+            << "\n" << std::flush;
+    }
+
+    void write(int64_t timestamp, const Ledger& ledger) {
+        if (!equity.is_open()) return;
+        equity << timestamp << "," << ledger.cash() << "," << ledger.equity()
+            << "\n" << std::flush;
+    }
+};
+
+/**
+ * PaperTrader
+ *
+ * Runs a strategy on the live bars of a symbol with a simulated broker.
+ * Signals become market orders sized by `sizer` and, with a risk
+ * checker, which must outlive the trader, are checked before being
+ * submitted (see `connect_risk`). Orders are filled by the broker on
+ * the next bar. After each bar, the equity is recorded in the journal,
+ * if one was opened. Bars not newer than the previous one are dropped,
+ * as live feeds may repeat them. More handlers can subscribe to the
+ * events of the session through `events`.
+ *
+ * Opening a journal that has fills rebuilds the account from them, so
+ * a restarted session continues with the cash and positions it had.
+ * The strategy starts anew, and bars not newer than the last fill are
+ * dropped.
+ *
+ * Example:
+ *
+ *     PaperTrader<RSIStrat> paper("BTCUSDT", RSIStrat(14), 10000.0,
+ *             fixed_size(0.01));
+ *     std::string error;
+ *     if (!paper.open("fills.csv", "equity.csv", error)) ...
+ *     paper.run(make_feed<Ohlcv, BinanceKlineParser>(queue.receiver()));
+ */
+template <class Strat>
+class PaperTrader {
+    std::string symbol;
+    Strat strat;
+    SimulatedBroker sim;
+    EventBus bus;
+    PaperJournal journal;
+    int64_t last = INT64_MIN;
+
+public:
+    PaperTrader(const std::string& symbol, const Strat& strat, double cash,
+            Sizer sizer, RiskChecker* checker = nullptr,
+            double slippage = 0.0, double commission = 0.0)
+        : symbol(symbol), strat(strat), sim(cash, slippage, commission) {
+        connect_strategy(bus, symbol, this->strat);
+        connect_risk(bus, sizer, checker);
+        connect_broker(bus, sim);
+        bus.subscribe<FillEvent>([this](const FillEvent& e) {
+            journal.write(e.fill);
+        });
+    }
+
+    PaperTrader(const PaperTrader&) = delete;
+    PaperTrader& operator=(const PaperTrader&) = delete;
+
+    /**
+     * Opens the journal files, before the first bar, and applies the
+     * fills already in it to the account. Without them, nothing is
+     * written.
+     */
+    bool open(const std::string& fills_path, const std::string& equity_path,
+            std::string& error) {
+        std::vector<Fill> previous;
+        if (!PaperJournal::read_fills(fills_path, previous, error)
+                || !journal.open(fills_path, equity_path, error))
+            return false;
+        for (const Fill& fill : previous) {
+            sim.restore(fill);
+            if (fill.timestamp > last) last = fill.timestamp;
+        }
+        return true;
+    }
+
+    /**
+     * Processes a bar. Returns false if it was dropped.
+     */
+    bool update(const Ohlcv& bar) {
+        if (bar.timestamp <= last) return false;
+        last = bar.timestamp;
+        bus.publish(MarketEvent{symbol, bar});
+        journal.write(bar.timestamp, sim.account());
+        return true;
+    }
+
+    /**
+     * Processes the bars of a feed until it ends. Returns the number of
+     * bars processed.
+     */
+    template <class Feed>
+    size_t run(Feed&& feed) {
+        size_t count = 0;
+        for (const auto& bar : feed)
+            if (update(bar)) count++;
+        return count;
+    }
+
+    const SimulatedBroker& broker() const noexcept { return sim; }
+
+    const Ledger& account() const noexcept { return sim.account(); }
+
+    EventBus& events() noexcept { return bus; }
+};
+
+} // namespace tzu
+
+#endif // PAPER_H
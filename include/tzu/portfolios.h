@@ -24,10 +24,11 @@ public:
  * BasicPortfolio implements transaction costs and simple stop-loss /
  * take-profit policies. Transaction costs are expressed as a fraction
  * of the transaction value (e.g. 0.001 == 0.1%).  This portfolio uses
- * all available cash to buy as many units as possible at each buy
- * signal, and liquidates all positions at each sell signal. Stop-loss
- * and take-profit are checked at each update and positions are
- * liquidated if the price breaches the stop-loss or take-profit
+ * the fraction of the available cash given by the signal volume (all of
+ * it with the default volume of 1.0) to buy as many units as possible
+ * at each buy signal, and liquidates all positions at each sell signal.
+ * Stop-loss and take-profit are checked at each update and positions
+ * are liquidated if the price breaches the stop-loss or take-profit
  * thresholds.  Stop-loss and take-profit are fractions relative to the
  * acquisition price (e.g. 0.1 == 10%). By default transaction costs are
  * 0 and stop-loss / take-profit are NaN (disabled).
@@ -133,8 +134,10 @@ private:
     }
 
     void execute_buy(const Signal& signal) {
+        if (!(signal.volume > 0.0)) return;
+        double budget = cash * std::fmin(signal.volume, 1.0);
         double unit_cost = signal.price * (1.0 + tx_cost_pct);
-        double qty = std::floor(cash / unit_cost);
+        double qty = std::floor(budget / unit_cost);
         if (qty > 0) {
             double cost = qty * signal.price;
             double commission = cost * tx_cost_pct;
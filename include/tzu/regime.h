@@ -0,0 +1,194 @@
+#ifndef REGIME_H
+#define REGIME_H
+
+#include <algorithm>
+#include <cmath>
+#include <cstddef>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines estimators of the persistence of a series over a
+ * rolling window, to tell trending markets from mean-reverting ones,
+ * e.g. to switch a strategy between trend following and mean reversion.
+ * They consume prices and work on their logs, so prices must be
+ * positive.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+/**
+ * The logs of the last `window` prices, oldest first.
+ */
+class LogWindow {
+    std::vector<double> logs;
+    size_t pos = 0;
+    size_t count = 0;
+
+public:
+    explicit LogWindow(size_t window) : logs(window, 0.0) {}
+
+    void add(double price) {
+        logs[pos] = std::log(price);
+        pos = (pos + 1) % logs.size();
+        if (count < logs.size()) count++;
+    }
+
+    bool full() const noexcept { return count == logs.size(); }
+
+    size_t size() const noexcept { return logs.size(); }
+
+    double operator[](size_t i) const {
+        return logs[(pos + i) % logs.size()];
+    }
+
+    /**
+     * Mean and variance, about their mean, of the changes over `lag`
+     * steps within the window.
+     */
+    void changes(size_t lag, double& mean, double& var) const {
+        size_t n = logs.size() - lag;
+        double sum = 0.0, sum2 = 0.0;
+        for (size_t i = 0; i < n; i++) {
+            double d = (*this)[i + lag] - (*this)[i];
+            sum += d;
+            sum2 += d * d;
+        }
+        mean = sum / n;
+        var = std::max(sum2 / n - mean * mean, 0.0);
+    }
+
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(logs, pos, count);
+    }
+
+    void reset() {
+        std::fill(logs.begin(), logs.end(), 0.0);
+        pos = count = 0;
+    }
+};
+
+} // namespace detail
+
+/**
+ * Hurst Exponent (Hurst)
+ *
+ * Rate at which the dispersion of the log price changes grows with the
+ * horizon, over the last `period` prices: the standard deviation of
+ * the changes over `lag` bars scales as lag^H, and H is the slope of
+ * the regression of their logs for lags from 1 to `max_lag`, a quarter
+ * of the period by default.
+ *
+ * H < 0.5: mean reverting, moves tend to reverse
+ * H = 0.5: random walk
+ * H > 0.5: trending, moves tend to persist
+ *
+ * The estimate is noisy on short windows; a hundred prices or more are
+ * advisable. Constant prices give 0.5. Returns NaN until the window is
+ * full.
+ */
+class Hurst: public Indicator<Hurst, double, double> {
+    double data = std::nan("");
+    detail::LogWindow window;
+    size_t lags;
+public:
+    explicit Hurst(size_t period, size_t max_lag = 0)
+        : window(std::max<size_t>(period, 3)),
+          lags(std::min(std::max<size_t>(max_lag > 0 ? max_lag : period / 4,
+                      2), window.size() - 1)) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.size(); }
+    double update(double value) {
+        window.add(value);
+        if (!window.full()) return data;
+        double sx = 0.0, sy = 0.0, sxx = 0.0, sxy = 0.0;
+        for (size_t lag = 1; lag <= lags; lag++) {
+            double mean = 0.0, var = 0.0;
+            window.changes(lag, mean, var);
+            if (var <= 1e-24) {
+                data = 0.5;
+                return data;
+            }
+            double x = std::log(static_cast<double>(lag));
+            double y = 0.5 * std::log(var);
+            sx += x;
+            sy += y;
+            sxx += x * x;
+            sxy += x * y;
+        }
+        double n = static_cast<double>(lags);
+        data = (n * sxy - sx * sy) / (n * sxx - sx * sx);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.reset();
+    }
+};
+
+/**
+ * Variance Ratio (VarianceRatio)
+ *
+ * Lo and MacKinlay's ratio of the variance of the log returns over
+ * `lag` bars to `lag` times that of the returns over one bar, within
+ * the last `period` prices, using overlapping returns and the mean
+ * return of the window:
+ *
+ * VR = Var(r[lag]) / (lag * Var(r[1]))
+ *
+ * VR < 1: mean reverting
+ * VR = 1: random walk
+ * VR > 1: trending
+ *
+ * Constant prices give 1. Returns NaN until the window is full.
+ */
+class VarianceRatio: public Indicator<VarianceRatio, double, double> {
+    double data = std::nan("");
+    detail::LogWindow window;
+    size_t lag;
+public:
+    VarianceRatio(size_t period, size_t lag = 2)
+        : window(std::max<size_t>(period, 3)),
+          lag(std::min(std::max<size_t>(lag, 2), window.size() - 1)) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.size(); }
+    double update(double value) {
+        window.add(value);
+        if (!window.full()) return data;
+        double mean = 0.0, var = 0.0;
+        window.changes(1, mean, var);
+        if (var <= 1e-24) {
+            data = 1.0;
+            return data;
+        }
+        size_t n = window.size() - lag;
+        double drift = lag * mean;
+        double sum2 = 0.0;
+        for (size_t i = 0; i < n; i++) {
+            double d = window[i + lag] - window[i] - drift;
+            sum2 += d * d;
+        }
+        data = sum2 / n / (lag * var);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.reset();
+    }
+};
+
+} // namespace tzu
+
+#endif // REGIME_H
@@ -0,0 +1,380 @@
+#ifndef REGISTRY_H
+#define REGISTRY_H
+
+#include <cctype>
+#include <cmath>
+#include <cstddef>
+#include <cstdlib>
+#include <functional>
+#include <map>
+#include <memory>
+#include <string>
+#include <vector>
+#include "anchored.h"
+#include "defs.h"
+#include "dynamic.h"
+#include "ehlers.h"
+#include "filters.h"
+#include "indicators.h"
+#include "regime.h"
+#include "statistics.h"
+#include "transforms.h"
+#include "volatility.h"
+
+/**
+ * This header defines a registry building indicators from text specs
+ * such as `"RSI(14)"` or `"Highest(20) of high"`, so they can come from
+ * configuration files or user input. The indicators are returned as
+ * `DynIndicator`s. `builtin_indicators()` knows the indicators of the
+ * library, and more can be added to a copy of it.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+inline std::string trim(const std::string& s) {
+    size_t first = s.find_first_not_of(" \t\r\n");
+    if (first == std::string::npos) return "";
+    size_t last = s.find_last_not_of(" \t\r\n");
+    return s.substr(first, last - first + 1);
+}
+
+inline bool parse_number(const std::string& s, double& value) {
+    std::string t = trim(s);
+    if (t.empty()) return false;
+    char* end;
+    value = std::strtod(t.c_str(), &end);
+    return *end == '\0' && std::isfinite(value);
+}
+
+inline bool parse_field(const std::string& s, OhlcvField& field) {
+    if (s == "open") field = OhlcvField::OPEN;
+    else if (s == "high") field = OhlcvField::HIGH;
+    else if (s == "low") field = OhlcvField::LOW;
+    else if (s == "close") field = OhlcvField::CLOSE;
+    else if (s == "volume") field = OhlcvField::VOLUME;
+    else return false;
+    return true;
+}
+
+inline double arg_or(const std::vector<double>& args, size_t i, double value) {
+    return i < args.size() ? args[i] : value;
+}
+
+inline size_t period_arg(const std::vector<double>& args, size_t i,
+        size_t value = 0) {
+    return i < args.size() ? static_cast<size_t>(args[i]) : value;
+}
+
+inline bool is_integer(double x, double low, double high) {
+    return x >= low && x <= high && x == std::floor(x);
+}
+
+inline bool in_range(double x, double low, double high) {
+    return x >= low && x <= high;
+}
+
+} // namespace detail
+
+/**
+ * IndicatorRegistry
+ *
+ * Builds indicators by name. Each name has a factory taking the
+ * arguments of the spec and the bar field to feed, and the number of
+ * arguments it accepts. The first `periods` arguments must be integers
+ * between 1 and `max_period`, and an optional check can reject other
+ * invalid ones. A spec is the name, optionally followed by the
+ * arguments in parentheses, and optionally by `of` and the field:
+ *
+ *     MACD(12, 26, 9)
+ *     Highest(20) of high
+ *     Pivots
+ */
+class IndicatorRegistry {
+public:
+    using Factory = std::function<DynIndicator(const std::vector<double>&,
+            OhlcvField)>;
+    using Check = std::function<bool(const std::vector<double>&)>;
+
+    static constexpr double max_period = 1e6;
+
+private:
+    struct Entry {
+        Factory make;
+        size_t min_args;
+        size_t max_args;
+        size_t periods;
+        Check check;
+    };
+    std::map<std::string, Entry> entries;
+
+public:
+    /**
+     * Registers an indicator, replacing any other with the same name.
+     */
+    void add(const std::string& name, size_t min_args, size_t max_args,
+            size_t periods, Factory make, Check check = nullptr) {
+        entries[name] = Entry{make, min_args, max_args, periods, check};
+    }
+
+    bool contains(const std::string& name) const {
+        return entries.count(name) > 0;
+    }
+
+    std::vector<std::string> names() const {
+        std::vector<std::string> all;
+        for (const auto& e : entries) all.push_back(e.first);
+        return all;
+    }
+
+    /**
+     * Builds the indicator of a spec. Returns null, with the reason in
+     * `error` if given, for unknown names and invalid arguments.
+     */
+    std::unique_ptr<DynIndicator> make(const std::string& spec,
+            std::string* error = nullptr) const {
+        std::string s = detail::trim(spec);
+        OhlcvField field = OhlcvField::CLOSE;
+        size_t of = s.rfind(" of ");
+        if (of != std::string::npos) {
+            if (!detail::parse_field(detail::trim(s.substr(of + 4)), field))
+                return fail(error, "unknown field in '" + spec + "'");
+            s = detail::trim(s.substr(0, of));
+        }
+        std::vector<double> args;
+        size_t open = s.find('(');
+        std::string name = detail::trim(s.substr(0, open));
+        if (open != std::string::npos) {
+            if (s.back() != ')')
+                return fail(error, "missing ')' in '" + spec + "'");
+            std::string inner = s.substr(open + 1, s.size() - open - 2);
+            size_t start = 0;
+            while (!detail::trim(inner).empty() && start <= inner.size()) {
+                size_t comma = inner.find(',', start);
+                if (comma == std::string::npos) comma = inner.size();
+                double value;
+                if (!detail::parse_number(inner.substr(start, comma - start),
+                            value))
+                    return fail(error, "invalid argument in '" + spec + "'");
+                args.push_back(value);
+                start = comma + 1;
+            }
+        }
+        auto it = entries.find(name);
+        if (it == entries.end())
+            return fail(error, "unknown indicator '" + name + "'");
+        const Entry& e = it->second;
+        if (args.size() < e.min_args || args.size() > e.max_args)
+            return fail(error, "wrong number of arguments in '" + spec + "'");
+        for (size_t i = 0; i < e.periods && i < args.size(); i++)
+            if (!detail::is_integer(args[i], 1.0, max_period))
+                return fail(error, "invalid period in '" + spec + "'");
+        if (e.check && !e.check(args))
+            return fail(error, "invalid argument in '" + spec + "'");
+        return std::unique_ptr<DynIndicator>(
+                new DynIndicator(e.make(args, field)));
+    }
+
+private:
+    static std::unique_ptr<DynIndicator> fail(std::string* error,
+            const std::string& message) {
+        if (error) *error = message;
+        return nullptr;
+    }
+};
+
+/**
+ * The indicators of the library, under their class names. Arguments
+ * follow the constructors, with the same defaults, except for `MVar`
+ * and `TimeMVar` whose degrees of freedom default to 1, as for
+ * `ZScore`. Degrees of freedom must be below the period, quantiles
+ * within [0, 1], noise variances positive and MAMA limits within
+ * (0, 1].
+ */
+inline const IndicatorRegistry& builtin_indicators() {
+    static const IndicatorRegistry registry = [] {
+        using Args = const std::vector<double>&;
+        using detail::arg_or;
+        using detail::in_range;
+        using detail::is_integer;
+        using detail::period_arg;
+        constexpr double max_period = IndicatorRegistry::max_period;
+        constexpr double max_duration = 1e18;
+        IndicatorRegistry r;
+        r.add("SMA", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(SMA(period_arg(a, 0)), f);
+        });
+        r.add("EMA", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(EMA(period_arg(a, 0), arg_or(a, 1, 2.0)), f);
+        });
+        r.add("WMA", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(WMA(period_arg(a, 0)), f);
+        });
+        r.add("HMA", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(HMA(period_arg(a, 0)), f);
+        });
+        r.add("ZLEMA", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(ZLEMA(period_arg(a, 0), arg_or(a, 1, 2.0)), f);
+        });
+        r.add("T3", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(T3(period_arg(a, 0), arg_or(a, 1, 0.7)), f);
+        });
+        r.add("KAMA", 1, 3, 3, [](Args a, OhlcvField f) {
+            return DynIndicator(KAMA(period_arg(a, 0), period_arg(a, 1, 2),
+                        period_arg(a, 2, 30)), f);
+        });
+        r.add("MVar", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(MVar(period_arg(a, 0),
+                        static_cast<size_t>(arg_or(a, 1, 1.0))), f);
+        }, [](Args a) {
+            return is_integer(arg_or(a, 1, 1.0), 0.0, a[0] - 1.0);
+        });
+        r.add("Sharpe", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(Sharpe(period_arg(a, 0), arg_or(a, 1, 1.0)), f);
+        });
+        r.add("Sortino", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(Sortino(period_arg(a, 0), arg_or(a, 1, 1.0)), f);
+        });
+        r.add("RSI", 0, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(RSI(period_arg(a, 0, 14)), f);
+        });
+        r.add("MACD", 3, 4, 3, [](Args a, OhlcvField f) {
+            return DynIndicator(MACD(period_arg(a, 0), period_arg(a, 1),
+                        period_arg(a, 2), arg_or(a, 3, 2.0)), f);
+        });
+        r.add("LinReg", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(LinReg(period_arg(a, 0), arg_or(a, 1, 2.0)), f);
+        });
+        r.add("ATR", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(ATR(period_arg(a, 0)), f);
+        });
+        r.add("ADX", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(ADX(period_arg(a, 0)), f);
+        });
+        r.add("ElderRay", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(ElderRay(period_arg(a, 0), arg_or(a, 1, 2.0)), f);
+        });
+        r.add("Highest", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(Highest(period_arg(a, 0)), f);
+        });
+        r.add("Lowest", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(Lowest(period_arg(a, 0)), f);
+        });
+        r.add("Aroon", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(Aroon(period_arg(a, 0)), f);
+        });
+        r.add("StochRSI", 0, 4, 4, [](Args a, OhlcvField f) {
+            return DynIndicator(StochRSI(period_arg(a, 0, 14),
+                        period_arg(a, 1, 14), period_arg(a, 2, 3),
+                        period_arg(a, 3, 3)), f);
+        });
+        r.add("Pivots", 0, 0, 0, [](Args, OhlcvField f) {
+            return DynIndicator(Pivots(), f);
+        });
+        r.add("TimeSMA", 1, 1, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(TimeSMA(static_cast<int64_t>(a[0])), f);
+        }, [](Args a) {
+            return is_integer(a[0], 1.0, max_duration);
+        });
+        r.add("TimeMVar", 1, 2, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(TimeMVar(static_cast<int64_t>(a[0]),
+                        static_cast<size_t>(arg_or(a, 1, 1.0))), f);
+        }, [](Args a) {
+            return is_integer(a[0], 1.0, max_duration)
+                && is_integer(arg_or(a, 1, 1.0), 0.0, max_period);
+        });
+        r.add("EWMAVol", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(EWMAVol(period_arg(a, 0)), f);
+        });
+        r.add("GARCH", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(GARCH::fit(period_arg(a, 0),
+                        period_arg(a, 1, 0)), f);
+        }, [](Args a) {
+            return is_integer(arg_or(a, 1, 0.0), 0.0, max_period);
+        });
+        r.add("Kalman", 1, 2, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(Kalman(a[0], arg_or(a, 1, 1.0)), f);
+        }, [](Args a) {
+            return a[0] > 0.0 && arg_or(a, 1, 1.0) > 0.0;
+        });
+        r.add("KalmanTrend", 2, 3, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(Kalman::trend(a[0], a[1], arg_or(a, 2, 1.0)),
+                    f);
+        }, [](Args a) {
+            return a[0] > 0.0 && a[1] > 0.0 && arg_or(a, 2, 1.0) > 0.0;
+        });
+        r.add("SuperSmoother", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(SuperSmoother(period_arg(a, 0)), f);
+        });
+        r.add("Roofing", 0, 2, 2, [](Args a, OhlcvField f) {
+            return DynIndicator(Roofing(period_arg(a, 0, 48),
+                        period_arg(a, 1, 10)), f);
+        });
+        r.add("Decycler", 0, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(Decycler(period_arg(a, 0, 125)), f);
+        });
+        r.add("FisherTransform", 0, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(FisherTransform(period_arg(a, 0, 10)), f);
+        });
+        r.add("MAMA", 0, 2, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(MAMA(arg_or(a, 0, 0.5), arg_or(a, 1, 0.05)), f);
+        }, [](Args a) {
+            return arg_or(a, 0, 0.5) > 0.0 && arg_or(a, 0, 0.5) <= 1.0
+                && arg_or(a, 1, 0.05) > 0.0 && arg_or(a, 1, 0.05) <= 1.0;
+        });
+        r.add("VWAP", 0, 0, 0, [](Args, OhlcvField f) {
+            return DynIndicator(VWAP(), f);
+        });
+        r.add("CumulativeReturn", 0, 0, 0, [](Args, OhlcvField f) {
+            return DynIndicator(CumulativeReturn(), f);
+        });
+        r.add("P2Quantile", 1, 1, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(P2Quantile(a[0]), f);
+        }, [](Args a) {
+            return in_range(a[0], 0.0, 1.0);
+        });
+        r.add("Quantile", 1, 2, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(Quantile(a[0], period_arg(a, 1, 0)), f);
+        }, [](Args a) {
+            return in_range(a[0], 0.0, 1.0)
+                && is_integer(arg_or(a, 1, 0.0), 0.0, max_period);
+        });
+        r.add("Percentile", 2, 2, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(Percentile(a[0], period_arg(a, 1)), f);
+        }, [](Args a) {
+            return in_range(a[0], 0.0, 1.0)
+                && is_integer(a[1], 1.0, max_period);
+        });
+        r.add("PercentRank", 1, 1, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(PercentRank(period_arg(a, 0)), f);
+        });
+        r.add("Hurst", 1, 2, 2, [](Args a, OhlcvField f) {
+            return DynIndicator(Hurst(period_arg(a, 0), period_arg(a, 1, 0)), f);
+        });
+        r.add("VarianceRatio", 1, 2, 2, [](Args a, OhlcvField f) {
+            return DynIndicator(VarianceRatio(period_arg(a, 0),
+                        period_arg(a, 1, 2)), f);
+        });
+        r.add("ZScore", 1, 2, 1, [](Args a, OhlcvField f) {
+            return DynIndicator(ZScore(period_arg(a, 0),
+                        static_cast<size_t>(arg_or(a, 1, 1.0))), f);
+        }, [](Args a) {
+            return is_integer(arg_or(a, 1, 1.0), 0.0, a[0] - 1.0);
+        });
+        r.add("FracDiff", 2, 2, 0, [](Args a, OhlcvField f) {
+            return DynIndicator(FracDiff(a[0], period_arg(a, 1)), f);
+        }, [](Args a) {
+            return is_integer(a[1], 1.0, max_period);
+        });
+        r.add("HeikinAshi", 0, 0, 0, [](Args, OhlcvField f) {
+            return DynIndicator(HeikinAshi(), f);
+        });
+        return r;
+    }();
+    return registry;
+}
+
+} // namespace tzu
+
+#endif // REGISTRY_H
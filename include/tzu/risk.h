@@ -0,0 +1,384 @@
+#ifndef RISK_H
+#define RISK_H
+
+#include <cstdint>
+#include <cmath>
+#include <cstddef>
+#include <map>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines pre-trade risk controls. Orders are described by
+ * a symbol, a signed quantity (positive to buy, negative to sell) and
+ * a reference price. Each order is checked against the configured
+ * limits before it is sent for execution: it is accepted, resized down
+ * to the largest quantity that respects every limit, or rejected. Every
+ * resize or rejection is recorded as a `RiskEvent`. A `CircuitBreaker`
+ * halts trading altogether when the account or the infrastructure
+ * misbehaves.
+ */
+
+namespace tzu {
+
+/**
+ * Risk limits. A NaN limit is disabled. Exposures are absolute values
+ * of quantity times price; gross exposure adds them up over all the
+ * symbols, net exposure adds the signed values. `max_daily_loss` is a
+ * positive amount: once the equity falls that much below its value at
+ * the start of the day, only orders that reduce positions are allowed
+ * until the next day.
+ */
+struct RiskLimits {
+    double max_position = std::nan("");
+    double max_gross_exposure = std::nan("");
+    double max_net_exposure = std::nan("");
+    double max_order_quantity = std::nan("");
+    double max_order_notional = std::nan("");
+    double max_daily_loss = std::nan("");
+};
+
+enum class RiskViolation {
+    NONE,
+    POSITION,
+    GROSS_EXPOSURE,
+    NET_EXPOSURE,
+    ORDER_QUANTITY,
+    ORDER_NOTIONAL,
+    DAILY_LOSS
+};
+
+enum class RiskAction {
+    ACCEPT,
+    RESIZE,
+    REJECT
+};
+
+/**
+ * Outcome of a risk check. `quantity` is the signed quantity allowed,
+ * and `violation` the first limit that constrained the order.
+ */
+struct RiskDecision {
+    RiskAction action = RiskAction::ACCEPT;
+    double quantity = 0.0;
+    RiskViolation violation = RiskViolation::NONE;
+};
+
+/**
+ * A record of an order that was resized or rejected.
+ */
+struct RiskEvent {
+    int64_t timestamp;
+    std::string symbol;
+    RiskAction action;
+    RiskViolation violation;
+    double requested;
+    double allowed;
+};
+
+inline const char* to_string(RiskViolation violation) {
+    switch (violation) {
+        case RiskViolation::NONE: return "NONE";
+        case RiskViolation::POSITION: return "POSITION";
+        case RiskViolation::GROSS_EXPOSURE: return "GROSS_EXPOSURE";
+        case RiskViolation::NET_EXPOSURE: return "NET_EXPOSURE";
+        case RiskViolation::ORDER_QUANTITY: return "ORDER_QUANTITY";
+        case RiskViolation::ORDER_NOTIONAL: return "ORDER_NOTIONAL";
+        case RiskViolation::DAILY_LOSS: return "DAILY_LOSS";
+    }
+    return "UNKNOWN";
+}
+
+inline std::ostream& operator<<(std::ostream& os, const RiskEvent& event) {
+    os << "timestamp:" << event.timestamp
+       << " symbol:" << event.symbol
+       << " action:" << (event.action == RiskAction::REJECT ? "REJECT"
+               : event.action == RiskAction::RESIZE ? "RESIZE" : "ACCEPT")
+       << " violation:" << to_string(event.violation)
+       << " requested:" << event.requested
+       << " allowed:" << event.allowed;
+    return os;
+}
+
+/**
+ * Pre-trade risk checker. It keeps track of the positions resulting
+ * from fills (`on_fill`), the latest prices (`mark`) and the account
+ * equity (`update_equity`) to evaluate the limits. If `resize` is
+ * false, orders that would break a limit are rejected instead of being
+ * resized.
+ */
+class RiskChecker {
+    struct Holding {
+        double quantity = 0.0;
+        double price = 0.0;
+    };
+    RiskLimits limits;
+    bool resize;
+    int64_t day_length;
+    std::map<std::string, Holding> holdings;
+    std::vector<RiskEvent> events;
+    int64_t day = INT64_MIN;
+    double day_start_equity = std::nan("");
+    double equity = std::nan("");
+
+    // Shrinks the magnitude of the increasing part of an order so that
+    // |current + qty| stays within `limit`.
+    static double cap(double current, double qty, double limit) {
+        double target = current + qty;
+        if (std::fabs(target) <= limit || std::fabs(target) <= std::fabs(current))
+            return qty;
+        double bound = target > 0.0 ? limit : -limit;
+        double allowed = bound - current;
+        // never flip the direction of the order
+        return allowed * qty > 0.0 ? allowed : 0.0;
+    }
+
+    void constrain(double& qty, double allowed, RiskViolation v,
+            RiskViolation& first) const {
+        if (std::fabs(allowed) < std::fabs(qty)) {
+            qty = allowed;
+            if (first == RiskViolation::NONE) first = v;
+        }
+    }
+
+    double gross_exposure() const {
+        double total = 0.0;
+        for (const auto& h : holdings)
+            total += std::fabs(h.second.quantity * h.second.price);
+        return total;
+    }
+
+    double net_exposure() const {
+        double total = 0.0;
+        for (const auto& h : holdings)
+            total += h.second.quantity * h.second.price;
+        return total;
+    }
+
+public:
+    RiskChecker(const RiskLimits& limits, bool resize = true,
+            int64_t day_length = 86400)
+        : limits(limits), resize(resize), day_length(day_length) {}
+
+    /**
+     * Checks an order and returns the decision. Accepted and resized
+     * orders are not applied to the positions until `on_fill` is
+     * called with the actual execution.
+     */
+    RiskDecision check(int64_t timestamp, const std::string& symbol,
+            double quantity, double price) {
+        RiskDecision decision;
+        decision.quantity = quantity;
+        Holding& h = holdings[symbol];
+        if (price > 0.0) h.price = price;
+        double current = h.quantity;
+        bool reduces = std::fabs(current + quantity) < std::fabs(current)
+            && current * quantity < 0.0;
+        double qty = quantity;
+        RiskViolation first = RiskViolation::NONE;
+        if (!std::isnan(limits.max_order_quantity))
+            constrain(qty, std::copysign(limits.max_order_quantity, qty),
+                    RiskViolation::ORDER_QUANTITY, first);
+        if (!std::isnan(limits.max_order_notional) && price > 0.0)
+            constrain(qty, std::copysign(limits.max_order_notional / price, qty),
+                    RiskViolation::ORDER_NOTIONAL, first);
+        if (!reduces) {
+            if (!std::isnan(limits.max_daily_loss) && !std::isnan(equity)
+                    && day_start_equity - equity >= limits.max_daily_loss)
+                constrain(qty, 0.0, RiskViolation::DAILY_LOSS, first);
+            if (!std::isnan(limits.max_position))
+                constrain(qty, cap(current, qty, limits.max_position),
+                        RiskViolation::POSITION, first);
+            if (!std::isnan(limits.max_gross_exposure) && price > 0.0) {
+                double others = gross_exposure() - std::fabs(current * price);
+                double room = (limits.max_gross_exposure - others) / price;
+                constrain(qty, cap(current, qty, room > 0.0 ? room : 0.0),
+                        RiskViolation::GROSS_EXPOSURE, first);
+            }
+            if (!std::isnan(limits.max_net_exposure) && price > 0.0) {
+                double net = net_exposure();
+                double target = net + qty * price;
+                if (std::fabs(target) > limits.max_net_exposure
+                        && std::fabs(target) > std::fabs(net)) {
+                    double bound = target > 0.0
+                        ? limits.max_net_exposure : -limits.max_net_exposure;
+                    double allowed = (bound - net) / price;
+                    constrain(qty, allowed * qty > 0.0 ? allowed : 0.0,
+                            RiskViolation::NET_EXPOSURE, first);
+                }
+            }
+        }
+        if (first == RiskViolation::NONE)
+            return decision;
+        decision.violation = first;
+        if (resize && qty != 0.0) {
+            decision.action = RiskAction::RESIZE;
+            decision.quantity = qty;
+        } else {
+            decision.action = RiskAction::REJECT;
+            decision.quantity = 0.0;
+        }
+        events.push_back(RiskEvent{timestamp, symbol, decision.action,
+                first, quantity, decision.quantity});
+        return decision;
+    }
+
+    /**
+     * Applies an executed quantity (signed) to the positions.
+     */
+    void on_fill(const std::string& symbol, double quantity, double price) {
+        Holding& h = holdings[symbol];
+        h.quantity += quantity;
+        h.price = price;
+    }
+
+    /**
+     * Updates the latest price of a symbol, used for exposures.
+     */
+    void mark(const std::string& symbol, double price) {
+        holdings[symbol].price = price;
+    }
+
+    /**
+     * Updates the account equity. The first value of each day (days
+     * are `day_length` units long) is the reference for the daily loss
+     * limit.
+     */
+    void update_equity(int64_t timestamp, double value) {
+        int64_t d = timestamp / day_length;
+        if (d != day) {
+            day = d;
+            day_start_equity = value;
+        }
+        equity = value;
+    }
+
+    double position(const std::string& symbol) const {
+        auto it = holdings.find(symbol);
+        return it == holdings.end() ? 0.0 : it->second.quantity;
+    }
+
+    const std::vector<RiskEvent>& get_events() const noexcept { return events; }
+};
+
+enum class BreakerTrigger {
+    NONE,
+    DRAWDOWN,
+    ERROR_RATE,
+    STALE_FEED,
+    MANUAL
+};
+
+inline const char* to_string(BreakerTrigger trigger) {
+    switch (trigger) {
+        case BreakerTrigger::NONE: return "NONE";
+        case BreakerTrigger::DRAWDOWN: return "DRAWDOWN";
+        case BreakerTrigger::ERROR_RATE: return "ERROR_RATE";
+        case BreakerTrigger::STALE_FEED: return "STALE_FEED";
+        case BreakerTrigger::MANUAL: return "MANUAL";
+    }
+    return "UNKNOWN";
+}
+
+/**
+ * Circuit breaker settings. A NaN or zero threshold disables the
+ * trigger. `max_drawdown` is a fraction of the equity peak (e.g. 0.2),
+ * the error-rate trigger fires when `max_errors` errors are recorded
+ * within `error_window` time units, and the stale-feed trigger fires
+ * when no data has been received for `max_staleness` time units.
+ * `flatten` asks the caller to close all positions when tripped.
+ */
+struct BreakerConfig {
+    double max_drawdown = std::nan("");
+    size_t max_errors = 0;
+    int64_t error_window = 60;
+    int64_t max_staleness = 0;
+    bool flatten = false;
+};
+
+/**
+ * Kill switch for unattended trading. Once tripped it stays halted,
+ * and `allow()` returns false, until it is explicitly reset. The
+ * caller feeds it with the equity, errors and data timestamps, and
+ * calls `check` periodically with the current time so a silent feed
+ * is detected even when no data arrives.
+ */
+class CircuitBreaker {
+    BreakerConfig config;
+    BreakerTrigger trigger = BreakerTrigger::NONE;
+    int64_t tripped_at = 0;
+    double peak = std::nan("");
+    std::vector<int64_t> errors;
+    int64_t last_data = INT64_MIN;
+
+    void trip(int64_t timestamp, BreakerTrigger reason) {
+        if (trigger != BreakerTrigger::NONE) return;
+        trigger = reason;
+        tripped_at = timestamp;
+    }
+
+public:
+    explicit CircuitBreaker(const BreakerConfig& config): config(config) {}
+
+    void update_equity(int64_t timestamp, double equity) {
+        if (std::isnan(peak) || equity > peak) peak = equity;
+        if (!std::isnan(config.max_drawdown) && config.max_drawdown > 0.0
+                && peak > 0.0 && (peak - equity) / peak >= config.max_drawdown)
+            trip(timestamp, BreakerTrigger::DRAWDOWN);
+    }
+
+    void record_error(int64_t timestamp) {
+        if (config.max_errors == 0) return;
+        errors.push_back(timestamp);
+        size_t first = 0;
+        while (first < errors.size()
+                && errors[first] <= timestamp - config.error_window)
+            first++;
+        errors.erase(errors.begin(), errors.begin() + first);
+        if (errors.size() >= config.max_errors)
+            trip(timestamp, BreakerTrigger::ERROR_RATE);
+    }
+
+    void on_data(int64_t timestamp) {
+        if (timestamp > last_data) last_data = timestamp;
+    }
+
+    /**
+     * Evaluates the time-based triggers at `now`. Returns true if
+     * trading is allowed.
+     */
+    bool check(int64_t now) {
+        if (config.max_staleness > 0 && last_data != INT64_MIN
+                && now - last_data > config.max_staleness)
+            trip(now, BreakerTrigger::STALE_FEED);
+        return allow();
+    }
+
+    void halt(int64_t timestamp) { trip(timestamp, BreakerTrigger::MANUAL); }
+
+    /**
+     * Trips the breaker for a feed reported as dead by an external
+     * monitor, e.g. a `Watchdog`.
+     */
+    void feed_lost(int64_t timestamp) {
+        trip(timestamp, BreakerTrigger::STALE_FEED);
+    }
+
+    void reset() {
+        trigger = BreakerTrigger::NONE;
+        tripped_at = 0;
+        peak = std::nan("");
+        errors.clear();
+    }
+
+    bool allow() const noexcept { return trigger == BreakerTrigger::NONE; }
+    bool should_flatten() const noexcept { return !allow() && config.flatten; }
+    BreakerTrigger reason() const noexcept { return trigger; }
+    int64_t since() const noexcept { return tripped_at; }
+};
+
+} // namespace tzu
+
+#endif // RISK_H
@@ -1,9 +1,12 @@
 #ifndef RUNNERS_H
 #define RUNNERS_H
 
+#include <type_traits>
+#include <vector>
 #include "strategies.h"
 #include "portfolios.h"
 #include "streamers.h"
+#include "lookahead.h"
 
 namespace tzu {
 
@@ -47,6 +50,43 @@ public:
     }
 };
 
+/**
+ * GuardedRunner works like `BasicRunner`, but first loads all the data
+ * from the streamer and runs a copy of the strategy through
+ * `check_lookahead`. If any lookahead issue is found, the diagnostic is
+ * written to standard error and the backtest is aborted, returning
+ * false. Since the data is loaded into memory, this runner is meant for
+ * validating strategies rather than for very large datasets.
+ */
+template <typename Portfolio, typename Strat, typename Streamer>
+class GuardedRunner: public Runner<GuardedRunner<Portfolio, Strat, Streamer>> {
+    Portfolio portfolio;
+    Strat strat;
+    Streamer streamer;
+public:
+    GuardedRunner(Portfolio& portfolio, Strat& strat, Streamer& streamer)
+        : portfolio(portfolio), strat(strat), streamer(streamer) {}
+    bool run(bool verbose = false) {
+        using Row = typename std::decay<decltype(*streamer.begin())>::type;
+        std::vector<Row> data;
+        for (const auto& row : streamer)
+            data.push_back(row);
+        LookaheadReport report = check_lookahead(strat, data);
+        if (!report.ok()) {
+            std::cerr << report << std::endl;
+            return false;
+        }
+        for (const auto& row : data) {
+            auto sig = strat.update(row);
+            portfolio.update(sig);
+            if (verbose) std::cout << portfolio << std::endl;
+        }
+        if (!verbose)
+            std::cout << portfolio << std::endl;
+        return true;
+    }
+};
+
 } // namespace tzu
 
 #endif // RUNNERS_H
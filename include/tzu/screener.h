@@ -0,0 +1,200 @@
+#ifndef SCREENER_H
+#define SCREENER_H
+
+#include <cstddef>
+#include <cstdint>
+#include <map>
+#include <memory>
+#include <ostream>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "dynamic.h"
+#include "engine.h"
+#include "expr.h"
+#include "registry.h"
+
+/**
+ * This header defines a screener, which evaluates the same conditions
+ * on many symbols and tells which of them meet all of them at their
+ * latest bar, e.g. for daily scans such as "RSI below 30 and close
+ * above the 200-day average". Indicators are declared by name with
+ * registry specs and kept per symbol by an `IndicatorEngine`, and
+ * conditions are expressions (see `expr.h`) using them.
+ */
+
+namespace tzu {
+
+/**
+ * A symbol meeting the conditions of a `Screener`, with its latest bar
+ * and the values at that bar of the named indicators and of the ones
+ * called in the conditions, under their calls in lower case (see
+ * `Expression::values`).
+ */
+struct ScreenerMatch {
+    std::string symbol;
+    Ohlcv bar;
+    std::map<std::string, double> values;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const ScreenerMatch& m) {
+    os << "symbol:" << m.symbol << " timestamp:" << m.bar.timestamp
+        << " close:" << m.bar.close;
+    for (const auto& v : m.values)
+        os << " " << v.first << ":" << v.second;
+    return os;
+}
+
+/**
+ * Screener
+ *
+ * Keeps the named indicators and the conditions for every symbol. Each
+ * bar updates the indicators of its symbol and evaluates all of its
+ * conditions, so crosses and the indicators called inside them see
+ * every bar. `matches` gives the symbols for which all the conditions
+ * held at their latest bar; without conditions, every symbol matches.
+ * Indicators and conditions added after some bars were received start
+ * fresh for the symbols already seen.
+ *
+ * Example:
+ *
+ *     Screener screener;
+ *     std::string error;
+ *     screener.add_indicator("sma200", "SMA(200)", error);
+ *     screener.add_condition("rsi(14) < 30", error);
+ *     screener.add_condition("close > sma200", error);
+ *     for (const auto& m : screener.scan(daily_bars))
+ *         std::cout << m << "\n";
+ */
+class Screener {
+    struct SymbolState {
+        std::vector<Expression> rules;
+        Ohlcv bar;
+        bool matched = false;
+    };
+    const IndicatorRegistry* registry;
+    IndicatorEngine engine;
+    std::vector<Expression> prototypes;
+    std::map<std::string, SymbolState> states;
+
+    SymbolState& state(const std::string& symbol) {
+        auto it = states.find(symbol);
+        if (it == states.end()) {
+            it = states.emplace(symbol, SymbolState()).first;
+            it->second.rules = prototypes;
+        }
+        return it->second;
+    }
+
+public:
+    explicit Screener(
+            const IndicatorRegistry& registry = builtin_indicators())
+        : registry(&registry) {}
+
+    /**
+     * Adds an indicator built from a registry spec, to be used by name
+     * in the conditions. Returns false, with the reason in `error`, for
+     * invalid specs and names already taken.
+     */
+    bool add_indicator(const std::string& name, const std::string& spec,
+            std::string& error) {
+        std::unique_ptr<DynIndicator> ind = registry->make(spec, &error);
+        if (!ind) {
+            error = name + ": " + error;
+            return false;
+        }
+        if (!engine.add(name, *ind)) {
+            error = "duplicate indicator '" + name + "'";
+            return false;
+        }
+        return true;
+    }
+
+    /**
+     * Adds a condition, which must hold for a symbol to match. Returns
+     * false, with the reason in `error`, if it can't be parsed.
+     */
+    bool add_condition(const std::string& rule, std::string& error) {
+        Expression e;
+        if (!e.parse(rule, error, engine.indicator_names(), *registry))
+            return false;
+        prototypes.push_back(e);
+        for (auto& st : states) st.second.rules.push_back(e);
+        return true;
+    }
+
+    /**
+     * Feeds a bar of a symbol and evaluates its conditions. Returns
+     * false if it was rejected for not being newer than the previous
+     * one.
+     */
+    bool update(const std::string& symbol, const Ohlcv& bar) {
+        if (!engine.update(symbol, bar)) return false;
+        SymbolState& st = state(symbol);
+        const std::vector<DynIndicator>& named = *engine.indicators(symbol);
+        st.bar = bar;
+        st.matched = true;
+        for (auto& rule : st.rules)
+            if (!rule.test(bar, named)) st.matched = false;
+        return true;
+    }
+
+    /**
+     * Feeds a batch of bars, in order. Returns the number of bars
+     * accepted.
+     */
+    size_t update(const std::vector<SymbolBar>& bars) {
+        size_t accepted = 0;
+        for (const auto& b : bars)
+            if (update(b.symbol, b.bar)) accepted++;
+        return accepted;
+    }
+
+    /**
+     * The symbols meeting all the conditions at their latest bar,
+     * sorted by symbol, leaving out those whose latest bar is older
+     * than `since`, e.g. delisted or halted ones.
+     */
+    std::vector<ScreenerMatch> matches(int64_t since = INT64_MIN) const {
+        std::vector<ScreenerMatch> all;
+        for (const auto& st : states) {
+            if (!st.second.matched || st.second.bar.timestamp < since)
+                continue;
+            ScreenerMatch m;
+            m.symbol = st.first;
+            m.bar = st.second.bar;
+            m.values = engine.snapshot(st.first).values;
+            for (const auto& rule : st.second.rules) {
+                std::map<std::string, double> called = rule.values();
+                m.values.insert(called.begin(), called.end());
+            }
+            all.push_back(m);
+        }
+        return all;
+    }
+
+    /**
+     * Feeds a batch of bars and returns the matches after it.
+     */
+    std::vector<ScreenerMatch> scan(const std::vector<SymbolBar>& bars,
+            int64_t since = INT64_MIN) {
+        update(bars);
+        return matches(since);
+    }
+
+    /**
+     * The indicators kept for the symbols, by name.
+     */
+    const IndicatorEngine& indicators() const noexcept { return engine; }
+
+    size_t num_conditions() const noexcept { return prototypes.size(); }
+
+    /**
+     * Number of symbols seen so far.
+     */
+    size_t size() const noexcept { return states.size(); }
+};
+
+} // namespace tzu
+
+#endif // SCREENER_H
@@ -0,0 +1,176 @@
+#ifndef SCRIPTING_H
+#define SCRIPTING_H
+
+/**
+ * This header defines strategies whose logic is written in Lua, so it
+ * can be changed without recompiling. Indicators still run in C++: they
+ * are registered by name and their values are passed to the script on
+ * every bar. It is only available when building with `TZU_WITH_LUA`
+ * defined and linking against Lua (5.1 or later), which the CMake
+ * option of the same name does.
+ *
+ * A script defines a global function `on_bar(bar, ind)`. `bar` is a
+ * table with the fields `timestamp`, `open`, `high`, `low`, `close`
+ * and `volume`, and `ind` maps the names of the registered indicators
+ * to their current values. The script calls `buy([volume])` or
+ * `sell([volume])` to emit a signal at the close price of the bar:
+ *
+ *     function on_bar(bar, ind)
+ *         if ind.fast > ind.slow then buy() end
+ *         if ind.fast < ind.slow then sell() end
+ *     end
+ */
+
+#ifdef TZU_WITH_LUA
+
+extern "C" {
+#include <lua.h>
+#include <lauxlib.h>
+#include <lualib.h>
+}
+#include <functional>
+#include <map>
+#include <memory>
+#include <string>
+#include "defs.h"
+#include "strategies.h"
+
+namespace tzu {
+
+/**
+ * A strategy running a Lua script on every bar. The Lua interpreter is
+ * shared by copies of the strategy, so tools that replay copies of a
+ * strategy, like `check_lookahead`, need scripts without global state.
+ * If the script can't be loaded or fails while running, `ok()` returns
+ * false, the error is available with `get_error()`, and no more
+ * signals are generated.
+ */
+class LuaStrat: public Strategy<LuaStrat, Ohlcv> {
+    std::shared_ptr<lua_State> state;
+    std::map<std::string, std::function<double(const Ohlcv&)>> indicators;
+    Signal pending;
+    std::string error;
+
+    static LuaStrat* self(lua_State* L) {
+        lua_getglobal(L, "__tzu_strat");
+        LuaStrat* strat = static_cast<LuaStrat*>(lua_touserdata(L, -1));
+        lua_pop(L, 1);
+        return strat;
+    }
+
+    static int order(lua_State* L, Side side) {
+        LuaStrat* strat = self(L);
+        if (strat != nullptr) {
+            strat->pending.side = side;
+            strat->pending.volume = luaL_optnumber(L, 1, 1.0);
+        }
+        return 0;
+    }
+
+    // Pops the error object of a failed call, which scripts can raise
+    // with values that are not strings.
+    static std::string pop_error(lua_State* L) {
+        std::string message = lua_isstring(L, -1)
+            ? lua_tostring(L, -1) : "error object is not a string";
+        lua_pop(L, 1);
+        return message;
+    }
+
+    static int buy(lua_State* L) { return order(L, Side::BUY); }
+    static int sell(lua_State* L) { return order(L, Side::SELL); }
+
+    void init(const std::string& source, bool is_file) {
+        lua_State* L = luaL_newstate();
+        state.reset(L, lua_close);
+        luaL_openlibs(L);
+        lua_pushcfunction(L, &LuaStrat::buy);
+        lua_setglobal(L, "buy");
+        lua_pushcfunction(L, &LuaStrat::sell);
+        lua_setglobal(L, "sell");
+        int status = is_file
+            ? luaL_loadfile(L, source.c_str())
+            : luaL_loadstring(L, source.c_str());
+        if (status == 0)
+            status = lua_pcall(L, 0, 0, 0);
+        if (status != 0) {
+            error = pop_error(L);
+            return;
+        }
+        lua_getglobal(L, "on_bar");
+        if (!lua_isfunction(L, -1))
+            error = "the script doesn't define on_bar";
+        lua_pop(L, 1);
+    }
+
+    static void set_number(lua_State* L, const char* key, double value) {
+        lua_pushnumber(L, value);
+        lua_setfield(L, -2, key);
+    }
+
+    LuaStrat() {}
+
+public:
+    /**
+     * Loads the script from a file.
+     */
+    explicit LuaStrat(const std::string& path) { init(path, true); }
+
+    /**
+     * Loads the script from a string with its source code.
+     */
+    static LuaStrat from_string(const std::string& code) {
+        LuaStrat strat;
+        strat.init(code, false);
+        return strat;
+    }
+
+    /**
+     * Registers an indicator, exposed to the script as `ind.<name>`.
+     * Indicators taking bars are fed with the whole bar, and the others
+     * with the given field. Only indicators with `double` outputs are
+     * supported.
+     */
+    template <typename Ind>
+    LuaStrat& add_indicator(const std::string& name, Ind indicator,
+            OhlcvField field = OhlcvField::CLOSE) {
+        indicators[name] = bar_indicator(indicator, field);
+        return *this;
+    }
+
+    bool ok() const noexcept { return error.empty(); }
+    const std::string& get_error() const noexcept { return error; }
+
+    Signal update(const Ohlcv& data) {
+        std::map<std::string, double> values;
+        for (auto& ind : indicators)
+            values[ind.first] = ind.second(data);
+        pending = Signal(data.timestamp, Side::NONE, data.close);
+        if (!ok())
+            return pending;
+        lua_State* L = state.get();
+        lua_pushlightuserdata(L, this);
+        lua_setglobal(L, "__tzu_strat");
+        lua_getglobal(L, "on_bar");
+        lua_newtable(L);
+        set_number(L, "timestamp", static_cast<double>(data.timestamp));
+        set_number(L, "open", data.open);
+        set_number(L, "high", data.high);
+        set_number(L, "low", data.low);
+        set_number(L, "close", data.close);
+        set_number(L, "volume", data.volume);
+        lua_newtable(L);
+        for (const auto& v : values)
+            set_number(L, v.first.c_str(), v.second);
+        if (lua_pcall(L, 2, 0, 0) != 0) {
+            error = pop_error(L);
+            pending.side = Side::NONE;
+        }
+        return pending;
+    }
+};
+
+} // namespace tzu
+
+#endif // TZU_WITH_LUA
+
+#endif // SCRIPTING_H
@@ -0,0 +1,297 @@
+#ifndef SERIALIZE_H
+#define SERIALIZE_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cstdlib>
+#include <istream>
+#include <ostream>
+#include <string>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines stream operators to write and read data points
+ * and indicator results as text, e.g. to log them, persist them, or
+ * send them to another process. Values are written as space separated
+ * `key:value` fields, the same format used by the portfolio summary:
+ *
+ *     timestamp:1700000000 open:10 high:12 low:9 close:11 volume:100
+ *
+ * Use `std::setprecision(17)` on the output stream for an exact round
+ * trip of doubles. NaN values are written as `nan` and read back.
+ * Reading fails (setting the stream failbit) if the fields are not the
+ * expected ones, in the same order.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+inline bool read_field(std::istream& is, const char* key, std::string& value) {
+    std::string token;
+    if (!(is >> token)) return false;
+    size_t sep = token.find(':');
+    if (sep == std::string::npos || token.compare(0, sep, key) != 0
+            || token.size() == sep + 1) {
+        is.setstate(std::ios::failbit);
+        return false;
+    }
+    value = token.substr(sep + 1);
+    return true;
+}
+
+inline bool read_field(std::istream& is, const char* key, double& out) {
+    std::string value;
+    if (!read_field(is, key, value)) return false;
+    char* end;
+    out = std::strtod(value.c_str(), &end);
+    if (*end != '\0') {
+        is.setstate(std::ios::failbit);
+        return false;
+    }
+    return true;
+}
+
+inline bool read_field(std::istream& is, const char* key, int64_t& out) {
+    std::string value;
+    if (!read_field(is, key, value)) return false;
+    char* end;
+    out = std::strtoll(value.c_str(), &end, 10);
+    if (*end != '\0') {
+        is.setstate(std::ios::failbit);
+        return false;
+    }
+    return true;
+}
+
+inline bool read_field(std::istream& is, const char* key, Side& out) {
+    std::string value;
+    if (!read_field(is, key, value)) return false;
+    if (value == "BUY") out = Side::BUY;
+    else if (value == "SELL") out = Side::SELL;
+    else if (value == "NONE") out = Side::NONE;
+    else {
+        is.setstate(std::ios::failbit);
+        return false;
+    }
+    return true;
+}
+
+inline const char* side_name(Side side) {
+    return side == Side::BUY ? "BUY" : side == Side::SELL ? "SELL" : "NONE";
+}
+
+} // namespace detail
+
+inline std::ostream& operator<<(std::ostream& os, const Ohlcv& bar) {
+    os << "timestamp:" << bar.timestamp
+       << " open:" << bar.open
+       << " high:" << bar.high
+       << " low:" << bar.low
+       << " close:" << bar.close
+       << " volume:" << bar.volume;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, Ohlcv& bar) {
+    Ohlcv in;
+    if (detail::read_field(is, "timestamp", in.timestamp)
+            && detail::read_field(is, "open", in.open)
+            && detail::read_field(is, "high", in.high)
+            && detail::read_field(is, "low", in.low)
+            && detail::read_field(is, "close", in.close)
+            && detail::read_field(is, "volume", in.volume))
+        bar = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const Tick& tick) {
+    os << "timestamp:" << tick.timestamp
+       << " price:" << tick.price
+       << " volume:" << tick.volume
+       << " side:" << detail::side_name(tick.side);
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, Tick& tick) {
+    Tick in;
+    if (detail::read_field(is, "timestamp", in.timestamp)
+            && detail::read_field(is, "price", in.price)
+            && detail::read_field(is, "volume", in.volume)
+            && detail::read_field(is, "side", in.side))
+        tick = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const SingleValue& value) {
+    os << "timestamp:" << value.timestamp << " value:" << value.value;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, SingleValue& value) {
+    SingleValue in;
+    if (detail::read_field(is, "timestamp", in.timestamp)
+            && detail::read_field(is, "value", in.value))
+        value = in;
+    return is;
+}
+
+/**
+ * Reads a signal in the format written by its output operator.
+ */
+inline std::istream& operator>>(std::istream& is, Signal& signal) {
+    Signal in;
+    std::string token;
+    // the output operator writes `key: value` with a trailing `)`
+    if (!(is >> token) || token != "timestamp:" || !(is >> in.timestamp)
+            || !(is >> token) || token != "side:" || !(is >> token)) {
+        is.setstate(std::ios::failbit);
+        return is;
+    }
+    if (token == "BUY") in.side = Side::BUY;
+    else if (token == "SELL") in.side = Side::SELL;
+    else if (token == "NONE") in.side = Side::NONE;
+    else {
+        is.setstate(std::ios::failbit);
+        return is;
+    }
+    if (!(is >> token) || token != "price:" || !(is >> in.price)
+            || !(is >> token) || token != "volume:" || !(is >> token)) {
+        is.setstate(std::ios::failbit);
+        return is;
+    }
+    if (!token.empty() && token[token.size() - 1] == ')')
+        token.erase(token.size() - 1);
+    char* end;
+    in.volume = std::strtod(token.c_str(), &end);
+    if (*end != '\0') {
+        is.setstate(std::ios::failbit);
+        return is;
+    }
+    signal = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const MACDResult& result) {
+    os << "macd:" << result.macd
+       << " signal:" << result.signal
+       << " histogram:" << result.histogram;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, MACDResult& result) {
+    MACDResult in;
+    if (detail::read_field(is, "macd", in.macd)
+            && detail::read_field(is, "signal", in.signal)
+            && detail::read_field(is, "histogram", in.histogram))
+        result = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const ADXResult& result) {
+    os << "adx:" << result.adx
+       << " plus_di:" << result.plus_di
+       << " minus_di:" << result.minus_di;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, ADXResult& result) {
+    ADXResult in;
+    if (detail::read_field(is, "adx", in.adx)
+            && detail::read_field(is, "plus_di", in.plus_di)
+            && detail::read_field(is, "minus_di", in.minus_di))
+        result = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const ElderRayResult& result) {
+    os << "bull:" << result.bull << " bear:" << result.bear;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, ElderRayResult& result) {
+    ElderRayResult in;
+    if (detail::read_field(is, "bull", in.bull)
+            && detail::read_field(is, "bear", in.bear))
+        result = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const AroonResult& result) {
+    os << "up:" << result.up
+       << " down:" << result.down
+       << " oscillator:" << result.oscillator;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, AroonResult& result) {
+    AroonResult in;
+    if (detail::read_field(is, "up", in.up)
+            && detail::read_field(is, "down", in.down)
+            && detail::read_field(is, "oscillator", in.oscillator))
+        result = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const StochRSIResult& result) {
+    os << "k:" << result.k
+       << " d:" << result.d;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, StochRSIResult& result) {
+    StochRSIResult in;
+    if (detail::read_field(is, "k", in.k)
+            && detail::read_field(is, "d", in.d))
+        result = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const LinRegResult& result) {
+    os << "slope:" << result.slope
+       << " intercept:" << result.intercept
+       << " value:" << result.value
+       << " forecast:" << result.forecast
+       << " r2:" << result.r2
+       << " upper:" << result.upper
+       << " lower:" << result.lower;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, LinRegResult& result) {
+    LinRegResult in;
+    if (detail::read_field(is, "slope", in.slope)
+            && detail::read_field(is, "intercept", in.intercept)
+            && detail::read_field(is, "value", in.value)
+            && detail::read_field(is, "forecast", in.forecast)
+            && detail::read_field(is, "r2", in.r2)
+            && detail::read_field(is, "upper", in.upper)
+            && detail::read_field(is, "lower", in.lower))
+        result = in;
+    return is;
+}
+
+inline std::ostream& operator<<(std::ostream& os, const PivotLevels& levels) {
+    os << "pp:" << levels.pp
+       << " r1:" << levels.r1 << " r2:" << levels.r2 << " r3:" << levels.r3
+       << " s1:" << levels.s1 << " s2:" << levels.s2 << " s3:" << levels.s3;
+    return os;
+}
+
+inline std::istream& operator>>(std::istream& is, PivotLevels& levels) {
+    PivotLevels in;
+    if (detail::read_field(is, "pp", in.pp)
+            && detail::read_field(is, "r1", in.r1)
+            && detail::read_field(is, "r2", in.r2)
+            && detail::read_field(is, "r3", in.r3)
+            && detail::read_field(is, "s1", in.s1)
+            && detail::read_field(is, "s2", in.s2)
+            && detail::read_field(is, "s3", in.s3))
+        levels = in;
+    return is;
+}
+
+} // namespace tzu
+
+#endif // SERIALIZE_H
@@ -0,0 +1,265 @@
+#ifndef STATE_H
+#define STATE_H
+
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <cstdlib>
+#include <deque>
+#include <istream>
+#include <ostream>
+#include <string>
+#include <type_traits>
+#include <utility>
+#include <vector>
+#include "defs.h"
+#include "numeric.h"
+
+/**
+ * This header defines the archives used to save and restore the
+ * internal state of the indicators (see `save_state` and `load_state`
+ * in `indicators.h`), so a live process can restart without replaying
+ * the history needed to warm them up again.
+ *
+ * Each indicator lists its state in a `serialize` member, shared by
+ * both archives:
+ *
+ *     template <class Archive>
+ *     void serialize(Archive& ar) { ar(data, prev, pos, len, sum); }
+ *
+ * Parameters fixed at construction are not part of the state, so it
+ * must be restored into an indicator built with the same parameters.
+ * Fixed-size windows are checked for that. The state is written as a
+ * line of space separated values after a `tzu-state` tag and a format
+ * version, with doubles written exactly.
+ */
+
+namespace tzu {
+
+const int STATE_VERSION = 1;
+
+class StateWriter {
+    std::ostream& os;
+    std::streamsize precision;
+
+    void item(double value) {
+        os << ' ';
+        if (std::isnan(value)) os << "nan";
+        else os << value;
+    }
+
+    void item(float value) { item(static_cast<double>(value)); }
+
+    template <typename T>
+    typename std::enable_if<std::is_integral<T>::value>::type item(T value) {
+        if (std::is_signed<T>::value)
+            os << ' ' << static_cast<long long>(value);
+        else
+            os << ' ' << static_cast<unsigned long long>(value);
+    }
+
+    template <typename T>
+    typename std::enable_if<std::is_enum<T>::value>::type item(T value) {
+        os << ' ' << static_cast<long long>(value);
+    }
+
+    template <int Decimals>
+    void item(Fixed<Decimals> value) {
+        os << ' ' << static_cast<long long>(value.raw());
+    }
+
+    void item(Ohlcv& bar) {
+        (*this)(bar.timestamp, bar.open, bar.high, bar.low, bar.close,
+                bar.volume);
+    }
+
+    void item(SingleValue& value) { (*this)(value.timestamp, value.value); }
+
+    template <typename A, typename B>
+    void item(std::pair<A, B>& value) { (*this)(value.first, value.second); }
+
+    template <typename T, size_t N>
+    void item(T (&values)[N]) {
+        for (size_t i = 0; i < N; i++) item(values[i]);
+    }
+
+    template <typename T>
+    void item(std::vector<T>& values) {
+        item(values.size());
+        for (auto& value : values) item(value);
+    }
+
+    template <typename T>
+    void item(std::deque<T>& values) {
+        item(values.size());
+        for (auto& value : values) item(value);
+    }
+
+    template <typename T>
+    auto item(T& value) -> decltype(value.serialize(*this), void()) {
+        value.serialize(*this);
+    }
+
+    template <typename T>
+    auto item(T& value) -> decltype(serialize(*this, value), void()) {
+        serialize(*this, value);
+    }
+
+public:
+    explicit StateWriter(std::ostream& os)
+        : os(os), precision(os.precision(17)) {
+        os << "tzu-state " << STATE_VERSION;
+    }
+
+    ~StateWriter() { os.precision(precision); }
+
+    void operator()() {}
+
+    template <typename T, typename... Rest>
+    void operator()(T& first, Rest&... rest) {
+        item(first);
+        (*this)(rest...);
+    }
+
+    /**
+     * Ends the state and tells whether it was written.
+     */
+    bool finish() {
+        os << '\n';
+        return static_cast<bool>(os);
+    }
+};
+
+/**
+ * Reads back what a `StateWriter` wrote. After a failure, the values
+ * read are meaningless, and `finish()` returns false.
+ */
+class StateReader {
+    std::istream& is;
+
+    void fail() { is.setstate(std::ios::failbit); }
+
+    void item(double& value) {
+        std::string s;
+        if (!(is >> s)) return;
+        char* end;
+        value = std::strtod(s.c_str(), &end);
+        if (*end != '\0') fail();
+    }
+
+    void item(float& value) {
+        double d = 0.0;
+        item(d);
+        value = static_cast<float>(d);
+    }
+
+    template <typename T>
+    typename std::enable_if<std::is_integral<T>::value>::type item(T& value) {
+        std::string s;
+        if (!(is >> s)) return;
+        char* end;
+        if (std::is_signed<T>::value)
+            value = static_cast<T>(std::strtoll(s.c_str(), &end, 10));
+        else
+            value = static_cast<T>(std::strtoull(s.c_str(), &end, 10));
+        if (*end != '\0') fail();
+    }
+
+    template <typename T>
+    typename std::enable_if<std::is_enum<T>::value>::type item(T& value) {
+        long long raw = 0;
+        item(raw);
+        value = static_cast<T>(raw);
+    }
+
+    template <int Decimals>
+    void item(Fixed<Decimals>& value) {
+        long long raw = 0;
+        item(raw);
+        value = Fixed<Decimals>::from_raw(raw);
+    }
+
+    void item(Ohlcv& bar) {
+        (*this)(bar.timestamp, bar.open, bar.high, bar.low, bar.close,
+                bar.volume);
+    }
+
+    void item(SingleValue& value) { (*this)(value.timestamp, value.value); }
+
+    template <typename A, typename B>
+    void item(std::pair<A, B>& value) { (*this)(value.first, value.second); }
+
+    template <typename T, size_t N>
+    void item(T (&values)[N]) {
+        for (size_t i = 0; i < N; i++) item(values[i]);
+    }
+
+    template <typename T>
+    void item(std::vector<T>& values) {
+        size_t n = 0;
+        item(n);
+        // windows are sized by the parameters of the indicator
+        if (n != values.size()) {
+            fail();
+            return;
+        }
+        for (auto& value : values) item(value);
+    }
+
+    template <typename T>
+    void item(std::deque<T>& values) {
+        size_t n = 0;
+        item(n);
+        values.clear();
+        // read one at a time, so a corrupt count fails at the end of the
+        // stream instead of allocating it
+        for (size_t i = 0; i < n && is; i++) {
+            T value = T();
+            item(value);
+            if (is) values.push_back(value);
+        }
+    }
+
+    template <typename T>
+    auto item(T& value) -> decltype(value.serialize(*this), void()) {
+        value.serialize(*this);
+    }
+
+    template <typename T>
+    auto item(T& value) -> decltype(serialize(*this, value), void()) {
+        serialize(*this, value);
+    }
+
+public:
+    explicit StateReader(std::istream& is): is(is) {
+        std::string tag;
+        int version = 0;
+        if (!(is >> tag >> version)) return;
+        if (tag != "tzu-state" || version != STATE_VERSION) fail();
+    }
+
+    void operator()() {}
+
+    template <typename T, typename... Rest>
+    void operator()(T& first, Rest&... rest) {
+        if (is) item(first);
+        (*this)(rest...);
+    }
+
+    /**
+     * Tells whether the whole state was read, and nothing else was left
+     * in its line.
+     */
+    bool finish() {
+        if (!is) return false;
+        if (is.eof()) return true;
+        std::string rest;
+        std::getline(is, rest);
+        if (rest.find_first_not_of(" \t\r") != std::string::npos) fail();
+        return static_cast<bool>(is);
+    }
+};
+
+} // namespace tzu
+
+#endif // STATE_H
@@ -0,0 +1,489 @@
+#ifndef STATISTICS_H
+#define STATISTICS_H
+
+#include <algorithm>
+#include <cstddef>
+#include <cmath>
+#include <deque>
+#include <utility>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines streaming statistics over a series of values,
+ * useful to build adaptive thresholds, e.g. trading only when the
+ * spread is below its 95th percentile or more than two standard
+ * deviations from its mean, and over pairs of series, e.g.
+ * to hedge an asset with another one. Pairs are updated with both
+ * values at once, as `update({a, b})`.
+ */
+
+namespace tzu {
+
+/**
+ * P² quantile estimator (Jain and Chlamtac, 1985).
+ *
+ * Estimates the p-quantile of all the values added so far with five
+ * markers, whose heights are adjusted with a piecewise-parabolic
+ * formula as new values arrive. Memory and time per update are O(1).
+ * Returns NaN until five values have been added.
+ */
+class P2Quantile: public Indicator<P2Quantile, double, double> {
+    double data = std::nan("");
+    double p;
+    double q[5];
+    double n[5];
+    double np[5];
+    double dn[5];
+    size_t count = 0;
+
+    double parabolic(int i, double d) const {
+        return q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+             + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+    }
+
+    double linear(int i, int d) const {
+        return q[i] + d * (q[i + d] - q[i]) / (n[i + d] - n[i]);
+    }
+
+public:
+    explicit P2Quantile(double p): p(p) {
+        for (int i = 0; i < 5; i++) {
+            q[i] = 0.0;
+            n[i] = i;
+        }
+        np[0] = 0.0; np[1] = 2.0 * p; np[2] = 4.0 * p;
+        np[3] = 2.0 + 2.0 * p; np[4] = 4.0;
+        dn[0] = 0.0; dn[1] = p / 2.0; dn[2] = p;
+        dn[3] = (1.0 + p) / 2.0; dn[4] = 1.0;
+    }
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 5; }
+    size_t size() const noexcept { return count; }
+    double update(double value) {
+        if (count < 5) {
+            q[count++] = value;
+            if (count == 5) {
+                std::sort(q, q + 5);
+                data = q[static_cast<int>(std::round(4.0 * p))];
+            }
+            return data;
+        }
+        count++;
+        int k;
+        if (value < q[0]) {
+            q[0] = value;
+            k = 0;
+        } else if (value >= q[4]) {
+            q[4] = value;
+            k = 3;
+        } else {
+            k = 0;
+            while (k < 3 && value >= q[k + 1]) k++;
+        }
+        for (int i = k + 1; i < 5; i++) n[i] += 1.0;
+        for (int i = 0; i < 5; i++) np[i] += dn[i];
+        for (int i = 1; i < 4; i++) {
+            double d = np[i] - n[i];
+            if ((d >= 1.0 && n[i + 1] - n[i] > 1.0)
+                    || (d <= -1.0 && n[i - 1] - n[i] < -1.0)) {
+                int s = d > 0.0 ? 1 : -1;
+                double h = parabolic(i, s);
+                q[i] = q[i - 1] < h && h < q[i + 1] ? h : linear(i, s);
+                n[i] += s;
+            }
+        }
+        data = q[2];
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, q, n, np, count);
+    }
+    void reset_state() {
+        *this = P2Quantile(p);
+    }
+};
+
+/**
+ * Streaming Quantile (Quantile)
+ *
+ * Approximate p-quantile with O(1) memory. With a zero window, it
+ * covers all the values added so far. Otherwise, two P² estimators are
+ * restarted alternately every `window` values, and the value returned
+ * is the one of the estimator with more data, so the estimate covers
+ * between the last `window` and `2 * window` values. Returns NaN until
+ * five values have been added.
+ */
+class Quantile: public Indicator<Quantile, double, double> {
+    double data = std::nan("");
+    double p;
+    size_t window;
+    P2Quantile a;
+    P2Quantile b;
+    size_t len = 0;
+public:
+    Quantile(double p, size_t window = 0)
+        : p(p), window(window), a(p), b(p) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 5; }
+    double update(double value) {
+        if (window > 0 && len > 0 && len % window == 0) {
+            if ((len / window) % 2 == 0)
+                a = P2Quantile(p);
+            else
+                b = P2Quantile(p);
+        }
+        len++;
+        a.update(value);
+        if (window > 0 && len > window)
+            b.update(value);
+        const P2Quantile& best = a.size() >= b.size() ? a : b;
+        data = best.get();
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, a, b, len);
+    }
+    void reset_state() {
+        data = std::nan("");
+        a = b = P2Quantile(p);
+        len = 0;
+    }
+};
+
+namespace detail {
+
+/**
+ * The last `window` values, also kept sorted.
+ */
+class SortedWindow {
+    std::vector<double> prev;
+    std::deque<double> sorted;
+    size_t pos = 0;
+
+public:
+    explicit SortedWindow(size_t window) : prev(window, 0.0) {}
+
+    void add(double value) {
+        if (sorted.size() == prev.size())
+            sorted.erase(std::lower_bound(sorted.begin(), sorted.end(),
+                        prev[pos]));
+        sorted.insert(std::upper_bound(sorted.begin(), sorted.end(), value),
+                value);
+        prev[pos] = value;
+        pos = (pos + 1) % prev.size();
+    }
+
+    bool full() const noexcept { return sorted.size() == prev.size(); }
+
+    size_t size() const noexcept { return prev.size(); }
+
+    /**
+     * Share of the values below `value`, ties counting half.
+     */
+    double rank(double value) const {
+        auto lo = std::lower_bound(sorted.begin(), sorted.end(), value);
+        auto hi = std::upper_bound(lo, sorted.end(), value);
+        double below = static_cast<double>(lo - sorted.begin());
+        double equal = static_cast<double>(hi - lo);
+        return (below + 0.5 * equal) / static_cast<double>(sorted.size());
+    }
+
+    /**
+     * The p-quantile, interpolating linearly between values.
+     */
+    double quantile(double p) const {
+        double h = p * static_cast<double>(sorted.size() - 1);
+        size_t i = static_cast<size_t>(std::floor(h));
+        if (i + 1 >= sorted.size()) return sorted.back();
+        return sorted[i] + (h - static_cast<double>(i))
+            * (sorted[i + 1] - sorted[i]);
+    }
+
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(prev, sorted, pos);
+    }
+
+    void reset() {
+        std::fill(prev.begin(), prev.end(), 0.0);
+        sorted.clear();
+        pos = 0;
+    }
+};
+
+} // namespace detail
+
+/**
+ * Rolling Percentile (Percentile)
+ *
+ * Exact p-quantile of the last `period` values, interpolating linearly
+ * between them, e.g. the median with 0.5. Updates take time linear in
+ * the period; for long windows, `Quantile` estimates it in constant
+ * time. `p` is clamped to [0, 1]. NaN values are skipped, returning
+ * NaN. Returns NaN until the window is full.
+ */
+class Percentile: public Indicator<Percentile, double, double> {
+    double data = std::nan("");
+    double p;
+    detail::SortedWindow window;
+public:
+    Percentile(double p, size_t period)
+        : p(std::max(0.0, std::min(1.0, p))), window(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.size(); }
+    double update(double value) {
+        if (std::isnan(value)) return data = value;
+        window.add(value);
+        if (window.full()) data = window.quantile(p);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.reset();
+    }
+};
+
+/**
+ * Rolling Percent Rank (PercentRank)
+ *
+ * Share of the previous `period` values below the latest one, from 0
+ * when it is the lowest to 1 when it is the highest, with ties
+ * counting half, e.g. the rank of the implied volatility within the
+ * last year. NaN values are skipped, returning NaN. Returns NaN until
+ * `period` values precede the latest one.
+ */
+class PercentRank: public Indicator<PercentRank, double, double> {
+    double data = std::nan("");
+    detail::SortedWindow window;
+public:
+    explicit PercentRank(size_t period) : window(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.size() + 1; }
+    double update(double value) {
+        if (std::isnan(value)) return data = value;
+        if (window.full()) data = window.rank(value);
+        window.add(value);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.reset();
+    }
+};
+
+/**
+ * Rolling Z-Score (ZScore)
+ *
+ * Distance of the latest value from the mean of the last `period`
+ * values, in standard deviations:
+ *
+ * z = (value - SMA) / sqrt(MVar)
+ *
+ * with the variance computed with `dof` degrees of freedom, as in
+ * `MVar`. The window includes the latest value. Returns NaN until the
+ * window is full, and zero if the values have no dispersion: a
+standard deviation below 1e-12 times the mean is taken as rounding
+noise, so the threshold follows the scale of the values.
+ */
+class ZScore: public Indicator<ZScore, double, double> {
+    double data = std::nan("");
+    SMA mean;
+    MVar var;
+public:
+    explicit ZScore(size_t period, size_t dof = 1)
+        : mean(period), var(period, dof) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return var.required_warmup(); }
+    double update(double value) {
+        double m = mean.update(value);
+        double v = var.update(value);
+        if (std::isnan(m) || std::isnan(v)) return data;
+        data = v > 1e-24 * m * m ? (value - m) / std::sqrt(v) : 0.0;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, mean, var);
+    }
+    void reset_state() {
+        data = std::nan("");
+        mean.reset();
+        var.reset();
+    }
+};
+
+namespace detail {
+
+/**
+ * Means and co-moments of the last `window` pairs of values, updated
+ * with Welford's method as pairs enter and leave the window, and
+ * recomputed from it each time it wraps around so rounding errors don't
+ * build up. Deviations are taken from the means, as in `MVar`.
+ */
+class PairWindow {
+    std::vector<std::pair<double, double>> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    double mx = 0.0;
+    double my = 0.0;
+    double cxx = 0.0;
+    double cyy = 0.0;
+    double cxy = 0.0;
+
+    void resum() {
+        double n = static_cast<double>(prev.size());
+        mx = my = 0.0;
+        for (const auto& p : prev) {
+            mx += p.first;
+            my += p.second;
+        }
+        mx /= n;
+        my /= n;
+        cxx = cyy = cxy = 0.0;
+        for (const auto& p : prev) {
+            double dx = p.first - mx;
+            double dy = p.second - my;
+            cxx += dx * dx;
+            cyy += dy * dy;
+            cxy += dx * dy;
+        }
+    }
+
+public:
+    explicit PairWindow(size_t window)
+        : prev(window, std::make_pair(0.0, 0.0)) {}
+
+    void add(double x, double y) {
+        size_t n = prev.size();
+        double old_mx = mx;
+        double old_my = my;
+        if (len < n) {
+            len++;
+            mx += (x - mx) / static_cast<double>(len);
+            my += (y - my) / static_cast<double>(len);
+            cxx += (x - old_mx) * (x - mx);
+            cyy += (y - old_my) * (y - my);
+            cxy += (x - old_mx) * (y - my);
+        } else {
+            double x0 = prev[pos].first;
+            double y0 = prev[pos].second;
+            mx += (x - x0) / static_cast<double>(n);
+            my += (y - y0) / static_cast<double>(n);
+            cxx += (x - old_mx) * (x - mx) - (x0 - old_mx) * (x0 - mx);
+            cyy += (y - old_my) * (y - my) - (y0 - old_my) * (y0 - my);
+            cxy += (x - old_mx) * (y - my) - (x0 - old_mx) * (y0 - my);
+        }
+        prev[pos] = std::make_pair(x, y);
+        pos = (pos + 1) % n;
+        if (pos == 0) resum();
+    }
+
+    bool full() const noexcept { return len == prev.size(); }
+
+    size_t size() const noexcept { return prev.size(); }
+
+    // co-moments, i.e. sums of the products of the deviations
+    double mxx() const { return std::max(cxx, 0.0); }
+    double myy() const { return std::max(cyy, 0.0); }
+    double mxy() const { return cxy; }
+
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(prev, pos, len, mx, my, cxx, cyy, cxy);
+    }
+
+    void reset() {
+        *this = PairWindow(prev.size());
+    }
+};
+
+} // namespace detail
+
+/**
+ * Rolling Correlation (Correlation)
+ *
+ * Pearson correlation of the last `period` pairs of values, from -1 to
+ * 1. For prices, correlating their returns rather than the prices
+ * themselves avoids spurious correlations between trending series.
+ * Returns NaN until `period` pairs have been added, and while any of
+ * the series is constant over the window.
+ */
+class Correlation: public Indicator<Correlation,
+        std::pair<double, double>, double> {
+    double data = std::nan("");
+    detail::PairWindow window;
+public:
+    explicit Correlation(size_t period) : window(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.size(); }
+    double update(std::pair<double, double> value) {
+        window.add(value.first, value.second);
+        if (!window.full()) return data;
+        double den = std::sqrt(window.mxx() * window.myy());
+        data = den > 0.0 ? std::fmax(-1.0, std::fmin(1.0, window.mxy() / den))
+            : std::nan("");
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.reset();
+    }
+};
+
+/**
+ * Rolling Beta (Beta)
+ *
+ * Slope of the least squares regression of the first values of the
+ * last `period` pairs on the second ones:
+ *
+ * beta = cov(a, b) / var(b)
+ *
+ * With the returns of an asset and of a benchmark, it is the beta of
+ * the asset; with prices, the hedge ratio of a pair. Returns NaN until
+ * `period` pairs have been added, and while the second series is
+ * constant over the window.
+ */
+class Beta: public Indicator<Beta, std::pair<double, double>, double> {
+    double data = std::nan("");
+    detail::PairWindow window;
+public:
+    explicit Beta(size_t period) : window(period) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return window.size(); }
+    double update(std::pair<double, double> value) {
+        window.add(value.first, value.second);
+        if (!window.full()) return data;
+        double var = window.myy();
+        data = var > 0.0 ? window.mxy() / var : std::nan("");
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, window);
+    }
+    void reset_state() {
+        data = std::nan("");
+        window.reset();
+    }
+};
+
+} // namespace tzu
+
+#endif // STATISTICS_H
@@ -5,12 +5,16 @@
 #include <cmath>
 #include <vector>
 #include <algorithm>
+#include <iomanip>
 #include <numeric>
+#include <ostream>
+#include <string>
 
 namespace tzu {
 
 /**
- * Trade record for tracking individual trades.
+ * Trade record for tracking individual trades. The symbol is empty for
+ * single-asset portfolios.
  */
 struct Trade {
     int64_t open_time = 0;
@@ -20,11 +24,13 @@ struct Trade {
     double quantity = 0.0;
     double profit = 0.0;
     bool closed = false;
+    std::string symbol;
     
     Trade() = default;
-    Trade(int64_t ot, int64_t ct, double op, double cp, double q, double p, bool c)
+    Trade(int64_t ot, int64_t ct, double op, double cp, double q, double p, bool c,
+          const std::string& s = "")
         : open_time(ot), close_time(ct), open_price(op), close_price(cp),
-          quantity(q), profit(p), closed(c) {}
+          quantity(q), profit(p), closed(c), symbol(s) {}
 };
 
 /**
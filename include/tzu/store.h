@@ -0,0 +1,299 @@
+#ifndef STORE_H
+#define STORE_H
+
+#include <cstdint>
+#include <cstdlib>
+#include <vector>
+#include <algorithm>
+#ifdef TZU_WITH_SQLITE
+#include <cmath>
+#include <string>
+#include <sqlite3.h>
+#endif
+#include "defs.h"
+#include "streamers.h"
+
+/**
+ * This header defines components to store time series data. Data
+ * providers revise past values and publish bars late, so a backtest
+ * that reads the final version of a dataset uses information that was
+ * not available at the time. The point-in-time store keeps every
+ * version of a record with the time it was ingested, and answers
+ * queries as the data existed at a given simulation time.
+ *
+ * When building with `TZU_WITH_SQLITE` defined and linking against
+ * SQLite 3, which the CMake option of the same name does, bars can also
+ * be persisted in a database, so downloaded history is fetched once and
+ * reused by later backtests and live warmups.
+ */
+
+namespace tzu {
+
+/**
+ * A record together with the time it became known (ingested). The
+ * record itself carries the time it refers to in its `timestamp`.
+ */
+template <typename T>
+struct Versioned {
+    int64_t ingest_time = 0;
+    T value;
+    Versioned() = default;
+    Versioned(int64_t ingest, const T& v) : ingest_time(ingest), value(v) {}
+};
+
+/**
+ * Specialization to parse versioned records. Expects lines with the
+ * ingest time followed by the fields of the record, e.g. for `Ohlcv`:
+ * ingest_time,timestamp,open,high,low,close,volume
+ */
+template <typename T>
+struct CsvParseTraits<Versioned<T>> {
+    static bool parse(const char* line_buffer, Versioned<T>& out) {
+        char* end;
+        int64_t ingest = std::strtol(line_buffer, &end, 10);
+        if (end == line_buffer) return false;
+        out.ingest_time = ingest;
+        return CsvParseTraits<T>::parse(end, out.value);
+    }
+};
+
+/**
+ * Point-in-time store. Keeps all the versions of each record, so that
+ * queries return the data as it existed at a simulation time: records
+ * ingested later are invisible, and revisions only replace previous
+ * values from their ingest time on.
+ */
+template <typename T>
+class PointInTime {
+    std::vector<Versioned<T>> versions;
+
+    // versions are sorted by record timestamp, then by ingest time
+    static bool before(const Versioned<T>& a, const Versioned<T>& b) {
+        if (a.value.timestamp != b.value.timestamp)
+            return a.value.timestamp < b.value.timestamp;
+        return a.ingest_time < b.ingest_time;
+    }
+
+    typename std::vector<Versioned<T>>::const_iterator
+    lower(int64_t timestamp) const {
+        return std::lower_bound(versions.begin(), versions.end(), timestamp,
+            [](const Versioned<T>& v, int64_t ts) {
+                return v.value.timestamp < ts;
+            });
+    }
+
+public:
+    /**
+     * Adds a version of a record, known from `ingest_time` on.
+     */
+    void insert(int64_t ingest_time, const T& value) {
+        Versioned<T> version(ingest_time, value);
+        auto it = std::upper_bound(versions.begin(), versions.end(),
+                version, before);
+        versions.insert(it, version);
+    }
+
+    void insert(const Versioned<T>& version) {
+        insert(version.ingest_time, version.value);
+    }
+
+    /**
+     * Adds all the versions provided by a streamer, e.g.
+     * `Csv<Versioned<Ohlcv>>`.
+     */
+    template <typename Streamer>
+    void load(Streamer& streamer) {
+        for (const auto& version : streamer)
+            insert(version);
+    }
+
+    /**
+     * Looks up the record for `timestamp` as known at `sim_time`.
+     * Returns false if it was not known yet.
+     */
+    bool as_of(int64_t sim_time, int64_t timestamp, T& out) const {
+        bool found = false;
+        for (auto it = lower(timestamp);
+                it != versions.end() && it->value.timestamp == timestamp;
+                ++it) {
+            if (it->ingest_time > sim_time) break;
+            out = it->value;
+            found = true;
+        }
+        return found;
+    }
+
+    /**
+     * Returns the records with a timestamp in `[from, to]`, in
+     * timestamp order, as known at `sim_time`.
+     */
+    std::vector<T> history(int64_t sim_time, int64_t from, int64_t to) const {
+        std::vector<T> out;
+        auto it = lower(from);
+        while (it != versions.end() && it->value.timestamp <= to) {
+            int64_t ts = it->value.timestamp;
+            bool found = false;
+            T latest;
+            for (; it != versions.end() && it->value.timestamp == ts; ++it) {
+                if (it->ingest_time <= sim_time) {
+                    latest = it->value;
+                    found = true;
+                }
+            }
+            if (found) out.push_back(latest);
+        }
+        return out;
+    }
+
+    /**
+     * Number of versions stored for a record timestamp. More than one
+     * means the record was revised.
+     */
+    size_t revisions(int64_t timestamp) const {
+        size_t count = 0;
+        for (auto it = lower(timestamp);
+                it != versions.end() && it->value.timestamp == timestamp;
+                ++it)
+            count++;
+        return count;
+    }
+
+    size_t size() const noexcept { return versions.size(); }
+};
+
+#ifdef TZU_WITH_SQLITE
+
+/**
+ * SqliteBarStore
+ *
+ * Bars persisted in a SQLite database, keyed by symbol, timeframe (any
+ * label, e.g. "1m" or "1d") and timestamp. Writing a bar that already
+ * exists replaces it, so overlapping downloads can be stored as they
+ * come. Missing (NaN) values are stored as NULL and read back as NaN.
+ * Operations return false on database errors, and a store that couldn't
+ * be opened is not `ok()`. The default path keeps the database in
+ * memory.
+ */
+class SqliteBarStore {
+    sqlite3* db = nullptr;
+    bool ok_ = false;
+
+    bool exec(const char* sql) {
+        return sqlite3_exec(db, sql, nullptr, nullptr, nullptr) == SQLITE_OK;
+    }
+
+    static void bind(sqlite3_stmt* stmt, int i, double value) {
+        if (std::isnan(value))
+            sqlite3_bind_null(stmt, i);
+        else
+            sqlite3_bind_double(stmt, i, value);
+    }
+
+    static double column(sqlite3_stmt* stmt, int i) {
+        if (sqlite3_column_type(stmt, i) == SQLITE_NULL) return std::nan("");
+        return sqlite3_column_double(stmt, i);
+    }
+
+public:
+    explicit SqliteBarStore(const std::string& path = ":memory:") {
+        if (sqlite3_open(path.c_str(), &db) != SQLITE_OK) return;
+        ok_ = exec("CREATE TABLE IF NOT EXISTS bars ("
+                "symbol TEXT NOT NULL, timeframe TEXT NOT NULL, "
+                "timestamp INTEGER NOT NULL, open REAL, high REAL, "
+                "low REAL, close REAL, volume REAL, "
+                "PRIMARY KEY (symbol, timeframe, timestamp))");
+    }
+
+    SqliteBarStore(const SqliteBarStore&) = delete;
+    SqliteBarStore& operator=(const SqliteBarStore&) = delete;
+
+    ~SqliteBarStore() { sqlite3_close(db); }
+
+    bool ok() const noexcept { return ok_; }
+
+    /**
+     * Inserts or replaces bars, in a single transaction.
+     */
+    bool upsert(const std::string& symbol, const std::string& timeframe,
+            const std::vector<Ohlcv>& bars) {
+        if (!ok_ || !exec("BEGIN")) return false;
+        sqlite3_stmt* stmt = nullptr;
+        bool done = sqlite3_prepare_v2(db,
+                "INSERT OR REPLACE INTO bars VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                -1, &stmt, nullptr) == SQLITE_OK;
+        for (size_t i = 0; done && i < bars.size(); i++) {
+            const Ohlcv& bar = bars[i];
+            sqlite3_bind_text(stmt, 1, symbol.c_str(), -1, SQLITE_TRANSIENT);
+            sqlite3_bind_text(stmt, 2, timeframe.c_str(), -1, SQLITE_TRANSIENT);
+            sqlite3_bind_int64(stmt, 3, bar.timestamp);
+            bind(stmt, 4, bar.open);
+            bind(stmt, 5, bar.high);
+            bind(stmt, 6, bar.low);
+            bind(stmt, 7, bar.close);
+            bind(stmt, 8, bar.volume);
+            done = sqlite3_step(stmt) == SQLITE_DONE;
+            sqlite3_reset(stmt);
+        }
+        sqlite3_finalize(stmt);
+        return exec(done ? "COMMIT" : "ROLLBACK") && done;
+    }
+
+    bool upsert(const std::string& symbol, const std::string& timeframe,
+            const Ohlcv& bar) {
+        return upsert(symbol, timeframe, std::vector<Ohlcv>(1, bar));
+    }
+
+    /**
+     * Bars with a timestamp in `[from, to]`, in timestamp order.
+     */
+    std::vector<Ohlcv> range(const std::string& symbol,
+            const std::string& timeframe, int64_t from = INT64_MIN,
+            int64_t to = INT64_MAX) const {
+        std::vector<Ohlcv> out;
+        sqlite3_stmt* stmt = nullptr;
+        if (!ok_ || sqlite3_prepare_v2(db,
+                "SELECT timestamp, open, high, low, close, volume FROM bars "
+                "WHERE symbol = ? AND timeframe = ? AND timestamp BETWEEN ? AND ? "
+                "ORDER BY timestamp", -1, &stmt, nullptr) != SQLITE_OK)
+            return out;
+        sqlite3_bind_text(stmt, 1, symbol.c_str(), -1, SQLITE_TRANSIENT);
+        sqlite3_bind_text(stmt, 2, timeframe.c_str(), -1, SQLITE_TRANSIENT);
+        sqlite3_bind_int64(stmt, 3, from);
+        sqlite3_bind_int64(stmt, 4, to);
+        while (sqlite3_step(stmt) == SQLITE_ROW)
+            out.push_back(Ohlcv(sqlite3_column_int64(stmt, 0),
+                        column(stmt, 1),
+                        column(stmt, 2),
+                        column(stmt, 3),
+                        column(stmt, 4),
+                        column(stmt, 5)));
+        sqlite3_finalize(stmt);
+        return out;
+    }
+
+    /**
+     * Timestamp of the latest bar stored, to download only what is
+     * missing. Returns false if there are no bars.
+     */
+    bool last_timestamp(const std::string& symbol, const std::string& timeframe,
+            int64_t& out) const {
+        sqlite3_stmt* stmt = nullptr;
+        if (!ok_ || sqlite3_prepare_v2(db,
+                "SELECT MAX(timestamp) FROM bars WHERE symbol = ? AND timeframe = ?",
+                -1, &stmt, nullptr) != SQLITE_OK)
+            return false;
+        sqlite3_bind_text(stmt, 1, symbol.c_str(), -1, SQLITE_TRANSIENT);
+        sqlite3_bind_text(stmt, 2, timeframe.c_str(), -1, SQLITE_TRANSIENT);
+        bool found = sqlite3_step(stmt) == SQLITE_ROW
+            && sqlite3_column_type(stmt, 0) != SQLITE_NULL;
+        if (found) out = sqlite3_column_int64(stmt, 0);
+        sqlite3_finalize(stmt);
+        return found;
+    }
+};
+
+#endif // TZU_WITH_SQLITE
+
+} // namespace tzu
+
+#endif // STORE_H
@@ -1,7 +1,9 @@
 #ifndef STRATEGIES_H
 #define STRATEGIES_H
 
+#include <vector>
 #include "indicators.h"
+#include "volatility.h"
 
 /**
  * This header file defines various trading strategies that utilize
@@ -123,6 +125,87 @@ public:
     }
 };
 
+/**
+ * The meta-labeling strategy wraps a primary strategy and a secondary
+ * model. When the primary strategy generates a buy signal, the model
+ * is called with the current features (see `meta_labels`) and returns
+ * the probability that the trade wins. Buy signals with a probability
+ * below the threshold are dropped, and if `size_by_probability` is set
+ * the signal volume is scaled by the probability. Sell signals are
+ * always forwarded, so that positions can be closed.
+ */
+template <typename Strat, typename Features, typename Model>
+class MetaLabelStrat: public Strategy<MetaLabelStrat<Strat, Features, Model>, Ohlcv> {
+    Strat primary;
+    Features features;
+    Model model;
+    double threshold;
+    bool size_by_probability;
+public:
+    MetaLabelStrat(const Strat& primary, const Features& features,
+            const Model& model, double threshold = 0.5,
+            bool size_by_probability = false)
+        : primary(primary), features(features), model(model),
+          threshold(threshold), size_by_probability(size_by_probability) {}
+    Signal update(const Ohlcv& data) {
+        Signal signal = primary.update(data);
+        std::vector<double> x = features(data);
+        if (signal.side != Side::BUY)
+            return signal;
+        double prob = model(x);
+        if (std::isnan(prob) || prob < threshold)
+            signal.side = Side::NONE;
+        else if (size_by_probability)
+            signal.volume *= prob;
+        return signal;
+    }
+};
+
+/**
+ * The volatility-targeting overlay wraps a strategy and scales the
+ * volume of its buy signals so that the position's volatility tracks a
+ * target annualized level:
+ *
+ * volume = volume * min(max_scale, target / (vol * sqrt(periods_per_year)))
+ *
+ * where vol is the per-period volatility estimated from the closes by
+ * `Vol` (e.g. `EWMAVol`). The scale is updated on every bar and
+ * applied when a position is opened. While the volatility is unknown,
+ * buy signals are dropped. Sell signals are always forwarded.
+ */
+template <typename Strat, typename Vol = EWMAVol>
+class VolTarget: public Strategy<VolTarget<Strat, Vol>, Ohlcv> {
+    Strat strat;
+    Vol vol;
+    double target;
+    double periods_per_year;
+    double max_scale;
+    double scale = std::nan("");
+public:
+    VolTarget(const Strat& strat, const Vol& vol, double target = 0.15,
+            double periods_per_year = 252.0, double max_scale = 1.0)
+        : strat(strat), vol(vol), target(target),
+          periods_per_year(periods_per_year), max_scale(max_scale) {}
+    /**
+     * Current scale applied to buy signals, NaN until the volatility
+     * is known.
+     */
+    double get() const noexcept { return scale; }
+    Signal update(const Ohlcv& data) {
+        Signal signal = strat.update(data);
+        double v = vol.update(data.close) * std::sqrt(periods_per_year);
+        if (!std::isnan(v))
+            scale = v > 0.0 ? std::fmin(max_scale, target / v) : max_scale;
+        if (signal.side != Side::BUY)
+            return signal;
+        if (std::isnan(scale))
+            signal.side = Side::NONE;
+        else
+            signal.volume *= scale;
+        return signal;
+    }
+};
+
 } // namespace tzu
 
 #endif // STRATEGIES_H
@@ -18,8 +18,14 @@
 #include <istream>
 #include <iostream>
 #include <array>
+#include <cctype>
+#include <cstdint>
+#include <cstdlib>
 #include <cstring>
+#include <string>
+#include <vector>
 #include "defs.h"
+#include "datetime.h"
 
 namespace tzu {
 
@@ -90,6 +96,23 @@ struct CsvParseTraits<SingleValue> {
     }
 };
 
+/**
+ * Specialization for CorporateAction data. Expects lines in the format:
+ * timestamp,split,dividend
+ */
+template<>
+struct CsvParseTraits<CorporateAction> {
+    static bool parse(const char* line_buffer, CorporateAction& out) {
+        char* end;
+        int64_t ts = std::strtol(line_buffer, &end, 10);
+        double split = std::strtod(end, &end);
+        double dividend = std::strtod(end, &end);
+        if (*end != '\0' && *end != '\n') return false;
+        out = CorporateAction{ts, split, dividend};
+        return true;
+    }
+};
+
 /**
  * An text input iterator that reads lines from a stream and parses them
  * into a specific data type.
@@ -189,6 +212,184 @@ public:
     Iterator end() { return Iterator(&input_, true); }
 };
 
+/**
+ * Layout of a CSV file with bars, for files that don't follow the
+ * `timestamp,open,high,low,close,volume` layout expected by `Csv`.
+ * Columns are zero-based indices; `volume` can be -1 if the file has
+ * no volumes. With an empty `time_format`, timestamps are read as
+ * integers and kept as they are. Otherwise they are parsed with
+ * `parse_time` (e.g. "%Y-%m-%d" or "%Y-%m-%dT%H:%M:%SZ"), shifted by
+ * `utc_offset` seconds to UTC (e.g. -18000 for times in UTC-5), unless
+ * the format has `%s` Unix seconds, which are in UTC already, and
+ * multiplied by `time_scale` (e.g. 1000 for milliseconds).
+ */
+struct CsvLayout {
+    int timestamp = 0;
+    int open = 1;
+    int high = 2;
+    int low = 3;
+    int close = 4;
+    int volume = 5;
+    char delimiter = ',';
+    std::string time_format;
+    int64_t utc_offset = 0;
+    int64_t time_scale = 1;
+    bool has_header = true;
+    bool columns_from_header = false;
+
+    /**
+     * Sets the columns from a header line, matching names without
+     * regard to case. The timestamp column can be named timestamp,
+     * date, time, datetime or ts; the volume column is optional.
+     * Returns false if any other column is missing.
+     */
+    bool map_columns(const std::string& header) {
+        int ts = -1, o = -1, h = -1, l = -1, c = -1, v = -1;
+        size_t start = 0;
+        for (int i = 0; start <= header.size(); i++) {
+            size_t stop = header.find(delimiter, start);
+            if (stop == std::string::npos) stop = header.size();
+            std::string name;
+            for (size_t k = start; k < stop; k++) {
+                char ch = header[k];
+                if (ch == '"' || ch == ' ' || ch == '\r') continue;
+                name += static_cast<char>(std::tolower(static_cast<unsigned char>(ch)));
+            }
+            if (name == "timestamp" || name == "date" || name == "time"
+                    || name == "datetime" || name == "ts") ts = i;
+            else if (name == "open" || name == "o") o = i;
+            else if (name == "high" || name == "h") h = i;
+            else if (name == "low" || name == "l") l = i;
+            else if (name == "close" || name == "c") c = i;
+            else if (name == "volume" || name == "vol" || name == "v") v = i;
+            start = stop + 1;
+        }
+        if (ts < 0 || o < 0 || h < 0 || l < 0 || c < 0) return false;
+        timestamp = ts; open = o; high = h; low = l; close = c; volume = v;
+        return true;
+    }
+
+    /**
+     * Parses a line into a bar. Returns false if a field is missing or
+     * is not valid.
+     */
+    bool parse(const std::string& line, Ohlcv& out) const {
+        std::vector<std::string> fields;
+        size_t start = 0;
+        while (start <= line.size()) {
+            size_t stop = line.find(delimiter, start);
+            if (stop == std::string::npos) stop = line.size();
+            size_t a = start, b = stop;
+            while (a < b && (line[a] == ' ' || line[a] == '"')) a++;
+            while (b > a && (line[b - 1] == ' ' || line[b - 1] == '"'
+                        || line[b - 1] == '\r')) b--;
+            fields.push_back(line.substr(a, b - a));
+            start = stop + 1;
+        }
+        double values[5];
+        const int columns[5] = {open, high, low, close, volume};
+        for (int k = 0; k < 5; k++) {
+            if (columns[k] < 0) {
+                values[k] = 0.0;
+                continue;
+            }
+            if (static_cast<size_t>(columns[k]) >= fields.size()) return false;
+            const char* text = fields[columns[k]].c_str();
+            char* end;
+            values[k] = std::strtod(text, &end);
+            if (end == text || *end != '\0') return false;
+        }
+        if (timestamp < 0 || static_cast<size_t>(timestamp) >= fields.size())
+            return false;
+        const std::string& ts_text = fields[timestamp];
+        int64_t ts;
+        if (time_format.empty()) {
+            char* end;
+            ts = std::strtoll(ts_text.c_str(), &end, 10);
+            if (end == ts_text.c_str() || *end != '\0') return false;
+        } else {
+            if (!parse_time(ts_text.c_str(), time_format.c_str(), ts))
+                return false;
+            bool epoch = time_format.find("%s") != std::string::npos;
+            ts = (epoch ? ts : ts - utc_offset) * time_scale;
+        }
+        out = Ohlcv(ts, values[0], values[1], values[2], values[3], values[4]);
+        return true;
+    }
+};
+
+/**
+ * A CSV reader for bars with a configurable layout. It can be iterated
+ * like `Csv`, or read at once with `load`. Lines that can't be parsed
+ * are skipped and counted.
+ */
+class CsvBars {
+    std::istream& input_;
+    CsvLayout layout_;
+    size_t skipped_ = 0;
+    bool valid_ = true;
+public:
+    CsvBars(std::istream& input, const CsvLayout& layout = CsvLayout())
+            : input_(input), layout_(layout) {
+        if (layout_.has_header) {
+            std::string header_line;
+            std::getline(input_, header_line);
+            if (layout_.columns_from_header)
+                valid_ = layout_.map_columns(header_line);
+        }
+    }
+
+    /**
+     * Reads the next bar. Returns false at the end of the input.
+     */
+    bool next(Ohlcv& out) {
+        std::string line;
+        while (valid_ && std::getline(input_, line)) {
+            if (line.empty() || line == "\r") continue;
+            if (layout_.parse(line, out)) return true;
+            skipped_++;
+        }
+        return false;
+    }
+
+    std::vector<Ohlcv> load() {
+        std::vector<Ohlcv> bars;
+        Ohlcv bar;
+        while (next(bar)) bars.push_back(bar);
+        return bars;
+    }
+
+    /**
+     * False if the columns couldn't be found in the header.
+     */
+    bool valid() const noexcept { return valid_; }
+    size_t skipped() const noexcept { return skipped_; }
+    const CsvLayout& layout() const noexcept { return layout_; }
+
+    class Iterator {
+        CsvBars* reader_;
+        Ohlcv current_;
+    public:
+        explicit Iterator(CsvBars* reader): reader_(reader) {
+            if (reader_ && !reader_->next(current_)) reader_ = nullptr;
+        }
+        Iterator& operator++() {
+            if (reader_ && !reader_->next(current_)) reader_ = nullptr;
+            return *this;
+        }
+        const Ohlcv& operator*() const { return current_; }
+        const Ohlcv* operator->() const { return &current_; }
+        bool operator==(const Iterator& other) const {
+            return reader_ == other.reader_;
+        }
+        bool operator!=(const Iterator& other) const {
+            return !(*this == other);
+        }
+    };
+    Iterator begin() { return Iterator(this); }
+    Iterator end() { return Iterator(nullptr); }
+};
+
 } // namespace tzu
 
 #endif // STREAMERS_H
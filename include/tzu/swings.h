@@ -0,0 +1,157 @@
+#ifndef SWINGS_H
+#define SWINGS_H
+
+#include <cstddef>
+#include <cstdint>
+#include <ostream>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines the detection of swing highs and lows: bars
+ * whose high or low is beyond those of the bars around them, as in
+ * Bill Williams' fractals. A swing point is only known once the bars
+ * after it have been seen, so it is reported late, with both the time
+ * it happened and the time it was confirmed; nothing is reported
+ * before it is, which avoids lookahead. Swing points are the input of
+ * the divergence detector and of support and resistance levels.
+ */
+
+namespace tzu {
+
+enum class SwingType {
+    HIGH,
+    LOW
+};
+
+inline const char* to_string(SwingType type) {
+    switch (type) {
+        case SwingType::HIGH: return "HIGH";
+        case SwingType::LOW: return "LOW";
+    }
+    return "UNKNOWN";
+}
+
+/**
+ * A swing point: the high of a swing high or the low of a swing low,
+ * at `timestamp`, confirmed by the bar at `confirmed`.
+ */
+struct Swing {
+    int64_t timestamp;
+    SwingType type;
+    double price;
+    int64_t confirmed;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const Swing& s) {
+    os << "timestamp:" << s.timestamp
+       << " swing:" << to_string(s.type)
+       << " price:" << s.price
+       << " confirmed:" << s.confirmed;
+    return os;
+}
+
+/**
+ * Detects swing points from a stream of bars. A bar is a swing high
+ * when its high is above the highs of the `left` bars before it and of
+ * the `right` bars after it, and a swing low when its low is below
+ * their lows, so swing points are confirmed `right` bars late. A bar
+ * can be both. `fractals()` detects Bill Williams' fractals, with two
+ * bars on each side.
+ *
+ * Example:
+ *
+ *     SwingDetector swings(5, 2);
+ *     if (swings.update(bar))
+ *         for (const auto& s : swings.get()) std::cout << s << "\n";
+ */
+class SwingDetector {
+    struct Point {
+        int64_t timestamp;
+        double high;
+        double low;
+    };
+    size_t left_;
+    size_t right_;
+    std::vector<Point> window;
+    size_t pos = 0;
+    size_t len = 0;
+    std::vector<Swing> found;
+
+    bool add(const Point& point) {
+        found.clear();
+        size_t n = window.size();
+        window[pos] = point;
+        pos = (pos + 1) % n;
+        if (len < n) len++;
+        if (len < n) return false;
+        // `pos` is now the oldest point
+        const Point& mid = window[(pos + left_) % n];
+        bool is_high = true, is_low = true;
+        for (size_t i = 0; i < n; i++) {
+            if (i == left_) continue;
+            const Point& p = window[(pos + i) % n];
+            if (p.high >= mid.high) is_high = false;
+            if (p.low <= mid.low) is_low = false;
+        }
+        if (is_high)
+            found.push_back({mid.timestamp, SwingType::HIGH, mid.high,
+                    point.timestamp});
+        if (is_low)
+            found.push_back({mid.timestamp, SwingType::LOW, mid.low,
+                    point.timestamp});
+        return !found.empty();
+    }
+
+public:
+    SwingDetector(size_t left, size_t right)
+        : left_(left), right_(right), window(left + right + 1) {}
+
+    static SwingDetector fractals() { return SwingDetector(2, 2); }
+
+    /**
+     * Adds a bar. Returns true if swing points were confirmed,
+     * available with `get()`.
+     */
+    bool update(const Ohlcv& bar) {
+        return add({bar.timestamp, bar.high, bar.low});
+    }
+
+    bool update(int64_t timestamp, double price) {
+        return add({timestamp, price, price});
+    }
+
+    /**
+     * The swing points confirmed by the last update, the high first.
+     */
+    const std::vector<Swing>& get() const noexcept { return found; }
+
+    size_t left() const noexcept { return left_; }
+
+    /**
+     * Number of bars after a swing point needed to confirm it.
+     */
+    size_t right() const noexcept { return right_; }
+
+    void reset() {
+        pos = len = 0;
+        found.clear();
+    }
+};
+
+/**
+ * Swing points of a series of bars, in the order they are confirmed.
+ */
+inline std::vector<Swing> find_swings(const std::vector<Ohlcv>& bars,
+        SwingDetector detector) {
+    std::vector<Swing> out;
+    for (const auto& bar : bars)
+        if (detector.update(bar))
+            out.insert(out.end(), detector.get().begin(),
+                    detector.get().end());
+    return out;
+}
+
+} // namespace tzu
+
+#endif // SWINGS_H
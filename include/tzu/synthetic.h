@@ -0,0 +1,161 @@
+#ifndef SYNTHETIC_H
+#define SYNTHETIC_H
+
+#include <cmath>
+#include <cstddef>
+#include <utility>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+#include "statistics.h"
+
+/**
+ * This header defines synthetic series combining the bars of two
+ * instruments, e.g. the spread of a pair for statistical arbitrage.
+ * Synthetic bars are `Ohlcv` like any others, so the usual indicators
+ * and strategies run on them, e.g. to trade the reversion of a spread
+ * to its mean.
+ */
+
+namespace tzu {
+
+enum class SyntheticKind {
+    SPREAD,
+    LOG_SPREAD,
+    RATIO
+};
+
+inline const char* to_string(SyntheticKind kind) {
+    switch (kind) {
+        case SyntheticKind::SPREAD: return "spread";
+        case SyntheticKind::LOG_SPREAD: return "log_spread";
+        case SyntheticKind::RATIO: return "ratio";
+    }
+    return "";
+}
+
+/**
+ * Synthetic Pair Series (Synthetic)
+ *
+ * Combines the bars `a` and `b` of two instruments at the same time
+ * into a bar of:
+ *
+ * SPREAD:     a - h * b
+ * LOG_SPREAD: log(a) - h * log(b)
+ * RATIO:      a / b
+ *
+ * with `h` the hedge ratio. With a positive `hedge_period`, spreads use
+ * the rolling `Beta` of the closes of `a` on those of `b` (or of their
+ * logs) over that many bars, estimated up to the previous bar so the
+ * spread of a bar doesn't depend on its own fit; they are NaN until it
+ * is available. Otherwise, `hedge_ratio` is used.
+ *
+ * Open and close are those of the combination. High and low bound it
+ * within the bar, combining the extremes of each instrument, since the
+ * actual path is unknown. Synthetic bars have no volume. Pairs of bars
+ * with different timestamps are skipped and counted, leaving the
+ * output unchanged.
+ *
+ * Example:
+ *
+ *     Synthetic spread(SyntheticKind::LOG_SPREAD, 60);
+ *     ZScore zscore(20);
+ *     Ohlcv bar = spread.update({ko_bar, pep_bar});
+ *     if (zscore.update(bar.close) > 2.0) ...  // short the spread
+ */
+class Synthetic: public Indicator<Synthetic, std::pair<Ohlcv, Ohlcv>, Ohlcv> {
+    Ohlcv data = Ohlcv(0, std::nan(""), std::nan(""), std::nan(""),
+            std::nan(""), 0.0);
+    SyntheticKind kind;
+    bool rolling;
+    double fixed;
+    Beta beta;
+    size_t skipped = 0;
+
+    double transform(double price) const {
+        return kind == SyntheticKind::LOG_SPREAD ? std::log(price) : price;
+    }
+
+public:
+    explicit Synthetic(SyntheticKind kind = SyntheticKind::SPREAD,
+            size_t hedge_period = 0, double hedge_ratio = 1.0)
+        : kind(kind), rolling(hedge_period > 0), fixed(hedge_ratio),
+          beta(hedge_period > 0 ? hedge_period : 1) {}
+    Ohlcv get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return rolling && kind != SyntheticKind::RATIO
+            ? beta.required_warmup() + 1 : 1;
+    }
+    Ohlcv update(std::pair<Ohlcv, Ohlcv> value) {
+        const Ohlcv& a = value.first;
+        const Ohlcv& b = value.second;
+        if (a.timestamp != b.timestamp) {
+            skipped++;
+            return data;
+        }
+        if (kind == SyntheticKind::RATIO) {
+            data = Ohlcv(a.timestamp, a.open / b.open, a.high / b.low,
+                    a.low / b.high, a.close / b.close, 0.0);
+            return data;
+        }
+        double h = hedge_ratio();
+        double lo = h * transform(b.low);
+        double hi = h * transform(b.high);
+        data = Ohlcv(a.timestamp,
+                transform(a.open) - h * transform(b.open),
+                transform(a.high) - std::fmin(lo, hi),
+                transform(a.low) - std::fmax(lo, hi),
+                transform(a.close) - h * transform(b.close), 0.0);
+        if (rolling)
+            beta.update(std::make_pair(transform(a.close),
+                        transform(b.close)));
+        return data;
+    }
+    /**
+     * Hedge ratio for the next bar, NaN while it is being estimated.
+     */
+    double hedge_ratio() const {
+        return rolling ? beta.get() : fixed;
+    }
+    /**
+     * Pairs skipped for having different timestamps.
+     */
+    size_t num_skipped() const noexcept { return skipped; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, beta, skipped);
+    }
+    void reset_state() {
+        data = Ohlcv(0, std::nan(""), std::nan(""), std::nan(""), std::nan(""),
+            0.0);
+        beta.reset();
+        skipped = 0;
+    }
+};
+
+/**
+ * Synthetic series of two series of bars, sorted by time, matching
+ * their bars by timestamp. Bars without a match in the other series
+ * are left out.
+ */
+inline std::vector<Ohlcv> synthesize(const std::vector<Ohlcv>& a,
+        const std::vector<Ohlcv>& b, Synthetic synthetic) {
+    std::vector<Ohlcv> out;
+    size_t i = 0, j = 0;
+    while (i < a.size() && j < b.size()) {
+        if (a[i].timestamp < b[j].timestamp) {
+            i++;
+        } else if (b[j].timestamp < a[i].timestamp) {
+            j++;
+        } else {
+            out.push_back(synthetic.update(std::make_pair(a[i], b[j])));
+            i++;
+            j++;
+        }
+    }
+    return out;
+}
+
+} // namespace tzu
+
+#endif // SYNTHETIC_H
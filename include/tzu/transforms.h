@@ -0,0 +1,415 @@
+#ifndef TRANSFORMS_H
+#define TRANSFORMS_H
+
+#include <cstddef>
+#include <cmath>
+#include <vector>
+#include <algorithm>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines streaming transforms. A transform has the same
+ * interface as an indicator (`get()` and `update()`), but its output
+ * is intended to be used as the input of another indicator instead of
+ * being interpreted directly, e.g. a denoised price series. Transforms
+ * can be chained in front of any indicator using the `Chain` class.
+ */
+
+namespace tzu {
+
+namespace detail {
+
+inline bool is_missing(double value) { return std::isnan(value); }
+
+inline bool is_missing(const Ohlcv& bar) { return std::isnan(bar.close); }
+
+} // namespace detail
+
+/**
+ * Chain
+ *
+ * Feeds the output of a first component into a second one, so that a
+ * transform can be placed in front of any indicator. While the first
+ * component is warming up (returns NaN, or a bar with a NaN close), the
+ * value is not forwarded, to avoid polluting the running state of the
+ * second component.
+ *
+ * Example: `Chain<WaveletDenoise, SMA> smooth(WaveletDenoise(32), SMA(10));`
+ */
+template <class First, class Second>
+class Chain: public Indicator<Chain<First, Second>,
+        typename First::input_type, typename Second::output_type> {
+    First first;
+    Second second;
+public:
+    Chain(const First& first, const Second& second)
+        : first(first), second(second) {}
+    typename Second::output_type get() const noexcept {
+        return second.get();
+    }
+    size_t required_warmup() const noexcept {
+        size_t a = first.required_warmup();
+        size_t b = second.required_warmup();
+        return a == 0 || b == 0 ? 0 : a + b - 1;
+    }
+    typename Second::output_type update(typename First::input_type value) {
+        typename First::output_type mid = first.update(value);
+        if (detail::is_missing(mid))
+            return second.get();
+        return second.update(mid);
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(first, second);
+    }
+    void reset_state() {
+        first.reset();
+        second.reset();
+    }
+};
+
+/**
+ * Wavelet Denoising (WaveletDenoise)
+ *
+ * Keeps the most recent values in a window whose size is a power of
+ * two, at least 2: other sizes are rounded down to one. On each update,
+ * the window is decomposed with the Haar wavelet transform, the detail
+ * coefficients are shrunk using a soft threshold, and the window is
+ * reconstructed. The returned value is the reconstructed version of the
+ * newest point, so no future data is used. Returns NaN until the window
+ * is full.
+ *
+ * The `levels` parameter sets how many decomposition levels are applied
+ * (more levels remove lower-frequency noise), at most as many as the
+ * window can be halved. If `threshold` is NaN, the universal threshold
+ * is used:
+ *
+ * threshold = sigma * sqrt(2 * ln(N))
+ *
+ * where sigma is estimated as the median absolute value of the finest
+ * detail coefficients divided by 0.6745. A threshold of zero gives a
+ * perfect reconstruction of the input.
+ */
+class WaveletDenoise: public Indicator<WaveletDenoise, double, double> {
+    double data = std::nan("");
+    std::vector<double> prev;
+    std::vector<double> work;
+    std::vector<double> scratch;
+    size_t pos = 0;
+    size_t len = 0;
+    size_t levels;
+    double threshold;
+
+    void forward(size_t n) {
+        const double norm = std::sqrt(2.0);
+        for (size_t i = 0; i < n / 2; i++) {
+            scratch[i] = (work[2 * i] + work[2 * i + 1]) / norm;
+            scratch[n / 2 + i] = (work[2 * i] - work[2 * i + 1]) / norm;
+        }
+        std::copy(scratch.begin(), scratch.begin() + n, work.begin());
+    }
+
+    void inverse(size_t n) {
+        const double norm = std::sqrt(2.0);
+        for (size_t i = 0; i < n / 2; i++) {
+            scratch[2 * i] = (work[i] + work[n / 2 + i]) / norm;
+            scratch[2 * i + 1] = (work[i] - work[n / 2 + i]) / norm;
+        }
+        std::copy(scratch.begin(), scratch.begin() + n, work.begin());
+    }
+
+    double universal_threshold() {
+        size_t n = work.size();
+        size_t half = n / 2;
+        for (size_t i = 0; i < half; i++)
+            scratch[i] = std::fabs(work[half + i]);
+        std::nth_element(scratch.begin(), scratch.begin() + half / 2,
+                scratch.begin() + half);
+        double sigma = scratch[half / 2] / 0.6745;
+        return sigma * std::sqrt(2.0 * std::log(static_cast<double>(n)));
+    }
+
+    static size_t power_of_two(size_t size) {
+        size_t n = 2;
+        while (n <= size / 2) n *= 2;
+        return n;
+    }
+
+public:
+    WaveletDenoise(size_t window_size, size_t levels = 1,
+            double threshold = std::nan(""))
+        : prev(power_of_two(window_size), 0.0), work(prev.size(), 0.0),
+          scratch(prev.size(), 0.0), levels(levels), threshold(threshold) {
+        while (this->levels > 0 && (prev.size() >> this->levels) == 0)
+            this->levels--;
+    }
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    double update(double value) {
+        if (len < prev.size()) len++;
+        prev[pos] = value;
+        pos = (pos + 1) % prev.size();
+        if (len < prev.size()) {
+            data = std::nan("");
+            return data;
+        }
+        size_t n = prev.size();
+        for (size_t i = 0; i < n; i++)
+            work[i] = prev[(pos + i) % n];
+        size_t m = n;
+        for (size_t l = 0; l < levels; l++, m /= 2)
+            forward(m);
+        // finest details occupy the upper half of `work` after the first
+        // level, which is what the noise estimate is based on
+        double lambda = std::isnan(threshold)
+            ? (levels > 0 ? universal_threshold() : 0.0)
+            : threshold;
+        for (size_t i = m; i < n; i++) {
+            double mag = std::fabs(work[i]) - lambda;
+            work[i] = mag > 0.0 ? std::copysign(mag, work[i]) : 0.0;
+        }
+        for (size_t l = 0; l < levels; l++) {
+            m *= 2;
+            inverse(m);
+        }
+        data = work[n - 1];
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len);
+    }
+    void reset_state() {
+        data = std::nan("");
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+    }
+};
+
+/**
+ * Sorts corporate actions by timestamp, as expected by the adjustment
+ * functions below.
+ */
+inline void sort_actions(std::vector<CorporateAction>& actions) {
+    std::sort(actions.begin(), actions.end(),
+        [](const CorporateAction& a, const CorporateAction& b) {
+            return a.timestamp < b.timestamp;
+        });
+}
+
+/**
+ * Back-adjusts a historical series in place, so that the most recent
+ * prices are the actual traded prices and earlier prices are scaled to
+ * remove the jumps caused by splits and dividends. Bars before a split
+ * have their prices divided by the split ratio and their volume
+ * multiplied by it. Bars before a dividend have their prices multiplied
+ * by `1 - dividend / close`, where `close` is the close of the bar
+ * before the ex-date. Bars and actions must be sorted by timestamp.
+ *
+ * Back adjustment rewrites history with information from the future,
+ * so it is meant for preparing a dataset, not for streaming.
+ */
+inline void back_adjust(std::vector<Ohlcv>& bars,
+        const std::vector<CorporateAction>& actions) {
+    double price_factor = 1.0;
+    double volume_factor = 1.0;
+    size_t next = actions.size();
+    for (size_t i = bars.size(); i-- > 0;) {
+        Ohlcv& bar = bars[i];
+        while (next > 0 && actions[next - 1].timestamp > bar.timestamp) {
+            const CorporateAction& action = actions[--next];
+            if (action.split > 0.0 && action.split != 1.0) {
+                price_factor /= action.split;
+                volume_factor *= action.split;
+            }
+            if (action.dividend > 0.0 && bar.close > 0.0)
+                price_factor *= 1.0 - action.dividend / bar.close;
+        }
+        bar.open *= price_factor;
+        bar.high *= price_factor;
+        bar.low *= price_factor;
+        bar.close *= price_factor;
+        bar.volume *= volume_factor;
+    }
+}
+
+/**
+ * Forward Adjustment (ForwardAdjust)
+ *
+ * Adjusts a stream of `Ohlcv` bars as they arrive, keeping the prices
+ * of the first bar unchanged and scaling later prices so that splits
+ * and dividends don't produce jumps. After a split, prices are
+ * multiplied by the split ratio and the volume divided by it. After a
+ * dividend, prices are divided by `1 - dividend / close`, where `close`
+ * is the actual close of the previous bar, as if the dividend were
+ * reinvested. Only actions with a timestamp at or before the current
+ * bar are applied, so this transform can be used in backtests and live.
+ */
+class ForwardAdjust: public Indicator<ForwardAdjust, Ohlcv, Ohlcv> {
+    Ohlcv data;
+    std::vector<CorporateAction> actions;
+    size_t next = 0;
+    double price_factor = 1.0;
+    double volume_factor = 1.0;
+    double last_close = std::nan("");
+public:
+    explicit ForwardAdjust(const std::vector<CorporateAction>& actions)
+        : actions(actions) {
+        sort_actions(this->actions);
+    }
+    Ohlcv get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    bool is_ready() const noexcept { return !std::isnan(last_close); }
+    Ohlcv update(const Ohlcv& value) {
+        while (next < actions.size()
+                && actions[next].timestamp <= value.timestamp) {
+            const CorporateAction& action = actions[next++];
+            if (action.split > 0.0 && action.split != 1.0) {
+                price_factor *= action.split;
+                volume_factor /= action.split;
+            }
+            // the dividend is expressed in the prices before the split
+            // of the same date, if any
+            if (action.dividend > 0.0 && last_close > 0.0)
+                price_factor /= 1.0 - action.dividend / last_close;
+        }
+        data = Ohlcv(value.timestamp,
+                value.open * price_factor, value.high * price_factor,
+                value.low * price_factor, value.close * price_factor,
+                value.volume * volume_factor);
+        last_close = value.close;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, next, price_factor, volume_factor, last_close);
+    }
+    void reset_state() {
+        data = Ohlcv();
+        next = 0;
+        price_factor = volume_factor = 1.0;
+        last_close = std::nan("");
+    }
+};
+
+/**
+ * Heikin-Ashi (HeikinAshi)
+ *
+ * Converts bars into Heikin-Ashi candles, which average out part of
+ * the noise of the raw prices:
+ *
+ * HA_Close = (Open + High + Low + Close) / 4
+ * HA_Open = (HA_Open_prev + HA_Close_prev) / 2
+ * HA_High = max(High, HA_Open, HA_Close)
+ * HA_Low = min(Low, HA_Open, HA_Close)
+ *
+ * The first candle opens at the midpoint of the open and the close.
+ * Timestamps and volumes are kept. Chain it in front of an indicator
+ * taking bars, e.g. `Chain<HeikinAshi, RSI>`.
+ */
+class HeikinAshi: public Indicator<HeikinAshi, Ohlcv, Ohlcv> {
+    Ohlcv data;
+    bool started = false;
+public:
+    Ohlcv get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 1; }
+    bool is_ready() const noexcept { return started; }
+    Ohlcv update(const Ohlcv& value) {
+        double close = (value.open + value.high + value.low + value.close) / 4.0;
+        double open = started
+            ? (data.open + data.close) / 2.0
+            : (value.open + value.close) / 2.0;
+        started = true;
+        data = Ohlcv(value.timestamp, open,
+                std::max(value.high, std::max(open, close)),
+                std::min(value.low, std::min(open, close)),
+                close, value.volume);
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, started);
+    }
+    void reset_state() {
+        data = Ohlcv();
+        started = false;
+    }
+};
+
+/**
+ * Returns the number of weights needed by a fractional difference of
+ * order `d`, keeping the weights whose magnitude is at least
+ * `threshold`. Capped at `max_window`.
+ */
+inline size_t frac_diff_window(double d, double threshold = 1e-4,
+        size_t max_window = 10000) {
+    double w = 1.0;
+    size_t k = 1;
+    while (k < max_window) {
+        w = -w * (d - k + 1.0) / k;
+        if (std::fabs(w) < threshold) break;
+        k++;
+    }
+    return k;
+}
+
+/**
+ * Fractional Differentiation (FracDiff)
+ *
+ * Applies a fixed-width window fractional difference of order `d` to
+ * the series:
+ *
+ * y_t = sum(w_k * x_(t-k)), k = 0 .. N-1
+ *
+ * with weights w_0 = 1 and w_k = -w_(k-1) * (d - k + 1) / k. With d = 1
+ * it is the usual first difference, and with d = 0 the series itself.
+ * Values of d between 0 and 1 make a price series stationary while
+ * preserving part of its memory, which is useful for statistical models
+ * and machine learning features. Returns NaN until N values have been
+ * added. Use `frac_diff_window` to choose N from a weight threshold.
+ */
+class FracDiff: public Indicator<FracDiff, double, double> {
+    double data = std::nan("");
+    std::vector<double> weights;
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+public:
+    FracDiff(double d, size_t window_size)
+        : weights(window_size, 1.0), prev(window_size, 0.0) {
+        for (size_t k = 1; k < window_size; k++)
+            weights[k] = -weights[k - 1] * (d - k + 1.0) / k;
+    }
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return prev.size(); }
+    double update(double value) {
+        size_t n = prev.size();
+        if (len < n) len++;
+        prev[pos] = value;
+        pos = (pos + 1) % n;
+        if (len < n) {
+            data = std::nan("");
+            return data;
+        }
+        double accum = 0.0;
+        // the newest value is at pos - 1, matched with w_0
+        for (size_t k = 0; k < n; k++)
+            accum += weights[k] * prev[(pos + n - 1 - k) % n];
+        data = accum;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, prev, pos, len);
+    }
+    void reset_state() {
+        data = std::nan("");
+        std::fill(prev.begin(), prev.end(), 0.0);
+        pos = len = 0;
+    }
+};
+
+} // namespace tzu
+
+#endif // TRANSFORMS_H
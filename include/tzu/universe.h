@@ -0,0 +1,376 @@
+#ifndef UNIVERSE_H
+#define UNIVERSE_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <map>
+#include <string>
+#include <vector>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines components that work across a universe of
+ * symbols. They consume per-symbol bar updates, expected to arrive in
+ * timestamp order (all the symbols for a timestamp before the next
+ * timestamp), and produce cross-sectional values. A period is
+ * completed when a bar with a newer timestamp arrives, or when
+ * `flush()` is called at the end of the stream.
+ */
+
+namespace tzu {
+
+/**
+ * Rolling extreme of the previous N values, not including the value
+ * being added. Used to detect new highs and new lows.
+ */
+class PriorExtreme {
+    std::vector<double> prev;
+    size_t pos = 0;
+    size_t len = 0;
+    bool highest;
+public:
+    PriorExtreme(size_t window_size, bool highest)
+        : prev(window_size, 0.0), highest(highest) {}
+    bool full() const noexcept { return len == prev.size(); }
+    double get() const noexcept {
+        if (len == 0) return std::nan("");
+        double ext = prev[0];
+        for (size_t i = 1; i < len; i++)
+            ext = highest ? std::fmax(ext, prev[i]) : std::fmin(ext, prev[i]);
+        return ext;
+    }
+    void update(double value) {
+        if (len < prev.size()) len++;
+        prev[pos] = value;
+        pos = (pos + 1) % prev.size();
+    }
+};
+
+/**
+ * Market breadth values for a single period.
+ *
+ * - `advances`, `declines`, `unchanged`: number of symbols whose close
+ *   went up, down or stayed the same relative to their previous close.
+ * - `ad_line`: cumulative sum of advances minus declines.
+ * - `pct_above_ma`: percentage (0-100) of the symbols with a valid
+ *   moving average whose close is above it. NaN if none is valid yet.
+ * - `new_highs`, `new_lows`: number of symbols whose high (low) is
+ *   above (below) the highest high (lowest low) of their previous N
+ *   bars.
+ */
+struct BreadthResult {
+    int64_t timestamp = 0;
+    size_t advances = 0;
+    size_t declines = 0;
+    size_t unchanged = 0;
+    double ad_line = 0.0;
+    double pct_above_ma = std::nan("");
+    size_t new_highs = 0;
+    size_t new_lows = 0;
+};
+
+/**
+ * Market Breadth (Breadth)
+ *
+ * Computes the advance/decline line, the percentage of symbols above
+ * their N-period simple moving average, and the count of new highs and
+ * lows over a lookback period, across all the symbols that have been
+ * seen. Each symbol keeps its own state, so symbols can join the
+ * universe at any time.
+ */
+class Breadth {
+    struct SymbolState {
+        SMA ma;
+        PriorExtreme highs;
+        PriorExtreme lows;
+        double last_close = std::nan("");
+        SymbolState(size_t ma_period, size_t high_low_period)
+            : ma(ma_period), highs(high_low_period, true),
+              lows(high_low_period, false) {}
+    };
+    std::map<std::string, SymbolState> symbols;
+    size_t ma_period;
+    size_t high_low_period;
+    BreadthResult data;
+    BreadthResult current;
+    size_t num_with_ma = 0;
+    size_t num_above_ma = 0;
+    bool pending = false;
+
+    void finalize() {
+        current.ad_line = data.ad_line
+            + static_cast<double>(current.advances)
+            - static_cast<double>(current.declines);
+        current.pct_above_ma = num_with_ma > 0
+            ? 100.0 * static_cast<double>(num_above_ma) / num_with_ma
+            : std::nan("");
+        data = current;
+        current = BreadthResult();
+        num_with_ma = 0;
+        num_above_ma = 0;
+        pending = false;
+    }
+
+public:
+    Breadth(size_t ma_period = 50, size_t high_low_period = 252)
+        : ma_period(ma_period), high_low_period(high_low_period) {}
+
+    /**
+     * Returns the values of the last completed period.
+     */
+    BreadthResult get() const noexcept { return data; }
+
+    /**
+     * Adds a bar for a symbol. Returns true if the bar completed the
+     * previous period, whose values are then available with `get()`.
+     */
+    bool update(const std::string& symbol, const Ohlcv& bar) {
+        bool completed = false;
+        if (pending && bar.timestamp > current.timestamp) {
+            finalize();
+            completed = true;
+        }
+        pending = true;
+        current.timestamp = bar.timestamp;
+        auto it = symbols.find(symbol);
+        if (it == symbols.end())
+            it = symbols.emplace(symbol,
+                    SymbolState(ma_period, high_low_period)).first;
+        SymbolState& st = it->second;
+        if (!std::isnan(st.last_close)) {
+            if (bar.close > st.last_close) current.advances++;
+            else if (bar.close < st.last_close) current.declines++;
+            else current.unchanged++;
+        }
+        st.last_close = bar.close;
+        double ma = st.ma.update(bar.close);
+        if (!std::isnan(ma)) {
+            num_with_ma++;
+            if (bar.close > ma) num_above_ma++;
+        }
+        if (st.highs.full() && bar.high > st.highs.get()) current.new_highs++;
+        if (st.lows.full() && bar.low < st.lows.get()) current.new_lows++;
+        st.highs.update(bar.high);
+        st.lows.update(bar.low);
+        return completed;
+    }
+
+    /**
+     * Completes the current period, if any, and returns its values.
+     * Intended to be called when the stream ends.
+     */
+    BreadthResult flush() {
+        if (pending) finalize();
+        return data;
+    }
+
+    /**
+     * Number of symbols seen so far.
+     */
+    size_t size() const noexcept { return symbols.size(); }
+};
+
+/**
+ * Weighting schemes for the `CompositeIndex`.
+ *
+ * - `PRICE`: every component counts as one unit, so higher priced
+ *   symbols have more influence (like the Dow Jones index). Weights
+ *   are only used to define the members of the basket.
+ * - `CAP`: weights are the number of shares of each component (e.g.
+ *   shares outstanding), so the index follows the basket's
+ *   capitalization.
+ * - `FIXED`: weights are fractions of the index value. They drift with
+ *   prices and are restored at each rebalance date.
+ */
+enum class IndexWeighting {
+    PRICE,
+    CAP,
+    FIXED
+};
+
+/**
+ * Composite Index (CompositeIndex)
+ *
+ * Builds a synthetic index from a weighted basket of symbols and emits
+ * it as an `Ohlcv` stream, so indicators and strategies can run
+ * against custom baskets or sector benchmarks. The index holds a number
+ * of units of each component:
+ *
+ * Index = sum(units_i * price_i) / divisor
+ *
+ * The index starts at `base` in the first period where all the
+ * components have data. New weights can be scheduled with
+ * `rebalance()`; they are applied at the first period whose timestamp
+ * is at or after the given date, adjusting the divisor (or the units,
+ * for `FIXED`) so that the index value stays continuous. Components
+ * without a bar in a period keep their last close. The open, high and
+ * low of the index are computed from the components' open, high and
+ * low, so the high and low are an approximation: components don't
+ * reach their extremes at the same time.
+ */
+class CompositeIndex {
+    struct Component {
+        double units = 0.0;
+        Ohlcv bar;
+        bool seen = false;
+        bool updated = false;
+    };
+    std::map<std::string, Component> components;
+    std::map<std::string, double> weights;
+    std::vector<std::pair<int64_t, std::map<std::string, double>>> schedule;
+    IndexWeighting scheme;
+    double base;
+    double divisor = std::nan("");
+    Ohlcv data;
+    int64_t current = 0;
+    bool pending = false;
+    bool rebalance_due = false;
+
+    double level() const {
+        double total = 0.0;
+        for (const auto& c : components)
+            total += c.second.units * c.second.bar.close;
+        return total / divisor;
+    }
+
+    void assign_units(double target) {
+        for (auto& c : components) {
+            auto w = weights.find(c.first);
+            double weight = w == weights.end() ? 0.0 : w->second;
+            if (scheme == IndexWeighting::PRICE)
+                c.second.units = w == weights.end() ? 0.0 : 1.0;
+            else if (scheme == IndexWeighting::CAP)
+                c.second.units = weight;
+            else
+                c.second.units = weight * target / c.second.bar.close;
+        }
+        double total = 0.0;
+        for (const auto& c : components)
+            total += c.second.units * c.second.bar.close;
+        divisor = total / target;
+    }
+
+    bool all_seen() const {
+        for (const auto& w : weights) {
+            auto it = components.find(w.first);
+            if (it == components.end() || !it->second.seen) return false;
+        }
+        return true;
+    }
+
+    // Called at the start of a period, when the components still hold
+    // the closes of the previous period. A rebalance is deferred until
+    // all the new members have data.
+    void apply_rebalance() {
+        while (!schedule.empty() && schedule.front().first <= current) {
+            weights = schedule.front().second;
+            schedule.erase(schedule.begin());
+            rebalance_due = true;
+        }
+        if (rebalance_due && !std::isnan(divisor) && all_seen()) {
+            assign_units(level());
+            rebalance_due = false;
+        }
+    }
+
+    bool finalize() {
+        if (std::isnan(divisor)) {
+            if (!all_seen()) {
+                pending = false;
+                return false;
+            }
+            assign_units(base);
+            rebalance_due = false;
+        }
+        Ohlcv bar(current, 0.0, 0.0, 0.0, 0.0, 0.0);
+        for (auto& c : components) {
+            const Ohlcv& b = c.second.bar;
+            double u = c.second.units;
+            if (c.second.updated) {
+                bar.open += u * b.open;
+                bar.high += u * b.high;
+                bar.low += u * b.low;
+                if (weights.count(c.first) > 0)
+                    bar.volume += b.volume;
+            } else {
+                bar.open += u * b.close;
+                bar.high += u * b.close;
+                bar.low += u * b.close;
+            }
+            bar.close += u * b.close;
+            c.second.updated = false;
+        }
+        bar.open /= divisor;
+        bar.high /= divisor;
+        bar.low /= divisor;
+        bar.close /= divisor;
+        data = bar;
+        pending = false;
+        return true;
+    }
+
+public:
+    CompositeIndex(const std::map<std::string, double>& weights,
+            IndexWeighting scheme = IndexWeighting::FIXED,
+            double base = 100.0)
+        : weights(weights), scheme(scheme), base(base) {
+        for (const auto& w : weights)
+            components[w.first];
+    }
+
+    /**
+     * Schedules new weights to be applied from the given timestamp on.
+     * Symbols missing from the new weights leave the index. New members
+     * are tracked from now on, so their prices are known when the
+     * rebalance takes place.
+     */
+    void rebalance(int64_t timestamp, const std::map<std::string, double>& w) {
+        auto it = schedule.begin();
+        while (it != schedule.end() && it->first <= timestamp) ++it;
+        schedule.insert(it, std::make_pair(timestamp, w));
+        for (const auto& c : w)
+            components[c.first];
+    }
+
+    /**
+     * Returns the index bar of the last completed period.
+     */
+    Ohlcv get() const noexcept { return data; }
+
+    /**
+     * Adds a bar for a symbol. Bars for symbols that are not part of
+     * the basket are ignored. Returns true if the bar completed the
+     * previous period and a new index bar is available with `get()`.
+     */
+    bool update(const std::string& symbol, const Ohlcv& bar) {
+        bool completed = false;
+        if (pending && bar.timestamp > current)
+            completed = finalize();
+        if (!pending) {
+            current = bar.timestamp;
+            pending = true;
+            apply_rebalance();
+        }
+        auto it = components.find(symbol);
+        if (it == components.end())
+            return completed;
+        it->second.bar = bar;
+        it->second.seen = true;
+        it->second.updated = true;
+        return completed;
+    }
+
+    /**
+     * Completes the current period, if any. Returns true if a new
+     * index bar is available with `get()`.
+     */
+    bool flush() {
+        return pending ? finalize() : false;
+    }
+};
+
+} // namespace tzu
+
+#endif // UNIVERSE_H
@@ -0,0 +1,153 @@
+#ifndef VALIDATE_H
+#define VALIDATE_H
+
+#include <cmath>
+#include <cstddef>
+#include <cstdint>
+#include <map>
+#include <ostream>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines a data-quality gate for streams of bars.
+ * Indicators trust their inputs, so a bar with a high below its low, a
+ * NaN price or a timestamp going backwards silently corrupts them. The
+ * validator checks each bar before it reaches them, reporting typed
+ * issues so a pipeline can log them, drop the bar, or stop.
+ */
+
+namespace tzu {
+
+enum class BarIssue {
+    NON_FINITE,
+    HIGH_BELOW_LOW,
+    OPEN_OUTSIDE_RANGE,
+    CLOSE_OUTSIDE_RANGE,
+    NEGATIVE_VOLUME,
+    DUPLICATE_TIME,
+    OUT_OF_ORDER,
+    GAP
+};
+
+inline const char* to_string(BarIssue issue) {
+    switch (issue) {
+        case BarIssue::NON_FINITE: return "non_finite";
+        case BarIssue::HIGH_BELOW_LOW: return "high_below_low";
+        case BarIssue::OPEN_OUTSIDE_RANGE: return "open_outside_range";
+        case BarIssue::CLOSE_OUTSIDE_RANGE: return "close_outside_range";
+        case BarIssue::NEGATIVE_VOLUME: return "negative_volume";
+        case BarIssue::DUPLICATE_TIME: return "duplicate_time";
+        case BarIssue::OUT_OF_ORDER: return "out_of_order";
+        case BarIssue::GAP: return "gap";
+    }
+    return "";
+}
+
+/**
+ * An issue found in a bar, with its position in the stream and its
+ * timestamp.
+ */
+struct BarFinding {
+    size_t index;
+    int64_t timestamp;
+    BarIssue issue;
+};
+
+inline std::ostream& operator<<(std::ostream& os, const BarFinding& f) {
+    os << "index:" << f.index
+       << " timestamp:" << f.timestamp
+       << " issue:" << to_string(f.issue);
+    return os;
+}
+
+/**
+ * BarValidator
+ *
+ * Checks a stream of bars. `update` returns whether a bar can be used:
+ * bars with inconsistent prices, negative volumes, or timestamps not
+ * after the previous valid bar are rejected. Gaps, when the time since
+ * the previous bar exceeds `max_gap` (zero to disable), are reported
+ * but don't reject the bar, since weekends and halts are normal in
+ * many markets. The findings of the latest bar are available with
+ * `get()`, and the totals with `count()`.
+ */
+class BarValidator {
+    int64_t max_gap;
+    size_t index = 0;
+    int64_t last = INT64_MIN;
+    std::vector<BarFinding> current;
+    std::map<BarIssue, size_t> totals;
+
+    void report(const Ohlcv& bar, BarIssue issue) {
+        current.push_back(BarFinding{index, bar.timestamp, issue});
+        totals[issue]++;
+    }
+
+public:
+    explicit BarValidator(int64_t max_gap = 0): max_gap(max_gap) {}
+
+    bool update(const Ohlcv& bar) {
+        current.clear();
+        if (!std::isfinite(bar.open) || !std::isfinite(bar.high)
+                || !std::isfinite(bar.low) || !std::isfinite(bar.close)
+                || !std::isfinite(bar.volume)) {
+            report(bar, BarIssue::NON_FINITE);
+        } else {
+            if (bar.high < bar.low)
+                report(bar, BarIssue::HIGH_BELOW_LOW);
+            if (bar.open > bar.high || bar.open < bar.low)
+                report(bar, BarIssue::OPEN_OUTSIDE_RANGE);
+            if (bar.close > bar.high || bar.close < bar.low)
+                report(bar, BarIssue::CLOSE_OUTSIDE_RANGE);
+            if (bar.volume < 0.0)
+                report(bar, BarIssue::NEGATIVE_VOLUME);
+        }
+        if (last != INT64_MIN) {
+            if (bar.timestamp == last)
+                report(bar, BarIssue::DUPLICATE_TIME);
+            else if (bar.timestamp < last)
+                report(bar, BarIssue::OUT_OF_ORDER);
+        }
+        bool valid = current.empty();
+        if (valid) {
+            if (max_gap > 0 && last != INT64_MIN
+                    && bar.timestamp - last > max_gap)
+                report(bar, BarIssue::GAP);
+            last = bar.timestamp;
+        }
+        index++;
+        return valid;
+    }
+
+    /**
+     * Issues found in the latest bar.
+     */
+    const std::vector<BarFinding>& get() const noexcept { return current; }
+
+    size_t count(BarIssue issue) const {
+        auto it = totals.find(issue);
+        return it == totals.end() ? 0 : it->second;
+    }
+
+    size_t num_bars() const noexcept { return index; }
+};
+
+/**
+ * All the issues in a series of bars.
+ */
+inline std::vector<BarFinding> validate_bars(const std::vector<Ohlcv>& bars,
+        int64_t max_gap = 0) {
+    BarValidator validator(max_gap);
+    std::vector<BarFinding> findings;
+    for (const auto& bar : bars) {
+        validator.update(bar);
+        findings.insert(findings.end(), validator.get().begin(),
+                validator.get().end());
+    }
+    return findings;
+}
+
+} // namespace tzu
+
+#endif // VALIDATE_H
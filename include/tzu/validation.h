@@ -0,0 +1,119 @@
+#ifndef VALIDATION_H
+#define VALIDATION_H
+
+#include <cstddef>
+#include <cstdint>
+#include <cmath>
+#include <utility>
+#include <vector>
+#include "defs.h"
+
+/**
+ * This header defines tools to evaluate strategies and models out of
+ * sample. Financial samples are not independent: a label computed at
+ * time t usually depends on prices until some later time (its span),
+ * so a plain K-fold split leaks information from the test fold into
+ * the training folds. The purged K-fold split, as described by López
+ * de Prado, removes from training every sample whose span overlaps
+ * the test fold (purging), and also the samples that immediately
+ * follow the test fold (embargo), since serial correlation lets them
+ * carry information about it.
+ */
+
+namespace tzu {
+
+/**
+ * The time interval a sample depends on, from the time it is observed
+ * (`start`) to the last time used to compute its label (`end`).
+ */
+struct Span {
+    int64_t start;
+    int64_t end;
+    Span(int64_t s = 0, int64_t e = 0) : start(s), end(e) {}
+};
+
+/**
+ * Indices of the training and testing samples of a fold.
+ */
+struct Fold {
+    std::vector<size_t> train;
+    std::vector<size_t> test;
+};
+
+/**
+ * Splits samples into `k` folds with contiguous test sets. Samples must
+ * be sorted by start time. Training samples whose span overlaps the
+ * test period are purged, and `embargo` (a fraction of the number of
+ * samples, e.g. 0.01) samples following the test set are dropped.
+ */
+inline std::vector<Fold> purged_kfold(const std::vector<Span>& spans,
+        size_t k, double embargo = 0.0) {
+    std::vector<Fold> folds;
+    size_t n = spans.size();
+    if (k < 2 || n < k) return folds;
+    size_t h = static_cast<size_t>(std::ceil(embargo * n));
+    for (size_t f = 0; f < k; f++) {
+        size_t first = f * n / k;
+        size_t last = (f + 1) * n / k;    // exclusive
+        int64_t test_start = spans[first].start;
+        int64_t test_end = spans[first].end;
+        for (size_t i = first; i < last; i++)
+            if (spans[i].end > test_end) test_end = spans[i].end;
+        Fold fold;
+        for (size_t i = 0; i < n; i++) {
+            if (i >= first && i < last) {
+                fold.test.push_back(i);
+                continue;
+            }
+            bool overlaps = spans[i].start <= test_end
+                && spans[i].end >= test_start;
+            bool embargoed = i >= last && i < last + h;
+            if (!overlaps && !embargoed)
+                fold.train.push_back(i);
+        }
+        folds.push_back(fold);
+    }
+    return folds;
+}
+
+/**
+ * Builds the spans of bar samples whose labels look `horizon` bars
+ * ahead, e.g. a forward return over the next `horizon` bars.
+ */
+inline std::vector<Span> horizon_spans(const std::vector<Ohlcv>& bars,
+        size_t horizon) {
+    std::vector<Span> spans;
+    spans.reserve(bars.size());
+    for (size_t i = 0; i < bars.size(); i++) {
+        size_t j = i + horizon < bars.size() ? i + horizon : bars.size() - 1;
+        spans.push_back(Span(bars[i].timestamp, bars[j].timestamp));
+    }
+    return spans;
+}
+
+/**
+ * Runs a purged K-fold cross validation. The evaluation callable
+ * receives the training and testing samples of each fold, e.g. to fit
+ * parameters on the former and run a backtest on the latter, and
+ * returns a score. Returns the scores of all the folds.
+ */
+template <typename T, typename Evaluate>
+std::vector<double> cross_validate(const std::vector<T>& samples,
+        const std::vector<Span>& spans, size_t k, double embargo,
+        Evaluate evaluate) {
+    std::vector<double> scores;
+    for (const Fold& fold : purged_kfold(spans, k, embargo)) {
+        std::vector<T> train;
+        std::vector<T> test;
+        train.reserve(fold.train.size());
+        test.reserve(fold.test.size());
+        for (size_t i : fold.train) train.push_back(samples[i]);
+        for (size_t i : fold.test) test.push_back(samples[i]);
+        scores.push_back(evaluate(train, test));
+    }
+    return scores;
+}
+
+} // namespace tzu
+
+#endif // VALIDATION_H
@@ -0,0 +1,283 @@
+#ifndef VOLATILITY_H
+#define VOLATILITY_H
+
+#include <cstddef>
+#include <cmath>
+#include <deque>
+#include <utility>
+#include "defs.h"
+#include "indicators.h"
+
+/**
+ * This header defines volatility estimators. They consume prices,
+ * compute simple returns internally, and return the estimated standard
+ * deviation of returns per period (not annualized). Multiply by the
+ * square root of the number of periods per year to annualize.
+ */
+
+namespace tzu {
+
+/**
+ * Exponentially Weighted Volatility (EWMAVol)
+ *
+ * RiskMetrics-style volatility estimate. The variance is updated with
+ * each new return r as:
+ *
+ * var = alpha * r^2 + (1 - alpha) * var
+ *
+ * with alpha = 2 / (span + 1). The first return initializes the
+ * variance. Returns NaN until two prices have been added.
+ */
+class EWMAVol: public Indicator<EWMAVol, double, double> {
+    double data = std::nan("");
+    double alpha;
+    double var = std::nan("");
+    double last = std::nan("");
+public:
+    explicit EWMAVol(size_t span) : alpha(2.0 / (span + 1.0)) {}
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept { return 2; }
+    double update(double value) {
+        if (!std::isnan(last) && last != 0.0) {
+            double r = value / last - 1.0;
+            var = std::isnan(var) ? r * r : alpha * r * r + (1.0 - alpha) * var;
+            data = std::sqrt(var);
+        }
+        last = value;
+        return data;
+    }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, var, last);
+    }
+    void reset_state() {
+        data = var = last = std::nan("");
+    }
+};
+
+namespace detail {
+
+/**
+ * Negative log-likelihood, up to constants, of zero mean returns under
+ * a GARCH(1,1) with the given parameters, starting from the variance
+ * `var0`. Updates `var` to the forecast after the last return.
+ */
+inline double garch_nll(const std::deque<double>& returns, double omega,
+        double alpha, double beta, double var0, double& var) {
+    double nll = 0.0;
+    var = var0;
+    for (double r : returns) {
+        nll += std::log(var) + r * r / var;
+        var = omega + alpha * r * r + beta * var;
+    }
+    return nll;
+}
+
+/**
+ * Minimizes a function of two variables with the Nelder-Mead simplex
+ * method, starting from (x, y) with the given step, and sets (x, y) to
+ * the minimum found.
+ */
+template <typename F>
+void nelder_mead(F f, double& x, double& y, double step,
+        int iterations = 500) {
+    double px[3] = {x, x + step, x};
+    double py[3] = {y, y, y + step};
+    double v[3];
+    for (int i = 0; i < 3; i++) v[i] = f(px[i], py[i]);
+    for (int it = 0; it < iterations; it++) {
+        // sort the vertices from best to worst
+        for (int i = 1; i < 3; i++) {
+            for (int j = i; j > 0 && v[j] < v[j - 1]; j--) {
+                std::swap(px[j], px[j - 1]);
+                std::swap(py[j], py[j - 1]);
+                std::swap(v[j], v[j - 1]);
+            }
+        }
+        if (v[2] - v[0] <= 1e-12 * (std::fabs(v[0]) + 1e-12)) break;
+        double cx = (px[0] + px[1]) / 2.0;
+        double cy = (py[0] + py[1]) / 2.0;
+        double rx = 2.0 * cx - px[2];
+        double ry = 2.0 * cy - py[2];
+        double rv = f(rx, ry);
+        if (rv < v[0]) {
+            double ex = 3.0 * cx - 2.0 * px[2];
+            double ey = 3.0 * cy - 2.0 * py[2];
+            double ev = f(ex, ey);
+            bool expand = ev < rv;
+            px[2] = expand ? ex : rx;
+            py[2] = expand ? ey : ry;
+            v[2] = expand ? ev : rv;
+        } else if (rv < v[1]) {
+            px[2] = rx;
+            py[2] = ry;
+            v[2] = rv;
+        } else {
+            // contract towards the better of the worst and reflected points
+            bool outside = rv < v[2];
+            double kx = (cx + (outside ? rx : px[2])) / 2.0;
+            double ky = (cy + (outside ? ry : py[2])) / 2.0;
+            double kv = f(kx, ky);
+            if (kv < (outside ? rv : v[2])) {
+                px[2] = kx;
+                py[2] = ky;
+                v[2] = kv;
+            } else {
+                for (int i = 1; i < 3; i++) {
+                    px[i] = (px[0] + px[i]) / 2.0;
+                    py[i] = (py[0] + py[i]) / 2.0;
+                    v[i] = f(px[i], py[i]);
+                }
+            }
+        }
+    }
+    int best = 0;
+    for (int i = 1; i < 3; i++)
+        if (v[i] < v[best]) best = i;
+    x = px[best];
+    y = py[best];
+}
+
+/**
+ * Maximum likelihood estimates of alpha and beta of a GARCH(1,1), with
+ * omega set so the long-run variance is the mean squared return. The
+ * likelihood is maximized with Nelder-Mead over unconstrained variables
+ * mapped to the persistence alpha + beta in (0, 0.999) and to the share
+ * of alpha in it, so the estimates are always stationary.
+ */
+inline void garch_fit(const std::deque<double>& returns, double& omega,
+        double& alpha, double& beta) {
+    double var0 = 0.0;
+    for (double r : returns) var0 += r * r;
+    var0 /= returns.size();
+    omega = var0;
+    alpha = 0.0;
+    beta = 0.0;
+    if (var0 <= 1e-24) return;
+    const double max_persistence = 0.999;
+    auto logistic = [](double u) { return 1.0 / (1.0 + std::exp(-u)); };
+    auto nll = [&](double u, double t) {
+        double p = max_persistence * logistic(u);
+        double a = p * logistic(t);
+        double var = 0.0;
+        return garch_nll(returns, var0 * (1.0 - p), a, p - a, var0, var);
+    };
+    // start from alpha = 0.1 and beta = 0.8
+    double u = std::log(0.9 / (max_persistence - 0.9));
+    double t = std::log(0.1 / 0.8);
+    nelder_mead(nll, u, t, 0.5);
+    double p = max_persistence * logistic(u);
+    alpha = p * logistic(t);
+    beta = p - alpha;
+    omega = var0 * (1.0 - p);
+}
+
+} // namespace detail
+
+/**
+ * GARCH(1,1) Volatility Forecast (GARCH)
+ *
+ * One-step-ahead forecast of the volatility of the returns, whose
+ * variance follows:
+ *
+ * var = omega + alpha * r^2 + beta * var
+ *
+ * with r the latest return, taken as having zero mean. Shocks raise
+ * the forecast, which then decays to the long-run variance
+ * omega / (1 - alpha - beta) at the rate alpha + beta.
+ *
+ * The parameters are either given, the variance starting at its long
+ * run value, or estimated by maximum likelihood with `fit` over the
+ * last `window` returns, and refit every `refit_every` returns, or
+ * never for zero. Fitted parameters keep the long-run variance at the
+ * mean squared return of the window. Returns NaN until the first return
+ * or the first fit.
+ *
+ * Example:
+ *
+ *     GARCH garch = GARCH::fit(500, 20);
+ *     double vol = garch.update(bar.close);
+ *     double var = garch.variance();  // for tomorrow
+ */
+class GARCH: public Indicator<GARCH, double, double> {
+    double data = std::nan("");
+    double w;
+    double a;
+    double b;
+    size_t window = 0;
+    size_t refit = 0;
+    std::deque<double> returns;
+    size_t since = 0;
+    bool fitted = false;
+    double var = std::nan("");
+    double last = std::nan("");
+
+    void estimate() {
+        detail::garch_fit(returns, w, a, b);
+        double var0 = w / (1.0 - a - b);
+        detail::garch_nll(returns, w, a, b, var0, var);
+        fitted = true;
+        since = 0;
+    }
+
+public:
+    GARCH(double omega, double alpha, double beta)
+        : w(omega), a(alpha), b(beta) {}
+
+    static GARCH fit(size_t window, size_t refit_every = 0) {
+        GARCH garch(0.0, 0.0, 0.0);
+        garch.window = window < 10 ? 10 : window;
+        garch.refit = refit_every;
+        return garch;
+    }
+
+    double get() const noexcept { return data; }
+    size_t required_warmup() const noexcept {
+        return window > 0 ? window + 1 : 2;
+    }
+    double update(double value) {
+        if (!std::isnan(last) && last != 0.0) {
+            double r = value / last - 1.0;
+            if (window == 0) {
+                if (std::isnan(var))
+                    var = a + b < 1.0 ? w / (1.0 - a - b) : r * r;
+                var = w + a * r * r + b * var;
+            } else {
+                returns.push_back(r);
+                if (returns.size() > window) returns.pop_front();
+                since++;
+                if (fitted) var = w + a * r * r + b * var;
+                if (returns.size() == window
+                        && (!fitted || (refit > 0 && since >= refit)))
+                    estimate();
+            }
+            if (!std::isnan(var)) data = std::sqrt(var);
+        }
+        last = value;
+        return data;
+    }
+    /**
+     * Forecast of the variance of the next return.
+     */
+    double variance() const noexcept { return var; }
+    double omega() const noexcept { return w; }
+    double alpha() const noexcept { return a; }
+    double beta() const noexcept { return b; }
+    template <class Archive>
+    void serialize(Archive& ar) {
+        ar(data, w, a, b, returns, since, fitted, var, last);
+    }
+    void reset_state() {
+        data = var = last = std::nan("");
+        returns.clear();
+        since = 0;
+        if (window > 0) {
+            w = a = b = 0.0;
+            fitted = false;
+        }
+    }
+};
+
+} // namespace tzu
+
+#endif // VOLATILITY_H
@@ -0,0 +1,108 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include <string>
+#include <thread>
+#include <vector>
+#include "alerts.h"
+
+using namespace tzu;
+
+namespace {
+
+Ohlcv bar_at(int64_t t, double close) {
+    return Ohlcv(t, close - 0.5, close + 1.0, close - 1.0, close, 100.0);
+}
+
+} // namespace
+
+TEST(AlertMonitor, ThresholdsTriggerWhenBreached) {
+    AlertMonitor monitor;
+    std::string error;
+    ASSERT_TRUE(monitor.add("high", "close > 10", error)) << error;
+    std::vector<Alert> alerts;
+    monitor.add_sink([&alerts](const Alert& a) { alerts.push_back(a); });
+    double closes[] = {9.0, 11.0, 12.0, 9.0, 13.0};
+    for (int t = 0; t < 5; t++) monitor.update("A", bar_at(t, closes[t]));
+    ASSERT_EQ(alerts.size(), 2u);
+    EXPECT_EQ(alerts[0].name, "high");
+    EXPECT_EQ(alerts[0].symbol, "A");
+    EXPECT_EQ(alerts[0].timestamp, 1);
+    EXPECT_DOUBLE_EQ(alerts[0].price, 11.0);
+    EXPECT_EQ(alerts[0].message, "close > 10");
+    EXPECT_EQ(alerts[1].timestamp, 4);
+    EXPECT_EQ(monitor.num_alerts(), 2u);
+}
+
+TEST(AlertMonitor, KeepsConditionsPerSymbol) {
+    AlertMonitor monitor;
+    std::string error;
+    ASSERT_TRUE(monitor.add("cross", "close crosses_above sma(3)", error));
+    std::vector<Alert> alerts;
+    monitor.add_sink([&alerts](const Alert& a) { alerts.push_back(a); });
+    double a[] = {10.0, 9.0, 8.0, 7.0, 12.0};
+    for (int t = 0; t < 5; t++) {
+        monitor.update("A", bar_at(t, a[t]));
+        monitor.update("B", bar_at(t, 10.0 - t));
+    }
+    ASSERT_EQ(alerts.size(), 1u);
+    EXPECT_EQ(alerts[0].symbol, "A");
+    EXPECT_EQ(alerts[0].timestamp, 4);
+    EXPECT_EQ(monitor.update("A", bar_at(4, 20.0)), 0u);
+}
+
+TEST(AlertMonitor, DetectorsTriggerEachTime) {
+    AlertMonitor monitor;
+    int count = 0;
+    monitor.add("every other", [count](const Ohlcv&, std::string& message)
+            mutable {
+        message = "bar " + std::to_string(++count);
+        return count % 2 == 0;
+    });
+    std::vector<Alert> alerts;
+    monitor.add_sink([&alerts](const Alert& a) { alerts.push_back(a); });
+    for (int t = 0; t < 4; t++) {
+        monitor.update("A", bar_at(t, 1.0));
+        monitor.update("B", bar_at(t, 1.0));
+    }
+    ASSERT_EQ(alerts.size(), 4u);
+    EXPECT_EQ(alerts[0].symbol, "A");
+    EXPECT_EQ(alerts[0].message, "bar 2");
+    EXPECT_EQ(alerts[1].symbol, "B");
+    EXPECT_EQ(alerts[1].message, "bar 2");
+    EXPECT_EQ(alerts[3].message, "bar 4");
+}
+
+TEST(AlertMonitor, ReportsInvalidRules) {
+    AlertMonitor monitor;
+    std::string error;
+    EXPECT_FALSE(monitor.add("bad", "close >", error));
+    EXPECT_FALSE(error.empty());
+    EXPECT_EQ(monitor.num_conditions(), 0u);
+}
+
+TEST(AlertSinks, StreamWebhookAndChannel) {
+    Alert alert;
+    alert.name = "say \"hi\"";
+    alert.symbol = "A";
+    alert.timestamp = 7;
+    alert.price = 1.5;
+    alert.message = "close > 1";
+    std::ostringstream os;
+    stream_sink(os)(alert);
+    EXPECT_EQ(os.str(), "alert:say \"hi\" symbol:A timestamp:7 price:1.5 "
+            "message:close > 1\n");
+    std::string url, body;
+    webhook_sink("http://hook", [&](const std::string& u,
+                const std::string& b) { url = u; body = b; })(alert);
+    EXPECT_EQ(url, "http://hook");
+    EXPECT_EQ(body, "{\"name\":\"say \\\"hi\\\"\",\"symbol\":\"A\","
+            "\"timestamp\":7,\"price\":1.5,\"message\":\"close > 1\"}");
+    Channel<Alert> channel;
+    AlertSink sink = channel_sink(channel);
+    std::thread producer([&] { sink(alert); channel.close(); });
+    Alert received;
+    ASSERT_TRUE(channel.pop(received));
+    producer.join();
+    EXPECT_EQ(received.timestamp, 7);
+    EXPECT_FALSE(channel.pop(received));
+}
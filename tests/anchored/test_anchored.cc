@@ -0,0 +1,111 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <vector>
+#include "anchored.h"
+
+using namespace tzu;
+
+namespace {
+
+// bars with typical price and close 100 + i, volume 1 + i
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 10; i++) {
+        double c = 100.0 + i;
+        bars.push_back(Ohlcv(i * 60, c, c + 1.0, c - 1.0, c, 1.0 + i));
+    }
+    return bars;
+}
+
+double vwap(const std::vector<Ohlcv>& bars, size_t from, size_t to) {
+    double pv = 0.0, v = 0.0;
+    for (size_t i = from; i <= to; i++) {
+        pv += bars[i].close * bars[i].volume;
+        v += bars[i].volume;
+    }
+    return pv / v;
+}
+
+} // namespace
+
+TEST(VWAP, AveragesTypicalPricesByVolume) {
+    VWAP vwap_all;
+    EXPECT_TRUE(std::isnan(vwap_all.update(Ohlcv(0, 10.0, 12.0, 8.0, 10.0, 0.0))));
+    vwap_all.update(Ohlcv(1, 10.0, 12.0, 9.0, 12.0, 2.0));
+    EXPECT_DOUBLE_EQ(vwap_all.update(Ohlcv(2, 10.0, 10.0, 7.0, 7.0, 1.0)),
+            (11.0 * 2.0 + 8.0) / 3.0);
+}
+
+TEST(CumulativeReturn, MeasuresChangeSinceFirstValue) {
+    CumulativeReturn ret;
+    EXPECT_DOUBLE_EQ(ret.update(50.0), 0.0);
+    EXPECT_NEAR(ret.update(55.0), 0.1, 1e-12);
+    EXPECT_NEAR(ret.update(45.0), -0.1, 1e-12);
+}
+
+TEST(Anchored, StartsAtFutureAnchors) {
+    std::vector<Ohlcv> bars = sample_bars();
+    Anchored<VWAP> avwap(VWAP(), 2);
+    EXPECT_TRUE(avwap.anchor(bars[3].timestamp));
+    for (size_t i = 0; i < 3; i++) EXPECT_TRUE(std::isnan(avwap.update(bars[i])));
+    for (size_t i = 3; i < 6; i++)
+        EXPECT_DOUBLE_EQ(avwap.update(bars[i]), vwap(bars, 3, i));
+    // a second anchor, started later, becomes the main output
+    EXPECT_TRUE(avwap.anchor(bars[7].timestamp));
+    avwap.update(bars[6]);
+    EXPECT_DOUBLE_EQ(avwap.get(), vwap(bars, 3, 6));
+    EXPECT_DOUBLE_EQ(avwap.update(bars[7]), vwap(bars, 7, 7));
+    double first = 0.0;
+    ASSERT_TRUE(avwap.value(bars[3].timestamp, first));
+    EXPECT_DOUBLE_EQ(first, vwap(bars, 3, 7));
+    EXPECT_EQ(avwap.anchors(), (std::vector<int64_t>{180, 420}));
+}
+
+TEST(Anchored, ReplaysPastAnchorsFromHistory) {
+    std::vector<Ohlcv> bars = sample_bars();
+    Anchored<VWAP> avwap(VWAP(), 2, 4);
+    for (size_t i = 0; i < 8; i++) avwap.update(bars[i]);
+    // bars 4 to 7 are kept
+    EXPECT_FALSE(avwap.anchor(bars[3].timestamp));
+    EXPECT_TRUE(avwap.anchors().empty());
+    ASSERT_TRUE(avwap.anchor(bars[5].timestamp));
+    EXPECT_DOUBLE_EQ(avwap.get(), vwap(bars, 5, 7));
+    EXPECT_DOUBLE_EQ(avwap.update(bars[8]), vwap(bars, 5, 8));
+}
+
+TEST(Anchored, ReplacesTheOldestAnchor) {
+    std::vector<Ohlcv> bars = sample_bars();
+    Anchored<CumulativeReturn> momentum(CumulativeReturn(), 2, 10);
+    for (const auto& bar : bars) momentum.update(bar);
+    ASSERT_TRUE(momentum.anchor(bars[2].timestamp));
+    ASSERT_TRUE(momentum.anchor(bars[0].timestamp));
+    // the main output is the latest anchor in time
+    EXPECT_DOUBLE_EQ(momentum.get(), 109.0 / 102.0 - 1.0);
+    ASSERT_TRUE(momentum.anchor(bars[5].timestamp));
+    EXPECT_EQ(momentum.anchors(), (std::vector<int64_t>{0, 300}));
+    EXPECT_DOUBLE_EQ(momentum.get(), 109.0 / 105.0 - 1.0);
+    EXPECT_TRUE(momentum.remove(bars[5].timestamp));
+    EXPECT_FALSE(momentum.remove(bars[5].timestamp));
+    EXPECT_NEAR(momentum.get(), 0.09, 1e-12);
+    double out = 0.0;
+    EXPECT_FALSE(momentum.value(bars[2].timestamp, out));
+}
+
+TEST(Anchored, StateIsRestored) {
+    std::vector<Ohlcv> bars = sample_bars();
+    Anchored<VWAP> avwap(VWAP(), 3, 5);
+    for (size_t i = 0; i < 5; i++) avwap.update(bars[i]);
+    avwap.anchor(bars[1].timestamp);
+    avwap.anchor(bars[8].timestamp);
+    std::stringstream ss;
+    ASSERT_TRUE(avwap.save_state(ss));
+    Anchored<VWAP> restored(VWAP(), 3, 5);
+    ASSERT_TRUE(restored.load_state(ss));
+    EXPECT_EQ(restored.anchors(), avwap.anchors());
+    for (size_t i = 5; i < bars.size(); i++)
+        EXPECT_DOUBLE_EQ(restored.update(bars[i]), avwap.update(bars[i]));
+    avwap.reset();
+    EXPECT_TRUE(avwap.anchors().empty());
+    EXPECT_TRUE(std::isnan(avwap.get()));
+}
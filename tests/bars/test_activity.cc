@@ -0,0 +1,44 @@
+#include <gtest/gtest.h>
+#include "bars.h"
+
+using namespace tzu;
+
+TEST(VolumeBars, CompletesAtThreshold) {
+    VolumeBars bars(10.0);
+    EXPECT_FALSE(bars.update(Tick(1, 100.0, 4.0, Side::BUY)));
+    EXPECT_FALSE(bars.update(Tick(2, 102.0, 4.0, Side::BUY)));
+    EXPECT_DOUBLE_EQ(bars.accumulated(), 8.0);
+    // the tick completing the bar is not split
+    ASSERT_TRUE(bars.update(Tick(3, 99.0, 5.0, Side::SELL)));
+    ASSERT_EQ(bars.completed().size(), 1u);
+    Ohlcv bar = bars.get();
+    EXPECT_EQ(bar.timestamp, 1);
+    EXPECT_DOUBLE_EQ(bar.open, 100.0);
+    EXPECT_DOUBLE_EQ(bar.high, 102.0);
+    EXPECT_DOUBLE_EQ(bar.low, 99.0);
+    EXPECT_DOUBLE_EQ(bar.close, 99.0);
+    EXPECT_DOUBLE_EQ(bar.volume, 13.0);
+    EXPECT_DOUBLE_EQ(bars.accumulated(), 0.0);
+    EXPECT_FALSE(bars.update(Tick(4, 98.0, 2.0, Side::SELL)));
+    EXPECT_TRUE(bars.completed().empty());
+    ASSERT_TRUE(bars.flush());
+    EXPECT_EQ(bars.get().timestamp, 4);
+    EXPECT_DOUBLE_EQ(bars.get().volume, 2.0);
+    EXPECT_FALSE(bars.flush());
+}
+
+TEST(DollarBars, CompletesAtValueTraded) {
+    DollarBars bars(1000.0);
+    EXPECT_FALSE(bars.update(Ohlcv(0, 10.0, 12.0, 9.0, 11.0, 50.0)));
+    // 550 + 20 * 25 = 1050
+    ASSERT_TRUE(bars.update(Ohlcv(1, 11.0, 21.0, 11.0, 20.0, 25.0)));
+    EXPECT_EQ(bars.get().timestamp, 0);
+    EXPECT_DOUBLE_EQ(bars.get().open, 10.0);
+    EXPECT_DOUBLE_EQ(bars.get().high, 21.0);
+    EXPECT_DOUBLE_EQ(bars.get().low, 9.0);
+    EXPECT_DOUBLE_EQ(bars.get().close, 20.0);
+    EXPECT_DOUBLE_EQ(bars.get().volume, 75.0);
+    // at a higher price, fewer units complete a bar
+    ASSERT_TRUE(bars.update(Tick(2, 100.0, 10.0, Side::NONE)));
+    EXPECT_DOUBLE_EQ(bars.get().volume, 10.0);
+}
@@ -0,0 +1,94 @@
+#include <gtest/gtest.h>
+#include <vector>
+#include "bars.h"
+
+using namespace tzu;
+
+TEST(GapFiller, FillsMissingIntervals) {
+    GapFiller flat(60, GapPolicy::FLAT_BAR);
+    ASSERT_TRUE(flat.update(Ohlcv(0, 10.0, 12.0, 9.0, 11.0, 5.0)));
+    ASSERT_EQ(flat.completed().size(), 1u);
+    ASSERT_TRUE(flat.update(Ohlcv(180, 11.0, 13.0, 10.0, 12.0, 3.0)));
+    const auto& bars = flat.completed();
+    ASSERT_EQ(bars.size(), 3u);
+    EXPECT_EQ(bars[0].timestamp, 60);
+    EXPECT_EQ(bars[1].timestamp, 120);
+    EXPECT_DOUBLE_EQ(bars[0].open, 11.0);
+    EXPECT_DOUBLE_EQ(bars[0].high, 11.0);
+    EXPECT_DOUBLE_EQ(bars[0].low, 11.0);
+    EXPECT_DOUBLE_EQ(bars[0].volume, 0.0);
+    EXPECT_EQ(bars[2].timestamp, 180);
+    EXPECT_DOUBLE_EQ(bars[2].volume, 3.0);
+    EXPECT_EQ(flat.get().timestamp, 180);
+    EXPECT_EQ(flat.filled(), 2u);
+
+    GapFiller forward(60, GapPolicy::FORWARD_FILL);
+    forward.update(Ohlcv(0, 10.0, 12.0, 9.0, 11.0, 5.0));
+    forward.update(Ohlcv(120, 11.0, 13.0, 10.0, 12.0, 3.0));
+    ASSERT_EQ(forward.completed().size(), 2u);
+    EXPECT_DOUBLE_EQ(forward.completed()[0].open, 10.0);
+    EXPECT_DOUBLE_EQ(forward.completed()[0].high, 12.0);
+    EXPECT_DOUBLE_EQ(forward.completed()[0].low, 9.0);
+    EXPECT_DOUBLE_EQ(forward.completed()[0].volume, 0.0);
+}
+
+TEST(GapFiller, SkipsLongGapsAndDropsLateBars) {
+    GapFiller filler(60, GapPolicy::FLAT_BAR, 2);
+    filler.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    filler.update(Ohlcv(180, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_EQ(filler.completed().size(), 3u);
+    filler.update(Ohlcv(600, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_EQ(filler.completed().size(), 1u);
+    EXPECT_FALSE(filler.update(Ohlcv(600, 2.0, 2.0, 2.0, 2.0, 1.0)));
+    EXPECT_TRUE(filler.completed().empty());
+    EXPECT_EQ(filler.dropped(), 1u);
+
+    GapFiller skip(60, GapPolicy::SKIP);
+    skip.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    skip.update(Ohlcv(600, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_EQ(skip.completed().size(), 1u);
+    EXPECT_EQ(skip.filled(), 0u);
+
+    GapFiller unlimited(1, GapPolicy::FLAT_BAR);
+    unlimited.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    unlimited.update(Ohlcv(MAX_GAP_FILL + 1, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_EQ(unlimited.filled(), MAX_GAP_FILL);
+    unlimited.update(Ohlcv(INT64_MAX / 2, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_EQ(unlimited.completed().size(), 1u);
+}
+
+TEST(GapFiller, PassesBarsThroughWithoutInterval) {
+    GapFiller filler(0);
+    filler.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    ASSERT_TRUE(filler.update(Ohlcv(300, 2.0, 2.0, 2.0, 2.0, 1.0)));
+    EXPECT_EQ(filler.completed().size(), 1u);
+    EXPECT_EQ(filler.filled(), 0u);
+    GapFiller negative(-60, GapPolicy::FLAT_BAR);
+    negative.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_TRUE(negative.update(Ohlcv(300, 2.0, 2.0, 2.0, 2.0, 1.0)));
+    EXPECT_EQ(negative.filled(), 0u);
+}
+
+TEST(Resampler, FillsEmptyPeriods) {
+    Resampler five(300, 0, GapPolicy::FLAT_BAR);
+    EXPECT_FALSE(five.update(Tick(10, 100.0, 1.0, Side::BUY)));
+    EXPECT_FALSE(five.update(Tick(200, 102.0, 1.0, Side::BUY)));
+    ASSERT_TRUE(five.update(Tick(1000, 105.0, 1.0, Side::SELL)));
+    const auto& bars = five.completed();
+    ASSERT_EQ(bars.size(), 3u);
+    EXPECT_EQ(bars[0].timestamp, 0);
+    EXPECT_DOUBLE_EQ(bars[0].volume, 2.0);
+    EXPECT_EQ(bars[1].timestamp, 300);
+    EXPECT_EQ(bars[2].timestamp, 600);
+    EXPECT_DOUBLE_EQ(bars[2].close, 102.0);
+    EXPECT_DOUBLE_EQ(bars[2].volume, 0.0);
+    EXPECT_EQ(five.get().timestamp, 600);
+    ASSERT_TRUE(five.flush());
+    ASSERT_EQ(five.completed().size(), 1u);
+    EXPECT_EQ(five.get().timestamp, 900);
+
+    Resampler minute(60, 0, GapPolicy::FORWARD_FILL);
+    minute.update(Tick(0, 1.0, 1.0, Side::BUY));
+    ASSERT_TRUE(minute.update(Tick(INT64_MAX / 2, 1.0, 1.0, Side::BUY)));
+    EXPECT_EQ(minute.completed().size(), 1u);
+}
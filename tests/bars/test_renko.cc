@@ -0,0 +1,71 @@
+#include <gtest/gtest.h>
+#include <vector>
+#include "bars.h"
+
+using namespace tzu;
+
+TEST(Renko, BuildsBricksAndReversals) {
+    Renko renko(1.0);
+    std::vector<Ohlcv> out;
+    double prices[] = {10.0, 10.5, 11.2, 13.1, 12.5, 10.9, 9.8};
+    for (int i = 0; i < 7; ++i)
+        if (renko.update(Tick(i, prices[i], 1.0, Side::NONE)))
+            out.insert(out.end(), renko.completed().begin(),
+                    renko.completed().end());
+    // up to 11, 12 and 13; the reversal needs a move down to 12 - 1
+    ASSERT_EQ(out.size(), 5u);
+    EXPECT_DOUBLE_EQ(out[0].open, 10.0);
+    EXPECT_DOUBLE_EQ(out[0].close, 11.0);
+    EXPECT_DOUBLE_EQ(out[0].volume, 3.0);
+    EXPECT_EQ(out[2].timestamp, 3);
+    EXPECT_DOUBLE_EQ(out[2].close, 13.0);
+    EXPECT_DOUBLE_EQ(out[3].open, 12.0);
+    EXPECT_DOUBLE_EQ(out[3].close, 11.0);
+    EXPECT_DOUBLE_EQ(out[3].low, 11.0);
+    EXPECT_DOUBLE_EQ(out[4].close, 10.0);
+    EXPECT_EQ(renko.get().timestamp, 6);
+    EXPECT_FALSE(renko.flush());
+}
+
+TEST(Renko, AtrSizingWaitsForAtr) {
+    Renko renko(1.0, 3);
+    for (int i = 0; i < 2; ++i)
+        EXPECT_FALSE(renko.update(Ohlcv(i, 10.0, 30.0, 5.0, 10.0, 1.0)));
+    // ATR of (25 + 25 + 1) / 3 = 17 once available
+    renko.update(Ohlcv(2, 10.0, 10.5, 9.5, 10.0, 1.0));
+    EXPECT_FALSE(renko.update(Ohlcv(3, 10.0, 20.0, 10.0, 20.0, 1.0)));
+}
+
+TEST(Renko, AtrSizingUsesPreviousBars) {
+    Renko renko(1.0, 1);
+    renko.update(Ohlcv(0, 10.0, 11.0, 9.0, 10.0, 1.0));
+    EXPECT_FALSE(renko.update(Ohlcv(1, 10.0, 10.5, 9.5, 10.0, 1.0)));
+    // sized by the true range of 1 of the previous bar, not by this one
+    ASSERT_TRUE(renko.update(Ohlcv(2, 10.0, 30.0, 10.0, 30.0, 1.0)));
+    EXPECT_EQ(renko.completed().size(), 20u);
+    RangeBars bars(1.0, 1);
+    bars.update(Ohlcv(0, 10.0, 11.0, 9.0, 10.0, 1.0));
+    EXPECT_FALSE(bars.update(Ohlcv(1, 10.0, 10.5, 9.5, 10.0, 1.0)));
+    ASSERT_TRUE(bars.update(Ohlcv(2, 10.0, 30.0, 10.0, 30.0, 1.0)));
+    EXPECT_EQ(bars.completed().size(), 20u);
+}
+
+TEST(RangeBars, SplitsPriceMovesIntoRanges) {
+    RangeBars bars(2.0);
+    EXPECT_FALSE(bars.update(Tick(0, 10.0, 1.0, Side::NONE)));
+    EXPECT_FALSE(bars.update(Tick(1, 11.5, 1.0, Side::NONE)));
+    // jump to 15.5 completes 10-12 and 12-14
+    ASSERT_TRUE(bars.update(Tick(2, 15.5, 1.0, Side::NONE)));
+    ASSERT_EQ(bars.completed().size(), 2u);
+    EXPECT_DOUBLE_EQ(bars.completed()[0].open, 10.0);
+    EXPECT_DOUBLE_EQ(bars.completed()[0].high, 12.0);
+    EXPECT_DOUBLE_EQ(bars.completed()[0].close, 12.0);
+    EXPECT_DOUBLE_EQ(bars.completed()[0].volume, 3.0);
+    EXPECT_DOUBLE_EQ(bars.completed()[1].low, 12.0);
+    EXPECT_DOUBLE_EQ(bars.completed()[1].close, 14.0);
+    EXPECT_DOUBLE_EQ(bars.partial().open, 14.0);
+    EXPECT_DOUBLE_EQ(bars.partial().close, 15.5);
+    ASSERT_TRUE(bars.flush());
+    EXPECT_DOUBLE_EQ(bars.get().high, 15.5);
+    EXPECT_FALSE(bars.flush());
+}
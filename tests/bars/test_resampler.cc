@@ -0,0 +1,67 @@
+#include <gtest/gtest.h>
+#include <fstream>
+#include <vector>
+#include "bars.h"
+#include "streamers.h"
+
+using namespace tzu;
+
+TEST(Resampler, AggregatesBarsIntoLongerTimeframe) {
+    Resampler five(300);
+    std::vector<Ohlcv> out;
+    for (int i = 0; i < 12; ++i) {
+        double p = 100.0 + i;
+        if (five.update(Ohlcv(i * 60, p, p + 2.0, p - 1.0, p + 0.5, 1.0)))
+            out.push_back(five.get());
+    }
+    ASSERT_TRUE(five.flush());
+    out.push_back(five.get());
+    ASSERT_EQ(out.size(), 3u);
+    EXPECT_EQ(out[0].timestamp, 0);
+    EXPECT_DOUBLE_EQ(out[0].open, 100.0);
+    EXPECT_DOUBLE_EQ(out[0].high, 106.0);
+    EXPECT_DOUBLE_EQ(out[0].low, 99.0);
+    EXPECT_DOUBLE_EQ(out[0].close, 104.5);
+    EXPECT_DOUBLE_EQ(out[0].volume, 5.0);
+    EXPECT_EQ(out[2].timestamp, 600);
+    EXPECT_DOUBLE_EQ(out[2].volume, 2.0);
+    EXPECT_FALSE(five.flush());
+}
+
+TEST(Resampler, AggregatesTicksAndDropsLateData) {
+    Resampler minute(60, 30);
+    EXPECT_FALSE(minute.update(Tick(40, 10.0, 1.0, Side::BUY)));
+    EXPECT_FALSE(minute.update(Tick(80, 12.0, 2.0, Side::SELL)));
+    EXPECT_TRUE(minute.update(Tick(95, 11.0, 1.0, Side::BUY)));
+    EXPECT_EQ(minute.get().timestamp, 30);
+    EXPECT_DOUBLE_EQ(minute.get().high, 12.0);
+    EXPECT_DOUBLE_EQ(minute.get().close, 12.0);
+    EXPECT_FALSE(minute.update(Tick(85, 9.0, 1.0, Side::BUY)));
+    EXPECT_EQ(minute.dropped(), 1u);
+    EXPECT_DOUBLE_EQ(minute.partial().open, 11.0);
+}
+
+TEST(Resampler, ClampsNonPositiveTimeframe) {
+    Resampler none(0);
+    EXPECT_FALSE(none.update(Ohlcv(10, 1.0, 2.0, 0.5, 1.5, 1.0)));
+    ASSERT_TRUE(none.update(Ohlcv(11, 2.0, 3.0, 1.5, 2.5, 1.0)));
+    EXPECT_EQ(none.get().timestamp, 10);
+    Resampler negative(-60);
+    EXPECT_FALSE(negative.update(Ohlcv(10, 1.0, 2.0, 0.5, 1.5, 1.0)));
+    EXPECT_TRUE(negative.update(Ohlcv(11, 2.0, 3.0, 1.5, 2.5, 1.0)));
+}
+
+TEST(Resampler, DailyToWeeklyKeepsVolume) {
+    std::ifstream file("../data/btcusd.csv");
+    ASSERT_TRUE(file.is_open());
+    Csv<Ohlcv> csv(file);
+    Resampler weekly(7 * 86400);
+    double daily_volume = 0.0;
+    double weekly_volume = 0.0;
+    for (const auto& bar : csv) {
+        daily_volume += bar.volume;
+        if (weekly.update(bar)) weekly_volume += weekly.get().volume;
+    }
+    if (weekly.flush()) weekly_volume += weekly.get().volume;
+    EXPECT_NEAR(weekly_volume, daily_volume, daily_volume * 1e-12);
+}
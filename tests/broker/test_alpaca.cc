@@ -0,0 +1,134 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <string>
+#include <vector>
+#include "alpaca.h"
+
+using namespace tzu;
+
+namespace {
+
+const char ORDER[] = R"({"id":"61e69015","client_order_id":"tzu-1",)"
+    R"("submitted_at":"2024-01-02T15:30:00.123456Z","symbol":"AAPL",)"
+    R"("qty":"10","filled_qty":"4","filled_avg_price":"150.5",)"
+    R"("type":"limit","side":"sell","time_in_force":"day",)"
+    R"("limit_price":"151","stop_price":null,"status":"partially_filled",)"
+    R"("legs":null})";
+
+// answers with canned responses and records the requests
+struct FakeHttp {
+    std::vector<HttpRequest>* requests;
+    std::vector<HttpResponse>* responses;
+    bool operator()(const HttpRequest& req, HttpResponse& res) {
+        requests->push_back(req);
+        if (responses->empty()) return false;
+        res = responses->front();
+        responses->erase(responses->begin());
+        return true;
+    }
+};
+
+HttpResponse ok(const std::string& body) {
+    HttpResponse res;
+    res.status = 200;
+    res.body = body;
+    return res;
+}
+
+} // namespace
+
+TEST(AlpacaBroker, SubmitsOrders) {
+    std::vector<HttpRequest> requests;
+    std::vector<HttpResponse> responses = {ok(ORDER)};
+    AlpacaBroker alpaca("https://paper-api.alpaca.markets", "key", "secret",
+            FakeHttp{&requests, &responses});
+    Order order = Order::limit("AAPL", -10.0, 151.0);
+    order.id = "tzu-1";
+    EXPECT_EQ(alpaca.submit(0, order), "61e69015");
+    ASSERT_EQ(requests.size(), 1u);
+    EXPECT_EQ(requests[0].method, "POST");
+    EXPECT_EQ(requests[0].url, "https://paper-api.alpaca.markets/v2/orders");
+    EXPECT_EQ(requests[0].headers[0].first, "APCA-API-KEY-ID");
+    EXPECT_EQ(requests[0].headers[0].second, "key");
+    EXPECT_EQ(requests[0].body, R"({"symbol":"AAPL","qty":"10",)"
+            R"("side":"sell","type":"limit","time_in_force":"day",)"
+            R"("limit_price":"151","client_order_id":"tzu-1"})");
+}
+
+TEST(AlpacaBroker, EscapesOrderStrings) {
+    std::vector<HttpRequest> requests;
+    std::vector<HttpResponse> responses = {ok(ORDER)};
+    AlpacaBroker alpaca("https://api", "key", "secret",
+            FakeHttp{&requests, &responses});
+    Order order = Order::market("AAPL\",\"qty\":\"1000", 1.0);
+    order.id = "a\\b";
+    alpaca.submit(0, order);
+    ASSERT_EQ(requests.size(), 1u);
+    EXPECT_EQ(requests[0].body,
+            R"({"symbol":"AAPL\",\"qty\":\"1000","qty":"1",)"
+            R"("side":"buy","type":"market","time_in_force":"day",)"
+            R"("client_order_id":"a\\b"})");
+}
+
+TEST(AlpacaBroker, ParsesOrders) {
+    std::vector<HttpRequest> requests;
+    std::vector<HttpResponse> responses = {ok(ORDER),
+        ok(std::string("[") + ORDER + "," + ORDER + "]")};
+    AlpacaBroker alpaca("https://api", "key", "secret",
+            FakeHttp{&requests, &responses});
+    Order order = alpaca.order("61e69015");
+    EXPECT_EQ(requests[0].url, "https://api/v2/orders/61e69015");
+    EXPECT_EQ(order.id, "61e69015");
+    EXPECT_EQ(order.symbol, "AAPL");
+    EXPECT_EQ(order.timestamp, 1704209400);
+    EXPECT_EQ(order.type, OrderType::LIMIT);
+    EXPECT_EQ(order.status, OrderStatus::PARTIAL);
+    EXPECT_DOUBLE_EQ(order.quantity, -10.0);
+    EXPECT_DOUBLE_EQ(order.filled, -4.0);
+    EXPECT_DOUBLE_EQ(order.avg_fill_price, 150.5);
+    EXPECT_DOUBLE_EQ(order.limit_price, 151.0);
+    EXPECT_TRUE(std::isnan(order.stop_price));
+    EXPECT_EQ(alpaca.open_orders().size(), 2u);
+    EXPECT_EQ(requests[1].url, "https://api/v2/orders?status=open");
+}
+
+TEST(AlpacaBroker, ReportsTheAccountState) {
+    std::vector<HttpRequest> requests;
+    std::vector<HttpResponse> responses = {
+        ok(R"({"id":"acc","cash":"2500.75","equity":"5000"})"),
+        ok(R"([{"asset_id":"a1","symbol":"AAPL","qty":"5","side":"long"},)"
+            R"({"asset_id":"a2","symbol":"TSLA","qty":"3","side":"short"}])"),
+        ok(std::string("[") + ORDER + "]")};
+    AlpacaBroker alpaca("https://api", "key", "secret",
+            FakeHttp{&requests, &responses});
+    AccountState state = alpaca.state();
+    EXPECT_DOUBLE_EQ(state.cash, 2500.75);
+    EXPECT_DOUBLE_EQ(state.positions["AAPL"], 5.0);
+    EXPECT_DOUBLE_EQ(state.positions["TSLA"], -3.0);
+    ASSERT_EQ(state.orders.count("61e69015"), 1u);
+    EXPECT_DOUBLE_EQ(state.orders["61e69015"].quantity, -6.0);
+    EXPECT_DOUBLE_EQ(state.orders["61e69015"].price, 151.0);
+    EXPECT_TRUE(alpaca.last_error().empty());
+}
+
+TEST(AlpacaBroker, CancelsReplacesAndReportsErrors) {
+    std::vector<HttpRequest> requests;
+    HttpResponse rejected;
+    rejected.status = 422;
+    rejected.body = R"({"code":42210000,"message":"order is not open"})";
+    std::vector<HttpResponse> responses = {ok(""), rejected, ok(ORDER)};
+    AlpacaBroker alpaca("https://api", "key", "secret",
+            FakeHttp{&requests, &responses});
+    EXPECT_TRUE(alpaca.cancel("abc"));
+    EXPECT_EQ(requests[0].method, "DELETE");
+    EXPECT_EQ(requests[0].url, "https://api/v2/orders/abc");
+    EXPECT_FALSE(alpaca.cancel("abc"));
+    EXPECT_EQ(alpaca.last_error(),
+            "DELETE /v2/orders/abc: HTTP 422 order is not open");
+    EXPECT_EQ(alpaca.replace(0, "abc", 10.0, 151.0), "61e69015");
+    EXPECT_EQ(requests[2].method, "PATCH");
+    EXPECT_EQ(requests[2].body, R"({"qty":"10","limit_price":"151"})");
+    EXPECT_TRUE(alpaca.submit(0, Order::market("AAPL", 1.0)).empty());
+    EXPECT_EQ(alpaca.last_error(), "POST /v2/orders: no response");
+    EXPECT_EQ(alpaca.order("x").status, OrderStatus::REJECTED);
+}
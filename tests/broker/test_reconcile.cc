@@ -0,0 +1,50 @@
+#include <gtest/gtest.h>
+#include "broker.h"
+
+using namespace tzu;
+
+static AccountState account(double cash, double btc) {
+    AccountState state;
+    state.cash = cash;
+    state.positions["BTC"] = btc;
+    return state;
+}
+
+TEST(Reconcile, MatchingStatesHaveNoDiscrepancies) {
+    AccountState a = account(1000.0, 1.5);
+    a.orders["1"] = OpenOrder{"1", "BTC", 0.5, 100.0};
+    EXPECT_TRUE(reconcile(0, a, a).empty());
+}
+
+TEST(Reconcile, ReportsMissedFillsAndUnknownOrders) {
+    AccountState internal = account(1000.0, 1.0);
+    internal.orders["1"] = OpenOrder{"1", "BTC", 0.5, 100.0};
+    AccountState broker = account(950.0, 1.5);
+    broker.positions["ETH"] = 2.0;
+    broker.orders["2"] = OpenOrder{"2", "ETH", -1.0, 10.0};
+    std::vector<Discrepancy> found = reconcile(5, internal, broker);
+    ASSERT_EQ(found.size(), 5u);
+    EXPECT_EQ(found[0].kind, DiscrepancyKind::CASH);
+    EXPECT_EQ(found[1].kind, DiscrepancyKind::POSITION);
+    EXPECT_EQ(found[1].key, "BTC");
+    EXPECT_DOUBLE_EQ(found[1].broker, 1.5);
+    EXPECT_EQ(found[2].key, "ETH");
+    EXPECT_EQ(found[3].kind, DiscrepancyKind::MISSING_ORDER);
+    EXPECT_EQ(found[4].kind, DiscrepancyKind::UNKNOWN_ORDER);
+    EXPECT_EQ(found[4].key, "2");
+}
+
+TEST(Reconciler, RunsPeriodicallyAndAdoptsBrokerState) {
+    int calls = 0;
+    auto fetch = [&calls]() { calls++; return account(900.0, 2.0); };
+    auto task = make_reconciler(fetch, 60, ReconcilePolicy::ADOPT_BROKER);
+    AccountState internal = account(1000.0, 1.0);
+    EXPECT_EQ(task.update(0, internal), 2u);
+    EXPECT_DOUBLE_EQ(internal.cash, 900.0);
+    EXPECT_DOUBLE_EQ(internal.positions["BTC"], 2.0);
+    EXPECT_EQ(task.update(30, internal), 0u);
+    EXPECT_EQ(calls, 1);
+    EXPECT_EQ(task.update(60, internal), 0u);
+    EXPECT_EQ(calls, 2);
+    EXPECT_EQ(task.get_events().size(), 2u);
+}
@@ -0,0 +1,109 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include "bars.h"
+#include "calendar.h"
+
+using namespace tzu;
+
+namespace {
+
+Calendar nyse_2024() {
+    return Calendar::nyse()
+        .add_holiday(2024, 7, 4)
+        .add_early_close(2024, 7, 3, 13 * 3600);
+}
+
+} // namespace
+
+TEST(Calendar, DaylightSavingTime) {
+    Calendar nyse = Calendar::nyse();
+    EXPECT_EQ(nyse.utc_offset(to_unix(2024, 1, 15)), -18000);
+    EXPECT_EQ(nyse.utc_offset(to_unix(2024, 7, 1)), -14400);
+    // 2:00 local on the second Sunday of March, first Sunday of November
+    EXPECT_EQ(nyse.utc_offset(to_unix(2024, 3, 10, 6, 59, 59)), -18000);
+    EXPECT_EQ(nyse.utc_offset(to_unix(2024, 3, 10, 7)), -14400);
+    EXPECT_EQ(nyse.utc_offset(to_unix(2024, 11, 3, 5, 59, 59)), -14400);
+    EXPECT_EQ(nyse.utc_offset(to_unix(2024, 11, 3, 6)), -18000);
+    // 1:00 UTC on the last Sundays of March and October
+    Calendar xetra(3600, DstRule::EU);
+    EXPECT_EQ(xetra.utc_offset(to_unix(2024, 3, 31, 0, 59, 59)), 3600);
+    EXPECT_EQ(xetra.utc_offset(to_unix(2024, 3, 31, 1)), 7200);
+    EXPECT_EQ(xetra.utc_offset(to_unix(2024, 10, 27, 1)), 3600);
+}
+
+TEST(Calendar, Sessions) {
+    Calendar nyse = nyse_2024();
+    int64_t open, close;
+    ASSERT_TRUE(nyse.session(days_from_civil(2024, 7, 1), open, close));
+    EXPECT_EQ(open, to_unix(2024, 7, 1, 13, 30));
+    EXPECT_EQ(close, to_unix(2024, 7, 1, 20));
+    ASSERT_TRUE(nyse.session(days_from_civil(2024, 1, 16), open, close));
+    EXPECT_EQ(open, to_unix(2024, 1, 16, 14, 30));
+    ASSERT_TRUE(nyse.session(days_from_civil(2024, 7, 1), open, close, true));
+    EXPECT_EQ(open, to_unix(2024, 7, 1, 8));
+    EXPECT_EQ(close, to_unix(2024, 7, 2));
+    EXPECT_FALSE(nyse.session(days_from_civil(2024, 7, 4), open, close));
+    EXPECT_FALSE(nyse.session(days_from_civil(2024, 7, 6), open, close));
+    ASSERT_TRUE(nyse.session(days_from_civil(2024, 7, 3), open, close));
+    EXPECT_EQ(close, to_unix(2024, 7, 3, 17));
+    ASSERT_TRUE(nyse.session(days_from_civil(2024, 7, 3), open, close, true));
+    EXPECT_EQ(close, to_unix(2024, 7, 4));
+    EXPECT_FALSE(nyse.is_open(to_unix(2024, 7, 1, 13, 29, 59)));
+    EXPECT_TRUE(nyse.is_open(to_unix(2024, 7, 1, 13, 30)));
+    EXPECT_FALSE(nyse.is_open(to_unix(2024, 7, 1, 20)));
+    EXPECT_TRUE(nyse.is_open(to_unix(2024, 7, 1, 20), true));
+    EXPECT_TRUE(Calendar().is_open(to_unix(2024, 7, 6, 12)));
+}
+
+TEST(Calendar, OvernightSessions) {
+    // 17:00 to 16:00 Chicago time, without DST for the test
+    Calendar cme = Calendar(-6 * 3600).set_hours(17 * 3600, 16 * 3600);
+    int64_t sunday_evening = to_unix(2024, 7, 7, 23, 30);
+    EXPECT_EQ(cme.trading_day(sunday_evening), days_from_civil(2024, 7, 8));
+    int64_t open, close;
+    ASSERT_TRUE(cme.session(days_from_civil(2024, 7, 8), open, close));
+    EXPECT_EQ(open, to_unix(2024, 7, 7, 23));
+    EXPECT_EQ(close, to_unix(2024, 7, 8, 22));
+    EXPECT_FALSE(cme.is_open(to_unix(2024, 7, 8, 22, 30)));
+}
+
+TEST(SessionBars, AlignsToSessions) {
+    SessionBars hourly(nyse_2024(), 3600);
+    // pre market
+    EXPECT_FALSE(hourly.update(Ohlcv(to_unix(2024, 7, 1, 12), 9, 9, 9, 9, 1)));
+    EXPECT_EQ(hourly.dropped(), 1u);
+    EXPECT_FALSE(hourly.update(Ohlcv(to_unix(2024, 7, 1, 13, 30),
+                    10, 11, 9, 10, 1)));
+    EXPECT_FALSE(hourly.update(Ohlcv(to_unix(2024, 7, 1, 14, 29),
+                    10, 12, 10, 11, 2)));
+    ASSERT_TRUE(hourly.update(Ohlcv(to_unix(2024, 7, 1, 14, 30),
+                    11, 11, 11, 11, 1)));
+    EXPECT_EQ(hourly.get().timestamp, to_unix(2024, 7, 1, 13, 30));
+    EXPECT_DOUBLE_EQ(hourly.get().high, 12.0);
+    EXPECT_DOUBLE_EQ(hourly.get().close, 11.0);
+    EXPECT_DOUBLE_EQ(hourly.get().volume, 3.0);
+
+    SessionBars daily(nyse_2024());
+    EXPECT_FALSE(daily.update(Ohlcv(to_unix(2024, 7, 1, 13, 30), 10, 11, 9, 10, 1)));
+    EXPECT_FALSE(daily.update(Ohlcv(to_unix(2024, 7, 1, 19, 59), 10, 13, 10, 12, 1)));
+    // the holiday is skipped
+    EXPECT_FALSE(daily.update(Ohlcv(to_unix(2024, 7, 4, 15), 1, 1, 1, 1, 1)));
+    ASSERT_TRUE(daily.update(Ohlcv(to_unix(2024, 7, 5, 13, 30), 12, 12, 12, 12, 1)));
+    EXPECT_EQ(daily.get().timestamp, to_unix(2024, 7, 1, 13, 30));
+    EXPECT_DOUBLE_EQ(daily.get().high, 13.0);
+    EXPECT_DOUBLE_EQ(daily.get().close, 12.0);
+    ASSERT_TRUE(daily.flush());
+    EXPECT_EQ(daily.get().timestamp, to_unix(2024, 7, 5, 13, 30));
+}
+
+TEST(SessionVWAP, RestartsEachSession) {
+    SessionVWAP vwap(Calendar::nyse());
+    EXPECT_DOUBLE_EQ(vwap.update(Ohlcv(to_unix(2024, 7, 1, 13, 30),
+                    10, 12, 9, 9, 1)), 10.0);
+    EXPECT_DOUBLE_EQ(vwap.update(Ohlcv(to_unix(2024, 7, 1, 14),
+                    10, 13, 13, 13, 3)), 12.25);
+    EXPECT_DOUBLE_EQ(vwap.update(Ohlcv(to_unix(2024, 7, 2, 13, 30),
+                    20, 20, 20, 20, 1)), 20.0);
+    EXPECT_TRUE(std::isnan(vwap.update(Ohlcv(to_unix(2024, 7, 3, 13, 30),
+                        20, 20, 20, 20, 0))));
+}
@@ -0,0 +1,57 @@
+#include <gtest/gtest.h>
+#include <chrono>
+#include <cstdint>
+#include "datetime.h"
+#include "defs.h"
+
+using namespace tzu;
+
+TEST(Datetime, FormatsTimes) {
+    int64_t t = to_unix(2024, 3, 5, 7, 8, 9);
+    EXPECT_EQ(format_time(t, "%Y-%m-%d %H:%M:%S"), "2024-03-05 07:08:09");
+    EXPECT_EQ(format_time(t, "%Y%m%d"), "20240305");
+    EXPECT_EQ(format_time(t, "%H:%M", -5 * 3600), "02:08");
+    EXPECT_EQ(format_time(-1, "%Y-%m-%d %H:%M:%S %s"),
+            "1969-12-31 23:59:59 -1");
+    EXPECT_EQ(format_time(t, "100%% %q"), "100% %q");
+    int64_t parsed;
+    ASSERT_TRUE(parse_time(format_time(t, "%Y-%m-%dT%H:%M:%S").c_str(),
+                "%Y-%m-%dT%H:%M:%S", parsed));
+    EXPECT_EQ(parsed, t);
+}
+
+TEST(Datetime, Timeframes) {
+    int64_t tf = 0;
+    EXPECT_TRUE(parse_timeframe("30s", tf));
+    EXPECT_EQ(tf, 30);
+    EXPECT_TRUE(parse_timeframe("5m", tf));
+    EXPECT_EQ(tf, 300);
+    EXPECT_TRUE(parse_timeframe("4h", tf));
+    EXPECT_EQ(tf, 14400);
+    EXPECT_TRUE(parse_timeframe("1w", tf));
+    EXPECT_EQ(tf, 604800);
+    EXPECT_TRUE(parse_timeframe("60", tf));
+    EXPECT_EQ(tf, 60);
+    EXPECT_FALSE(parse_timeframe("0m", tf));
+    EXPECT_FALSE(parse_timeframe("1y", tf));
+    EXPECT_FALSE(parse_timeframe("1hh", tf));
+    EXPECT_FALSE(parse_timeframe("h", tf));
+    EXPECT_EQ(bucket_start(to_unix(2024, 3, 5, 7, 8), 3600),
+            to_unix(2024, 3, 5, 7));
+    EXPECT_EQ(bucket_start(-1, 60), -60);
+    EXPECT_EQ(bucket_start(100, 60, 30), 90);
+}
+
+TEST(Datetime, ChronoTimePoints) {
+    using namespace std::chrono;
+    int64_t t = to_unix(2024, 3, 5);
+    system_clock::time_point tp = to_time_point(t);
+    EXPECT_EQ(duration_cast<seconds>(tp.time_since_epoch()).count(), t);
+    EXPECT_EQ(to_timestamp(tp), t);
+    EXPECT_EQ(to_timestamp<milliseconds>(tp), t * 1000);
+    EXPECT_EQ(to_time_point<milliseconds>(t * 1000), tp);
+    EXPECT_EQ(to_timestamp(system_clock::time_point(milliseconds(-1500))), -2);
+    Ohlcv bar(t * 1000, 1.0, 1.0, 1.0, 1.0, 1.0);
+    EXPECT_EQ(time_of<milliseconds>(bar), tp);
+    EXPECT_EQ(time_of(Tick(t, 1.0, 1.0, Side::BUY)), tp);
+}
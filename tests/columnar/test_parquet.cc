@@ -0,0 +1,142 @@
+#ifdef TZU_WITH_PARQUET
+
+#include <gtest/gtest.h>
+#include <cmath>
+#include <cstdio>
+#include <vector>
+#include "columnar.h"
+
+using namespace tzu;
+
+TEST(Parquet, RoundTripsBarsAcrossRowGroups) {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 250; i++)
+        bars.push_back(Ohlcv(60 * i, 100.0 + i, 101.0 + i, 99.0 + i,
+                    100.5 + i, 10.0 * i));
+    const char* path = "test_bars.parquet";
+    ASSERT_TRUE(write_parquet(path, bars, ColumnLayout(), 100));
+    ParquetBars file(path);
+    ASSERT_TRUE(file.ok());
+    std::vector<Ohlcv> read;
+    for (const auto& bar : file) read.push_back(bar);
+    std::remove(path);
+    ASSERT_EQ(read.size(), bars.size());
+    EXPECT_EQ(read[249].timestamp, 60 * 249);
+    EXPECT_DOUBLE_EQ(read[150].close, 250.5);
+    EXPECT_DOUBLE_EQ(read[150].volume, 1500.0);
+}
+
+TEST(Parquet, ReadsCustomColumnsWithoutVolume) {
+    ColumnLayout layout;
+    layout.timestamp = "time";
+    layout.volume = "";
+    const char* path = "test_layout.parquet";
+    ASSERT_TRUE(write_parquet(path, {Ohlcv(1, 1.0, 2.0, 0.5, 1.5, 7.0)}, layout));
+    ParquetBars file(path, layout);
+    ASSERT_TRUE(file.ok());
+    size_t n = 0;
+    for (const auto& bar : file) {
+        EXPECT_DOUBLE_EQ(bar.close, 1.5);
+        EXPECT_DOUBLE_EQ(bar.volume, 0.0);
+        n++;
+    }
+    EXPECT_EQ(n, 1u);
+    // the default layout doesn't match the columns: no bars
+    ParquetBars other(path);
+    EXPECT_FALSE(other.ok());
+    EXPECT_TRUE(other.begin() == other.end());
+    std::remove(path);
+    EXPECT_FALSE(ParquetBars("test_missing.parquet").ok());
+}
+
+TEST(Parquet, ConvertsTimestampUnits) {
+    arrow::TimestampBuilder timestamp(arrow::timestamp(arrow::TimeUnit::MILLI),
+            arrow::default_memory_pool());
+    arrow::DoubleBuilder price;
+    ASSERT_TRUE(timestamp.Append(60000).ok());
+    ASSERT_TRUE(price.Append(1.5).ok());
+    std::vector<std::shared_ptr<arrow::Array>> columns(2);
+    ASSERT_TRUE(timestamp.Finish(&columns[0]).ok());
+    ASSERT_TRUE(price.Finish(&columns[1]).ok());
+    std::vector<std::shared_ptr<arrow::Field>> fields = {
+        arrow::field("timestamp", arrow::timestamp(arrow::TimeUnit::MILLI)),
+        arrow::field("price", arrow::float64())
+    };
+    std::shared_ptr<arrow::Table> table = arrow::Table::Make(
+            arrow::schema(fields), columns);
+    const char* path = "test_units.parquet";
+    auto out = arrow::io::FileOutputStream::Open(path);
+    ASSERT_TRUE(out.ok());
+    ASSERT_TRUE(parquet::arrow::WriteTable(*table,
+                arrow::default_memory_pool(), *out, 100).ok());
+    ASSERT_TRUE((*out)->Close().ok());
+    ColumnLayout layout;
+    layout.open = layout.high = layout.low = layout.close = "price";
+    layout.volume = "";
+    std::vector<int64_t> seen;
+    for (int64_t scale : {1, 1000, 1000000}) {
+        layout.time_scale = scale;
+        ParquetBars file(path, layout);
+        for (const auto& bar : file) seen.push_back(bar.timestamp);
+    }
+    // a scale that isn't a multiple of the unit is rejected
+    layout.time_scale = 7;
+    ParquetBars odd(path, layout);
+    EXPECT_FALSE(odd.ok());
+    EXPECT_TRUE(odd.begin() == odd.end());
+    std::remove(path);
+    EXPECT_EQ(seen, (std::vector<int64_t>{60, 60000, 60000000}));
+}
+
+TEST(Parquet, RejectsMistypedColumnsAndReadsNulls) {
+    arrow::Int64Builder timestamp;
+    arrow::DoubleBuilder price;
+    arrow::Int64Builder volume;
+    ASSERT_TRUE(timestamp.Append(60).ok());
+    ASSERT_TRUE(timestamp.AppendNull().ok());
+    ASSERT_TRUE(timestamp.Append(180).ok());
+    ASSERT_TRUE(price.Append(1.5).ok());
+    ASSERT_TRUE(price.Append(2.5).ok());
+    ASSERT_TRUE(price.AppendNull().ok());
+    for (int i = 0; i < 3; i++) ASSERT_TRUE(volume.Append(10).ok());
+    std::vector<std::shared_ptr<arrow::Array>> columns(3);
+    ASSERT_TRUE(timestamp.Finish(&columns[0]).ok());
+    ASSERT_TRUE(price.Finish(&columns[1]).ok());
+    ASSERT_TRUE(volume.Finish(&columns[2]).ok());
+    std::vector<std::shared_ptr<arrow::Field>> fields = {
+        arrow::field("timestamp", arrow::int64()),
+        arrow::field("price", arrow::float64()),
+        arrow::field("volume", arrow::int64())
+    };
+    std::shared_ptr<arrow::Table> table = arrow::Table::Make(
+            arrow::schema(fields), columns);
+    const char* path = "test_schema.parquet";
+    auto out = arrow::io::FileOutputStream::Open(path);
+    ASSERT_TRUE(out.ok());
+    ASSERT_TRUE(parquet::arrow::WriteTable(*table,
+                arrow::default_memory_pool(), *out, 100).ok());
+    ASSERT_TRUE((*out)->Close().ok());
+    ColumnLayout layout;
+    layout.open = layout.high = layout.low = layout.close = "price";
+    // integer volumes
+    ParquetBars mistyped(path, layout);
+    EXPECT_FALSE(mistyped.ok());
+    EXPECT_TRUE(mistyped.begin() == mistyped.end());
+    layout.volume = "size";
+    ParquetBars missing(path, layout);
+    EXPECT_FALSE(missing.ok());
+    layout.volume = "";
+    ParquetBars file(path, layout);
+    ASSERT_TRUE(file.ok());
+    std::vector<Ohlcv> bars;
+    for (const auto& bar : file) bars.push_back(bar);
+    std::remove(path);
+    // the row without a timestamp is skipped
+    ASSERT_EQ(bars.size(), 2u);
+    EXPECT_DOUBLE_EQ(bars[0].open, 1.5);
+    EXPECT_EQ(bars[1].timestamp, 180);
+    EXPECT_TRUE(std::isnan(bars[1].open));
+    EXPECT_TRUE(std::isnan(bars[1].close));
+}
+
+#endif // TZU_WITH_PARQUET
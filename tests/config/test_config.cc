@@ -0,0 +1,155 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <string>
+#include <vector>
+#include "config.h"
+
+using namespace tzu;
+
+namespace {
+
+const char* crossover_config = R"cfg(
+# EMA crossover
+symbols = ["BTCUSD", "ETHUSD"]  # traded together
+timeframe = 3600
+
+[indicators]
+fast = "EMA(3)"
+slow = "EMA(8)"
+
+[rules]
+entry = "fast crosses_above slow"
+exit = "fast crosses_below slow"
+
+[risk]
+tx_cost = 0.001
+stop_loss = 0.1
+)cfg";
+
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 100; i++) {
+        double c = 100.0 + 10.0 * std::sin(i * 0.15);
+        bars.push_back(Ohlcv(i, c, c + 1.0, c - 1.0, c, 1.0));
+    }
+    return bars;
+}
+
+bool parse(const std::string& text, StrategyConfig& config,
+        std::string& error) {
+    std::istringstream is(text);
+    return parse_config(is, config, error);
+}
+
+} // namespace
+
+TEST(Config, ParsesStrategies) {
+    StrategyConfig config;
+    std::string error;
+    ASSERT_TRUE(parse(crossover_config, config, error)) << error;
+    ASSERT_EQ(config.symbols.size(), 2u);
+    EXPECT_EQ(config.symbols[1], "ETHUSD");
+    EXPECT_EQ(config.timeframe, 3600);
+    ASSERT_EQ(config.indicators.size(), 2u);
+    EXPECT_EQ(config.indicators[0].first, "fast");
+    EXPECT_EQ(config.indicators[0].second, "EMA(3)");
+    EXPECT_EQ(config.entry, "fast crosses_above slow");
+    EXPECT_DOUBLE_EQ(config.tx_cost, 0.001);
+    EXPECT_DOUBLE_EQ(config.stop_loss, 0.1);
+    EXPECT_TRUE(std::isnan(config.take_profit));
+}
+
+TEST(Config, ReportsErrorsWithTheLine) {
+    StrategyConfig config;
+    std::string error;
+    EXPECT_FALSE(parse("timeframe = 60\nfoo = 1\n", config, error));
+    EXPECT_EQ(error, "line 2: unknown key 'foo'");
+    EXPECT_FALSE(parse("[orders]\n", config, error));
+    EXPECT_EQ(error, "line 1: unknown section 'orders'");
+    EXPECT_FALSE(parse("[risk]\nstop_loss = \"ten\"\n", config, error));
+    EXPECT_EQ(error, "line 2: expected a number");
+    EXPECT_FALSE(parse("[indicators]\na = \"SMA(2)\"\na = \"SMA(3)\"\n",
+                config, error));
+    EXPECT_EQ(error, "line 3: duplicate indicator 'a'");
+    EXPECT_FALSE(parse("symbols = [\"A\", B]\n", config, error));
+}
+
+TEST(Config, StrategyFollowsItsRules) {
+    StrategyConfig config;
+    std::string error;
+    ASSERT_TRUE(parse(crossover_config, config, error));
+    ConfigStrat strat;
+    ASSERT_TRUE(strat.configure(config, error)) << error;
+    EMA fast(3), slow(8);
+    double prev_fast = std::nan(""), prev_slow = std::nan("");
+    Side last = Side::NONE;
+    size_t buys = 0, sells = 0;
+    for (const auto& bar : sample_bars()) {
+        double f = fast.update(bar.close), s = slow.update(bar.close);
+        Side expected = Side::NONE;
+        if (prev_fast <= prev_slow && f > s && last != Side::BUY)
+            last = expected = Side::BUY;
+        else if (prev_fast >= prev_slow && f < s && last != Side::SELL)
+            last = expected = Side::SELL;
+        prev_fast = f;
+        prev_slow = s;
+        Signal signal = strat.update(bar);
+        EXPECT_EQ(signal.side, expected) << bar.timestamp;
+        if (signal.side == Side::BUY) buys++;
+        if (signal.side == Side::SELL) sells++;
+    }
+    EXPECT_GT(buys, 0u);
+    EXPECT_GT(sells, 0u);
+}
+
+TEST(Config, RulesReadOutputsFieldsAndNumbers) {
+    StrategyConfig config;
+    config.indicators.push_back(std::make_pair("macd", "MACD(3, 6, 3)"));
+    config.entry = "macd.histogram > 0";
+    config.exit = "close < 95";
+    std::string error;
+    ConfigStrat strat;
+    ASSERT_TRUE(strat.configure(config, error)) << error;
+    bool bought = false, sold = false;
+    for (const auto& bar : sample_bars()) {
+        Side side = strat.update(bar).side;
+        if (side == Side::BUY) bought = true;
+        if (side == Side::SELL) {
+            EXPECT_LT(bar.close, 95.0);
+            sold = true;
+        }
+    }
+    EXPECT_TRUE(bought);
+    EXPECT_TRUE(sold);
+}
+
+TEST(Config, RejectsInvalidStrategies) {
+    StrategyConfig config;
+    config.indicators.push_back(std::make_pair("rsi", "RSI(14)"));
+    std::string error;
+    ConfigStrat strat;
+    EXPECT_FALSE(strat.configure(config, error));
+    EXPECT_EQ(error, "missing entry rule");
+    config.entry = "rsi < oversold";
+    EXPECT_FALSE(strat.configure(config, error));
+    EXPECT_EQ(error, "entry: unknown name 'oversold'");
+    config.entry = "rsi below 30";
+    EXPECT_FALSE(strat.configure(config, error));
+    EXPECT_EQ(error, "entry: unexpected 'below'");
+    config.entry = "rsi < 30";
+    config.indicators.push_back(std::make_pair("ma", "SMA()"));
+    EXPECT_FALSE(strat.configure(config, error));
+    EXPECT_EQ(error, "ma: wrong number of arguments in 'SMA()'");
+}
+
+TEST(Config, PortfolioTakesTheRiskSettings) {
+    StrategyConfig config;
+    config.stop_loss = 0.1;
+    BasicPortfolio portfolio = make_portfolio(config);
+    portfolio.update(Signal(0, Side::BUY, 100.0));
+    portfolio.update(Signal(1, Side::NONE, 85.0));
+    std::ostringstream os;
+    os << portfolio;
+    EXPECT_NE(os.str().find("num_stop_loss:1"), std::string::npos) << os.str();
+}
@@ -0,0 +1,118 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <map>
+#include <string>
+#include <vector>
+#include "expr.h"
+
+using namespace tzu;
+
+namespace {
+
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 120; i++) {
+        double c = 100.0 + 10.0 * std::sin(i * 0.15) + 0.1 * i;
+        bars.push_back(Ohlcv(i, c - 0.5, c + 1.0, c - 1.0, c, 10.0));
+    }
+    return bars;
+}
+
+double eval(const std::string& text, const Ohlcv& bar) {
+    Expression e;
+    std::string error;
+    EXPECT_TRUE(e.parse(text, error)) << error;
+    return e.update(bar);
+}
+
+} // namespace
+
+TEST(Expression, ArithmeticAndPrecedence) {
+    Ohlcv bar(0, 1.0, 4.0, 0.5, 2.0, 10.0);
+    EXPECT_DOUBLE_EQ(eval("1 + 2 * 3", bar), 7.0);
+    EXPECT_DOUBLE_EQ(eval("(1 + 2) * 3", bar), 9.0);
+    EXPECT_DOUBLE_EQ(eval("-close + high / 2", bar), 0.0);
+    EXPECT_DOUBLE_EQ(eval("1 - 2 - 3", bar), -4.0);
+    EXPECT_DOUBLE_EQ(eval("close > open && volume >= 10", bar), 1.0);
+    EXPECT_DOUBLE_EQ(eval("close > high || !(low < 1)", bar), 0.0);
+    EXPECT_DOUBLE_EQ(eval("close < 1 or close == 2 and not 0", bar), 1.0);
+    EXPECT_DOUBLE_EQ(eval("1e2 != 100", bar), 0.0);
+}
+
+TEST(Expression, CallsIndicators) {
+    Expression e;
+    std::string error;
+    ASSERT_TRUE(e.parse("rsi(14) < 30 && close > ema(50)", error)) << error;
+    RSI rsi(14);
+    EMA ema(50);
+    for (const auto& bar : sample_bars()) {
+        double r = rsi.update(bar), m = ema.update(bar.close);
+        EXPECT_EQ(e.test(bar), r < 30.0 && bar.close > m) << bar.timestamp;
+    }
+}
+
+TEST(Expression, OutputsFieldsAndNamedIndicators) {
+    Expression e;
+    std::string error;
+    std::vector<std::string> names = {"trend"};
+    ASSERT_TRUE(e.parse("macd(3, 6, 3).signal - trend + highest(5, high)",
+                error, names)) << error;
+    std::vector<DynIndicator> named = {DynIndicator(SMA(4))};
+    MACD macd(3, 6, 3);
+    Highest highest(5);
+    for (const auto& bar : sample_bars()) {
+        named[0].update(bar);
+        double expected = macd.update(bar.close).signal - named[0].get()
+            + highest.update(bar.high);
+        double value = e.update(bar, named);
+        if (std::isnan(expected)) EXPECT_TRUE(std::isnan(value));
+        else EXPECT_DOUBLE_EQ(value, expected);
+    }
+    std::map<std::string, double> values = e.values();
+    EXPECT_EQ(values.size(), 4u);
+    EXPECT_DOUBLE_EQ(values.at("macd(3, 6, 3).signal"), macd.get().signal);
+    EXPECT_DOUBLE_EQ(values.at("highest(5, high)"), highest.get());
+}
+
+TEST(Expression, CrossesSeeEveryBar) {
+    Expression e;
+    std::string error;
+    // the cross is evaluated even when the left side is false
+    ASSERT_TRUE(e.parse("volume > 100 && sma(3) crosses_above sma(8)", error));
+    Expression cross;
+    ASSERT_TRUE(cross.parse("sma(3) crosses_above sma(8)", error));
+    size_t crosses = 0;
+    for (auto bar : sample_bars()) {
+        bool c = cross.test(bar);
+        if (c) crosses++;
+        bar.volume = 1000.0;
+        Expression copy = e;
+        EXPECT_EQ(copy.test(bar), c) << bar.timestamp;
+        bar.volume = 10.0;
+        EXPECT_FALSE(e.test(bar));
+    }
+    EXPECT_GT(crosses, 0u);
+}
+
+TEST(Expression, ReportsErrors) {
+    Expression e;
+    std::string error;
+    EXPECT_FALSE(e.parse("close >", error));
+    EXPECT_EQ(error, "unexpected end of expression");
+    EXPECT_FALSE(e.parse("close > foo", error));
+    EXPECT_EQ(error, "unknown name 'foo'");
+    EXPECT_FALSE(e.parse("foo(3) > 1", error));
+    EXPECT_EQ(error, "unknown indicator 'foo'");
+    EXPECT_FALSE(e.parse("sma(0) > 1", error));
+    EXPECT_EQ(error, "invalid period in 'SMA(0)'");
+    EXPECT_FALSE(e.parse("(close > 1", error));
+    EXPECT_EQ(error, "expected ')'");
+    EXPECT_FALSE(e.parse("close > 1 1", error));
+    EXPECT_EQ(error, "unexpected '1'");
+    EXPECT_FALSE(e.parse("close $ 1", error));
+    EXPECT_EQ(error, "unexpected '$'");
+    EXPECT_FALSE(e.parse("macd(3, 6, 3).sgnal > 0", error));
+    EXPECT_EQ(error, "unknown output 'sgnal'");
+    EXPECT_TRUE(e.empty());
+    EXPECT_TRUE(std::isnan(e.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0))));
+}
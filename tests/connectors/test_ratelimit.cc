@@ -0,0 +1,51 @@
+#include <gtest/gtest.h>
+#include "connectors.h"
+
+using namespace tzu;
+
+TEST(RateLimiter, GrantsUntilEmptyThenDelays) {
+    RateLimiter limiter;
+    limiter.add_limit(10.0, 1000);
+    limiter.set_weight("klines", 2.0);
+    for (int i = 0; i < 5; ++i)
+        EXPECT_EQ(limiter.acquire(0, "klines"), 0);
+    EXPECT_EQ(limiter.acquire(0, "klines"), 200);
+    // the queued request is accounted for
+    EXPECT_EQ(limiter.acquire(0, "ping"), 300);
+    EXPECT_EQ(limiter.acquire(1000, "ping"), 0);
+}
+
+TEST(TokenBucket, ClampsNonPositiveWindow) {
+    TokenBucket bucket(2.0, 0);
+    bucket.take(0, 2.0);
+    EXPECT_EQ(bucket.delay(0, 1.0), 1);
+    EXPECT_DOUBLE_EQ(bucket.available(1), 2.0);
+}
+
+TEST(RateLimiter, ShedsRequestsOverMaxDelay) {
+    RateLimiter limiter(100);
+    limiter.add_limit(1200.0, 60000).add_limit(5.0, 10000);
+    for (int i = 0; i < 5; ++i)
+        EXPECT_EQ(limiter.acquire(0, "order"), 0);
+    EXPECT_EQ(limiter.acquire(0, "order"), -1);
+    EXPECT_EQ(limiter.acquire(2000, "order"), 0);
+    EXPECT_EQ(limiter.num_shed(), 1u);
+    limiter.set_weight("huge", 2000.0);
+    EXPECT_EQ(limiter.acquire(100000, "huge"), -1);
+}
+
+TEST(Throttle, SleepsBeforeCallsOverTheLimit) {
+    int64_t now = 0;
+    std::vector<int64_t> sleeps;
+    RateLimiter limiter;
+    limiter.add_limit(2.0, 100);
+    auto throttle = make_throttle(limiter,
+            [&now]() { return now; },
+            [&now, &sleeps](int64_t d) { sleeps.push_back(d); now += d; });
+    int calls = 0;
+    for (int i = 0; i < 3; ++i)
+        EXPECT_TRUE(throttle.call("", [&calls]() { calls++; }));
+    EXPECT_EQ(calls, 3);
+    ASSERT_EQ(sleeps.size(), 1u);
+    EXPECT_EQ(sleeps[0], 50);
+}
@@ -0,0 +1,59 @@
+#include <gtest/gtest.h>
+#include "connectors.h"
+
+using namespace tzu;
+
+TEST(Retry, BackoffGrowsExponentiallyUpToMax) {
+    RetryPolicy policy;
+    policy.initial_delay = 100;
+    policy.max_delay = 1000;
+    EXPECT_EQ(backoff_delay(policy, 1), 100);
+    EXPECT_EQ(backoff_delay(policy, 3), 400);
+    EXPECT_EQ(backoff_delay(policy, 5), 1000);
+    EXPECT_EQ(backoff_delay(policy, 2, 1.0), 100);
+}
+
+TEST(Retry, RetriesWithSameIdempotencyKey) {
+    std::vector<int64_t> sleeps;
+    RetryPolicy policy;
+    policy.jitter = 0.0;
+    auto retry = make_retry(policy,
+            [&sleeps](int64_t d) { sleeps.push_back(d); }, "order");
+    std::vector<std::string> keys;
+    bool ok = retry.call([&keys](const std::string& key) {
+        keys.push_back(key);
+        return keys.size() == 3;
+    });
+    EXPECT_TRUE(ok);
+    ASSERT_EQ(keys.size(), 3u);
+    EXPECT_EQ(keys[0], "order-1");
+    EXPECT_EQ(keys[2], "order-1");
+    EXPECT_EQ(sleeps, (std::vector<int64_t>{100, 200}));
+    EXPECT_EQ(retry.next_key(), "order-2");
+}
+
+TEST(Retry, RecordsGiveUps) {
+    RetryPolicy policy;
+    policy.max_attempts = 3;
+    int sleeps = 0;
+    auto retry = make_retry(policy, [&sleeps](int64_t) { sleeps++; });
+    int attempts = 0;
+    EXPECT_FALSE(retry.call("k", [&attempts](const std::string&) {
+        attempts++;
+        return false;
+    }));
+    EXPECT_EQ(attempts, 3);
+    EXPECT_EQ(sleeps, 2);
+    ASSERT_EQ(retry.get_events().size(), 1u);
+    EXPECT_EQ(retry.get_events()[0].key, "k");
+}
+
+TEST(Retry, GeneratesASessionPrefix) {
+    auto noop = [](int64_t) {};
+    auto a = make_retry(RetryPolicy(), noop);
+    auto b = make_retry(RetryPolicy(), noop);
+    std::string key = a.next_key();
+    EXPECT_EQ(key.compare(0, 4, "tzu-"), 0);
+    EXPECT_EQ(key.size(), 14u);
+    EXPECT_NE(key, b.next_key());
+}
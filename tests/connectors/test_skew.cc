@@ -0,0 +1,31 @@
+#include <gtest/gtest.h>
+#include "connectors.h"
+
+using namespace tzu;
+
+TEST(ClockSkew, EstimatesSkewFromMinimumOffset) {
+    ClockSkew clock(10, 1000);
+    // the local clock is 2000 ahead, with varying latency
+    EXPECT_EQ(clock.observe(1000, 3050), FeedStatus::OK);
+    EXPECT_EQ(clock.observe(2000, 4010), FeedStatus::OK);
+    EXPECT_EQ(clock.observe(3000, 5200), FeedStatus::OK);
+    EXPECT_EQ(clock.get(), 2010);
+    EXPECT_EQ(clock.to_exchange(6010), 4000);
+}
+
+TEST(ClockSkew, FlagsStaleOutOfOrderAndDuplicateMessages) {
+    ClockSkew clock(10, 1000);
+    clock.observe(1000, 1010);
+    EXPECT_EQ(clock.observe(2000, 3500), FeedStatus::STALE);
+    EXPECT_EQ(clock.observe(1500, 3600), FeedStatus::OUT_OF_ORDER);
+    EXPECT_EQ(clock.observe(2000, 3700), FeedStatus::DUPLICATE);
+    EXPECT_EQ(clock.observe(4000, 4020), FeedStatus::OK);
+}
+
+TEST(ClockSkew, BoundariesUseExchangeTime) {
+    ClockSkew clock;
+    clock.observe(60000, 58000);   // local clock 2s behind
+    // 59000 local is 61000 at the exchange: the 60000 minute started
+    EXPECT_EQ(clock.boundary(59000, 60000), 60000);
+    EXPECT_EQ(clock.boundary(57000, 60000), 0);
+}
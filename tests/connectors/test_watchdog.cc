@@ -0,0 +1,54 @@
+#include <gtest/gtest.h>
+#include "connectors.h"
+#include "risk.h"
+
+using namespace tzu;
+
+TEST(Watchdog, EscalatesStatusWithSilence) {
+    WatchdogConfig config;
+    config.degraded = 10;
+    config.stale = 20;
+    config.dead = 50;
+    Watchdog dog(config);
+    dog.heartbeat("BTC", 0);
+    EXPECT_TRUE(dog.check(5).empty());
+    std::vector<LivenessEvent> events = dog.check(12);
+    ASSERT_EQ(events.size(), 1u);
+    EXPECT_EQ(events[0].status, Liveness::DEGRADED);
+    EXPECT_EQ(dog.check(25)[0].status, Liveness::STALE);
+    EXPECT_EQ(dog.check(60)[0].status, Liveness::DEAD);
+    EXPECT_TRUE(dog.check(70).empty());
+}
+
+TEST(Watchdog, RecoversOnHeartbeat) {
+    WatchdogConfig config;
+    config.degraded = 10;
+    Watchdog dog(config);
+    dog.heartbeat("BTC", 0);
+    dog.heartbeat("ETH", 0);
+    dog.heartbeat("ETH", 15);
+    dog.check(15);
+    EXPECT_EQ(dog.status("BTC"), Liveness::DEGRADED);
+    EXPECT_EQ(dog.status("ETH"), Liveness::ALIVE);
+    EXPECT_FALSE(dog.all_alive());
+    dog.heartbeat("BTC", 16);
+    std::vector<LivenessEvent> events = dog.check(16);
+    ASSERT_EQ(events.size(), 1u);
+    EXPECT_EQ(events[0].key, "BTC");
+    EXPECT_EQ(events[0].status, Liveness::ALIVE);
+    EXPECT_TRUE(dog.all_alive());
+}
+
+TEST(Watchdog, DeadFeedTripsCircuitBreaker) {
+    WatchdogConfig config;
+    config.dead = 30;
+    Watchdog dog(config);
+    CircuitBreaker breaker(BreakerConfig{});
+    dog.heartbeat("BTC", 0);
+    for (int64_t now = 0; now <= 40; now += 10)
+        for (const auto& e : dog.check(now))
+            if (e.status == Liveness::DEAD) breaker.feed_lost(now);
+    EXPECT_FALSE(breaker.allow());
+    EXPECT_EQ(breaker.reason(), BreakerTrigger::STALE_FEED);
+    EXPECT_EQ(breaker.since(), 30);
+}
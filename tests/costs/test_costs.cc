@@ -0,0 +1,42 @@
+#include <gtest/gtest.h>
+#include "costs.h"
+#include "orders.h"
+
+using namespace tzu;
+
+TEST(Costs, CommissionModels) {
+    PerShareCommission per_share(0.005, 1.0, 0.01);
+    EXPECT_DOUBLE_EQ(per_share(100.0, 50.0), 1.0);      // minimum
+    EXPECT_DOUBLE_EQ(per_share(-1000.0, 50.0), 5.0);
+    EXPECT_DOUBLE_EQ(per_share(1000.0, 0.2), 2.0);      // capped at 1%
+    PercentageCommission pct(0.001, 2.0);
+    EXPECT_DOUBLE_EQ(pct(10.0, 100.0), 2.0);
+    EXPECT_DOUBLE_EQ(pct(-100.0, 100.0), 10.0);
+    TieredCommission tiered({{0.0, 0.002}, {10000.0, 0.001}});
+    EXPECT_DOUBLE_EQ(tiered(100.0, 100.0), 20.0);
+    EXPECT_DOUBLE_EQ(tiered(100.0, 100.0), 10.0);
+}
+
+TEST(Costs, SlippageModels) {
+    Ohlcv bar(0, 100.0, 101.0, 99.0, 100.0, 10000.0);
+    EXPECT_DOUBLE_EQ(FixedSlippage(0.01)(5.0, 100.0, bar), 101.0);
+    EXPECT_DOUBLE_EQ(SpreadSlippage(0.002)(-5.0, 100.0, bar), 99.9);
+    VolumeImpactSlippage impact(0.1);
+    EXPECT_DOUBLE_EQ(impact(100.0, 100.0, bar), 101.0);  // 1% of the volume
+    EXPECT_DOUBLE_EQ(impact(-2500.0, 100.0, bar), 95.0);
+}
+
+TEST(Costs, UsedBySimulatedBroker) {
+    SimulatedBroker broker(100000.0);
+    broker.set_commission(PerShareCommission(0.01));
+    broker.set_slippage(VolumeImpactSlippage(0.1));
+    broker.submit(0, Order::market("AAA", 100.0));
+    std::string limit = broker.submit(0, Order::limit("AAA", -100.0, 110.0));
+    ASSERT_TRUE(broker.update("AAA", Ohlcv(1, 100.0, 111.0, 99.0, 105.0, 10000.0)));
+    ASSERT_EQ(broker.fills().size(), 2u);
+    EXPECT_DOUBLE_EQ(broker.fills()[0].price, 101.0);
+    EXPECT_DOUBLE_EQ(broker.fills()[0].commission, 1.0);
+    // limit orders don't take liquidity
+    EXPECT_DOUBLE_EQ(broker.order(limit).avg_fill_price, 110.0);
+    EXPECT_DOUBLE_EQ(broker.account().commissions(), 2.0);
+}
@@ -0,0 +1,77 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include "currency.h"
+#include "streamers.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(FxRates, UsesLastKnownRate) {
+    FxRates rates("USD");
+    rates.add("EUR", SingleValue{10, 1.10});
+    rates.add("EUR", SingleValue{20, 1.20});
+    EXPECT_TRUE(std::isnan(rates.rate("EUR", 5)));
+    EXPECT_DOUBLE_EQ(rates.rate("EUR", 10), 1.10);
+    EXPECT_DOUBLE_EQ(rates.rate("EUR", 15), 1.10);
+    EXPECT_DOUBLE_EQ(rates.rate("EUR", 25), 1.20);
+    EXPECT_DOUBLE_EQ(rates.rate("USD", 0), 1.0);
+}
+
+TEST(FxRates, ConvertsAcrossCurrencies) {
+    FxRates rates("USD");
+    std::istringstream input("timestamp,rate\n1,1.25\n");
+    Csv<SingleValue> csv(input);
+    rates.load("GBP", csv);
+    rates.add("EUR", SingleValue{1, 1.0});
+    EXPECT_DOUBLE_EQ(rates.convert(100.0, "GBP", 1), 125.0);
+    EXPECT_DOUBLE_EQ(rates.convert(100.0, "GBP", 1, "EUR"), 125.0);
+    EXPECT_DOUBLE_EQ(rates.convert(100.0, "USD", 1, "GBP"), 80.0);
+}
+
+TEST(FxConvert, ConvertsBarPrices) {
+    FxRates rates("USD");
+    rates.add("EUR", SingleValue{0, 2.0});
+    FxConvert convert(rates, "EUR");
+    Ohlcv bar = convert.update(Ohlcv{1, 1.0, 2.0, 0.5, 1.5, 100.0});
+    EXPECT_DOUBLE_EQ(bar.open, 2.0);
+    EXPECT_DOUBLE_EQ(bar.high, 4.0);
+    EXPECT_DOUBLE_EQ(bar.low, 1.0);
+    EXPECT_DOUBLE_EQ(bar.close, 3.0);
+    EXPECT_DOUBLE_EQ(bar.volume, 100.0);
+}
+
+TEST(FxPortfolio, ReportsInBaseCurrency) {
+    FxRates rates("USD");
+    rates.add("EUR", SingleValue{1, 2.0});
+    FxPortfolio<BasicPortfolio> portfolio(BasicPortfolio(1000.0), rates, "EUR");
+    portfolio.update(Signal{0, Side::BUY, 10.0});
+    portfolio.update(Signal{1, Side::BUY, 10.0});
+    portfolio.update(Signal{2, Side::SELL, 12.0});
+    std::ostringstream out;
+    out << portfolio;
+    // 50 units bought at 20 USD and sold at 24 USD
+    EXPECT_NE(out.str().find("curr_cash:1200.0000"), std::string::npos);
+}
+
+TEST(FxLedger, AccountsInBaseCurrency) {
+    FxRates rates("USD");
+    rates.add("EUR", SingleValue{1, 2.0});
+    rates.add("EUR", SingleValue{3, 1.5});
+    FxLedger ledger(rates, 1000.0);
+    ledger.set_currency("SAP", "EUR");
+    EXPECT_FALSE(ledger.on_fill(0, "SAP", 10.0, 10.0));
+    EXPECT_TRUE(ledger.on_fill(1, "SAP", 10.0, 10.0, 1.0));
+    EXPECT_TRUE(ledger.on_fill(2, "AAPL", 1.0, 100.0));
+    EXPECT_DOUBLE_EQ(ledger.account().cash(), 1000.0 - 202.0 - 100.0);
+    EXPECT_DOUBLE_EQ(ledger.account().commissions(), 2.0);
+    // the euro falls from 2.0 to 1.5 USD while the price rises to 12 EUR
+    EXPECT_TRUE(ledger.mark("SAP", 12.0, 3));
+    EXPECT_DOUBLE_EQ(ledger.account().unrealized(), 10.0 * (18.0 - 20.0));
+    EXPECT_TRUE(ledger.on_fill(4, "SAP", -10.0, 12.0));
+    EXPECT_DOUBLE_EQ(ledger.account().realized(), -20.0);
+    ASSERT_EQ(ledger.account().trades().size(), 1u);
+    EXPECT_DOUBLE_EQ(ledger.account().trades()[0].profit, -20.0);
+    std::ostringstream out;
+    out << ledger;
+    EXPECT_EQ(out.str().find("currency:USD cash:"), 0u);
+}
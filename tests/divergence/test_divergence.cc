@@ -0,0 +1,65 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include "divergence.h"
+
+using namespace tzu;
+
+TEST(Divergence, RegularBearishOnHigherHighLowerOscillator) {
+    DivergenceDetector detector(1);
+    double prices[] = {1.0, 3.0, 2.0, 4.0, 3.0};
+    double osc[] = {50.0, 70.0, 55.0, 65.0, 50.0};
+    for (int i = 0; i < 4; ++i)
+        EXPECT_FALSE(detector.update(i, prices[i], osc[i]));
+    // the second swing high (t = 3) is confirmed one bar later
+    ASSERT_TRUE(detector.update(4, prices[4], osc[4]));
+    ASSERT_EQ(detector.get().size(), 1u);
+    Divergence d = detector.get()[0];
+    EXPECT_EQ(d.kind, DivergenceKind::REGULAR_BEARISH);
+    EXPECT_EQ(d.timestamp, 4);
+    EXPECT_EQ(d.first, 1);
+    EXPECT_EQ(d.second, 3);
+    std::ostringstream os;
+    os << d;
+    EXPECT_EQ(os.str(), "timestamp:4 divergence:REGULAR_BEARISH first:1 second:3");
+}
+
+TEST(Divergence, BullishFromBarLows) {
+    DivergenceDetector detector(1);
+    // lows 9, 7, 8, 6, 8; oscillator higher at the lower low
+    double lows[] = {9.0, 7.0, 8.0, 6.0, 8.0, 7.5, 7.0, 7.5};
+    double osc[] = {40.0, 20.0, 35.0, 25.0, 40.0, 30.0, 20.0, 30.0};
+    std::vector<DivergenceKind> kinds;
+    for (int i = 0; i < 8; ++i)
+        if (detector.update(Ohlcv(i, 10.0, 11.0 + (i % 2), lows[i], 10.0, 1.0), osc[i]))
+            kinds.push_back(detector.get()[0].kind);
+    ASSERT_EQ(kinds.size(), 2u);
+    EXPECT_EQ(kinds[0], DivergenceKind::REGULAR_BULLISH);
+    // higher low at t = 6 with a lower oscillator
+    EXPECT_EQ(kinds[1], DivergenceKind::HIDDEN_BULLISH);
+}
+
+TEST(Divergence, SkipsWarmupOscillatorValues) {
+    DivergenceDetector detector(1);
+    double prices[] = {1.0, 3.0, 2.0, 4.0, 3.0};
+    double osc[] = {NAN, NAN, 55.0, 65.0, 50.0};
+    bool any = false;
+    for (int i = 0; i < 5; ++i)
+        any = detector.update(i, prices[i], osc[i]) || any;
+    EXPECT_FALSE(any);
+}
+
+TEST(Divergence, UsesTheGivenSwingDetector) {
+    // swing highs need two bars before and one after
+    DivergenceDetector detector(SwingDetector(2, 1));
+    double prices[] = {1.0, 2.0, 3.0, 2.0, 1.5, 2.5, 4.0, 3.0};
+    double osc[] = {50.0, 60.0, 70.0, 60.0, 55.0, 60.0, 65.0, 50.0};
+    for (int i = 0; i < 7; ++i)
+        EXPECT_FALSE(detector.update(i, prices[i], osc[i]));
+    ASSERT_TRUE(detector.update(7, prices[7], osc[7]));
+    Divergence d = detector.get()[0];
+    EXPECT_EQ(d.kind, DivergenceKind::REGULAR_BEARISH);
+    EXPECT_EQ(d.first, 2);
+    EXPECT_EQ(d.second, 6);
+    EXPECT_EQ(d.timestamp, 7);
+}
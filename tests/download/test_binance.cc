@@ -0,0 +1,121 @@
+#include <gtest/gtest.h>
+#include <cstdint>
+#include <string>
+#include <vector>
+#include "download.h"
+
+using namespace tzu;
+
+namespace {
+
+// klines of one hour from `first` (in hours since the epoch)
+std::string klines(int64_t first, int64_t count) {
+    std::string body = "[";
+    for (int64_t i = 0; i < count; i++) {
+        int64_t t = (first + i) * 3600000;
+        std::string p = std::to_string(100 + first + i);
+        if (i > 0) body += ",";
+        body += "[" + std::to_string(t) + ",\"" + p + ".0\",\"" + p
+            + ".5\",\"" + p + ".25\",\"" + p + ".125\",\"10.0\","
+            + std::to_string(t + 3599999) + ",\"1000.0\",5,\"1\",\"100\",\"0\"]";
+    }
+    return body + "]";
+}
+
+struct FakeBinance {
+    std::vector<std::string>* urls;
+    int64_t available;  // hours with klines, from zero
+    bool operator()(const HttpRequest& req, HttpResponse& res) {
+        urls->push_back(req.url);
+        size_t s = req.url.find("startTime=") + 10;
+        size_t e = req.url.find("endTime=") + 8;
+        size_t l = req.url.find("limit=") + 6;
+        int64_t start = std::stoll(req.url.substr(s));
+        int64_t end = std::stoll(req.url.substr(e));
+        int64_t limit = std::stoll(req.url.substr(l));
+        int64_t first = (start + 3599999) / 3600000;
+        int64_t last = end / 3600000;
+        if (last >= available) last = available - 1;
+        int64_t count = last - first + 1;
+        if (count > limit) count = limit;
+        res.status = 200;
+        res.body = klines(first, count < 0 ? 0 : count);
+        return true;
+    }
+};
+
+} // namespace
+
+TEST(BinanceDownloader, PaginatesTheRange) {
+    std::vector<std::string> urls;
+    int64_t now = 0;
+    std::vector<int64_t> sleeps;
+    RateLimiter limiter(INT64_MAX, 2.0);
+    limiter.add_limit(4.0, 1000);
+    BinanceDownloader binance(FakeBinance{&urls, 1000}, limiter,
+            [&now] { return now; },
+            [&](int64_t ms) { sleeps.push_back(ms); now += ms; },
+            "https://api", 3);
+    std::vector<Ohlcv> bars;
+    ASSERT_TRUE(binance.download("BTCUSDT", "1h", 3600, 8 * 3600, bars))
+        << binance.last_error();
+    ASSERT_EQ(bars.size(), 7u);
+    for (size_t i = 0; i < bars.size(); i++)
+        EXPECT_EQ(bars[i].timestamp, static_cast<int64_t>(i + 1) * 3600);
+    EXPECT_DOUBLE_EQ(bars[0].open, 101.0);
+    EXPECT_DOUBLE_EQ(bars[0].high, 101.5);
+    EXPECT_DOUBLE_EQ(bars[0].low, 101.25);
+    EXPECT_DOUBLE_EQ(bars[0].close, 101.125);
+    EXPECT_DOUBLE_EQ(bars[0].volume, 10.0);
+    ASSERT_EQ(urls.size(), 3u);
+    EXPECT_EQ(urls[0], "https://api/api/v3/klines?symbol=BTCUSDT&interval=1h"
+            "&startTime=3600000&endTime=28799999&limit=3");
+    EXPECT_NE(urls[1].find("startTime=10800001"), std::string::npos);
+    // two requests fill the limit of 4 per second; the third waits
+    ASSERT_EQ(sleeps.size(), 1u);
+    EXPECT_EQ(sleeps[0], 500);
+}
+
+TEST(BinanceDownloader, StopsAtTheLastAvailableKline) {
+    std::vector<std::string> urls;
+    BinanceDownloader binance(FakeBinance{&urls, 5});
+    std::vector<Ohlcv> bars;
+    ASSERT_TRUE(binance.download("BTCUSDT", "1h", 0, 100 * 3600, bars));
+    EXPECT_EQ(bars.size(), 5u);
+    EXPECT_EQ(binance.num_requests(), 1u);
+}
+
+TEST(BinanceDownloader, ReportsErrors) {
+    BinanceDownloader binance([](const HttpRequest&, HttpResponse& res) {
+        res.status = 400;
+        res.body = R"({"code":-1120,"msg":"Invalid interval."})";
+        return true;
+    });
+    std::vector<Ohlcv> bars;
+    EXPECT_FALSE(binance.download("BTCUSDT", "2x", 0, 3600, bars));
+    EXPECT_NE(binance.last_error().find("HTTP 400 Invalid interval."),
+            std::string::npos);
+    BinanceDownloader broken([](const HttpRequest&, HttpResponse& res) {
+        res.status = 200;
+        res.body = "[[1,\"x\"]]";
+        return true;
+    });
+    EXPECT_FALSE(broken.download("BTCUSDT", "1h", 0, 3600, bars));
+    EXPECT_NE(broken.last_error().find("malformed"), std::string::npos);
+    EXPECT_TRUE(bars.empty());
+}
+
+TEST(BinanceDownloader, ClampsThePageSize) {
+    std::vector<std::string> urls;
+    std::vector<Ohlcv> bars;
+    BinanceDownloader large(FakeBinance{&urls, 5}, binance_rate_limiter(),
+            [] { return int64_t(0); }, [](int64_t) {}, "https://api", 5000);
+    ASSERT_TRUE(large.download("BTCUSDT", "1h", 0, 3600, bars));
+    EXPECT_NE(urls.back().find("limit=1000"), std::string::npos);
+    BinanceDownloader empty(FakeBinance{&urls, 5}, binance_rate_limiter(),
+            [] { return int64_t(0); }, [](int64_t) {}, "https://api", 0);
+    bars.clear();
+    ASSERT_TRUE(empty.download("BTCUSDT", "1h", 0, 3 * 3600, bars));
+    EXPECT_NE(urls.back().find("limit=1"), std::string::npos);
+    EXPECT_EQ(bars.size(), 3u);
+}
@@ -0,0 +1,122 @@
+#include <gtest/gtest.h>
+#include <cstdint>
+#include <string>
+#include <vector>
+#include "download.h"
+
+using namespace tzu;
+
+namespace {
+
+const int64_t DAY0 = 1704153600;  // 2024-01-02
+
+// four days of split-adjusted prices, a dividend of 0.5 (1.0 before
+// the split) on the second, a 2:1 split on the third, and a holiday
+std::string yahoo_chart() {
+    std::string t[5];
+    for (int i = 0; i < 5; i++)
+        t[i] = std::to_string(DAY0 + i * 86400 + 52200);
+    return R"({"chart":{"result":[{"meta":{"currency":"USD","symbol":"AAPL"},)"
+        R"("timestamp":[)" + t[0] + "," + t[1] + "," + t[2] + "," + t[3]
+        + "," + t[4] + R"(],"events":{"dividends":{")" + t[1]
+        + R"(":{"amount":0.5,"date":)" + t[1] + R"(}},"splits":{")" + t[2]
+        + R"(":{"date":)" + t[2] + R"(,"numerator":2,"denominator":1,)"
+        R"("splitRatio":"2:1"}}},"indicators":{"quote":[{)"
+        R"("open":[50,51,52,53,null],"high":[51,52,53,54,null],)"
+        R"("low":[49,50,51,52,null],"close":[50,51,52,53,null],)"
+        R"("volume":[200,200,100,100,null]}],)"
+        R"("adjclose":[{"adjclose":[49.5,51,52,53,null]}]}}],"error":null}})";
+}
+
+HttpClient answer(const std::string& body, std::string* url = nullptr,
+        int status = 200) {
+    return [body, url, status](const HttpRequest& req, HttpResponse& res) {
+        if (url) *url = req.url;
+        res.status = status;
+        res.body = body;
+        return true;
+    };
+}
+
+std::vector<double> closes(const std::vector<Ohlcv>& bars) {
+    std::vector<double> out;
+    for (const auto& bar : bars) out.push_back(bar.close);
+    return out;
+}
+
+} // namespace
+
+TEST(YahooDownloader, AdjustsAsAsked) {
+    std::string url;
+    YahooDownloader yahoo(answer(yahoo_chart(), &url), "https://yahoo");
+    std::vector<Ohlcv> raw, splits, all;
+    std::vector<CorporateAction> actions;
+    ASSERT_TRUE(yahoo.download("AAPL", DAY0, DAY0 + 5 * 86400, raw,
+                PriceAdjustment::NONE, &actions)) << yahoo.last_error();
+    EXPECT_EQ(url, "https://yahoo/v8/finance/chart/AAPL?period1=1704153600"
+            "&period2=1704585600&interval=1d&events=div%7Csplit");
+    ASSERT_EQ(raw.size(), 4u);
+    EXPECT_EQ(raw[0].timestamp, DAY0);
+    EXPECT_EQ(raw[3].timestamp, DAY0 + 3 * 86400);
+    EXPECT_EQ(closes(raw), std::vector<double>({100.0, 102.0, 52.0, 53.0}));
+    EXPECT_DOUBLE_EQ(raw[0].high, 102.0);
+    EXPECT_DOUBLE_EQ(raw[0].volume, 100.0);
+    ASSERT_EQ(actions.size(), 2u);
+    EXPECT_EQ(actions[0].timestamp, DAY0 + 86400);
+    EXPECT_DOUBLE_EQ(actions[0].dividend, 1.0);
+    EXPECT_EQ(actions[1].timestamp, DAY0 + 2 * 86400);
+    EXPECT_DOUBLE_EQ(actions[1].split, 2.0);
+    ASSERT_TRUE(yahoo.download("AAPL", DAY0, DAY0 + 5 * 86400, splits,
+                PriceAdjustment::SPLITS));
+    EXPECT_EQ(closes(splits), std::vector<double>({50.0, 51.0, 52.0, 53.0}));
+    EXPECT_DOUBLE_EQ(splits[0].volume, 200.0);
+    ASSERT_TRUE(yahoo.download("AAPL", DAY0, DAY0 + 5 * 86400, all));
+    EXPECT_DOUBLE_EQ(all[0].close, 49.5);
+    EXPECT_DOUBLE_EQ(all[1].close, 51.0);
+}
+
+TEST(YahooDownloader, ReportsErrors) {
+    YahooDownloader missing(answer(R"({"chart":{"result":null,"error":)"
+                R"({"code":"Not Found","description":"No data found"}}})",
+                nullptr, 404));
+    std::vector<Ohlcv> bars;
+    EXPECT_FALSE(missing.download("NOPE", DAY0, DAY0 + 86400, bars));
+    EXPECT_NE(missing.last_error().find("HTTP 404 No data found"),
+            std::string::npos);
+    YahooDownloader empty(answer(R"({"chart":{"result":[{"meta":{}}]}})"));
+    EXPECT_FALSE(empty.download("AAPL", DAY0, DAY0 + 86400, bars));
+    EXPECT_TRUE(bars.empty());
+}
+
+TEST(StooqDownloader, ParsesDailyCsv) {
+    std::string url;
+    StooqDownloader stooq(answer("Date,Open,High,Low,Close,Volume\r\n"
+                "2024-01-02,187.15,188.44,183.885,185.64,82488674\r\n"
+                "2024-01-03,184.22,185.88,183.43,184.25,58414460\r\n", &url),
+            "https://stooq");
+    std::vector<Ohlcv> bars;
+    ASSERT_TRUE(stooq.download("aapl.us", DAY0, DAY0 + 2 * 86400, bars));
+    EXPECT_EQ(url, "https://stooq/q/d/l/?s=aapl.us&d1=20240102&d2=20240103"
+            "&i=d");
+    ASSERT_EQ(bars.size(), 2u);
+    EXPECT_EQ(bars[1].timestamp, DAY0 + 86400);
+    EXPECT_DOUBLE_EQ(bars[1].open, 184.22);
+    EXPECT_DOUBLE_EQ(bars[1].close, 184.25);
+    EXPECT_DOUBLE_EQ(bars[1].volume, 58414460.0);
+    StooqDownloader none(answer("No data"));
+    EXPECT_FALSE(none.download("nope.us", DAY0, DAY0 + 86400, bars));
+    EXPECT_NE(none.last_error().find("no data"), std::string::npos);
+}
+
+TEST(Datetime, CivilFromDays) {
+    int64_t year;
+    unsigned month, day;
+    civil_from_days(days_from_civil(2024, 2, 29), year, month, day);
+    EXPECT_EQ(year, 2024);
+    EXPECT_EQ(month, 2u);
+    EXPECT_EQ(day, 29u);
+    civil_from_days(-1, year, month, day);
+    EXPECT_EQ(year, 1969);
+    EXPECT_EQ(month, 12u);
+    EXPECT_EQ(day, 31u);
+}
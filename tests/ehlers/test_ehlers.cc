@@ -0,0 +1,107 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include "ehlers.h"
+
+using namespace tzu;
+
+namespace {
+
+const double pi = 3.14159265358979323846;
+
+double wave(int i, double period) {
+    return 100.0 + 5.0 * std::sin(2.0 * pi * i / period);
+}
+
+} // namespace
+
+TEST(SuperSmoother, FollowsTheRecursion) {
+    SuperSmoother ss(10);
+    double a = std::exp(-1.414 * pi / 10.0);
+    double c2 = 2.0 * a * std::cos(1.414 * pi / 10.0);
+    double c3 = -a * a;
+    double c1 = 1.0 - c2 - c3;
+    EXPECT_DOUBLE_EQ(ss.update(10.0), 10.0);
+    EXPECT_DOUBLE_EQ(ss.update(12.0), 12.0);
+    EXPECT_NEAR(ss.update(11.0), c1 * 11.5 + c2 * 12.0 + c3 * 10.0, 1e-12);
+}
+
+TEST(SuperSmoother, RemovesShortCycles) {
+    SuperSmoother ss(20);
+    double lo = 1e9, hi = -1e9;
+    for (int i = 0; i < 400; i++) {
+        double out = ss.update(wave(i, 5.0));
+        if (i >= 300) {
+            lo = std::fmin(lo, out);
+            hi = std::fmax(hi, out);
+        }
+    }
+    EXPECT_LT(hi - lo, 1.0);
+    EXPECT_NEAR((hi + lo) / 2.0, 100.0, 0.1);
+}
+
+TEST(Roofing, KeepsCyclesWithinTheBand) {
+    Roofing roof(48, 10);
+    double trend = 0.0, cycle = 0.0;
+    for (int i = 0; i < 400; i++) {
+        trend = roof.update(100.0 + 0.5 * i);
+    }
+    Roofing roof2(48, 10);
+    for (int i = 0; i < 400; i++) {
+        double out = roof2.update(wave(i, 20.0));
+        if (i >= 300) cycle = std::fmax(cycle, std::fabs(out));
+    }
+    EXPECT_NEAR(trend, 0.0, 1e-6);
+    EXPECT_GT(cycle, 2.5);
+}
+
+TEST(Decycler, FollowsTrendsWithoutCycles) {
+    Decycler trend(60), cycle(60);
+    double residual = 0.0;
+    for (int i = 0; i < 400; i++) {
+        trend.update(100.0 + 0.5 * i);
+        double out = cycle.update(wave(i, 10.0));
+        if (i >= 300) residual = std::fmax(residual, std::fabs(out - 100.0));
+    }
+    EXPECT_NEAR(trend.get(), 100.0 + 0.5 * 399, 1e-6);
+    EXPECT_LT(residual, 1.5);
+    Decycler flat(60);
+    EXPECT_DOUBLE_EQ(flat.update(50.0), 50.0);
+    for (int i = 0; i < 20; i++) flat.update(50.0);
+    EXPECT_NEAR(flat.get(), 50.0, 1e-12);
+}
+
+TEST(FisherTransform, TransformsThePositionInTheRange) {
+    FisherTransform fisher(3);
+    EXPECT_TRUE(std::isnan(fisher.update(Ohlcv(0, 1.0, 1.5, 0.5, 1.0, 0.0))));
+    EXPECT_TRUE(std::isnan(fisher.update(Ohlcv(1, 2.0, 2.5, 1.5, 2.0, 0.0))));
+    // the median 3 is at the top of the range 1..3
+    double first = fisher.update(Ohlcv(2, 3.0, 3.5, 2.5, 3.0, 0.0));
+    EXPECT_NEAR(first, 0.5 * std::log(1.33 / 0.67), 1e-12);
+    EXPECT_TRUE(std::isnan(fisher.trigger()));
+    double second = fisher.update(Ohlcv(3, 4.0, 4.5, 3.5, 4.0, 0.0));
+    EXPECT_GT(second, first);
+    EXPECT_DOUBLE_EQ(fisher.trigger(), first);
+}
+
+TEST(MAMA, MeasuresTheDominantCycle) {
+    MAMA mama;
+    for (int i = 0; i < 31; i++)
+        EXPECT_TRUE(std::isnan(mama.update(wave(i, 20.0)).mama));
+    for (int i = 31; i < 400; i++) mama.update(wave(i, 20.0));
+    EXPECT_NEAR(mama.period(), 20.0, 2.0);
+    MAMAResult r = mama.get();
+    EXPECT_NEAR(r.mama, 100.0, 5.0);
+    EXPECT_NEAR(r.fama, 100.0, 5.0);
+}
+
+TEST(MAMA, FollowsATrend) {
+    MAMA mama;
+    MAMAResult r = {0.0, 0.0};
+    for (int i = 0; i < 200; i++) r = mama.update(100.0 + i);
+    EXPECT_LT(r.mama, 299.0);
+    EXPECT_GT(r.mama, r.fama);
+    MAMA flat;
+    for (int i = 0; i < 50; i++) r = flat.update(42.0);
+    EXPECT_DOUBLE_EQ(r.mama, 42.0);
+    EXPECT_DOUBLE_EQ(r.fama, 42.0);
+}
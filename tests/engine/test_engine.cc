@@ -0,0 +1,113 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <string>
+#include <vector>
+#include "engine.h"
+
+using namespace tzu;
+
+namespace {
+
+Ohlcv bar_at(int64_t t, double close) {
+    return Ohlcv(t, close, close + 1.0, close - 1.0, close, 100.0);
+}
+
+double price(size_t symbol, int t) {
+    return 50.0 + 10.0 * symbol + 5.0 * std::sin(t * 0.2 + symbol);
+}
+
+} // namespace
+
+TEST(IndicatorEngine, KeepsIndicatorsPerSymbol) {
+    IndicatorEngine engine;
+    ASSERT_TRUE(engine.add("sma", SMA(2)));
+    ASSERT_FALSE(engine.add("sma", SMA(3)));
+    engine.update("A", bar_at(0, 1.0));
+    engine.update("B", bar_at(0, 10.0));
+    engine.update("A", bar_at(1, 3.0));
+    engine.update("B", bar_at(1, 20.0));
+    EXPECT_EQ(engine.size(), 2u);
+    EXPECT_DOUBLE_EQ(engine.get("A", "sma"), 2.0);
+    EXPECT_DOUBLE_EQ(engine.get("B", "sma"), 15.0);
+    EXPECT_TRUE(std::isnan(engine.get("C", "sma")));
+    EXPECT_TRUE(std::isnan(engine.get("A", "ema")));
+}
+
+TEST(IndicatorEngine, RejectsBarsNotNewer) {
+    IndicatorEngine engine;
+    engine.add("sma", SMA(2));
+    EXPECT_TRUE(engine.update("A", bar_at(5, 1.0)));
+    EXPECT_FALSE(engine.update("A", bar_at(5, 100.0)));
+    EXPECT_FALSE(engine.update("A", bar_at(4, 100.0)));
+    EXPECT_TRUE(engine.update("B", bar_at(4, 1.0)));
+    EXPECT_TRUE(engine.update("A", bar_at(6, 3.0)));
+    EXPECT_DOUBLE_EQ(engine.get("A", "sma"), 2.0);
+}
+
+TEST(IndicatorEngine, SnapshotsAndNamedOutputs) {
+    IndicatorEngine engine;
+    engine.add("macd", MACD(3, 5, 2));
+    engine.add("high", Highest(3), OhlcvField::HIGH);
+    for (int t = 0; t < 20; t++) {
+        engine.update("X", bar_at(t, 10.0 + t));
+        engine.update("Y", bar_at(t, 30.0 - t));
+    }
+    std::vector<SymbolSnapshot> all = engine.snapshot();
+    ASSERT_EQ(all.size(), 2u);
+    EXPECT_EQ(all[0].symbol, "X");
+    EXPECT_EQ(all[0].timestamp, 19);
+    EXPECT_DOUBLE_EQ(all[0].values.at("high"), 30.0);
+    EXPECT_DOUBLE_EQ(all[1].values.at("high"), 14.0);
+    EXPECT_GT(all[0].values.at("macd"), 0.0);
+    EXPECT_LT(all[1].values.at("macd"), 0.0);
+    double signal = 0.0;
+    EXPECT_TRUE(engine.get_output("X", "macd", "signal", signal));
+    EXPECT_FALSE(std::isnan(signal));
+    EXPECT_FALSE(engine.get_output("X", "macd", "nope", signal));
+    ASSERT_NE(engine.indicator("Y", "macd"), nullptr);
+    EXPECT_NE(engine.indicator("Y", "macd")->target<MACD>(), nullptr);
+    EXPECT_TRUE(engine.snapshot("Z").values.empty());
+}
+
+TEST(IndicatorEngine, IndicatorsAddedLaterStartFresh) {
+    IndicatorEngine engine;
+    engine.add("sma", SMA(2));
+    engine.update("A", bar_at(0, 1.0));
+    engine.add("ema", EMA(2));
+    EXPECT_TRUE(std::isnan(engine.get("A", "ema")));
+    engine.update("A", bar_at(1, 3.0));
+    engine.update("A", bar_at(2, 5.0));
+    EXPECT_FALSE(std::isnan(engine.get("A", "ema")));
+    EXPECT_DOUBLE_EQ(engine.get("A", "sma"), 4.0);
+}
+
+TEST(IndicatorEngine, ParallelBatchesMatchSequentialUpdates) {
+    IndicatorEngine sequential, parallel;
+    for (IndicatorEngine* e : {&sequential, &parallel}) {
+        e->add("rsi", RSI(14));
+        e->add("mvar", MVar(10, 1));
+        e->add("adx", ADX(5));
+    }
+    std::vector<SymbolBar> batch;
+    for (int t = 0; t < 200; t++) {
+        for (size_t s = 0; s < 40; s++) {
+            std::string symbol = "S" + std::to_string(s);
+            double c = price(s, t);
+            Ohlcv bar(t, price(s, t - 1), c + 1.0, c - 1.0, c, 100.0);
+            batch.push_back(SymbolBar{symbol, bar});
+            sequential.update(symbol, bar);
+        }
+    }
+    // a duplicate is rejected as in sequential updates
+    batch.push_back(batch.back());
+    EXPECT_EQ(parallel.update(batch, 4), batch.size() - 1);
+    std::vector<SymbolSnapshot> a = sequential.snapshot();
+    std::vector<SymbolSnapshot> b = parallel.snapshot();
+    ASSERT_EQ(a.size(), b.size());
+    for (size_t i = 0; i < a.size(); i++) {
+        EXPECT_EQ(a[i].symbol, b[i].symbol);
+        EXPECT_EQ(a[i].timestamp, b[i].timestamp);
+        EXPECT_FALSE(std::isnan(b[i].values.at("rsi")));
+        EXPECT_EQ(a[i].values, b[i].values);
+    }
+}
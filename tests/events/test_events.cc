@@ -0,0 +1,91 @@
+#include <gtest/gtest.h>
+#include <string>
+#include <vector>
+#include "events.h"
+
+using namespace tzu;
+
+namespace {
+
+Ohlcv bar_at(int64_t t, double close) {
+    return Ohlcv(t, close, close + 1.0, close - 1.0, close, 1000.0);
+}
+
+// buys at the first bar and sells at the third
+struct OnceStrat {
+    int count = 0;
+    Signal update(const Ohlcv& bar) {
+        count++;
+        Side side = count == 1 ? Side::BUY
+            : count == 3 ? Side::SELL : Side::NONE;
+        return Signal(bar.timestamp, side, bar.close);
+    }
+};
+
+} // namespace
+
+TEST(EventBus, DeliversInPublishingOrder) {
+    EventBus bus;
+    std::vector<std::string> log;
+    bus.subscribe<MarketEvent>([&](const MarketEvent& e) {
+        log.push_back("market " + e.symbol);
+        bus.publish(SignalEvent{e.symbol, Signal(e.bar.timestamp, Side::BUY)});
+    });
+    bus.subscribe<MarketEvent>([&](const MarketEvent& e) {
+        log.push_back("market2 " + e.symbol);
+    });
+    bus.subscribe<SignalEvent>([&](const SignalEvent& e) {
+        log.push_back("signal " + e.symbol);
+    });
+    bus.publish(MarketEvent{"A", bar_at(1, 10.0)});
+    std::vector<std::string> expected = {"market A", "market2 A", "signal A"};
+    EXPECT_EQ(log, expected);
+    EXPECT_EQ(bus.num_events(), 2u);
+}
+
+TEST(EventBus, RunsABacktestThroughTheComponents) {
+    EventBus bus;
+    OnceStrat strat;
+    SimulatedBroker broker(1000.0);
+    std::vector<Fill> fills;
+    connect_strategy(bus, "A", strat);
+    connect_risk(bus, fixed_size(2.0));
+    connect_broker(bus, broker);
+    bus.subscribe<FillEvent>([&](const FillEvent& e) {
+        fills.push_back(e.fill);
+    });
+    std::vector<Ohlcv> bars = {bar_at(1, 10.0), bar_at(2, 11.0),
+        bar_at(3, 12.0), bar_at(4, 13.0)};
+    EXPECT_EQ(replay(bus, "A", bars), 4u);
+    bus.publish(MarketEvent{"B", bar_at(5, 1.0)});
+    EXPECT_EQ(strat.count, 4);
+    ASSERT_EQ(fills.size(), 2u);
+    EXPECT_EQ(fills[0].timestamp, 2);
+    EXPECT_DOUBLE_EQ(fills[0].quantity, 2.0);
+    EXPECT_DOUBLE_EQ(fills[0].price, 11.0);
+    EXPECT_EQ(fills[1].timestamp, 4);
+    EXPECT_DOUBLE_EQ(fills[1].quantity, -2.0);
+    EXPECT_DOUBLE_EQ(broker.account().cash(), 1004.0);
+}
+
+TEST(EventBus, RiskCheckerResizesOrders) {
+    EventBus bus;
+    OnceStrat strat;
+    SimulatedBroker broker(1000.0);
+    RiskLimits limits;
+    limits.max_position = 3.0;
+    RiskChecker checker(limits);
+    std::vector<Order> orders;
+    connect_strategy(bus, "A", strat);
+    connect_risk(bus, fixed_size(5.0), &checker);
+    connect_broker(bus, broker);
+    bus.subscribe<OrderEvent>([&](const OrderEvent& e) {
+        orders.push_back(e.order);
+    });
+    std::vector<Ohlcv> bars = {bar_at(1, 10.0), bar_at(2, 11.0)};
+    replay(bus, "A", bars);
+    ASSERT_EQ(orders.size(), 1u);
+    EXPECT_DOUBLE_EQ(orders[0].quantity, 3.0);
+    EXPECT_EQ(orders[0].timestamp, 1);
+    EXPECT_DOUBLE_EQ(broker.account().cash(), 967.0);
+}
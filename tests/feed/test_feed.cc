@@ -0,0 +1,92 @@
+#include <gtest/gtest.h>
+#include <string>
+#include <thread>
+#include <vector>
+#include "feed.h"
+#include "indicators.h"
+
+using namespace tzu;
+
+namespace {
+
+const char* KLINE_OPEN =
+    "{\"e\":\"kline\",\"E\":1700000030000,\"s\":\"BTCUSDT\",\"k\":{"
+    "\"t\":1700000000000,\"T\":1700000059999,\"s\":\"BTCUSDT\",\"i\":\"1m\","
+    "\"o\":\"100.0\",\"c\":\"101.0\",\"h\":\"102.0\",\"l\":\"99.0\","
+    "\"v\":\"5.5\",\"x\":false}}";
+
+const char* KLINE_CLOSED =
+    "{\"e\":\"kline\",\"E\":1700000060000,\"s\":\"BTCUSDT\",\"k\":{"
+    "\"t\":1700000000000,\"T\":1700000059999,\"s\":\"BTCUSDT\",\"i\":\"1m\","
+    "\"o\":\"100.0\",\"c\":\"101.5\",\"h\":\"102.0\",\"l\":\"99.0\","
+    "\"v\":\"7.25\",\"x\":true}}";
+
+struct Replay {
+    std::vector<std::string>* messages;
+    size_t pos;
+    bool operator()(std::string& message) {
+        if (pos >= messages->size()) return false;
+        message = (*messages)[pos++];
+        return true;
+    }
+};
+
+} // namespace
+
+TEST(Feed, YieldsClosedKlines) {
+    std::vector<std::string> messages = {
+        "{\"result\":null,\"id\":1}", KLINE_OPEN, KLINE_CLOSED
+    };
+    auto feed = make_feed<Ohlcv, BinanceKlineParser>(Replay{&messages, 0});
+    std::vector<Ohlcv> bars;
+    for (const auto& bar : feed) bars.push_back(bar);
+    ASSERT_EQ(bars.size(), 1u);
+    EXPECT_EQ(bars[0].timestamp, 1700000000);
+    EXPECT_DOUBLE_EQ(bars[0].open, 100.0);
+    EXPECT_DOUBLE_EQ(bars[0].high, 102.0);
+    EXPECT_DOUBLE_EQ(bars[0].low, 99.0);
+    EXPECT_DOUBLE_EQ(bars[0].close, 101.5);
+    EXPECT_DOUBLE_EQ(bars[0].volume, 7.25);
+    EXPECT_EQ(feed.num_skipped(), 2u);
+}
+
+TEST(Feed, ParsesTrades) {
+    Tick tick;
+    ASSERT_TRUE(BinanceTradeParser::parse(
+        "{\"e\":\"trade\",\"E\":1700000000100,\"s\":\"BTCUSDT\",\"t\":12345,"
+        "\"p\":\"100.25\",\"q\":\"0.5\",\"T\":1700000000050,\"m\":true}", tick));
+    EXPECT_EQ(tick.timestamp, 1700000000050);
+    EXPECT_DOUBLE_EQ(tick.price, 100.25);
+    EXPECT_DOUBLE_EQ(tick.volume, 0.5);
+    EXPECT_EQ(tick.side, Side::SELL);
+    EXPECT_FALSE(BinanceTradeParser::parse(KLINE_CLOSED, tick));
+}
+
+TEST(Feed, MessageQueueBridgesThreads) {
+    MessageQueue queue;
+    std::thread client([&queue] {
+        for (int i = 0; i < 3; i++) queue.push(KLINE_CLOSED);
+        queue.close();
+    });
+    auto feed = make_feed<Ohlcv, BinanceKlineParser>(queue.receiver());
+    SMA sma(3);
+    double value = 0.0;
+    for (const auto& bar : feed) value = sma.update(bar.close);
+    client.join();
+    EXPECT_DOUBLE_EQ(value, 101.5);
+}
+
+TEST(Feed, ChannelTakesWithoutWaiting) {
+    Channel<int> channel;
+    int value = 0;
+    EXPECT_FALSE(channel.try_pop(value));
+    channel.push(1);
+    channel.push(2);
+    ASSERT_TRUE(channel.try_pop(value));
+    EXPECT_EQ(value, 1);
+    channel.close();
+    channel.push(3);
+    ASSERT_TRUE(channel.pop(value));
+    EXPECT_EQ(value, 2);
+    EXPECT_FALSE(channel.pop(value));
+}
@@ -0,0 +1,38 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include "filters.h"
+
+using namespace tzu;
+
+TEST(Kalman, CorrectsThePrediction) {
+    Kalman kalman(1.0, 1.0);
+    EXPECT_DOUBLE_EQ(kalman.update(10.0), 10.0);
+    EXPECT_TRUE(std::isnan(kalman.gain()));
+    // prior variance 1 + 1, gain 2 / 3
+    EXPECT_NEAR(kalman.update(12.0), 10.0 + 2.0 / 3.0 * 2.0, 1e-12);
+    EXPECT_NEAR(kalman.variance(), 2.0 / 3.0, 1e-12);
+    EXPECT_DOUBLE_EQ(kalman.slope(), 0.0);
+}
+
+TEST(Kalman, GainSettles) {
+    Kalman kalman(1.0, 1.0);
+    for (int i = 0; i < 50; i++) kalman.update(5.0);
+    EXPECT_NEAR(kalman.gain(), (std::sqrt(5.0) - 1.0) / 2.0, 1e-9);
+    EXPECT_DOUBLE_EQ(kalman.get(), 5.0);
+    Kalman slow(0.01, 1.0);
+    for (int i = 0; i < 50; i++) slow.update(5.0);
+    EXPECT_LT(slow.gain(), 0.2);
+}
+
+TEST(Kalman, TrendFollowsSlope) {
+    Kalman trend = Kalman::trend(0.01, 0.001, 1.0);
+    Kalman level(0.01, 1.0);
+    for (int t = 0; t < 200; t++) {
+        trend.update(2.0 * t + 1.0);
+        level.update(2.0 * t + 1.0);
+    }
+    EXPECT_NEAR(trend.slope(), 2.0, 1e-6);
+    EXPECT_NEAR(trend.get(), 2.0 * 199 + 1.0, 1e-6);
+    // without a slope, the level lags behind
+    EXPECT_LT(level.get(), 2.0 * 199 + 1.0 - 5.0);
+}
@@ -0,0 +1,60 @@
+#include <gtest/gtest.h>
+#include "fixtures.h"
+#include <sstream>
+
+using namespace tzu;
+
+static std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 10; ++i)
+        bars.push_back(Ohlcv(i, 10.0 + i, 12.0 + i, 9.0 + i, 11.0 + i % 3, 1.0));
+    return bars;
+}
+
+TEST(Fixtures, RoundTripsThroughFiles) {
+    FixtureSet set;
+    ASSERT_TRUE(set.add("sma:3"));
+    ASSERT_TRUE(set.add("rsi:2"));
+    EXPECT_FALSE(set.add("foo:3"));
+    EXPECT_FALSE(set.add("sma:x"));
+    Fixture fixture = set.run(sample_bars(), "v1");
+    std::stringstream file;
+    write_fixture(file, fixture);
+    Fixture loaded;
+    ASSERT_TRUE(read_fixture(file, loaded));
+    EXPECT_EQ(loaded.label, "v1");
+    EXPECT_EQ(loaded.columns, fixture.columns);
+    EXPECT_TRUE(std::isnan(loaded.values[0][0]));
+    EXPECT_TRUE(compare_fixtures(fixture, loaded, 0.0).ok());
+}
+
+TEST(Fixtures, ReportsChangedValues) {
+    FixtureSet set;
+    set.add("sma:3");
+    Fixture golden = set.run(sample_bars());
+    Fixture current = golden;
+    current.values[5][0] += 1e-6;
+    FixtureReport report = compare_fixtures(golden, current, 1e-9);
+    EXPECT_FALSE(report.ok());
+    ASSERT_EQ(report.mismatches.size(), 1u);
+    EXPECT_EQ(report.mismatches[0].timestamp, 5);
+    EXPECT_TRUE(compare_fixtures(golden, current, 1e-6).ok());
+}
+
+TEST(Fixtures, ReportsColumnChanges) {
+    FixtureSet a;
+    a.add("sma:3");
+    a.add("ema:3");
+    FixtureSet b;
+    b.add("sma:3");
+    b.add("mvar:3");
+    FixtureReport report = compare_fixtures(a.run(sample_bars()),
+            b.run(sample_bars()));
+    ASSERT_EQ(report.missing_columns.size(), 1u);
+    EXPECT_EQ(report.missing_columns[0], "ema:3");
+    ASSERT_EQ(report.new_columns.size(), 1u);
+    EXPECT_TRUE(report.mismatches.empty());
+    std::stringstream bad("timestamp,sma:3\n1,2\n");
+    Fixture f;
+    EXPECT_FALSE(read_fixture(bad, f));
+}
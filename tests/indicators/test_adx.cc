@@ -0,0 +1,42 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+static Ohlcv trending_bar(int i) {
+    double base = 100.0 + i;
+    return Ohlcv(i, base, base + 1.5, base - 0.5, base + 1.0, 10.0);
+}
+
+TEST(ADX, ReturnsNaNDuringWarmup) {
+    ADX adx(3);
+    for (int i = 0; i < 3; ++i) {
+        ADXResult r = adx.update(trending_bar(i));
+        EXPECT_TRUE(std::isnan(r.plus_di));
+        EXPECT_TRUE(std::isnan(r.adx));
+    }
+    ADXResult r = adx.update(trending_bar(3));
+    EXPECT_FALSE(std::isnan(r.plus_di));
+    EXPECT_TRUE(std::isnan(r.adx));
+    adx.update(trending_bar(4));
+    EXPECT_FALSE(std::isnan(adx.update(trending_bar(5)).adx));
+}
+
+TEST(ADX, StrongUptrendHasHighADXAndPositiveDI) {
+    ADX adx(5);
+    ADXResult r;
+    for (int i = 0; i < 30; ++i) r = adx.update(trending_bar(i));
+    EXPECT_DOUBLE_EQ(r.minus_di, 0.0);
+    EXPECT_NEAR(r.plus_di, 50.0, 1e-9);
+    EXPECT_NEAR(r.adx, 100.0, 1e-9);
+}
+
+TEST(ADX, ResetRestartsWarmup) {
+    ADX adx(2);
+    for (int i = 0; i < 10; ++i) adx.update(trending_bar(i));
+    EXPECT_FALSE(std::isnan(adx.get().adx));
+    adx.reset();
+    EXPECT_TRUE(std::isnan(adx.get().adx));
+    EXPECT_TRUE(std::isnan(adx.update(trending_bar(0)).plus_di));
+}
@@ -0,0 +1,30 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(Applied, SelectsBarPrice) {
+    Ohlcv bar(0, 10.0, 14.0, 8.0, 12.0, 1.0);
+    EXPECT_DOUBLE_EQ(bar_price(bar, PriceSource::OPEN), 10.0);
+    EXPECT_DOUBLE_EQ(bar_price(bar, PriceSource::MEDIAN), 11.0);
+    EXPECT_DOUBLE_EQ(bar_price(bar, PriceSource::TYPICAL), 34.0 / 3.0);
+    EXPECT_DOUBLE_EQ(bar_price(bar, PriceSource::WEIGHTED), 11.5);
+}
+
+TEST(Applied, FeedsWrappedIndicator) {
+    Applied<SMA> sma(SMA(2), PriceSource::MEDIAN);
+    EXPECT_TRUE(std::isnan(sma.update(Ohlcv(0, 1.0, 4.0, 2.0, 3.0, 1.0))));
+    EXPECT_DOUBLE_EQ(sma.update(Ohlcv(1, 1.0, 8.0, 4.0, 3.0, 1.0)), 4.5);
+    EXPECT_DOUBLE_EQ(sma.inner().get(), 4.5);
+}
+
+TEST(Applied, KeepsStructuredOutput) {
+    auto macd = make_applied(MACD(2, 3, 2), PriceSource::TYPICAL);
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 6; ++i)
+        bars.push_back(Ohlcv(i, 10.0, 12.0 + i, 8.0, 11.0, 1.0));
+    std::vector<MACDResult> out = macd.compute_batch(bars);
+    ASSERT_EQ(out.size(), 6u);
+    EXPECT_FALSE(std::isnan(out.back().macd));
+}
@@ -0,0 +1,44 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include "indicators.h"
+#include "serialize.h"
+
+using namespace tzu;
+
+TEST(Aroon, ReturnsNaNDuringWarmup) {
+    Aroon aroon(3);
+    for (int i = 0; i < 3; ++i) {
+        AroonResult r = aroon.update(Ohlcv(i, 10.0, 11.0, 9.0, 10.0, 1.0));
+        EXPECT_TRUE(std::isnan(r.up));
+        EXPECT_TRUE(std::isnan(r.down));
+    }
+    EXPECT_FALSE(std::isnan(aroon.update(Ohlcv(3, 10.0, 11.0, 9.0, 10.0, 1.0)).up));
+}
+
+TEST(Aroon, MeasuresAgeOfExtremes) {
+    Aroon aroon(4);
+    double highs[] = {10.0, 14.0, 12.0, 11.0, 13.0};
+    double lows[] = {5.0, 8.0, 7.0, 4.0, 6.0};
+    AroonResult r = {0.0, 0.0, 0.0};
+    for (int i = 0; i < 5; ++i)
+        r = aroon.update(Ohlcv(i, 0.0, highs[i], lows[i], 0.0, 1.0));
+    // highest high 3 bars ago, lowest low 1 bar ago
+    EXPECT_DOUBLE_EQ(r.up, 25.0);
+    EXPECT_DOUBLE_EQ(r.down, 75.0);
+    EXPECT_DOUBLE_EQ(r.oscillator, -50.0);
+    // a new high: the most recent bar wins ties
+    r = aroon.update(Ohlcv(5, 0.0, 14.0, 6.0, 0.0, 1.0));
+    EXPECT_DOUBLE_EQ(r.up, 100.0);
+    EXPECT_DOUBLE_EQ(aroon.get().down, 50.0);
+}
+
+TEST(Aroon, Serializes) {
+    std::stringstream ss;
+    ss << AroonResult{100.0, 25.0, 75.0};
+    EXPECT_EQ(ss.str(), "up:100 down:25 oscillator:75");
+    AroonResult r = {0.0, 0.0, 0.0};
+    ss >> r;
+    EXPECT_DOUBLE_EQ(r.down, 25.0);
+    EXPECT_DOUBLE_EQ(r.oscillator, 75.0);
+}
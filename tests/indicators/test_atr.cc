@@ -0,0 +1,19 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(ATR, AveragesTrueRange) {
+    ATR atr(2);
+    EXPECT_TRUE(std::isnan(atr.update(Ohlcv(0, 10.0, 11.0, 9.0, 10.0, 1.0))));
+    // gap up: true range from the previous close
+    EXPECT_DOUBLE_EQ(atr.update(Ohlcv(1, 13.0, 14.0, 13.0, 13.5, 1.0)), 3.0);
+    EXPECT_DOUBLE_EQ(atr.update(Ohlcv(2, 13.5, 14.5, 13.5, 14.0, 1.0)), 2.0);
+}
+
+TEST(ATR, TrueRangeWithoutPreviousClose) {
+    Ohlcv bar(0, 10.0, 12.0, 9.0, 11.0, 1.0);
+    EXPECT_DOUBLE_EQ(true_range(bar, std::nan("")), 3.0);
+    EXPECT_DOUBLE_EQ(true_range(bar, 15.0), 6.0);
+}
@@ -0,0 +1,54 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+#include <vector>
+
+using namespace tzu;
+
+static std::vector<double> series(size_t n) {
+    std::vector<double> v;
+    for (size_t i = 0; i < n; ++i) v.push_back(100.0 + std::sin(i * 0.1) * 10.0);
+    return v;
+}
+
+TEST(ComputeBatch, MatchesStreamingUpdates) {
+    std::vector<double> values = series(200);
+    EMA batch(10);
+    EMA stream(10);
+    std::vector<double> out = batch.compute_batch(values);
+    ASSERT_EQ(out.size(), values.size());
+    for (size_t i = 0; i < values.size(); ++i) {
+        double expected = stream.update(values[i]);
+        if (std::isnan(expected)) EXPECT_TRUE(std::isnan(out[i]));
+        else EXPECT_DOUBLE_EQ(out[i], expected);
+    }
+}
+
+TEST(ComputeBatch, SMAFastPathKeepsStateConsistent) {
+    std::vector<double> values = series(50);
+    SMA batch(7);
+    SMA stream(7);
+    std::vector<double> out = batch.compute_batch(values);
+    for (size_t i = 0; i < values.size(); ++i) {
+        double expected = stream.update(values[i]);
+        if (i < 6) EXPECT_TRUE(std::isnan(out[i]));
+        else EXPECT_DOUBLE_EQ(out[i], expected);
+    }
+    EXPECT_DOUBLE_EQ(batch.get(), stream.get());
+    EXPECT_DOUBLE_EQ(batch.update(3.0), stream.update(3.0));
+    std::vector<double> more = batch.compute_batch(std::vector<double>{1.0, 2.0});
+    stream.update(1.0);
+    EXPECT_DOUBLE_EQ(more[1], stream.update(2.0));
+}
+
+TEST(ComputeBatch, ShortInputStaysInWarmup) {
+    SMA sma(5);
+    std::vector<double> out = sma.compute_batch(std::vector<double>{1.0, 2.0});
+    EXPECT_TRUE(std::isnan(out[1]));
+    sma.update(3.0);
+    sma.update(4.0);
+    EXPECT_DOUBLE_EQ(sma.update(5.0), 3.0);
+    RSI rsi(2);
+    std::vector<Ohlcv> bars(3, Ohlcv(0, 1.0, 1.0, 1.0, 2.0, 0.0));
+    EXPECT_EQ(rsi.compute_batch(bars).size(), 3u);
+}
@@ -0,0 +1,198 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <type_traits>
+#include <vector>
+#include "anchored.h"
+#include "calendar.h"
+#include "ehlers.h"
+#include "filters.h"
+#include "indicators.h"
+#include "orderbook.h"
+#include "regime.h"
+#include "statistics.h"
+#include "transforms.h"
+#include "volatility.h"
+
+using namespace tzu;
+
+namespace {
+
+template <typename T>
+using Base = Indicator<T, typename T::input_type, typename T::output_type>;
+
+// every indicator derives from the common base with its own types
+static_assert(std::is_base_of<Base<SMA>, SMA>::value, "SMA");
+static_assert(std::is_base_of<Base<EMA>, EMA>::value, "EMA");
+static_assert(std::is_base_of<Base<WMA>, WMA>::value, "WMA");
+static_assert(std::is_base_of<Base<HMA>, HMA>::value, "HMA");
+static_assert(std::is_base_of<Base<ZLEMA>, ZLEMA>::value, "ZLEMA");
+static_assert(std::is_base_of<Base<T3>, T3>::value, "T3");
+static_assert(std::is_base_of<Base<KAMA>, KAMA>::value, "KAMA");
+static_assert(std::is_base_of<Base<Kalman>, Kalman>::value, "Kalman");
+static_assert(std::is_base_of<Base<SuperSmoother>, SuperSmoother>::value, "SuperSmoother");
+static_assert(std::is_base_of<Base<Roofing>, Roofing>::value, "Roofing");
+static_assert(std::is_base_of<Base<Decycler>, Decycler>::value, "Decycler");
+static_assert(std::is_base_of<Base<FisherTransform>, FisherTransform>::value, "FisherTransform");
+static_assert(std::is_base_of<Base<MAMA>, MAMA>::value, "MAMA");
+static_assert(std::is_base_of<Base<VWAP>, VWAP>::value, "VWAP");
+static_assert(std::is_base_of<Base<CumulativeReturn>, CumulativeReturn>::value, "CumulativeReturn");
+static_assert(std::is_base_of<Base<Anchored<VWAP>>, Anchored<VWAP>>::value, "Anchored");
+static_assert(std::is_base_of<Base<MVar>, MVar>::value, "MVar");
+static_assert(std::is_base_of<Base<Sharpe>, Sharpe>::value, "Sharpe");
+static_assert(std::is_base_of<Base<Sortino>, Sortino>::value, "Sortino");
+static_assert(std::is_base_of<Base<RSI>, RSI>::value, "RSI");
+static_assert(std::is_base_of<Base<MACD>, MACD>::value, "MACD");
+static_assert(std::is_base_of<Base<LinReg>, LinReg>::value, "LinReg");
+static_assert(std::is_base_of<Base<ATR>, ATR>::value, "ATR");
+static_assert(std::is_base_of<Base<ADX>, ADX>::value, "ADX");
+static_assert(std::is_base_of<Base<ElderRay>, ElderRay>::value, "ElderRay");
+static_assert(std::is_base_of<Base<Highest>, Highest>::value, "Highest");
+static_assert(std::is_base_of<Base<Lowest>, Lowest>::value, "Lowest");
+static_assert(std::is_base_of<Base<Aroon>, Aroon>::value, "Aroon");
+static_assert(std::is_base_of<Base<StochRSI>, StochRSI>::value, "StochRSI");
+static_assert(std::is_base_of<Base<Pivots>, Pivots>::value, "Pivots");
+static_assert(std::is_base_of<Base<TimeSMA>, TimeSMA>::value, "TimeSMA");
+static_assert(std::is_base_of<Base<TimeMVar>, TimeMVar>::value, "TimeMVar");
+static_assert(std::is_base_of<Base<EWMAVol>, EWMAVol>::value, "EWMAVol");
+static_assert(std::is_base_of<Base<GARCH>, GARCH>::value, "GARCH");
+static_assert(std::is_base_of<Base<P2Quantile>, P2Quantile>::value, "P2Quantile");
+static_assert(std::is_base_of<Base<Quantile>, Quantile>::value, "Quantile");
+static_assert(std::is_base_of<Base<Percentile>, Percentile>::value, "Percentile");
+static_assert(std::is_base_of<Base<PercentRank>, PercentRank>::value, "PercentRank");
+static_assert(std::is_base_of<Base<Hurst>, Hurst>::value, "Hurst");
+static_assert(std::is_base_of<Base<VarianceRatio>, VarianceRatio>::value, "VarianceRatio");
+static_assert(std::is_base_of<Base<ZScore>, ZScore>::value, "ZScore");
+static_assert(std::is_base_of<Base<Correlation>, Correlation>::value, "Correlation");
+static_assert(std::is_base_of<Base<Beta>, Beta>::value, "Beta");
+static_assert(std::is_base_of<Base<FracDiff>, FracDiff>::value, "FracDiff");
+static_assert(std::is_base_of<Base<HeikinAshi>, HeikinAshi>::value, "HeikinAshi");
+static_assert(std::is_base_of<Base<SessionVWAP>, SessionVWAP>::value, "SessionVWAP");
+static_assert(std::is_base_of<Base<BidAskSpread>, BidAskSpread>::value, "BidAskSpread");
+static_assert(std::is_base_of<Base<BookImbalance>, BookImbalance>::value, "BookImbalance");
+static_assert(std::is_base_of<Base<Microprice>, Microprice>::value, "Microprice");
+
+bool same(double a, double b) {
+    return (std::isnan(a) && std::isnan(b)) || a == b;
+}
+
+// the batch API and `get` agree with the updates one by one
+template <typename Ind>
+void check_scalar(Ind a, const std::vector<double>& xs) {
+    Ind b = a;
+    std::vector<double> batch = b.compute_batch(xs);
+    ASSERT_EQ(batch.size(), xs.size());
+    for (size_t i = 0; i < xs.size(); i++) {
+        double v = a.update(xs[i]);
+        EXPECT_TRUE(same(v, batch[i])) << i;
+    }
+    EXPECT_TRUE(same(a.get(), b.get()));
+}
+
+// outputs are missing during the warmup, and all of them are finite
+// from `lookback()` on, so no placeholder leaks from internal buffers
+template <typename Ind>
+void check_outputs(Ind ind) {
+    using Out = typename Ind::output_type;
+    size_t lookback = ind.lookback();
+    for (int i = 0; i < 120; i++) {
+        double c = 100.0 + 10.0 * std::sin(i * 0.4) + 0.3 * i;
+        Ohlcv bar(i, c - 0.7, c + 1.2, c - 1.4, c, 50.0 + i);
+        Out out = ind.update(detail::from_bar<typename Ind::input_type>(bar));
+        std::vector<double> values = Outputs<Out>::values(out);
+        size_t n = static_cast<size_t>(i);
+        EXPECT_EQ(ind.is_ready(), n >= lookback) << i;
+        bool missing = false, finite = true;
+        for (double v : values) {
+            if (std::isnan(v)) missing = true;
+            if (!std::isfinite(v)) finite = false;
+        }
+        if (n < lookback) EXPECT_TRUE(missing) << i;
+        else EXPECT_TRUE(finite) << i;
+    }
+}
+
+} // namespace
+
+TEST(Conformance, NoMissingOutputsAfterWarmup) {
+    check_outputs(SMA(5));
+    check_outputs(EMA(5));
+    check_outputs(WMA(5));
+    check_outputs(HMA(9));
+    check_outputs(ZLEMA(5));
+    check_outputs(T3(3));
+    check_outputs(KAMA(10));
+    check_outputs(Kalman(0.1));
+    check_outputs(Kalman::trend(0.1, 0.01));
+    check_outputs(SuperSmoother(10));
+    check_outputs(Roofing(48, 10));
+    check_outputs(Decycler(60));
+    check_outputs(FisherTransform(10));
+    check_outputs(MAMA());
+    check_outputs(VWAP());
+    check_outputs(CumulativeReturn());
+    check_outputs(MVar(5, 1));
+    check_outputs(Sharpe(10));
+    check_outputs(Sortino(10));
+    check_outputs(RSI(14));
+    check_outputs(MACD(12, 26, 9));
+    check_outputs(LinReg(10));
+    check_outputs(ATR(14));
+    check_outputs(ADX(14));
+    check_outputs(ElderRay(13));
+    check_outputs(Highest(10));
+    check_outputs(Lowest(10));
+    check_outputs(Aroon(14));
+    check_outputs(StochRSI(14, 14, 3, 3));
+    check_outputs(Pivots());
+    check_outputs(EWMAVol(10));
+    check_outputs(GARCH(1e-5, 0.1, 0.8));
+    check_outputs(GARCH::fit(30, 10));
+    check_outputs(P2Quantile(0.5));
+    check_outputs(Quantile(0.5, 20));
+    check_outputs(Percentile(0.9, 10));
+    check_outputs(PercentRank(10));
+    check_outputs(Hurst(40, 8));
+    check_outputs(VarianceRatio(20, 4));
+    check_outputs(ZScore(10));
+    check_outputs(FracDiff(0.4, 10));
+    check_outputs(WaveletDenoise(16));
+    check_outputs(HeikinAshi());
+    check_outputs(SessionVWAP());
+    check_outputs(Chain<EMA, SMA>(EMA(3), SMA(4)));
+}
+
+TEST(Conformance, ScalarIndicatorsAgreeWithBatch) {
+    std::vector<double> xs;
+    for (int i = 0; i < 60; i++) xs.push_back(100.0 + 10.0 * std::sin(i * 0.4) + i);
+    check_scalar(SMA(5), xs);
+    check_scalar(EMA(5), xs);
+    check_scalar(WMA(5), xs);
+    check_scalar(HMA(9), xs);
+    check_scalar(ZLEMA(5), xs);
+    check_scalar(T3(3), xs);
+    check_scalar(KAMA(10), xs);
+    check_scalar(Kalman::trend(0.1, 0.01), xs);
+    check_scalar(SuperSmoother(10), xs);
+    check_scalar(Roofing(48, 10), xs);
+    check_scalar(Decycler(60), xs);
+    check_scalar(CumulativeReturn(), xs);
+    check_scalar(MVar(5, 1), xs);
+    check_scalar(EWMAVol(10), xs);
+    check_scalar(GARCH::fit(30, 10), xs);
+    check_scalar(ZScore(10), xs);
+    check_scalar(Percentile(0.9, 10), xs);
+    check_scalar(PercentRank(10), xs);
+    check_scalar(Hurst(40, 8), xs);
+    check_scalar(VarianceRatio(20, 4), xs);
+}
+
+TEST(Conformance, MVarWorksThroughTheBase) {
+    MVar mvar(3, 1);
+    EXPECT_DOUBLE_EQ(mvar.init_from(std::vector<double>{1.0, 2.0, 3.0}), 1.0);
+    std::vector<double> out = mvar.compute_batch(std::vector<double>{4.0});
+    ASSERT_EQ(out.size(), 1u);
+    EXPECT_DOUBLE_EQ(out[0], 1.0);
+    // window 3, 4, 7
+    EXPECT_NEAR(mvar.peek(7.0), 13.0 / 3.0, 1e-12);
+    EXPECT_DOUBLE_EQ(mvar.get(), 1.0);
+}
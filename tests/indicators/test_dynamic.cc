@@ -0,0 +1,87 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <utility>
+#include <vector>
+#include "dynamic.h"
+#include "transforms.h"
+
+using namespace tzu;
+
+namespace {
+
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 60; i++) {
+        double c = 100.0 + 5.0 * std::sin(i * 0.3) + 0.1 * i;
+        bars.push_back(Ohlcv(i * 60, c - 0.5, c + 1.0, c - 1.0, c, 10.0));
+    }
+    return bars;
+}
+
+} // namespace
+
+TEST(DynIndicator, HoldsMixedIndicators) {
+    std::vector<DynIndicator> inds;
+    inds.push_back(DynIndicator(SMA(5)));
+    inds.push_back(DynIndicator(ATR(5)));
+    inds.push_back(DynIndicator(MACD(3, 6, 4)));
+    inds.push_back(DynIndicator(EMA(5), OhlcvField::HIGH));
+    SMA sma(5);
+    ATR atr(5);
+    MACD macd(3, 6, 4);
+    EMA ema(5);
+    for (const auto& bar : sample_bars()) {
+        for (auto& ind : inds) ind.update(bar);
+        sma.update(bar.close);
+        atr.update(bar);
+        macd.update(bar.close);
+        ema.update(bar.high);
+    }
+    EXPECT_DOUBLE_EQ(inds[0].get(), sma.get());
+    EXPECT_DOUBLE_EQ(inds[1].get(), atr.get());
+    EXPECT_DOUBLE_EQ(inds[2].get(), macd.get().macd);
+    EXPECT_DOUBLE_EQ(inds[3].get(), ema.get());
+    for (const auto& ind : inds) EXPECT_TRUE(ind.is_ready());
+    EXPECT_EQ(inds[2].required_warmup(), macd.required_warmup());
+
+    const MACD* held = inds[2].target<MACD>();
+    ASSERT_NE(held, nullptr);
+    EXPECT_DOUBLE_EQ(held->get().signal, macd.get().signal);
+    EXPECT_EQ(inds[2].target<SMA>(), nullptr);
+}
+
+TEST(DynIndicator, CopiesAreIndependent) {
+    DynIndicator a(SMA(2));
+    a.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    DynIndicator b = a;
+    EXPECT_FALSE(b.is_ready());
+    b.update(Ohlcv(1, 3.0, 3.0, 3.0, 3.0, 1.0));
+    EXPECT_DOUBLE_EQ(b.get(), 2.0);
+    EXPECT_TRUE(std::isnan(a.get()));
+
+    std::stringstream ss;
+    ASSERT_TRUE(b.save_state(ss));
+    DynIndicator c(SMA(2));
+    ASSERT_TRUE(c.load_state(ss));
+    EXPECT_DOUBLE_EQ(c.get(), 2.0);
+
+    DynIndicator ha(HeikinAshi{});
+    ha.update(Ohlcv(0, 1.0, 3.0, 1.0, 3.0, 1.0));
+    EXPECT_DOUBLE_EQ(ha.get(), 2.0);
+}
+
+TEST(DynIndicator, MovedFromHoldsNothing) {
+    DynIndicator a(SMA(2));
+    DynIndicator b(std::move(a));
+    EXPECT_TRUE(std::isnan(a.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0))));
+    EXPECT_FALSE(a.is_ready());
+    EXPECT_EQ(a.required_warmup(), 0u);
+    EXPECT_TRUE(a.outputs().empty());
+    std::stringstream ss;
+    EXPECT_FALSE(a.save_state(ss));
+    DynIndicator c(a);
+    EXPECT_TRUE(std::isnan(c.get()));
+    c = b;
+    EXPECT_EQ(c.required_warmup(), 2u);
+}
@@ -0,0 +1,28 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(ElderRay, ReturnsNaNDuringWarmup) {
+    ElderRay elder(3);
+    ElderRayResult r = elder.update(Ohlcv(0, 10.0, 11.0, 9.0, 10.0, 1.0));
+    EXPECT_TRUE(std::isnan(r.bull));
+    EXPECT_TRUE(std::isnan(r.bear));
+    r = elder.update(Ohlcv(1, 10.0, 11.0, 9.0, 10.0, 1.0));
+    EXPECT_TRUE(std::isnan(r.bull));
+}
+
+TEST(ElderRay, PowerIsMeasuredFromEMA) {
+    ElderRay elder(3);
+    EMA ema(3);
+    double closes[] = {10.0, 11.0, 12.0, 13.0, 12.5};
+    ElderRayResult r = {0.0, 0.0};
+    for (int i = 0; i < 5; ++i) {
+        double c = closes[i];
+        r = elder.update(Ohlcv(i, c, c + 1.0, c - 2.0, c, 1.0));
+        ema.update(c);
+    }
+    EXPECT_DOUBLE_EQ(r.bull, 13.5 - ema.get());
+    EXPECT_DOUBLE_EQ(r.bear, 10.5 - ema.get());
+}
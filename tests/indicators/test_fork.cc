@@ -0,0 +1,35 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(Fork, PeekDoesNotModifyState) {
+    SMA sma(3);
+    sma.update(1.0);
+    sma.update(2.0);
+    sma.update(3.0);
+    EXPECT_DOUBLE_EQ(sma.peek(6.0), 11.0 / 3.0);
+    EXPECT_DOUBLE_EQ(sma.get(), 2.0);
+    EXPECT_DOUBLE_EQ(sma.update(4.0), 3.0);
+}
+
+TEST(Fork, BranchEvolvesIndependently) {
+    EMA ema(2);
+    ema.update(10.0);
+    ema.update(12.0);
+    EMA branch = ema.fork();
+    branch.update(100.0);
+    branch.update(100.0);
+    EXPECT_DOUBLE_EQ(ema.get(), 11.0);
+    EXPECT_GT(branch.get(), 90.0);
+}
+
+TEST(Fork, PeekWorksWithStructuredOutputs) {
+    MACD macd(2, 3, 2);
+    for (int i = 0; i < 6; ++i) macd.update(i);
+    MACDResult before = macd.get();
+    MACDResult preview = macd.peek(20.0);
+    EXPECT_GT(preview.macd, before.macd);
+    EXPECT_DOUBLE_EQ(macd.get().macd, before.macd);
+}
@@ -0,0 +1,44 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(Highest, ReturnsNaNDuringWarmup) {
+    Highest highest(3);
+    EXPECT_TRUE(std::isnan(highest.update(1.0)));
+    EXPECT_TRUE(std::isnan(highest.update(3.0)));
+    EXPECT_DOUBLE_EQ(highest.update(2.0), 3.0);
+}
+
+TEST(Highest, DropsValuesLeavingTheWindow) {
+    Highest highest(3);
+    Lowest lowest(3);
+    double xs[] = {5.0, 1.0, 4.0, 2.0, 3.0, 0.5};
+    double highs[] = {5.0, 4.0, 4.0, 3.0};
+    double lows[] = {1.0, 1.0, 2.0, 0.5};
+    for (int i = 0; i < 6; i++) {
+        highest.update(xs[i]);
+        lowest.update(xs[i]);
+        if (i < 2) continue;
+        EXPECT_DOUBLE_EQ(highest.get(), highs[i - 2]) << i;
+        EXPECT_DOUBLE_EQ(lowest.get(), lows[i - 2]) << i;
+    }
+    EXPECT_EQ(highest.age(), 1u);
+    EXPECT_EQ(lowest.age(), 0u);
+}
+
+TEST(Highest, MostRecentWinsTies) {
+    Highest highest(4);
+    highest.update(2.0);
+    highest.update(2.0);
+    highest.update(1.0);
+    EXPECT_EQ(highest.age(), 1u);
+}
+
+TEST(Highest, HighestHighFromBars) {
+    Applied<Highest> hh(Highest(2), PriceSource::HIGH);
+    hh.update(Ohlcv(0, 1.0, 9.0, 0.5, 1.0, 1.0));
+    EXPECT_DOUBLE_EQ(hh.update(Ohlcv(1, 1.0, 2.0, 0.5, 1.0, 1.0)), 9.0);
+    EXPECT_DOUBLE_EQ(hh.update(Ohlcv(2, 1.0, 3.0, 0.5, 1.0, 1.0)), 3.0);
+}
@@ -0,0 +1,58 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <fstream>
+#include <vector>
+#include "indicators.h"
+#include "streamers.h"
+
+using namespace tzu;
+
+TEST(Indicated, YieldsOutputsOfScalarsAndBars) {
+    std::vector<double> xs = {1.0, 2.0, 3.0, 4.0, 5.0};
+    std::vector<double> out;
+    for (double v : make_indicated(xs, SMA(3)))
+        out.push_back(v);
+    ASSERT_EQ(out.size(), 5u);
+    EXPECT_TRUE(std::isnan(out[1]));
+    EXPECT_DOUBLE_EQ(out[2], 2.0);
+    EXPECT_DOUBLE_EQ(out[4], 4.0);
+
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 30; i++)
+        bars.push_back(Ohlcv(i, 10.0 + i, 12.0 + i, 9.0 + i, 11.0 + i, 1.0));
+    auto closes = make_indicated(bars, EMA(5));
+    auto ranges = make_indicated(bars, ATR(5));
+    EMA ema(5);
+    ATR atr(5);
+    auto c = closes.begin();
+    auto r = ranges.begin();
+    for (const auto& bar : bars) {
+        double e = ema.update(bar.close);
+        double a = atr.update(bar);
+        EXPECT_EQ(std::isnan(*c), std::isnan(e));
+        if (!std::isnan(e)) {
+            EXPECT_DOUBLE_EQ(*c, e);
+        }
+        if (!std::isnan(a)) {
+            EXPECT_DOUBLE_EQ(*r, a);
+        }
+        ++c;
+        ++r;
+    }
+    EXPECT_TRUE(c == closes.end());
+    EXPECT_DOUBLE_EQ(closes.indicator().get(), ema.get());
+}
+
+TEST(Indicated, WalksStreamers) {
+    std::ifstream file("../data/btcusd.csv");
+    ASSERT_TRUE(file.is_open());
+    Csv<Ohlcv> csv(file);
+    auto macd = make_indicated(csv, MACD(12, 26, 9));
+    size_t n = 0, valid = 0;
+    for (const MACDResult& r : macd) {
+        n++;
+        if (!std::isnan(r.signal)) valid++;
+    }
+    ASSERT_GT(n, 34u);
+    EXPECT_EQ(valid, n - macd.indicator().lookback());
+}
@@ -0,0 +1,33 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+#include <vector>
+
+using namespace tzu;
+
+TEST(InitFrom, MatchesUpdateLoop) {
+    std::vector<double> history = {1.0, 3.0, 2.0, 5.0, 4.0, 6.0, 8.0};
+    EMA warm(3);
+    EMA loop(3);
+    for (double v : history) loop.update(v);
+    EXPECT_DOUBLE_EQ(warm.init_from(history), loop.get());
+    EXPECT_DOUBLE_EQ(warm.update(7.0), loop.update(7.0));
+}
+
+TEST(InitFrom, SMASkipsValuesOutsideTheWindow) {
+    std::vector<double> history;
+    for (int i = 0; i < 1000; ++i) history.push_back(i);
+    SMA sma(4);
+    sma.update(1e9);
+    EXPECT_DOUBLE_EQ(sma.init_from(history), 997.5);
+    EXPECT_DOUBLE_EQ(sma.update(1000.0), 998.5);
+}
+
+TEST(InitFrom, ShortHistoryLeavesIndicatorWarmingUp) {
+    std::vector<Ohlcv> bars = {Ohlcv(0, 1.0, 2.0, 0.5, 1.5, 10.0)};
+    RSI rsi(3);
+    EXPECT_TRUE(std::isnan(rsi.init_from(bars)));
+    SMA sma(3);
+    EXPECT_TRUE(std::isnan(sma.init_from(std::vector<double>{1.0, 2.0})));
+    EXPECT_DOUBLE_EQ(sma.update(3.0), 2.0);
+}
@@ -0,0 +1,56 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <vector>
+#include "indicators.h"
+
+using namespace tzu;
+
+namespace {
+
+// direct evaluation of the definition, summing the window every time
+std::vector<double> naive_kama(const std::vector<double>& x, size_t period,
+        size_t fast, size_t slow) {
+    std::vector<double> out(x.size(), std::nan(""));
+    double fsc = 2.0 / (fast + 1.0), ssc = 2.0 / (slow + 1.0);
+    double kama = std::nan("");
+    for (size_t i = period; i < x.size(); i++) {
+        double vol = 0.0;
+        for (size_t j = i - period + 1; j <= i; j++) vol += std::fabs(x[j] - x[j - 1]);
+        double er = vol > 0.0 ? std::fabs(x[i] - x[i - period]) / vol : 0.0;
+        double sc = std::pow(er * (fsc - ssc) + ssc, 2.0);
+        double base = std::isnan(kama) ? x[i - 1] : kama;
+        kama = base + sc * (x[i] - base);
+        out[i] = kama;
+    }
+    return out;
+}
+
+} // namespace
+
+TEST(KAMA, MatchesDefinition) {
+    std::vector<double> x;
+    for (int i = 0; i < 100; i++) x.push_back(50.0 + 5.0 * std::sin(i * 0.3) + 0.1 * i);
+    std::vector<double> expected = naive_kama(x, 10, 2, 30);
+    KAMA kama(10);
+    for (size_t i = 0; i < x.size(); i++) {
+        double v = kama.update(x[i]);
+        if (i < 10) EXPECT_TRUE(std::isnan(v));
+        else EXPECT_NEAR(v, expected[i], 1e-9);
+    }
+}
+
+TEST(KAMA, FollowsSteadyTrendAtFastRate) {
+    KAMA kama(5, 2, 30);
+    double v = 0.0;
+    for (int i = 0; i < 20; i++) v = kama.update(i);
+    // ER is 1: smoothing is (2/3)^2 and the lag settles at 1.25
+    EXPECT_NEAR(v, 19.0 - 1.25, 1e-3);
+}
+
+TEST(KAMA, FlatInNoise) {
+    KAMA kama(4);
+    double v = 0.0;
+    for (int i = 0; i < 40; i++) v = kama.update(i % 2 == 0 ? 10.0 : 11.0);
+    EXPECT_GT(v, 10.0);
+    EXPECT_LT(v, 11.0);
+}
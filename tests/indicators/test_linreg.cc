@@ -0,0 +1,41 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(LinReg, ReturnsNaNDuringWarmup) {
+    LinReg lr(4);
+    for (int i = 0; i < 3; ++i)
+        EXPECT_TRUE(std::isnan(lr.update(i).slope));
+    EXPECT_FALSE(std::isnan(lr.update(3.0).slope));
+}
+
+TEST(LinReg, FitsExactLine) {
+    LinReg lr(5);
+    LinRegResult r = lr.get();
+    for (int i = 0; i < 12; ++i)
+        r = lr.update(3.0 + 2.0 * i);
+    // window holds x = 7..11, refitted from x = 0
+    EXPECT_NEAR(r.slope, 2.0, 1e-12);
+    EXPECT_NEAR(r.intercept, 17.0, 1e-12);
+    EXPECT_NEAR(r.value, 25.0, 1e-12);
+    EXPECT_NEAR(r.forecast, 27.0, 1e-12);
+    EXPECT_NEAR(r.r2, 1.0, 1e-12);
+    EXPECT_NEAR(r.upper, r.value, 1e-9);
+    EXPECT_NEAR(r.lower, r.value, 1e-9);
+}
+
+TEST(LinReg, ChannelAroundNoisyValues) {
+    LinReg lr(4, 1.0);
+    LinRegResult r = lr.get();
+    double values[] = {1.0, 3.0, 2.0, 4.0};
+    for (double v : values) r = lr.update(v);
+    // slope = 0.8, intercept = 1.3, residuals -0.3 0.9 -0.9 0.3
+    EXPECT_NEAR(r.slope, 0.8, 1e-12);
+    EXPECT_NEAR(r.intercept, 1.3, 1e-12);
+    double stderr_ = std::sqrt(1.8 / 2.0);
+    EXPECT_NEAR(r.upper - r.value, stderr_, 1e-12);
+    EXPECT_NEAR(r.value - r.lower, stderr_, 1e-12);
+    EXPECT_NEAR(r.r2, 1.0 - 1.8 / 5.0, 1e-12);
+}
@@ -29,3 +29,32 @@ TEST(MACD, HistogramEqualsMACDMinusSignal) {
     if (!std::isnan(result.macd) && !std::isnan(result.signal))
         EXPECT_NEAR(result.histogram, result.macd - result.signal, 1e-10);
 }
+
+TEST(MACD, UpdateReturnsTheWholeResult) {
+    MACD macd(3, 5, 3);
+    EMA fast(3), slow(5), signal(3);
+    for (int i = 0; i < 20; ++i) {
+        double price = 100.0 + i * (i % 3 == 0 ? -1.0 : 1.5);
+        MACDResult r = macd.update(price);
+        MACDResult g = macd.get();
+        double f = fast.update(price);
+        double s = slow.update(price);
+        EXPECT_EQ(std::isnan(r.macd), std::isnan(g.macd));
+        EXPECT_EQ(std::isnan(r.signal), std::isnan(g.signal));
+        if (i < 5) {
+            EXPECT_TRUE(std::isnan(r.macd));
+            continue;
+        }
+        double sig = signal.update(f - s);
+        EXPECT_DOUBLE_EQ(r.macd, f - s);
+        EXPECT_DOUBLE_EQ(g.macd, r.macd);
+        if (std::isnan(sig)) {
+            EXPECT_TRUE(std::isnan(r.signal));
+            EXPECT_TRUE(std::isnan(r.histogram));
+        } else {
+            EXPECT_DOUBLE_EQ(r.signal, sig);
+            EXPECT_DOUBLE_EQ(r.histogram, r.macd - sig);
+            EXPECT_DOUBLE_EQ(g.histogram, r.histogram);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 #include <gtest/gtest.h>
 #include "indicators.h"
 #include <cmath>
+#include <vector>
 
 using namespace tzu;
 
@@ -27,3 +28,32 @@ TEST(MVar, SlidingWindowUpdatesCorrectly) {
     double result = mvar.update(40.0);
     EXPECT_NEAR(result, 100.0, 1e-10);
 }
+
+TEST(MVar, KeepsFractionalValues) {
+    MVar mvar(3, 0);
+    mvar.update(1.5);
+    mvar.update(2.25);
+    double result = mvar.update(2.75);
+    // mean 2.1666..., deviations -2/3, 1/12, 7/12
+    EXPECT_NEAR(result, (64.0 + 1.0 + 49.0) / 144.0 / 3.0, 1e-12);
+}
+
+TEST(MVar, SmallVariancesAtLargePriceLevels) {
+    const size_t n = 20;
+    MVar mvar(n, 1);
+    std::vector<double> xs;
+    for (int i = 0; i < 100000; i++) {
+        double x = 100000.0 + 0.001 * std::sin(i * 0.7) + 1e-4 * (i % 7);
+        xs.push_back(x);
+        double result = mvar.update(x);
+        if (i % 997 != 0 || xs.size() < n) continue;
+        double mean = 0.0;
+        for (size_t k = xs.size() - n; k < xs.size(); k++) mean += xs[k];
+        mean /= n;
+        double expected = 0.0;
+        for (size_t k = xs.size() - n; k < xs.size(); k++)
+            expected += (xs[k] - mean) * (xs[k] - mean);
+        expected /= n - 1;
+        EXPECT_NEAR(result, expected, expected * 1e-6) << i;
+    }
+}
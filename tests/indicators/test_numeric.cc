@@ -0,0 +1,72 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <vector>
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(Numeric, FixedArithmeticIsExact) {
+    using Price = Fixed<2>;
+    Price a(0.1), b(0.2);
+    EXPECT_EQ((a + b).raw(), 30);
+    EXPECT_TRUE(a + b == Price(0.3));
+    EXPECT_EQ((Price(1.0) / Price(3.0)).raw(), 33);
+    EXPECT_EQ((Price(2.5) * Price(1.5)).raw(), 375);
+    EXPECT_TRUE(-a < b);
+    std::stringstream ss;
+    ss << Price(12.34) << " " << NumericTraits<Price>::missing();
+    EXPECT_EQ(ss.str(), "12.34 nan");
+}
+
+TEST(Numeric, FixedProductsAreExactAndChecked) {
+    using Price = Decimal;
+    // 12345678.12345678 * 3 needs more than the 64 bits of long double
+    Price big = Price::from_raw(1234567812345678LL);
+    EXPECT_EQ((big * Price(3.0)).raw(), 3703703437037034LL);
+    EXPECT_EQ((big / Price(-2.0)).raw(), -617283906172839LL);
+    EXPECT_EQ((Price(-1.0) / Price(3.0)).raw(), -33333333);
+    EXPECT_EQ((Price(2.0) / Price(3.0)).raw(), 66666667);
+    EXPECT_TRUE(NumericTraits<Price>::is_missing(Price(1.0) / Price(0.0)));
+    EXPECT_TRUE(NumericTraits<Price>::is_missing(big * big));
+    Price nan = NumericTraits<Price>::missing();
+    EXPECT_TRUE(NumericTraits<Price>::is_missing(nan * Price(1.0)));
+    EXPECT_TRUE(NumericTraits<Price>::is_missing(Price(1.0) / nan));
+}
+
+TEST(Numeric, MovingAveragesOnFixedPoint) {
+    using Price = Fixed<4>;
+    BasicSMA<Price> sma(4);
+    BasicWMA<Price> wma(3);
+    BasicEMA<Price> ema(3);
+    double prices[] = {10.1, 10.2, 10.3, 10.4, 10.5};
+    for (int i = 0; i < 3; i++) {
+        EXPECT_TRUE(NumericTraits<Price>::is_missing(sma.update(Price(prices[i]))));
+        wma.update(Price(prices[i]));
+        ema.update(Price(prices[i]));
+    }
+    EXPECT_EQ(sma.update(Price(prices[3])).raw(), 102500);
+    EXPECT_EQ(sma.update(Price(prices[4])).raw(), 103500);
+    // (10.1 + 2 * 10.2 + 3 * 10.3) / 6
+    EXPECT_EQ(wma.get().raw(), 102333);
+    EXPECT_EQ(ema.get().raw(), 102000);
+}
+
+TEST(Numeric, SinglePrecisionMatchesDouble) {
+    BasicSMA<float> sma32(5);
+    SMA sma64(5);
+    BasicEMA<float> ema32(5);
+    EMA ema64(5);
+    for (int i = 0; i < 50; i++) {
+        double x = 100.0 + std::sin(i * 0.3);
+        float s = sma32.update(static_cast<float>(x));
+        double d = sma64.update(x);
+        if (i < 4) EXPECT_TRUE(std::isnan(s));
+        else EXPECT_NEAR(s, d, 1e-4);
+        float e = ema32.update(static_cast<float>(x));
+        if (i >= 4) EXPECT_NEAR(e, ema64.update(x), 1e-3);
+        else ema64.update(x);
+    }
+    std::vector<float> batch = BasicSMA<float>(2).compute_batch(std::vector<float>{1.0f, 2.0f, 4.0f});
+    EXPECT_FLOAT_EQ(batch[2], 3.0f);
+}
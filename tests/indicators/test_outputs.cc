@@ -0,0 +1,56 @@
+#include <gtest/gtest.h>
+#include <string>
+#include <vector>
+#include "dynamic.h"
+#include "transforms.h"
+
+using namespace tzu;
+
+TEST(Outputs, NamesMultipleOutputs) {
+    MACD macd(2, 3, 2);
+    for (int i = 0; i < 10; i++) macd.update(i * i);
+    std::vector<std::string> names = macd.outputs();
+    ASSERT_EQ(names.size(), 3u);
+    EXPECT_EQ(names[1], "signal");
+    double value = 0.0;
+    ASSERT_TRUE(macd.get_output("histogram", value));
+    EXPECT_DOUBLE_EQ(value, macd.get().histogram);
+    value = 42.0;
+    EXPECT_FALSE(macd.get_output("adx", value));
+    EXPECT_DOUBLE_EQ(value, 42.0);
+
+    ADX adx(3);
+    Aroon aroon(3);
+    for (int i = 0; i < 10; i++) {
+        Ohlcv bar(i, 10.0 + i, 11.0 + i, 9.0 + i, 10.5 + i, 1.0);
+        adx.update(bar);
+        aroon.update(bar);
+    }
+    ASSERT_TRUE(adx.get_output("plus_di", value));
+    EXPECT_DOUBLE_EQ(value, adx.get().plus_di);
+    ASSERT_TRUE(aroon.get_output("up", value));
+    EXPECT_DOUBLE_EQ(value, 100.0);
+    EXPECT_EQ(LinReg(5).outputs().size(), 7u);
+    EXPECT_EQ(Pivots().outputs().front(), "pp");
+}
+
+TEST(Outputs, SingleOutputsAndBars) {
+    SMA sma(1);
+    sma.update(3.0);
+    EXPECT_EQ(sma.outputs(), std::vector<std::string>{"value"});
+    double value = 0.0;
+    ASSERT_TRUE(sma.get_output("value", value));
+    EXPECT_DOUBLE_EQ(value, 3.0);
+
+    HeikinAshi ha;
+    ha.update(Ohlcv(0, 1.0, 3.0, 1.0, 3.0, 5.0));
+    ASSERT_TRUE(ha.get_output("volume", value));
+    EXPECT_DOUBLE_EQ(value, 5.0);
+
+    DynIndicator dyn(ElderRay(2));
+    EXPECT_EQ(dyn.outputs().size(), 2u);
+    dyn.update(Ohlcv(0, 1.0, 3.0, 1.0, 2.0, 1.0));
+    dyn.update(Ohlcv(1, 2.0, 4.0, 2.0, 3.0, 1.0));
+    ASSERT_TRUE(dyn.get_output("bear", value));
+    EXPECT_DOUBLE_EQ(value, 2.0 - 2.5);
+}
@@ -0,0 +1,39 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include "bars.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(Pivots, ClassicAndFibonacciLevels) {
+    Ohlcv day(0, 100.0, 110.0, 90.0, 105.0, 1.0);
+    PivotLevels c = pivot_levels(day);
+    EXPECT_DOUBLE_EQ(c.pp, 305.0 / 3.0);
+    EXPECT_DOUBLE_EQ(c.r1, 2.0 * c.pp - 90.0);
+    EXPECT_DOUBLE_EQ(c.s2, c.pp - 20.0);
+    EXPECT_DOUBLE_EQ(c.r3, 110.0 + 2.0 * (c.pp - 90.0));
+    PivotLevels f = pivot_levels(day, PivotScheme::FIBONACCI);
+    EXPECT_DOUBLE_EQ(f.r2, f.pp + 0.618 * 20.0);
+    EXPECT_DOUBLE_EQ(f.s3, f.pp - 20.0);
+}
+
+TEST(Pivots, CamarillaLevelsAroundClose) {
+    PivotLevels l = pivot_levels(Ohlcv(0, 100.0, 112.0, 100.0, 106.0, 1.0),
+            PivotScheme::CAMARILLA);
+    EXPECT_DOUBLE_EQ(l.r1, 106.0 + 1.1);
+    EXPECT_DOUBLE_EQ(l.s3, 106.0 - 3.3);
+}
+
+TEST(Pivots, FedByResampledDailyBars) {
+    Resampler daily(86400);
+    Pivots pivots;
+    EXPECT_TRUE(std::isnan(pivots.get().pp));
+    for (int h = 0; h < 24; ++h)
+        if (daily.update(Ohlcv(h * 3600, 10.0, 12.0 + h, 8.0, 11.0, 1.0)))
+            pivots.update(daily.get());
+    EXPECT_TRUE(std::isnan(pivots.get().pp));
+    // the first bar of the next day completes the previous one
+    if (daily.update(Ohlcv(86400, 11.0, 11.0, 11.0, 11.0, 1.0)))
+        pivots.update(daily.get());
+    EXPECT_DOUBLE_EQ(pivots.get().pp, (35.0 + 8.0 + 11.0) / 3.0);
+}
@@ -0,0 +1,77 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <memory>
+#include <string>
+#include "registry.h"
+
+using namespace tzu;
+
+TEST(Registry, BuildsIndicatorsFromSpecs) {
+    const IndicatorRegistry& registry = builtin_indicators();
+    std::unique_ptr<DynIndicator> sma = registry.make("SMA(3)");
+    ASSERT_TRUE(sma != nullptr);
+    ASSERT_NE(sma->target<SMA>(), nullptr);
+    EXPECT_EQ(sma->required_warmup(), 3u);
+    std::unique_ptr<DynIndicator> macd = registry.make(" MACD( 12, 26 ,9 ) ");
+    ASSERT_TRUE(macd != nullptr);
+    EXPECT_NE(macd->target<MACD>(), nullptr);
+    EXPECT_TRUE(registry.make("Pivots") != nullptr);
+    EXPECT_TRUE(registry.make("RSI") != nullptr);
+}
+
+TEST(Registry, FeedsTheGivenField) {
+    std::unique_ptr<DynIndicator> hh =
+        builtin_indicators().make("Highest(2) of high");
+    ASSERT_TRUE(hh != nullptr);
+    hh->update(Ohlcv(0, 1.0, 9.0, 0.5, 1.0, 1.0));
+    EXPECT_DOUBLE_EQ(hh->update(Ohlcv(1, 1.0, 2.0, 0.5, 1.0, 1.0)), 9.0);
+}
+
+TEST(Registry, ReportsInvalidSpecs) {
+    const IndicatorRegistry& registry = builtin_indicators();
+    std::string error;
+    EXPECT_TRUE(registry.make("Foo(3)", &error) == nullptr);
+    EXPECT_EQ(error, "unknown indicator 'Foo'");
+    EXPECT_TRUE(registry.make("SMA", &error) == nullptr);
+    EXPECT_EQ(error, "wrong number of arguments in 'SMA'");
+    EXPECT_TRUE(registry.make("SMA(2.5)", &error) == nullptr);
+    EXPECT_EQ(error, "invalid period in 'SMA(2.5)'");
+    EXPECT_TRUE(registry.make("SMA(0)") == nullptr);
+    EXPECT_TRUE(registry.make("SMA(x)") == nullptr);
+    EXPECT_TRUE(registry.make("SMA(3") == nullptr);
+    EXPECT_TRUE(registry.make("SMA(3) of bid") == nullptr);
+    EXPECT_TRUE(registry.make("Percentile(-0.5, 3)", &error) == nullptr);
+    EXPECT_EQ(error, "invalid argument in 'Percentile(-0.5, 3)'");
+    EXPECT_TRUE(registry.make("Percentile(0.5, 0)") == nullptr);
+    EXPECT_TRUE(registry.make("Percentile(0.5, 3)") != nullptr);
+    EXPECT_TRUE(registry.make("SMA(1e30)", &error) == nullptr);
+    EXPECT_EQ(error, "invalid period in 'SMA(1e30)'");
+    EXPECT_TRUE(registry.make("SMA(1e12)") == nullptr);
+    EXPECT_TRUE(registry.make("FracDiff(0.4, 0)") == nullptr);
+    EXPECT_TRUE(registry.make("MVar(5, 5)") == nullptr);
+    EXPECT_TRUE(registry.make("MVar(5, -1)") == nullptr);
+    EXPECT_TRUE(registry.make("MVar(5, 4)") != nullptr);
+    EXPECT_TRUE(registry.make("ZScore(5, 9)") == nullptr);
+    EXPECT_TRUE(registry.make("TimeMVar(60, 0.5)") == nullptr);
+    EXPECT_TRUE(registry.make("TimeSMA(1e30)") == nullptr);
+    EXPECT_TRUE(registry.make("TimeSMA(86400000)") != nullptr);
+    EXPECT_TRUE(registry.make("P2Quantile(2)") == nullptr);
+    EXPECT_TRUE(registry.make("Quantile(0.5, -1)") == nullptr);
+    EXPECT_TRUE(registry.make("Quantile(0.5)") != nullptr);
+    EXPECT_TRUE(registry.make("Kalman(-1)") == nullptr);
+    EXPECT_TRUE(registry.make("KalmanTrend(1, 0)") == nullptr);
+    EXPECT_TRUE(registry.make("MAMA(1.5)") == nullptr);
+    EXPECT_TRUE(registry.make("GARCH(100, -5)") == nullptr);
+}
+
+TEST(Registry, AcceptsCustomIndicators) {
+    IndicatorRegistry registry = builtin_indicators();
+    registry.add("Fast", 0, 0, 0, [](const std::vector<double>&, OhlcvField f) {
+        return DynIndicator(EMA(3), f);
+    });
+    EXPECT_TRUE(registry.contains("Fast"));
+    EXPECT_FALSE(builtin_indicators().contains("Fast"));
+    std::unique_ptr<DynIndicator> fast = registry.make("Fast");
+    ASSERT_TRUE(fast != nullptr);
+    EXPECT_NE(fast->target<EMA>(), nullptr);
+}
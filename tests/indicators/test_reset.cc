@@ -0,0 +1,123 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <string>
+#include <vector>
+#include "anchored.h"
+#include "calendar.h"
+#include "currency.h"
+#include "dynamic.h"
+#include "ehlers.h"
+#include "filters.h"
+#include "regime.h"
+#include "statistics.h"
+#include "transforms.h"
+#include "volatility.h"
+
+using namespace tzu;
+
+namespace {
+
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 70; i++) {
+        double c = 100.0 + 8.0 * std::sin(i * 0.35) + 0.2 * i;
+        bars.push_back(Ohlcv(i * 60, c - 0.5, c + 1.5, c - 1.5, c, 10.0 + i));
+    }
+    return bars;
+}
+
+template <typename Ind>
+std::string state_of(const Ind& ind) {
+    std::ostringstream os;
+    ind.save_state(os);
+    return os.str();
+}
+
+// a reset indicator has the state of a fresh one, and behaves like it
+template <typename Ind>
+void check_reset(Ind fresh) {
+    Ind used = fresh;
+    typename Ind::output_type out;
+    for (const auto& bar : sample_bars())
+        used.try_update(detail::from_bar<typename Ind::input_type>(bar), out);
+    ASSERT_NE(state_of(used), state_of(fresh));
+    used.reset();
+    EXPECT_EQ(state_of(used), state_of(fresh));
+    EXPECT_FALSE(used.is_ready());
+    for (const auto& bar : sample_bars()) {
+        auto in = detail::from_bar<typename Ind::input_type>(bar);
+        EXPECT_EQ(used.try_update(in, out), fresh.try_update(in, out));
+    }
+    EXPECT_EQ(state_of(used), state_of(fresh));
+}
+
+} // namespace
+
+TEST(Reset, ClearsEveryIndicator) {
+    check_reset(SMA(5));
+    check_reset(EMA(5));
+    check_reset(WMA(5));
+    check_reset(HMA(9));
+    check_reset(ZLEMA(5));
+    check_reset(T3(3));
+    check_reset(KAMA(10));
+    check_reset(Kalman::trend(0.1, 0.01));
+    check_reset(SuperSmoother(10));
+    check_reset(Roofing(48, 10));
+    check_reset(Decycler(60));
+    check_reset(FisherTransform(10));
+    check_reset(MAMA());
+    check_reset(VWAP());
+    check_reset(CumulativeReturn());
+    check_reset(MVar(5, 1));
+    check_reset(Sharpe(10));
+    check_reset(Sortino(10));
+    check_reset(RSI(14));
+    check_reset(MACD(12, 26, 9));
+    check_reset(LinReg(10));
+    check_reset(ATR(14));
+    check_reset(ADX(14));
+    check_reset(ElderRay(13));
+    check_reset(Highest(10));
+    check_reset(Lowest(10));
+    check_reset(Aroon(14));
+    check_reset(StochRSI(5, 5, 3, 3));
+    check_reset(Pivots());
+    check_reset(TimeSMA(300));
+    check_reset(TimeMVar(300, 1));
+    check_reset(SessionVWAP(Calendar::nyse()));
+    check_reset(EWMAVol(10));
+    check_reset(GARCH::fit(30, 10));
+    check_reset(P2Quantile(0.5));
+    check_reset(Quantile(0.5, 20));
+    check_reset(Percentile(0.9, 10));
+    check_reset(PercentRank(10));
+    check_reset(Hurst(20));
+    check_reset(VarianceRatio(20, 4));
+    check_reset(ZScore(10));
+    check_reset(FracDiff(0.4, 10));
+    check_reset(WaveletDenoise(16));
+    check_reset(HeikinAshi());
+    check_reset(ForwardAdjust({CorporateAction(600, 2.0, 0.0)}));
+    check_reset(Chain<EMA, SMA>(EMA(3), SMA(4)));
+    check_reset(Applied<SMA>(SMA(5), PriceSource::TYPICAL));
+    check_reset(BasicSMA<Decimal>(5));
+}
+
+TEST(Reset, ClearsTheLastTimestamp) {
+    ATR atr(3);
+    double out;
+    atr.try_update(Ohlcv(100, 1.0, 2.0, 0.5, 1.5, 1.0), out);
+    atr.reset();
+    EXPECT_EQ(atr.try_update(Ohlcv(50, 1.0, 2.0, 0.5, 1.5, 1.0), out),
+            IndicatorStatus::WARMING_UP);
+    EXPECT_TRUE(std::isnan(atr.get()));
+
+    DynIndicator dyn(SMA(2));
+    dyn.update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    dyn.update(Ohlcv(1, 1.0, 1.0, 1.0, 1.0, 1.0));
+    dyn.reset();
+    EXPECT_TRUE(std::isnan(dyn.get()));
+    EXPECT_FALSE(dyn.is_ready());
+}
@@ -0,0 +1,70 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <cstdlib>
+#include <vector>
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(RollingExtreme, MatchesAScanOfTheWindow) {
+    const size_t period = 7;
+    detail::RollingMax highest(period);
+    detail::RollingMin lowest(period);
+    std::vector<double> xs;
+    std::srand(7);
+    for (int i = 0; i < 500; i++) {
+        // few distinct values, so ties are common
+        double x = std::rand() % 10;
+        xs.push_back(x);
+        highest.push(x);
+        lowest.push(x);
+        size_t first = xs.size() > period ? xs.size() - period : 0;
+        double high = xs[first], low = xs[first];
+        size_t high_at = first, low_at = first;
+        for (size_t k = first; k < xs.size(); k++) {
+            if (xs[k] >= high) { high = xs[k]; high_at = k; }
+            if (xs[k] <= low) { low = xs[k]; low_at = k; }
+        }
+        EXPECT_EQ(highest.value(), high);
+        EXPECT_EQ(lowest.value(), low);
+        EXPECT_EQ(highest.age(), xs.size() - 1 - high_at);
+        EXPECT_EQ(lowest.age(), xs.size() - 1 - low_at);
+        EXPECT_EQ(highest.full(), xs.size() >= period);
+    }
+}
+
+TEST(RollingExtreme, IsEmptyAfterClear) {
+    detail::RollingMax highest(3);
+    EXPECT_TRUE(std::isnan(highest.value()));
+    highest.push(1.0);
+    highest.push(2.0);
+    highest.clear();
+    EXPECT_TRUE(std::isnan(highest.value()));
+    EXPECT_FALSE(highest.full());
+    highest.push(0.5);
+    EXPECT_EQ(highest.value(), 0.5);
+}
+
+TEST(RollingExtreme, AroonMatchesAScanOnLongPeriods) {
+    const size_t period = 200;
+    Aroon aroon(period);
+    std::vector<double> highs;
+    double price = 100.0;
+    std::srand(11);
+    for (int i = 0; i < 2000; i++) {
+        price += (std::rand() % 21 - 10) * 0.1;
+        highs.push_back(price + 1.0);
+        AroonResult r = aroon.update(Ohlcv(i, price, price + 1.0,
+                    price - 1.0, price, 1.0));
+        if (highs.size() <= period) continue;
+        size_t age = 0;
+        double high = -1.0;
+        for (size_t k = highs.size() - period - 1; k < highs.size(); k++) {
+            if (highs[k] >= high) {
+                high = highs[k];
+                age = highs.size() - 1 - k;
+            }
+        }
+        EXPECT_DOUBLE_EQ(r.up, 100.0 * (period - age) / period) << i;
+    }
+}
@@ -0,0 +1,33 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+#include <vector>
+
+using namespace tzu;
+
+TEST(RuntimePeriods, WarmupFollowsPeriodGivenAtRunTime) {
+    std::vector<size_t> periods = {2, 5, 9};
+    for (size_t period : periods) {
+        SMA sma(period);
+        for (size_t i = 1; i < period; ++i)
+            EXPECT_TRUE(std::isnan(sma.update(1.0)));
+        EXPECT_DOUBLE_EQ(sma.update(1.0), 1.0);
+    }
+}
+
+TEST(RuntimePeriods, IndicatorsWithDifferentPeriodsShareAType) {
+    std::vector<EMA> emas;
+    for (size_t p = 1; p <= 4; ++p) emas.push_back(EMA(p));
+    for (int i = 0; i < 4; ++i)
+        for (auto& ema : emas) ema.update(10.0);
+    for (const auto& ema : emas)
+        EXPECT_DOUBLE_EQ(ema.get(), 10.0);
+}
+
+TEST(RuntimePeriods, MACDPeriodsFromConfiguration) {
+    size_t config[] = {3, 6, 2};
+    MACD macd(config[0], config[1], config[2]);
+    for (int i = 0; i < 6; ++i)
+        EXPECT_TRUE(std::isnan(macd.update(i).macd));
+    EXPECT_FALSE(std::isnan(macd.update(6.0).macd));
+}
@@ -0,0 +1,34 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(Sharpe, RollingRatio) {
+    Sharpe sharpe(4, 252);
+    double returns[] = {0.5, 0.1, -0.1, 0.1, 0.1};
+    for (int i = 0; i < 3; ++i)
+        EXPECT_TRUE(std::isnan(sharpe.update(returns[i])));
+    sharpe.update(returns[3]);
+    double value = sharpe.update(returns[4]);
+    // window 0.1, -0.1, 0.1, 0.1
+    double stddev = std::sqrt((3 * 0.0025 + 0.0225) / 3.0);
+    EXPECT_NEAR(value, 0.05 / stddev * std::sqrt(252.0), 1e-9);
+}
+
+TEST(Sortino, PenalizesOnlyLosses) {
+    Sortino sortino(4);
+    double returns[] = {0.1, -0.1, 0.1, 0.1};
+    double value = 0.0;
+    for (double r : returns) value = sortino.update(r);
+    EXPECT_NEAR(value, 0.05 / std::sqrt(0.01 / 4.0), 1e-12);
+    // the loss leaves the window
+    for (int i = 0; i < 2; ++i) value = sortino.update(0.02);
+    EXPECT_DOUBLE_EQ(value, 0.0);
+}
+
+TEST(Sharpe, ConstantReturnsHaveNoRatio) {
+    Sharpe sharpe(3);
+    for (int i = 0; i < 5; ++i) sharpe.update(0.01);
+    EXPECT_DOUBLE_EQ(sharpe.get(), 0.0);
+}
@@ -1,6 +1,7 @@
 #include <gtest/gtest.h>
 #include "indicators.h"
 #include <cmath>
+#include <vector>
 
 using namespace tzu;
 
@@ -27,3 +28,23 @@ TEST(SMA, SlidingWindowUpdatesCorrectly) {
     double result = sma.update(40.0);
     EXPECT_DOUBLE_EQ(result, 30.0);
 }
+
+TEST(SMA, RecoversFromRoundingAfterLargeValues) {
+    SMA sma(4);
+    sma.update(1e15);
+    for (int i = 0; i < 8; i++) sma.update(0.1);
+    EXPECT_EQ(sma.get(), (0.1 + 0.1 + 0.1 + 0.1) / 4.0);
+}
+
+TEST(SMA, DoesNotDriftOverLongSessions) {
+    const size_t n = 47;
+    SMA sma(n);
+    std::vector<double> xs;
+    for (int i = 0; i < 1000000; i++) {
+        xs.push_back(std::fmod(i * 7919.123, 1000.0) * (i % 3 ? 1.0 : 1e6));
+        sma.update(xs.back());
+    }
+    double sum = 0.0;
+    for (size_t k = xs.size() - n; k < xs.size(); k++) sum += xs[k];
+    EXPECT_NEAR(sma.get(), sum / n, 1e-6);
+}
@@ -0,0 +1,193 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <vector>
+#include "anchored.h"
+#include "calendar.h"
+#include "ehlers.h"
+#include "filters.h"
+#include "indicators.h"
+#include "regime.h"
+#include "statistics.h"
+#include "transforms.h"
+#include "volatility.h"
+
+using namespace tzu;
+
+namespace {
+
+bool same(double a, double b) {
+    return (std::isnan(a) && std::isnan(b)) || a == b;
+}
+
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 80; i++) {
+        double c = 100.0 + 10.0 * std::sin(i * 0.3) + 0.2 * i;
+        bars.push_back(Ohlcv(i * 60, c - 0.5, c + 1.5, c - 1.5, c,
+                    100.0 + i));
+    }
+    return bars;
+}
+
+// an indicator restored halfway continues exactly as the original
+template <typename Ind, typename Input, typename Eq>
+void check_restore(Ind original, Ind fresh, const std::vector<Input>& xs,
+        Eq eq) {
+    size_t half = xs.size() / 2;
+    for (size_t i = 0; i < half; i++) original.update(xs[i]);
+    std::stringstream ss;
+    ASSERT_TRUE(original.save_state(ss));
+    ASSERT_TRUE(fresh.load_state(ss));
+    for (size_t i = half; i < xs.size(); i++)
+        EXPECT_TRUE(eq(original.update(xs[i]), fresh.update(xs[i]))) << i;
+}
+
+template <typename Ind>
+void check_scalar(Ind ind) {
+    std::vector<double> xs;
+    for (const auto& bar : sample_bars()) xs.push_back(bar.close);
+    check_restore(ind, ind, xs, same);
+}
+
+template <typename Ind>
+void check_bars(Ind ind) {
+    check_restore(ind, ind, sample_bars(), same);
+}
+
+} // namespace
+
+TEST(State, ScalarIndicatorsResumeExactly) {
+    check_scalar(SMA(5));
+    check_scalar(EMA(5));
+    check_scalar(WMA(5));
+    check_scalar(HMA(9));
+    check_scalar(ZLEMA(5));
+    check_scalar(T3(3));
+    check_scalar(KAMA(10));
+    check_scalar(Kalman::trend(0.1, 0.01));
+    check_scalar(SuperSmoother(10));
+    check_scalar(Roofing(48, 10));
+    check_scalar(Decycler(60));
+    check_bars(FisherTransform(10));
+    check_bars(VWAP());
+    check_scalar(CumulativeReturn());
+    check_scalar(MVar(5, 1));
+    check_scalar(Sharpe(10));
+    check_scalar(Sortino(10));
+    check_scalar(Highest(10));
+    check_scalar(Lowest(10));
+    check_scalar(EWMAVol(10));
+    check_scalar(GARCH::fit(30, 10));
+    check_scalar(P2Quantile(0.9));
+    check_scalar(Quantile(0.5, 16));
+    check_scalar(Percentile(0.9, 10));
+    check_scalar(PercentRank(10));
+    check_scalar(Hurst(20));
+    check_scalar(VarianceRatio(20, 4));
+    check_scalar(ZScore(10));
+    check_scalar(FracDiff(0.4, 10));
+    check_scalar(WaveletDenoise(16, 2));
+    check_scalar(Chain<EMA, SMA>(EMA(3), SMA(4)));
+}
+
+TEST(State, BarIndicatorsResumeExactly) {
+    check_bars(RSI(14));
+    check_bars(ATR(14));
+    check_bars(SessionVWAP());
+    check_bars(Applied<SMA>(SMA(5), PriceSource::TYPICAL));
+    auto adx_eq = [](ADXResult a, ADXResult b) {
+        return same(a.adx, b.adx) && same(a.plus_di, b.plus_di)
+            && same(a.minus_di, b.minus_di);
+    };
+    check_restore(ADX(14), ADX(14), sample_bars(), adx_eq);
+    auto aroon_eq = [](AroonResult a, AroonResult b) {
+        return same(a.up, b.up) && same(a.down, b.down);
+    };
+    check_restore(Aroon(14), Aroon(14), sample_bars(), aroon_eq);
+    auto stoch_eq = [](StochRSIResult a, StochRSIResult b) {
+        return same(a.k, b.k) && same(a.d, b.d);
+    };
+    check_restore(StochRSI(5, 5), StochRSI(5, 5), sample_bars(), stoch_eq);
+    auto ha_eq = [](Ohlcv a, Ohlcv b) {
+        return a.open == b.open && a.close == b.close;
+    };
+    check_restore(HeikinAshi(), HeikinAshi(), sample_bars(), ha_eq);
+    auto mama_eq = [](MAMAResult a, MAMAResult b) {
+        return same(a.mama, b.mama) && same(a.fama, b.fama);
+    };
+    std::vector<double> closes;
+    for (const auto& bar : sample_bars()) closes.push_back(bar.close);
+    check_restore(MAMA(), MAMA(), closes, mama_eq);
+}
+
+TEST(State, ResultsAreRestored) {
+    MACD macd(12, 26, 9);
+    for (const auto& bar : sample_bars()) macd.update(bar.close);
+    std::stringstream ss;
+    ASSERT_TRUE(macd.save_state(ss));
+    MACD restored(12, 26, 9);
+    ASSERT_TRUE(restored.load_state(ss));
+    EXPECT_EQ(restored.get().macd, macd.get().macd);
+    EXPECT_EQ(restored.get().signal, macd.get().signal);
+
+    TimeSMA tsma(300);
+    for (const auto& bar : sample_bars()) tsma.update(bar);
+    ss.str("");
+    ss.clear();
+    ASSERT_TRUE(tsma.save_state(ss));
+    TimeSMA tsma2(300);
+    ASSERT_TRUE(tsma2.load_state(ss));
+    EXPECT_EQ(tsma2.get(), tsma.get());
+    EXPECT_EQ(tsma2.update(SingleValue(10000, 1.0)),
+            tsma.update(SingleValue(10000, 1.0)));
+}
+
+TEST(State, DecimalValuesAreExact) {
+    BasicSMA<Decimal> sma(3);
+    sma.update(Decimal(0.1));
+    sma.update(Decimal(0.2));
+    std::stringstream ss;
+    ASSERT_TRUE(sma.save_state(ss));
+    BasicSMA<Decimal> restored(3);
+    ASSERT_TRUE(restored.load_state(ss));
+    EXPECT_EQ(restored.update(Decimal(0.3)), sma.update(Decimal(0.3)));
+}
+
+TEST(State, MismatchesAreRejected) {
+    SMA sma(5);
+    for (int i = 0; i < 7; i++) sma.update(i);
+    std::stringstream ss;
+    ASSERT_TRUE(sma.save_state(ss));
+    std::string saved = ss.str();
+
+    SMA other(6);
+    std::istringstream in(saved);
+    EXPECT_FALSE(other.load_state(in));
+    EXPECT_TRUE(std::isnan(other.update(1.0)));
+
+    EMA ema(5);
+    std::istringstream in2(saved);
+    EXPECT_FALSE(ema.load_state(in2));
+
+    std::istringstream garbage("tzu-state 1 x y z\n");
+    EXPECT_FALSE(SMA(5).load_state(garbage));
+    std::istringstream version("tzu-state 99 0\n");
+    EXPECT_FALSE(SMA(5).load_state(version));
+
+    std::istringstream count("tzu-state 1 -9223372036854775808 nan "
+            "99999999999999 1 2 3 4 1 1 6\n");
+    EXPECT_FALSE(TimeSMA(10).load_state(count));
+}
+
+TEST(State, TimestampCheckIsRestored) {
+    ATR atr(3);
+    double out;
+    atr.try_update(Ohlcv(100, 1.0, 2.0, 0.5, 1.5, 1.0), out);
+    std::stringstream ss;
+    ASSERT_TRUE(atr.save_state(ss));
+    ATR restored(3);
+    ASSERT_TRUE(restored.load_state(ss));
+    EXPECT_EQ(restored.try_update(Ohlcv(50, 1.0, 2.0, 0.5, 1.5, 1.0), out),
+            IndicatorStatus::NON_MONOTONIC_TIME);
+}
@@ -0,0 +1,76 @@
+#include <gtest/gtest.h>
+#include <algorithm>
+#include <cmath>
+#include <sstream>
+#include <vector>
+#include "indicators.h"
+#include "serialize.h"
+
+using namespace tzu;
+
+namespace {
+
+Ohlcv bar_at(int i) {
+    double open = 100.0 + 3.0 * std::sin(i * 0.7);
+    double close = open + 2.0 * std::cos(i * 0.45);
+    return Ohlcv(i, open, std::max(open, close) + 1.0,
+            std::min(open, close) - 1.0, close, 1.0);
+}
+
+} // namespace
+
+TEST(StochRSI, ReturnsNaNDuringWarmup) {
+    StochRSI stoch(3, 4, 2, 2);
+    // %K needs 3 + 4 + 2 - 2 bars, %D one more
+    for (int i = 0; i < 6; i++) {
+        StochRSIResult r = stoch.update(bar_at(i));
+        EXPECT_TRUE(std::isnan(r.k));
+        EXPECT_TRUE(std::isnan(r.d));
+    }
+    StochRSIResult r = stoch.update(bar_at(6));
+    EXPECT_FALSE(std::isnan(r.k));
+    EXPECT_TRUE(std::isnan(r.d));
+    EXPECT_FALSE(stoch.is_ready());
+    r = stoch.update(bar_at(7));
+    EXPECT_FALSE(std::isnan(r.d));
+    EXPECT_TRUE(stoch.is_ready());
+    EXPECT_EQ(stoch.required_warmup(), 8u);
+}
+
+TEST(StochRSI, MatchesStochasticOfRSI) {
+    StochRSI stoch(5, 6, 3, 3);
+    RSI rsi(5);
+    std::vector<double> rsis;
+    SMA k(3), d(3);
+    for (int i = 0; i < 60; i++) {
+        Ohlcv bar = bar_at(i);
+        StochRSIResult r = stoch.update(bar);
+        double v = rsi.update(bar);
+        if (std::isnan(v)) continue;
+        rsis.push_back(v);
+        if (rsis.size() < 6) continue;
+        auto first = rsis.end() - 6;
+        double high = *std::max_element(first, rsis.end());
+        double low = *std::min_element(first, rsis.end());
+        double raw = high > low ? 100.0 * (v - low) / (high - low) : 0.0;
+        double kv = k.update(raw);
+        if (std::isnan(kv)) continue;
+        double dv = d.update(kv);
+        EXPECT_NEAR(r.k, kv, 1e-9) << i;
+        if (!std::isnan(dv)) {
+            EXPECT_NEAR(r.d, dv, 1e-9) << i;
+        }
+        EXPECT_GE(r.k, -1e-9);
+        EXPECT_LE(r.k, 100.0 + 1e-9);
+    }
+}
+
+TEST(StochRSI, Serializes) {
+    std::stringstream ss;
+    ss << StochRSIResult{80.0, 65.5};
+    EXPECT_EQ(ss.str(), "k:80 d:65.5");
+    StochRSIResult r = {0.0, 0.0};
+    ss >> r;
+    EXPECT_DOUBLE_EQ(r.k, 80.0);
+    EXPECT_DOUBLE_EQ(r.d, 65.5);
+}
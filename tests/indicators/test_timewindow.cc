@@ -0,0 +1,46 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include "registry.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(TimeSMA, ReturnsNaNUntilDurationIsCovered) {
+    TimeSMA sma(10);
+    EXPECT_TRUE(std::isnan(sma.update(SingleValue(0, 1.0))));
+    EXPECT_TRUE(std::isnan(sma.update(SingleValue(9, 2.0))));
+    EXPECT_DOUBLE_EQ(sma.update(SingleValue(10, 3.0)), 2.5);
+}
+
+TEST(TimeSMA, AveragesIrregularlySpacedValues) {
+    TimeSMA sma(10);
+    sma.update(SingleValue(0, 10.0));
+    sma.update(SingleValue(1, 20.0));
+    sma.update(SingleValue(2, 30.0));
+    EXPECT_DOUBLE_EQ(sma.update(SingleValue(11, 40.0)), 35.0);
+    EXPECT_DOUBLE_EQ(sma.update(Ohlcv(30, 0.0, 0.0, 0.0, 50.0, 0.0)), 50.0);
+}
+
+TEST(TimeMVar, MatchesCountWindowOnRegularData) {
+    TimeMVar tvar(3, 1);
+    MVar mvar(3, 1);
+    double values[] = {1.0, 4.0, 2.0, 8.0, 5.0, 7.0};
+    for (int i = 0; i < 6; ++i) {
+        double expected = mvar.update(values[i]);
+        double result = tvar.update(SingleValue(i, values[i]));
+        if (i >= 3) {
+            EXPECT_NEAR(result, expected, 1e-12);
+        }
+    }
+}
+
+TEST(TimeSMA, ClampsNonPositiveDuration) {
+    TimeSMA sma(0);
+    EXPECT_TRUE(std::isnan(sma.update(SingleValue(0, 1.0))));
+    EXPECT_DOUBLE_EQ(sma.update(SingleValue(1, 3.0)), 3.0);
+    TimeMVar tvar(-5, 0);
+    tvar.update(SingleValue(0, 1.0));
+    EXPECT_DOUBLE_EQ(tvar.update(SingleValue(1, 4.0)), 0.0);
+    EXPECT_FALSE(builtin_indicators().make("TimeSMA(0)"));
+    EXPECT_FALSE(builtin_indicators().make("TimeMVar(-1)"));
+}
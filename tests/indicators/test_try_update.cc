@@ -0,0 +1,48 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <string>
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(TryUpdate, TellsWarmupFromInvalidInput) {
+    SMA sma(2);
+    double out = 0.0;
+    EXPECT_EQ(sma.try_update(1.0, out), IndicatorStatus::WARMING_UP);
+    EXPECT_TRUE(std::isnan(out));
+    out = -1.0;
+    EXPECT_EQ(sma.try_update(std::nan(""), out), IndicatorStatus::INVALID_PRICE);
+    EXPECT_DOUBLE_EQ(out, -1.0);
+    // the rejected value didn't enter the window
+    EXPECT_EQ(sma.try_update(3.0, out), IndicatorStatus::OK);
+    EXPECT_DOUBLE_EQ(out, 2.0);
+    EXPECT_EQ(std::string(to_string(IndicatorStatus::INVALID_PRICE)), "invalid_price");
+}
+
+TEST(TryUpdate, ChecksBarsAndTimestamps) {
+    ATR atr(2);
+    double out = 0.0;
+    EXPECT_EQ(atr.try_update(Ohlcv(10, 1.0, 2.0, 0.5, 1.5, -1.0), out),
+            IndicatorStatus::INVALID_VOLUME);
+    EXPECT_EQ(atr.try_update(Ohlcv(10, 1.0, INFINITY, 0.5, 1.5, 1.0), out),
+            IndicatorStatus::INVALID_PRICE);
+    EXPECT_EQ(atr.try_update(Ohlcv(10, 1.0, 2.0, 0.5, 1.5, 0.0), out),
+            IndicatorStatus::WARMING_UP);
+    EXPECT_EQ(atr.try_update(Ohlcv(9, 1.0, 2.0, 0.5, 1.5, 1.0), out),
+            IndicatorStatus::NON_MONOTONIC_TIME);
+    EXPECT_EQ(atr.try_update(Ohlcv(11, 1.5, 2.5, 1.0, 2.0, 1.0), out),
+            IndicatorStatus::OK);
+    EXPECT_DOUBLE_EQ(out, 1.5);
+}
+
+TEST(TryUpdate, ChecksMainOutputOfResults) {
+    MACD macd(2, 3, 2);
+    MACDResult r;
+    for (int i = 0; i < 3; ++i)
+        EXPECT_EQ(macd.try_update(i, r), IndicatorStatus::WARMING_UP);
+    EXPECT_EQ(macd.try_update(3.0, r), IndicatorStatus::OK);
+    TimeSMA tsma(10);
+    double out = 0.0;
+    EXPECT_NE(tsma.try_update(SingleValue{5, 1.0}, out), IndicatorStatus::NON_MONOTONIC_TIME);
+    EXPECT_EQ(tsma.try_update(SingleValue{4, 1.0}, out), IndicatorStatus::NON_MONOTONIC_TIME);
+}
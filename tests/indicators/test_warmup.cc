@@ -0,0 +1,146 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <vector>
+#include "anchored.h"
+#include "ehlers.h"
+#include "filters.h"
+#include "indicators.h"
+#include "regime.h"
+#include "statistics.h"
+#include "transforms.h"
+#include "volatility.h"
+
+using namespace tzu;
+
+namespace {
+
+std::vector<Ohlcv> sample_bars() {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 120; i++) {
+        double c = 100.0 + 10.0 * std::sin(i * 0.3) + 0.2 * i;
+        bars.push_back(Ohlcv(i * 60, c - 0.5, c + 1.5, c - 1.5, c, 100.0));
+    }
+    return bars;
+}
+
+double main_output(double v) { return v; }
+double main_output(const MACDResult& r) { return r.histogram; }
+double main_output(const ADXResult& r) { return r.adx; }
+double main_output(const AroonResult& r) { return r.oscillator; }
+double main_output(const LinRegResult& r) { return r.upper; }
+double main_output(const StochRSIResult& r) { return r.d; }
+double main_output(const MAMAResult& r) { return r.fama; }
+
+// the output is missing for one input less than the required warmup
+template <typename Ind>
+void check_warmup(Ind ind) {
+    size_t n = ind.required_warmup();
+    ASSERT_GT(n, 0u);
+    std::vector<Ohlcv> bars = sample_bars();
+    Ind before = ind;
+    std::vector<Ohlcv> head(bars.begin(), bars.begin() + n - 1);
+    EXPECT_TRUE(std::isnan(main_output(before.warmup(head)))) << n;
+    head.push_back(bars[n - 1]);
+    EXPECT_FALSE(std::isnan(main_output(ind.warmup(head)))) << n;
+}
+
+} // namespace
+
+TEST(Warmup, RequiredWarmupMatchesFirstOutput) {
+    check_warmup(SMA(5));
+    check_warmup(EMA(5));
+    check_warmup(WMA(5));
+    check_warmup(HMA(9));
+    check_warmup(HMA(10));
+    check_warmup(ZLEMA(6));
+    check_warmup(T3(3));
+    check_warmup(KAMA(10));
+    check_warmup(Kalman::trend(0.1, 0.01));
+    check_warmup(SuperSmoother(10));
+    check_warmup(Roofing(48, 10));
+    check_warmup(Decycler(60));
+    check_warmup(FisherTransform(10));
+    check_warmup(MAMA());
+    check_warmup(VWAP());
+    check_warmup(CumulativeReturn());
+    check_warmup(MVar(5, 1));
+    check_warmup(Sharpe(10));
+    check_warmup(Sortino(10));
+    check_warmup(RSI(14));
+    check_warmup(MACD(12, 26, 9));
+    check_warmup(LinReg(10));
+    check_warmup(ATR(14));
+    check_warmup(ADX(14));
+    check_warmup(Highest(10));
+    check_warmup(Lowest(10));
+    check_warmup(Aroon(14));
+    check_warmup(StochRSI(5, 6, 3, 4));
+    check_warmup(EWMAVol(10));
+    check_warmup(GARCH(1e-5, 0.1, 0.8));
+    check_warmup(GARCH::fit(30, 10));
+    check_warmup(P2Quantile(0.5));
+    check_warmup(ZScore(10));
+    check_warmup(Percentile(0.9, 10));
+    check_warmup(PercentRank(10));
+    check_warmup(Hurst(20));
+    check_warmup(VarianceRatio(20, 4));
+    check_warmup(FracDiff(0.4, 10));
+    check_warmup(WaveletDenoise(16));
+    check_warmup(Chain<EMA, SMA>(EMA(3), SMA(4)));
+    check_warmup(Applied<SMA>(SMA(5), PriceSource::TYPICAL));
+}
+
+TEST(Warmup, FeedsClosesOrBars) {
+    std::vector<Ohlcv> bars = sample_bars();
+    SMA sma(10);
+    SMA manual(10);
+    for (const auto& bar : bars) manual.update(bar.close);
+    EXPECT_DOUBLE_EQ(sma.warmup(bars), manual.get());
+
+    ATR atr(14);
+    ATR manual_atr(14);
+    for (const auto& bar : bars) manual_atr.update(bar);
+    EXPECT_DOUBLE_EQ(atr.warmup(bars), manual_atr.get());
+
+    TimeSMA tsma(600);
+    EXPECT_EQ(tsma.required_warmup(), 0u);
+    EXPECT_FALSE(std::isnan(tsma.warmup(bars)));
+
+    BasicSMA<Decimal> dsma(3);
+    Decimal sum = Decimal(bars[117].close) + Decimal(bars[118].close)
+        + Decimal(bars[119].close);
+    EXPECT_EQ(dsma.warmup(bars), sum / Decimal(3.0));
+}
+
+TEST(Warmup, ReadinessFollowsLookback) {
+    MACD macd(3, 5, 4);
+    EXPECT_EQ(macd.lookback(), 8u);
+    std::vector<Ohlcv> bars = sample_bars();
+    for (size_t i = 0; i < bars.size(); i++) {
+        EXPECT_EQ(macd.is_ready(), i > macd.lookback()) << i;
+        macd.update(bars[i].close);
+    }
+
+    ADX adx(5);
+    EXPECT_EQ(adx.lookback(), 9u);
+    for (size_t i = 0; i <= adx.lookback(); i++) {
+        EXPECT_FALSE(adx.is_ready());
+        adx.update(bars[i]);
+    }
+    EXPECT_TRUE(adx.is_ready());
+
+    BasicSMA<Decimal> dsma(2);
+    dsma.update(Decimal(1.0));
+    EXPECT_FALSE(dsma.is_ready());
+    dsma.update(Decimal(2.0));
+    EXPECT_TRUE(dsma.is_ready());
+
+    HeikinAshi ha;
+    EXPECT_FALSE(ha.is_ready());
+    ha.update(bars[0]);
+    EXPECT_TRUE(ha.is_ready());
+
+    TimeSMA tsma(600);
+    EXPECT_EQ(tsma.lookback(), 0u);
+    EXPECT_FALSE(tsma.is_ready());
+}
@@ -0,0 +1,35 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(WMA, WeightsValuesLinearly) {
+    WMA wma(3);
+    EXPECT_TRUE(std::isnan(wma.update(1.0)));
+    EXPECT_TRUE(std::isnan(wma.update(2.0)));
+    EXPECT_DOUBLE_EQ(wma.update(3.0), (1.0 + 4.0 + 9.0) / 6.0);
+    EXPECT_DOUBLE_EQ(wma.update(6.0), (2.0 + 6.0 + 18.0) / 6.0);
+    EXPECT_DOUBLE_EQ(wma.update(0.0), (3.0 + 12.0 + 0.0) / 6.0);
+}
+
+TEST(HMA, WarmupLength) {
+    HMA hma(9);
+    // WMA(9) needs 9 values and the WMA(3) on top 2 more
+    for (int i = 0; i < 10; ++i)
+        EXPECT_TRUE(std::isnan(hma.update(i)));
+    EXPECT_FALSE(std::isnan(hma.update(10.0)));
+}
+
+TEST(HMA, LagsLessThanWMA) {
+    HMA hma(16);
+    WMA wma(16);
+    for (int i = 0; i < 40; ++i) {
+        hma.update(2.0 * i);
+        wma.update(2.0 * i);
+    }
+    // on a line, WMA(n) lags (n - 1) / 3 steps; the Hull combination
+    // leads by 1/3 step and its WMA(4) smoothing lags 1 step
+    EXPECT_NEAR(wma.get(), 78.0 - 2.0 * 5.0, 1e-9);
+    EXPECT_NEAR(hma.get(), 78.0 - 2.0 * 2.0 / 3.0, 1e-9);
+}
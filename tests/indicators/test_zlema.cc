@@ -0,0 +1,32 @@
+#include <gtest/gtest.h>
+#include "indicators.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(ZLEMA, WarmupLength) {
+    ZLEMA zlema(5);
+    // lag of 2 values, then 5 values for the EMA
+    for (int i = 0; i < 6; ++i)
+        EXPECT_TRUE(std::isnan(zlema.update(i)));
+    EXPECT_FALSE(std::isnan(zlema.update(6.0)));
+}
+
+TEST(ZLEMA, RemovesLagOnLinearTrend) {
+    ZLEMA zlema(5);
+    EMA ema(5);
+    for (int i = 0; i < 50; ++i) {
+        zlema.update(i);
+        ema.update(i);
+    }
+    EXPECT_NEAR(zlema.get(), 49.0, 1e-6);
+    EXPECT_NEAR(ema.get(), 47.0, 1e-6);
+}
+
+TEST(T3, WarmupAndConstantInput) {
+    T3 t3(3);
+    for (int i = 0; i < 12; ++i)
+        EXPECT_TRUE(std::isnan(t3.update(5.0)));
+    // the coefficients add up to one
+    EXPECT_NEAR(t3.update(5.0), 5.0, 1e-12);
+}
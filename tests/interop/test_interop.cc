@@ -0,0 +1,45 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <vector>
+#include "interop.h"
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(Interop, ColumnsRoundTrip) {
+    std::vector<Ohlcv> bars = {
+        Ohlcv(1, 10.0, 12.0, 9.0, 11.0, 100.0),
+        Ohlcv(2, 11.0, 13.0, 10.0, 12.5, 200.0)
+    };
+    OhlcvColumns cols = to_columns(bars);
+    ASSERT_EQ(cols.size(), 2u);
+    EXPECT_DOUBLE_EQ(cols.high[1], 13.0);
+    EXPECT_EQ(cols.timestamp[1], 2);
+    std::vector<Ohlcv> back = from_columns(cols);
+    ASSERT_EQ(back.size(), 2u);
+    EXPECT_DOUBLE_EQ(back[1].close, 12.5);
+    EXPECT_DOUBLE_EQ(back[1].volume, 200.0);
+    std::vector<double> closes = column(bars, OhlcvField::CLOSE);
+    EXPECT_DOUBLE_EQ(closes[0], 11.0);
+    std::vector<Ohlcv> no_volume = from_columns(cols.timestamp.data(),
+        cols.open.data(), cols.high.data(), cols.low.data(), cols.close.data(),
+        nullptr, cols.size());
+    EXPECT_DOUBLE_EQ(no_volume[0].volume, 0.0);
+}
+
+TEST(Interop, SelectsMembersOfResults) {
+    std::vector<ElderRayResult> results = {{1.0, -2.0}, {3.0, -4.0}};
+    std::vector<double> bears = column(results, &ElderRayResult::bear);
+    ASSERT_EQ(bears.size(), 2u);
+    EXPECT_DOUBLE_EQ(bears[1], -4.0);
+}
+
+TEST(Interop, ComputesIntoExternalBuffers) {
+    double in[5] = {1.0, 2.0, 3.0, 4.0, 5.0};
+    double out[5];
+    SMA sma(2);
+    compute_into(sma, in, 5, out);
+    EXPECT_TRUE(std::isnan(out[0]));
+    EXPECT_DOUBLE_EQ(out[1], 1.5);
+    EXPECT_DOUBLE_EQ(out[4], 4.5);
+}
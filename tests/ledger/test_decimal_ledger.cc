@@ -0,0 +1,43 @@
+#include <gtest/gtest.h>
+#include "ledger.h"
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(DecimalLedger, AccountsWithoutDrift) {
+    BasicLedger<Decimal> ledger(Decimal(1000.0));
+    // ten buys of 0.1 units at 0.1 each, fees of 0.01
+    for (int i = 0; i < 10; i++)
+        ledger.on_fill(i, "BTC", Decimal(0.1), Decimal(0.1), Decimal(0.01));
+    EXPECT_TRUE(ledger.cash() == Decimal(1000.0 - 0.1 - 0.1));
+    EXPECT_TRUE(ledger.commissions() == Decimal(0.1));
+    EXPECT_TRUE(ledger.holding("BTC").quantity == Decimal(1.0));
+    ledger.on_fill(10, "BTC", Decimal(-1.0), Decimal(0.3));
+    EXPECT_TRUE(ledger.holding("BTC").quantity == Decimal(0.0));
+    EXPECT_TRUE(ledger.realized() == Decimal(0.2));
+    EXPECT_TRUE(ledger.equity() == Decimal(1000.0 + 0.2 - 0.1));
+    ASSERT_EQ(ledger.trades().size(), 1u);
+    EXPECT_DOUBLE_EQ(ledger.trades()[0].profit, 0.2);
+}
+
+TEST(DecimalLedger, FlipsPositionsLikeDoubles) {
+    BasicLedger<Decimal> exact(Decimal(0.0));
+    Ledger approx(0.0);
+    exact.on_fill(1, "X", Decimal(2.0), Decimal(10.0));
+    approx.on_fill(1, "X", 2.0, 10.0);
+    exact.on_fill(2, "X", Decimal(-3.0), Decimal(12.0));
+    approx.on_fill(2, "X", -3.0, 12.0);
+    exact.mark("X", Ohlcv(3, 12.0, 12.0, 11.0, 11.5, 1.0));
+    approx.mark("X", Ohlcv(3, 12.0, 12.0, 11.0, 11.5, 1.0));
+    EXPECT_DOUBLE_EQ(static_cast<double>(exact.holding("X").avg_price),
+            approx.holding("X").avg_price);
+    EXPECT_DOUBLE_EQ(static_cast<double>(exact.equity()), approx.equity());
+    EXPECT_DOUBLE_EQ(static_cast<double>(exact.unrealized()), 0.5);
+}
+
+TEST(DecimalLedger, IndicatorsTakeDecimalPrices) {
+    auto sma = make_converted<Decimal>(SMA(2));
+    sma.update(Decimal(1.5));
+    EXPECT_DOUBLE_EQ(sma.update(Decimal(2.5)), 2.0);
+    EXPECT_DOUBLE_EQ(sma.inner().get(), 2.0);
+}
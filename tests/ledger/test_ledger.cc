@@ -0,0 +1,58 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include "ledger.h"
+
+using namespace tzu;
+
+TEST(Ledger, AveragesEntriesAndRealizesOnExit) {
+    Ledger ledger(10000.0);
+    ledger.on_fill(1, "AAA", 10.0, 100.0, 1.0);
+    ledger.on_fill(2, "AAA", 10.0, 110.0, 1.0);
+    Holding h = ledger.holding("AAA");
+    EXPECT_DOUBLE_EQ(h.quantity, 20.0);
+    EXPECT_DOUBLE_EQ(h.avg_price, 105.0);
+    ledger.mark("AAA", 120.0);
+    EXPECT_DOUBLE_EQ(ledger.unrealized(), 300.0);
+    ledger.on_fill(3, "AAA", -5.0, 120.0, 1.0);
+    EXPECT_DOUBLE_EQ(ledger.realized(), 75.0);
+    EXPECT_DOUBLE_EQ(ledger.holding("AAA").avg_price, 105.0);
+    ASSERT_EQ(ledger.trades().size(), 1u);
+    EXPECT_EQ(ledger.trades()[0].symbol, "AAA");
+    EXPECT_EQ(ledger.trades()[0].open_time, 1);
+    EXPECT_DOUBLE_EQ(ledger.trades()[0].quantity, 5.0);
+    EXPECT_DOUBLE_EQ(ledger.cash(), 10000.0 - 2100.0 + 600.0 - 3.0);
+    EXPECT_DOUBLE_EQ(ledger.equity(), ledger.cash() + 15.0 * 120.0);
+    EXPECT_DOUBLE_EQ(ledger.commissions(), 3.0);
+}
+
+TEST(Ledger, ShortsAndCrossesZero) {
+    Ledger ledger(1000.0);
+    ledger.on_fill(1, "BBB", -10.0, 50.0);
+    EXPECT_DOUBLE_EQ(ledger.cash(), 1500.0);
+    ledger.mark("BBB", Ohlcv(2, 50.0, 50.0, 40.0, 45.0, 1.0));
+    EXPECT_DOUBLE_EQ(ledger.unrealized(), 50.0);
+    // cover 10 and go long 5
+    ledger.on_fill(3, "BBB", 15.0, 40.0);
+    Holding h = ledger.holding("BBB");
+    EXPECT_DOUBLE_EQ(h.quantity, 5.0);
+    EXPECT_DOUBLE_EQ(h.avg_price, 40.0);
+    EXPECT_EQ(h.open_time, 3);
+    EXPECT_DOUBLE_EQ(h.realized, 100.0);
+    EXPECT_DOUBLE_EQ(ledger.trades()[0].quantity, -10.0);
+    EXPECT_DOUBLE_EQ(ledger.equity(), 1100.0);
+}
+
+TEST(Ledger, ExposureAcrossSymbols) {
+    Ledger ledger(10000.0);
+    ledger.on_fill(1, "AAA", 10.0, 100.0);
+    ledger.on_fill(1, "BBB", -20.0, 25.0);
+    EXPECT_DOUBLE_EQ(ledger.gross_exposure(), 1500.0);
+    EXPECT_DOUBLE_EQ(ledger.net_exposure(), 500.0);
+    EXPECT_DOUBLE_EQ(ledger.holding("CCC").quantity, 0.0);
+    ledger.on_fill(2, "AAA", -10.0, 90.0);
+    EXPECT_DOUBLE_EQ(ledger.holding("AAA").quantity, 0.0);
+    EXPECT_DOUBLE_EQ(ledger.gross_exposure(), 500.0);
+    std::ostringstream os;
+    os << ledger;
+    EXPECT_NE(os.str().find("realized:-100 "), std::string::npos);
+}
@@ -0,0 +1,69 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+#include "levels.h"
+
+using namespace tzu;
+
+namespace {
+
+Swing swing(int64_t ts, SwingType type, double price) {
+    return Swing{ts, type, price, ts + 2};
+}
+
+} // namespace
+
+TEST(SupportResistance, ClustersSwingPoints) {
+    SupportResistance sr(SwingDetector(2, 2), 0.01, 10);
+    sr.add(swing(1, SwingType::LOW, 100.0));
+    sr.add(swing(2, SwingType::HIGH, 110.0));
+    sr.add(swing(3, SwingType::LOW, 100.5));
+    sr.add(swing(4, SwingType::HIGH, 109.5));
+    sr.add(swing(5, SwingType::LOW, 100.2));
+    std::vector<Level> levels = sr.levels();
+    ASSERT_EQ(levels.size(), 2u);
+    EXPECT_EQ(levels[0].touches, 3u);
+    EXPECT_NEAR(levels[0].price, 100.7 / 3.0 + 200.0 / 3.0, 1e-9);
+    EXPECT_DOUBLE_EQ(levels[0].low, 100.0);
+    EXPECT_DOUBLE_EQ(levels[0].high, 100.5);
+    EXPECT_EQ(levels[0].first, 1);
+    EXPECT_EQ(levels[0].last, 5);
+    // weights 1/5, 3/5 and 5/5
+    EXPECT_NEAR(levels[0].strength, 9.0 / 5.0, 1e-12);
+    EXPECT_EQ(levels[1].touches, 2u);
+    EXPECT_NEAR(levels[1].strength, 6.0 / 5.0, 1e-12);
+    std::ostringstream os;
+    os << levels[1];
+    EXPECT_EQ(os.str(), "price:109.75 low:109.5 high:110 touches:2 strength:1.2");
+}
+
+TEST(SupportResistance, FindsNearestLevels) {
+    SupportResistance sr(SwingDetector(2, 2), 0.01, 3);
+    sr.add(swing(1, SwingType::HIGH, 120.0));
+    sr.add(swing(2, SwingType::LOW, 90.0));
+    sr.add(swing(3, SwingType::HIGH, 110.0));
+    sr.add(swing(4, SwingType::HIGH, 110.5));
+    // the oldest swing point is dropped
+    ASSERT_EQ(sr.points().size(), 3u);
+    Level level;
+    ASSERT_TRUE(sr.nearest_above(100.0, level));
+    EXPECT_DOUBLE_EQ(level.price, 110.25);
+    EXPECT_FALSE(sr.nearest_above(111.0, level));
+    ASSERT_TRUE(sr.nearest_below(111.0, level));
+    EXPECT_DOUBLE_EQ(level.price, 110.25);
+    ASSERT_TRUE(sr.nearest_below(111.0, level, 1));
+    EXPECT_FALSE(sr.nearest_below(100.0, level, 2));
+    ASSERT_TRUE(sr.nearest_below(100.0, level));
+    EXPECT_DOUBLE_EQ(level.price, 90.0);
+}
+
+TEST(SupportResistance, FindsSwingsFromBars) {
+    SupportResistance sr(SwingDetector(1, 1), 0.01);
+    double closes[] = {100.0, 105.0, 101.0, 104.9, 100.0, 105.1, 102.0};
+    for (int i = 0; i < 7; i++)
+        sr.update(Ohlcv(i, closes[i], closes[i], closes[i], closes[i], 1.0));
+    Level level;
+    ASSERT_TRUE(sr.nearest_above(102.0, level, 3));
+    EXPECT_NEAR(level.price, 105.0, 1e-9);
+    EXPECT_EQ(level.touches, 3u);
+}
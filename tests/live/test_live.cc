@@ -0,0 +1,235 @@
+#include <gtest/gtest.h>
+#include <atomic>
+#include <cstdlib>
+#include <future>
+#include <string>
+#include <thread>
+#include <vector>
+#include "feed.h"
+#include "indicators.h"
+#include "live.h"
+
+using namespace tzu;
+
+namespace {
+
+// "timestamp,close" messages; anything else is skipped
+struct CloseParser {
+    static bool parse(const std::string& msg, Ohlcv& out) {
+        size_t comma = msg.find(',');
+        if (comma == std::string::npos) return false;
+        double close = std::atof(msg.c_str() + comma + 1);
+        out = Ohlcv(std::atoll(msg.c_str()), close, close, close, close, 0.0);
+        return true;
+    }
+};
+
+RetryPolicy fast_retries(size_t attempts) {
+    RetryPolicy policy;
+    policy.max_attempts = attempts;
+    policy.initial_delay = 1;
+    policy.max_delay = 1;
+    return policy;
+}
+
+} // namespace
+
+TEST(LiveRunner, ReconnectsAndDropsReplayedValues) {
+    std::vector<std::vector<std::string>> sessions = {
+        {"hello", "1,10", "2,11"},
+        {"2,11", "3,12"},
+    };
+    size_t session = 0, pos = 0;
+    auto connect = [&] {
+        if (session >= sessions.size()) return false;
+        pos = 0;
+        return true;
+    };
+    auto receive = [&](std::string& msg) {
+        if (pos >= sessions[session].size()) {
+            session++;
+            return false;
+        }
+        msg = sessions[session][pos++];
+        return true;
+    };
+    std::vector<double> closes;
+    LiveRunner<Ohlcv, CloseParser> runner(connect, receive,
+            [&](const Ohlcv& bar) { closes.push_back(bar.close); },
+            LiveRunner<Ohlcv, CloseParser>::Disconnect(), fast_retries(3));
+    EXPECT_EQ(runner.run(), LiveStatus::FAILED);
+    std::vector<double> expected = {10.0, 11.0, 12.0};
+    EXPECT_EQ(closes, expected);
+    EXPECT_EQ(runner.num_reconnects(), 1u);
+    EXPECT_EQ(runner.num_skipped(), 1u);
+    EXPECT_EQ(runner.num_duplicates(), 1u);
+}
+
+TEST(LiveRunner, KeepsValuesSharingATimestamp) {
+    std::vector<std::vector<std::string>> sessions = {
+        {"1,10", "1,11", "0,9"},
+        {"1,11", "1,11", "2,12", "2,13"},
+    };
+    size_t session = 0, pos = 0;
+    auto connect = [&] {
+        if (session >= sessions.size()) return false;
+        pos = 0;
+        return true;
+    };
+    auto receive = [&](std::string& msg) {
+        if (pos >= sessions[session].size()) {
+            session++;
+            return false;
+        }
+        msg = sessions[session][pos++];
+        return true;
+    };
+    std::vector<double> closes;
+    LiveRunner<Ohlcv, CloseParser> runner(connect, receive,
+            [&](const Ohlcv& bar) { closes.push_back(bar.close); },
+            LiveRunner<Ohlcv, CloseParser>::Disconnect(), fast_retries(3));
+    EXPECT_EQ(runner.run(), LiveStatus::FAILED);
+    std::vector<double> expected = {10.0, 11.0, 12.0, 13.0};
+    EXPECT_EQ(closes, expected);
+    EXPECT_EQ(runner.num_duplicates(), 3u);
+}
+
+TEST(LiveRunner, BacksOffAfterDrops) {
+    RetryPolicy policy;
+    policy.initial_delay = 60000;
+    policy.max_delay = 60000;
+    std::atomic<int> connects(0);
+    LiveRunner<Ohlcv, CloseParser> runner(
+            [&] { connects++; return true; },
+            [](std::string&) { return false; },
+            [](const Ohlcv&) {},
+            LiveRunner<Ohlcv, CloseParser>::Disconnect(), policy);
+    runner.start();
+    std::this_thread::sleep_for(std::chrono::milliseconds(20));
+    runner.stop();
+    runner.join();
+    EXPECT_EQ(connects, 1);
+    EXPECT_EQ(runner.get_status(), LiveStatus::STOPPED);
+}
+
+TEST(LiveRunner, GivesUpAfterConsecutiveFailures) {
+    int attempts = 0;
+    LiveRunner<Ohlcv, CloseParser> runner(
+            [&] { attempts++; return false; },
+            [](std::string&) { return false; },
+            [](const Ohlcv&) {},
+            LiveRunner<Ohlcv, CloseParser>::Disconnect(), fast_retries(4));
+    EXPECT_EQ(runner.run(), LiveStatus::FAILED);
+    EXPECT_EQ(attempts, 4);
+}
+
+TEST(LiveRunner, StopsGracefully) {
+    MessageQueue queue;
+    std::atomic<int> received(0);
+    LiveRunner<Ohlcv, CloseParser> runner(
+            [] { return true; },
+            queue.receiver(),
+            [&](const Ohlcv&) { received++; },
+            [&] { queue.close(); });
+    EXPECT_EQ(runner.get_status(), LiveStatus::IDLE);
+    runner.start();
+    queue.push("1,10");
+    queue.push("2,11");
+    while (received < 2) std::this_thread::yield();
+    EXPECT_EQ(runner.get_status(), LiveStatus::RUNNING);
+    runner.stop();
+    runner.join();
+    EXPECT_EQ(runner.get_status(), LiveStatus::STOPPED);
+    EXPECT_EQ(runner.num_reconnects(), 0u);
+}
+
+TEST(LiveRunner, StopInterruptsTheBackoff) {
+    RetryPolicy policy;
+    policy.initial_delay = 60000;
+    policy.max_delay = 60000;
+    LiveRunner<Ohlcv, CloseParser> runner(
+            [] { return false; },
+            [](std::string&) { return false; },
+            [](const Ohlcv&) {},
+            LiveRunner<Ohlcv, CloseParser>::Disconnect(), policy);
+    runner.start();
+    std::this_thread::sleep_for(std::chrono::milliseconds(10));
+    runner.stop();
+    runner.join();
+    EXPECT_EQ(runner.get_status(), LiveStatus::STOPPED);
+}
+
+TEST(LiveRunner, RequestStopOnlySetsAFlag) {
+    MessageQueue queue;
+    LiveRunner<Ohlcv, CloseParser> runner(
+            [] { return true; },
+            queue.receiver(),
+            [](const Ohlcv&) {},
+            [&] { queue.close(); });
+    runner.start();
+    runner.request_stop();
+    // the loop notices once `receive` returns
+    queue.push("1,10");
+    runner.join();
+    EXPECT_EQ(runner.get_status(), LiveStatus::STOPPED);
+    runner.stop();
+}
+
+TEST(PushFeed, HandlesMessagesFromCallbacks) {
+    std::vector<double> closes;
+    PushFeed<Ohlcv, CloseParser> feed(
+            [&](const Ohlcv& bar) { closes.push_back(bar.close); });
+    std::thread client([&feed] {
+        feed.on_message("hello");
+        feed.on_message("1,10");
+        feed.on_message("1,11");
+        feed.on_reconnect();
+        feed.on_message("1,11");
+        feed.on_message("0,9");
+        feed.on_message("2,12");
+    });
+    client.join();
+    std::vector<double> expected = {10.0, 11.0, 12.0};
+    EXPECT_EQ(closes, expected);
+    EXPECT_EQ(feed.num_skipped(), 1u);
+    EXPECT_EQ(feed.num_duplicates(), 2u);
+}
+
+TEST(AsyncEngine, RunsCallsInOrder) {
+    IndicatorEngine engine;
+    engine.add("sma", SMA(2));
+    AsyncEngine async(engine);
+    std::vector<std::future<bool>> accepted;
+    for (int64_t i = 1; i <= 3; i++) {
+        double close = static_cast<double>(i);
+        accepted.push_back(async.update("AAA",
+                Ohlcv(i, close, close, close, close, 1.0)));
+    }
+    accepted.push_back(async.update("AAA", Ohlcv(2, 1.0, 1.0, 1.0, 1.0, 1.0)));
+    EXPECT_DOUBLE_EQ(async.get("AAA", "sma").get(), 2.5);
+    EXPECT_TRUE(accepted[2].get());
+    EXPECT_FALSE(accepted[3].get());
+    SymbolSnapshot snapshot = async.snapshot("AAA").get();
+    EXPECT_EQ(snapshot.timestamp, 3);
+    EXPECT_EQ(async.snapshot().get().size(), 1u);
+    // the engine given is copied
+    EXPECT_TRUE(engine.symbols().empty());
+}
+
+TEST(ThreadedBroker, ForwardsCallsToTheBroker) {
+    SimulatedBroker sim(1000.0);
+    ThreadedBroker threaded(sim);
+    AsyncBroker& broker = threaded;
+    std::future<std::string> id =
+        broker.submit(0, Order::limit("AAA", 10.0, 9.0));
+    std::future<std::vector<Order>> open = broker.open_orders();
+    std::string first = id.get();
+    ASSERT_FALSE(first.empty());
+    EXPECT_EQ(open.get().size(), 1u);
+    std::string next = broker.replace(1, first, 5.0, 8.0).get();
+    ASSERT_FALSE(next.empty());
+    EXPECT_DOUBLE_EQ(broker.order(next).get().limit_price, 8.0);
+    EXPECT_TRUE(broker.cancel(next).get());
+    EXPECT_FALSE(broker.cancel(next).get());
+    EXPECT_DOUBLE_EQ(broker.state().get().cash, 1000.0);
+}
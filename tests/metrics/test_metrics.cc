@@ -0,0 +1,47 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <vector>
+#include "metrics.h"
+
+using namespace tzu;
+
+TEST(Metrics, DrawdownAndDuration) {
+    std::vector<std::pair<int64_t, double>> curve = {
+        {0, 100.0}, {1, 120.0}, {2, 90.0}, {3, 110.0}, {4, 125.0}, {5, 120.0}
+    };
+    Metrics m = compute_metrics(curve, {}, {}, 1);
+    EXPECT_DOUBLE_EQ(m.total_return, 0.2);
+    EXPECT_DOUBLE_EQ(m.max_drawdown, 0.25);
+    // below the peak of t = 1 until t = 4
+    EXPECT_EQ(m.max_drawdown_duration, 3);
+}
+
+TEST(Metrics, RatiosFromReturns) {
+    MetricsTracker tracker(4);  // four periods per year
+    double equity[] = {100.0, 110.0, 99.0, 108.9, 119.79};
+    for (int i = 0; i < 5; ++i)
+        tracker.update(i, equity[i], i % 2 == 1);
+    Metrics m = tracker.get();
+    // returns 0.1, -0.1, 0.1, 0.1 over one year
+    double mean = 0.05;
+    double stddev = std::sqrt((3 * 0.0025 + 0.0225) / 3.0);
+    EXPECT_NEAR(m.sharpe, mean / stddev * 2.0, 1e-12);
+    EXPECT_NEAR(m.sortino, mean / std::sqrt(0.01 / 4.0) * 2.0, 1e-12);
+    EXPECT_NEAR(m.cagr, 0.1979, 1e-12);
+    EXPECT_NEAR(m.calmar, 0.1979 / 0.1, 1e-9);
+    EXPECT_DOUBLE_EQ(m.exposure, 0.5);
+}
+
+TEST(Metrics, TradeStatistics) {
+    std::vector<Trade> trades = {
+        Trade(0, 1, 10.0, 12.0, 1.0, 20.0, true),
+        Trade(1, 2, 12.0, 11.0, 1.0, -10.0, true),
+        Trade(2, 3, 11.0, 14.0, 1.0, 30.0, true),
+        Trade(3, 0, 14.0, 0.0, 1.0, 0.0, false),
+    };
+    Metrics m = compute_metrics({{0, 100.0}, {3, 140.0}}, trades);
+    EXPECT_EQ(m.num_trades, 3u);
+    EXPECT_DOUBLE_EQ(m.win_rate, 2.0 / 3.0);
+    EXPECT_DOUBLE_EQ(m.profit_factor, 5.0);
+    EXPECT_DOUBLE_EQ(m.expectancy, 40.0 / 3.0);
+}
@@ -0,0 +1,54 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <set>
+#include "optimize.h"
+#include "indicators.h"
+
+using namespace tzu;
+
+TEST(Optimize, GridCoversAllCombinations) {
+    Param fast = param_range("fast", 5, 15, 5);
+    ASSERT_EQ(fast.values.size(), 3u);
+    Param slow = param_range("slow", 0.1, 0.3, 0.1);
+    ASSERT_EQ(slow.values.size(), 3u);
+    std::vector<ParamSet> sets = grid({fast, slow});
+    ASSERT_EQ(sets.size(), 9u);
+    EXPECT_DOUBLE_EQ(sets[0].at("fast"), 5.0);
+    EXPECT_DOUBLE_EQ(sets[8].at("slow"), slow.values[2]);
+}
+
+TEST(Optimize, LatinHypercubeCoversEachStratum) {
+    Param p = param_range("p", 1, 10);
+    std::vector<ParamSet> sets = latin_hypercube({p, param_range("q", 0, 4)}, 10, 42);
+    ASSERT_EQ(sets.size(), 10u);
+    std::set<double> seen;
+    for (const auto& s : sets) seen.insert(s.at("p"));
+    // ten strata over ten values: each value once
+    EXPECT_EQ(seen.size(), 10u);
+    std::vector<ParamSet> random = random_samples({p}, 5, 1);
+    ASSERT_EQ(random.size(), 5u);
+    EXPECT_GE(random[0].at("p"), 1.0);
+}
+
+TEST(Optimize, RanksResultsInParallel) {
+    std::vector<double> prices;
+    for (int i = 0; i < 200; ++i) prices.push_back(100.0 + 10.0 * std::sin(i / 10.0));
+    auto evaluate = [&prices](const ParamSet& params) {
+        size_t period = static_cast<size_t>(params.at("period"));
+        if (period == 7) return std::nan("");
+        SMA sma(period);
+        double error = 0.0;
+        for (size_t i = 0; i + 1 < prices.size(); i++) {
+            double value = sma.update(prices[i]);
+            if (!std::isnan(value)) error += std::fabs(value - prices[i + 1]);
+        }
+        return error / prices.size();
+    };
+    std::vector<OptimizationResult> results =
+        optimize(grid({param_range("period", 1, 20)}), evaluate, 4, false);
+    ASSERT_EQ(results.size(), 20u);
+    EXPECT_DOUBLE_EQ(results[0].params.at("period"), 1.0);
+    EXPECT_TRUE(std::isnan(results.back().score));
+    for (size_t i = 1; i + 1 < results.size(); i++)
+        EXPECT_LE(results[i - 1].score, results[i].score);
+}
@@ -0,0 +1,79 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include "orderbook.h"
+
+using namespace tzu;
+
+namespace {
+
+BookSnapshot make_book() {
+    BookSnapshot book;
+    book.timestamp = 1;
+    book.bids = {BookLevel(100.0, 3.0), BookLevel(99.5, 5.0)};
+    book.asks = {BookLevel(100.5, 1.0), BookLevel(101.0, 2.0)};
+    return book;
+}
+
+} // namespace
+
+TEST(OrderBook, AppliesSnapshotsAndDeltas) {
+    OrderBook book;
+    EXPECT_TRUE(std::isnan(book.best_bid()));
+    book.update(make_book());
+    EXPECT_DOUBLE_EQ(book.best_bid(), 100.0);
+    EXPECT_DOUBLE_EQ(book.best_ask(), 100.5);
+    EXPECT_TRUE(book.update(BookDelta(2, Side::BUY, 100.25, 4.0)));
+    EXPECT_TRUE(book.update(BookDelta(3, Side::SELL, 100.5, 0.0)));
+    EXPECT_FALSE(book.update(BookDelta(4, Side::NONE, 100.0, 1.0)));
+    EXPECT_FALSE(book.update(BookDelta(4, Side::BUY, 100.0, -1.0)));
+    EXPECT_EQ(book.timestamp(), 3);
+    EXPECT_EQ(book.levels(Side::BUY), 3u);
+    EXPECT_EQ(book.levels(Side::SELL), 1u);
+    BookSnapshot top = book.snapshot(2);
+    ASSERT_EQ(top.bids.size(), 2u);
+    EXPECT_DOUBLE_EQ(top.bids[0].price, 100.25);
+    EXPECT_DOUBLE_EQ(top.bids[1].price, 100.0);
+    ASSERT_EQ(top.asks.size(), 1u);
+    EXPECT_DOUBLE_EQ(top.asks[0].price, 101.0);
+    EXPECT_EQ(book.snapshot().bids.size(), 3u);
+    std::ostringstream os;
+    os << top;
+    EXPECT_EQ(os.str(), "timestamp:3 bid:100.25 bid_size:4 ask:101 ask_size:2");
+}
+
+TEST(OrderBook, DropsInvalidSnapshotLevels) {
+    BookSnapshot snapshot = make_book();
+    double nan = std::nan("");
+    snapshot.bids.push_back(BookLevel(nan, 1.0));
+    snapshot.bids.push_back(BookLevel(99.0, INFINITY));
+    snapshot.asks.push_back(BookLevel(INFINITY, 1.0));
+    snapshot.asks.push_back(BookLevel(102.0, nan));
+    OrderBook book;
+    book.update(snapshot);
+    EXPECT_EQ(book.levels(Side::BUY), 2u);
+    EXPECT_EQ(book.levels(Side::SELL), 2u);
+    EXPECT_DOUBLE_EQ(book.best_bid(), 100.0);
+    EXPECT_DOUBLE_EQ(book.best_ask(), 100.5);
+}
+
+TEST(OrderBook, MicrostructureIndicators) {
+    BookSnapshot book = make_book();
+    BidAskSpread spread;
+    EXPECT_DOUBLE_EQ(spread.update(book), 0.5);
+    BidAskSpread relative(true);
+    EXPECT_DOUBLE_EQ(relative.update(book), 0.5 / 100.25);
+    BookImbalance imbalance;
+    EXPECT_DOUBLE_EQ(imbalance.update(book), 0.5);
+    BookImbalance deep(2);
+    EXPECT_DOUBLE_EQ(deep.update(book), (8.0 - 3.0) / 11.0);
+    Microprice micro;
+    // more size on the bid side pulls the price towards the ask
+    EXPECT_DOUBLE_EQ(micro.update(book), (100.5 * 3.0 + 100.0 * 1.0) / 4.0);
+    book.asks.clear();
+    EXPECT_TRUE(std::isnan(spread.update(book)));
+    EXPECT_TRUE(std::isnan(micro.update(book)));
+    EXPECT_DOUBLE_EQ(imbalance.update(book), 1.0);
+    double out = 0.0;
+    EXPECT_EQ(micro.try_update(book, out), IndicatorStatus::WARMING_UP);
+}
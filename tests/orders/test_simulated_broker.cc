@@ -0,0 +1,90 @@
+#include <gtest/gtest.h>
+#include "orders.h"
+
+using namespace tzu;
+
+TEST(SimulatedBroker, MarketOrderFillsAtNextOpen) {
+    SimulatedBroker broker(10000.0, 0.001, 0.0005);
+    std::string id = broker.submit(1, Order::market("AAA", 10.0));
+    // same bar as the submission: no fill
+    EXPECT_FALSE(broker.update("AAA", Ohlcv(1, 100.0, 101.0, 99.0, 100.0, 1e6)));
+    ASSERT_TRUE(broker.update("AAA", Ohlcv(2, 102.0, 103.0, 101.0, 102.5, 1e6)));
+    Fill fill = broker.fills()[0];
+    EXPECT_DOUBLE_EQ(fill.price, 102.0 * 1.001);
+    EXPECT_DOUBLE_EQ(fill.commission, 10.0 * fill.price * 0.0005);
+    EXPECT_EQ(broker.order(id).status, OrderStatus::FILLED);
+    EXPECT_DOUBLE_EQ(broker.account().holding("AAA").quantity, 10.0);
+    EXPECT_DOUBLE_EQ(broker.account().cash(),
+            10000.0 - 10.0 * fill.price - fill.commission);
+}
+
+TEST(SimulatedBroker, ConservativeLimitAndStopRules) {
+    SimulatedBroker broker(10000.0);
+    std::string limit = broker.submit(0, Order::limit("AAA", 5.0, 99.0));
+    std::string stop = broker.submit(0, Order::stop("AAA", -5.0, 95.0));
+    // touches 99 without going through: no fill
+    EXPECT_FALSE(broker.update("AAA", Ohlcv(1, 100.0, 101.0, 99.0, 100.0, 1e6)));
+    ASSERT_TRUE(broker.update("AAA", Ohlcv(2, 100.0, 100.5, 98.0, 99.0, 1e6)));
+    EXPECT_DOUBLE_EQ(broker.order(limit).avg_fill_price, 99.0);
+    EXPECT_TRUE(broker.order(stop).is_open());
+    // gaps below the stop: fills at the open
+    ASSERT_TRUE(broker.update("AAA", Ohlcv(3, 93.0, 94.0, 92.0, 93.5, 1e6)));
+    EXPECT_DOUBLE_EQ(broker.order(stop).avg_fill_price, 93.0);
+    EXPECT_TRUE(broker.open_orders().empty());
+    // a stop-limit triggered within the bar waits for the next one
+    std::string sl = broker.submit(3, Order::stop_limit("AAA", 5.0, 95.0, 96.0));
+    EXPECT_FALSE(broker.update("AAA", Ohlcv(4, 94.0, 95.5, 93.0, 95.0, 1e6)));
+    EXPECT_TRUE(broker.order(sl).triggered);
+    ASSERT_TRUE(broker.update("AAA", Ohlcv(5, 95.5, 96.0, 95.0, 95.8, 1e6)));
+    EXPECT_DOUBLE_EQ(broker.order(sl).avg_fill_price, 95.5);
+}
+
+TEST(SimulatedBroker, PartialFillsCancelAndRisk) {
+    SimulatedBroker broker(100000.0, 0.0, 0.0, 0.1);
+    RiskLimits limits;
+    limits.max_position = 150.0;
+    RiskChecker checker(limits);
+    broker.set_risk_checker(&checker);
+    std::string id = broker.submit(0, Order::market("AAA", 200.0));
+    // resized to the position limit
+    EXPECT_DOUBLE_EQ(broker.order(id).quantity, 150.0);
+    broker.update("AAA", Ohlcv(1, 10.0, 10.0, 10.0, 10.0, 1000.0));
+    EXPECT_EQ(broker.order(id).status, OrderStatus::PARTIAL);
+    EXPECT_DOUBLE_EQ(broker.order(id).filled, 100.0);
+    EXPECT_DOUBLE_EQ(checker.position("AAA"), 100.0);
+    AccountState state = broker.state();
+    EXPECT_DOUBLE_EQ(state.positions["AAA"], 100.0);
+    EXPECT_DOUBLE_EQ(state.orders[id].quantity, 50.0);
+    EXPECT_TRUE(broker.cancel(id));
+    EXPECT_FALSE(broker.cancel(id));
+    EXPECT_EQ(broker.order(id).status, OrderStatus::CANCELED);
+    // no room left under the position limit
+    broker.update("AAA", Ohlcv(2, 10.0, 10.0, 10.0, 10.0, 1000.0));
+    std::string full = broker.submit(2, Order::market("AAA", 100.0));
+    EXPECT_DOUBLE_EQ(broker.order(full).quantity, 50.0);
+    broker.update("AAA", Ohlcv(3, 10.0, 10.0, 10.0, 10.0, 1000.0));
+    std::string none = broker.submit(3, Order::market("AAA", 10.0));
+    EXPECT_EQ(broker.order(none).status, OrderStatus::REJECTED);
+    EXPECT_EQ(broker.state().orders.count(none), 0u);
+    EXPECT_EQ(broker.order("missing").status, OrderStatus::REJECTED);
+    EXPECT_EQ(broker.order(broker.submit(2, Order::limit("AAA", 1.0, std::nan(""))))
+            .status, OrderStatus::REJECTED);
+}
+
+TEST(SimulatedBroker, ReplaceKeepsTheFilledPart) {
+    SimulatedBroker sim(100000.0, 0.0, 0.0, 0.1);
+    Broker& broker = sim;
+    std::string id = broker.submit(0, Order::limit("AAA", 100.0, 99.0));
+    ASSERT_TRUE(sim.update("AAA", Ohlcv(1, 98.0, 99.0, 97.0, 98.0, 500.0)));
+    EXPECT_DOUBLE_EQ(broker.order(id).filled, 50.0);
+    std::string next = broker.replace(1, id, 80.0, 97.5);
+    ASSERT_FALSE(next.empty());
+    EXPECT_NE(next, id);
+    EXPECT_EQ(broker.order(id).status, OrderStatus::CANCELED);
+    Order replaced = broker.order(next);
+    EXPECT_DOUBLE_EQ(replaced.quantity, 30.0);
+    EXPECT_DOUBLE_EQ(replaced.limit_price, 97.5);
+    ASSERT_EQ(broker.open_orders().size(), 1u);
+    EXPECT_TRUE(broker.replace(1, id, 10.0).empty());
+    EXPECT_DOUBLE_EQ(broker.state().positions["AAA"], 50.0);
+}
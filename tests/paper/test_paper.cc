@@ -0,0 +1,103 @@
+#include <gtest/gtest.h>
+#include <cstdio>
+#include <fstream>
+#include <string>
+#include <vector>
+#include "paper.h"
+
+using namespace tzu;
+
+namespace {
+
+Ohlcv bar_at(int64_t t, double close) {
+    return Ohlcv(t, close, close + 1.0, close - 1.0, close, 1000.0);
+}
+
+// buys at the first bar and sells at the third
+struct OnceStrat {
+    int count = 0;
+    Signal update(const Ohlcv& bar) {
+        count++;
+        Side side = count == 1 ? Side::BUY
+            : count == 3 ? Side::SELL : Side::NONE;
+        return Signal(bar.timestamp, side, bar.close);
+    }
+};
+
+std::vector<std::string> read_lines(const std::string& path) {
+    std::ifstream file(path);
+    std::vector<std::string> lines;
+    std::string line;
+    while (std::getline(file, line)) lines.push_back(line);
+    return lines;
+}
+
+const char FILLS[] = "paper_test_fills.csv";
+const char EQUITY[] = "paper_test_equity.csv";
+
+} // namespace
+
+TEST(PaperTrader, TradesWithTheSimulatedBroker) {
+    PaperTrader<OnceStrat> paper("A", OnceStrat(), 1000.0, fixed_size(2.0));
+    std::vector<Ohlcv> bars = {bar_at(1, 10.0), bar_at(2, 11.0),
+        bar_at(2, 50.0), bar_at(3, 12.0), bar_at(4, 13.0)};
+    EXPECT_EQ(paper.run(bars), 4u);
+    EXPECT_DOUBLE_EQ(paper.account().cash(), 1004.0);
+    EXPECT_EQ(paper.account().trades().size(), 1u);
+    EXPECT_TRUE(paper.broker().open_orders().empty());
+}
+
+TEST(PaperTrader, JournalsFillsAndEquity) {
+    std::remove(FILLS);
+    std::remove(EQUITY);
+    {
+        PaperTrader<OnceStrat> paper("A", OnceStrat(), 1000.0,
+                fixed_size(2.0));
+        std::string error;
+        ASSERT_TRUE(paper.open(FILLS, EQUITY, error)) << error;
+        paper.update(bar_at(1, 10.0));
+        paper.update(bar_at(2, 11.0));
+    }
+    {
+        // a restarted session rebuilds the account from the journal
+        PaperTrader<OnceStrat> paper("A", OnceStrat(), 1000.0,
+                fixed_size(2.0));
+        std::string error;
+        ASSERT_TRUE(paper.open(FILLS, EQUITY, error)) << error;
+        EXPECT_DOUBLE_EQ(paper.account().cash(), 978.0);
+        EXPECT_DOUBLE_EQ(paper.account().holding("A").quantity, 2.0);
+        EXPECT_FALSE(paper.update(bar_at(2, 11.0)));
+        EXPECT_TRUE(paper.update(bar_at(3, 12.0)));
+        paper.update(bar_at(4, 12.0));
+    }
+    std::vector<std::string> fills = read_lines(FILLS);
+    ASSERT_EQ(fills.size(), 3u);
+    EXPECT_EQ(fills[0], "timestamp,order_id,symbol,quantity,price,commission");
+    EXPECT_EQ(fills[1], "2,1,A,2,11,0");
+    // the order ids continue after the ones in the journal
+    EXPECT_EQ(fills[2], "4,2,A,2,12,0");
+    std::vector<std::string> equity = read_lines(EQUITY);
+    ASSERT_EQ(equity.size(), 5u);
+    EXPECT_EQ(equity[0], "timestamp,cash,equity");
+    EXPECT_EQ(equity[1], "1,1000,1000");
+    EXPECT_EQ(equity[2], "2,978,1000");
+    EXPECT_EQ(equity[3], "3,978,1002");
+    EXPECT_EQ(equity[4], "4,954,1002");
+    std::remove(FILLS);
+    std::remove(EQUITY);
+}
+
+TEST(PaperTrader, ReportsFilesThatCantBeOpened) {
+    PaperTrader<OnceStrat> paper("A", OnceStrat(), 1000.0, fixed_size(1.0));
+    std::string error;
+    EXPECT_FALSE(paper.open("no/such/dir/fills.csv", EQUITY, error));
+    EXPECT_EQ(error, "cannot open 'no/such/dir/fills.csv'");
+    {
+        std::ofstream corrupt(FILLS);
+        corrupt << "timestamp,order_id,symbol,quantity,price,commission\n"
+            << "2,1,A,two,11,0\n";
+    }
+    EXPECT_FALSE(paper.open(FILLS, EQUITY, error));
+    EXPECT_EQ(error, "invalid fill at line 2 of 'paper_test_fills.csv'");
+    std::remove(FILLS);
+}
@@ -0,0 +1,82 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <cstdint>
+#include <vector>
+#include "regime.h"
+
+using namespace tzu;
+
+namespace {
+
+// Gaussian-ish noise from a fixed LCG, so the tests are reproducible
+class Noise {
+    uint64_t state = 12345;
+    double uniform() {
+        state = state * 6364136223846793005ULL + 1442695040888963407ULL;
+        return static_cast<double>(state >> 11) / 9007199254740992.0;
+    }
+public:
+    double next() {
+        double sum = 0.0;
+        for (int i = 0; i < 12; i++) sum += uniform();
+        return sum - 6.0;
+    }
+};
+
+// Prices whose log returns follow r = phi * r + noise
+std::vector<double> prices(double phi, size_t n) {
+    Noise noise;
+    std::vector<double> out;
+    double logp = std::log(100.0), r = 0.0;
+    for (size_t i = 0; i < n; i++) {
+        r = phi * r + 0.01 * noise.next();
+        logp += r;
+        out.push_back(std::exp(logp));
+    }
+    return out;
+}
+
+template <class Ind>
+double last(Ind ind, const std::vector<double>& xs) {
+    double out = std::nan("");
+    for (double x : xs) out = ind.update(x);
+    return out;
+}
+
+} // namespace
+
+TEST(Hurst, SeparatesRegimes) {
+    double walk = last(Hurst(400, 20), prices(0.0, 400));
+    double trend = last(Hurst(400, 20), prices(0.8, 400));
+    double revert = last(Hurst(400, 20), prices(-0.8, 400));
+    EXPECT_NEAR(walk, 0.5, 0.1);
+    EXPECT_GT(trend, 0.65);
+    EXPECT_LT(revert, 0.35);
+}
+
+TEST(Hurst, WarmupAndConstantPrices) {
+    Hurst hurst(10);
+    for (int i = 0; i < 9; i++) EXPECT_TRUE(std::isnan(hurst.update(50.0)));
+    EXPECT_DOUBLE_EQ(hurst.update(50.0), 0.5);
+    EXPECT_EQ(hurst.required_warmup(), 10u);
+}
+
+TEST(VarianceRatio, MatchesDefinition) {
+    VarianceRatio vr(5, 2);
+    std::vector<double> logs = {0.0, 1.0, 2.0, 4.0, 6.0};
+    double out = std::nan("");
+    for (double x : logs) out = vr.update(std::exp(x));
+    // one bar returns 1, 1, 2, 2 with mean 1.5 and variance 0.25; two
+    // bar returns 2, 3, 4 deviate 1, 0, 1 from 3
+    EXPECT_NEAR(out, (2.0 / 3.0) / (2.0 * 0.25), 1e-12);
+    // alternating returns cancel over two bars
+    VarianceRatio zigzag(5, 2);
+    for (double x : {0.0, 1.0, 0.0, 1.0, 0.0}) out = zigzag.update(std::exp(x));
+    EXPECT_NEAR(out, 0.0, 1e-12);
+}
+
+TEST(VarianceRatio, SeparatesRegimes) {
+    EXPECT_NEAR(last(VarianceRatio(400, 4), prices(0.0, 400)), 1.0, 0.25);
+    EXPECT_GT(last(VarianceRatio(400, 4), prices(0.8, 400)), 2.0);
+    EXPECT_LT(last(VarianceRatio(400, 4), prices(-0.8, 400)), 0.5);
+}
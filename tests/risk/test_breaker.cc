@@ -0,0 +1,52 @@
+#include <gtest/gtest.h>
+#include "risk.h"
+
+using namespace tzu;
+
+TEST(CircuitBreaker, TripsOnDrawdownAndStaysHalted) {
+    BreakerConfig config;
+    config.max_drawdown = 0.1;
+    config.flatten = true;
+    CircuitBreaker breaker(config);
+    breaker.update_equity(1, 1000.0);
+    breaker.update_equity(2, 1200.0);
+    breaker.update_equity(3, 1100.0);
+    EXPECT_TRUE(breaker.allow());
+    breaker.update_equity(4, 1080.0);
+    EXPECT_FALSE(breaker.allow());
+    EXPECT_TRUE(breaker.should_flatten());
+    EXPECT_EQ(breaker.reason(), BreakerTrigger::DRAWDOWN);
+    EXPECT_EQ(breaker.since(), 4);
+    breaker.update_equity(5, 1300.0);
+    EXPECT_FALSE(breaker.allow());
+    breaker.reset();
+    EXPECT_TRUE(breaker.allow());
+}
+
+TEST(CircuitBreaker, TripsOnErrorBurst) {
+    BreakerConfig config;
+    config.max_errors = 3;
+    config.error_window = 10;
+    CircuitBreaker breaker(config);
+    breaker.record_error(0);
+    breaker.record_error(5);
+    breaker.record_error(12);
+    EXPECT_TRUE(breaker.allow());
+    breaker.record_error(14);
+    EXPECT_EQ(breaker.reason(), BreakerTrigger::ERROR_RATE);
+}
+
+TEST(CircuitBreaker, TripsOnStaleFeedAndManualHalt) {
+    BreakerConfig config;
+    config.max_staleness = 30;
+    CircuitBreaker breaker(config);
+    breaker.on_data(100);
+    EXPECT_TRUE(breaker.check(120));
+    EXPECT_FALSE(breaker.check(131));
+    EXPECT_EQ(breaker.reason(), BreakerTrigger::STALE_FEED);
+    EXPECT_FALSE(breaker.should_flatten());
+    CircuitBreaker manual(BreakerConfig{});
+    EXPECT_TRUE(manual.check(1000));
+    manual.halt(1001);
+    EXPECT_EQ(manual.reason(), BreakerTrigger::MANUAL);
+}
@@ -0,0 +1,55 @@
+#include <gtest/gtest.h>
+#include "risk.h"
+
+using namespace tzu;
+
+TEST(RiskChecker, AcceptsOrdersWithinLimits) {
+    RiskLimits limits;
+    limits.max_position = 10.0;
+    limits.max_order_notional = 1000.0;
+    RiskChecker risk(limits);
+    RiskDecision d = risk.check(0, "AAA", 5.0, 100.0);
+    EXPECT_EQ(d.action, RiskAction::ACCEPT);
+    EXPECT_DOUBLE_EQ(d.quantity, 5.0);
+    EXPECT_TRUE(risk.get_events().empty());
+}
+
+TEST(RiskChecker, ResizesOrRejectsOrdersOverLimits) {
+    RiskLimits limits;
+    limits.max_position = 10.0;
+    limits.max_gross_exposure = 1500.0;
+    RiskChecker risk(limits);
+    risk.on_fill("AAA", 8.0, 100.0);
+    RiskDecision d = risk.check(1, "AAA", 5.0, 100.0);
+    EXPECT_EQ(d.action, RiskAction::RESIZE);
+    EXPECT_EQ(d.violation, RiskViolation::POSITION);
+    EXPECT_DOUBLE_EQ(d.quantity, 2.0);
+    d = risk.check(2, "BBB", 10.0, 100.0);
+    EXPECT_EQ(d.violation, RiskViolation::GROSS_EXPOSURE);
+    EXPECT_DOUBLE_EQ(d.quantity, 7.0);
+    // reducing a position is always allowed
+    d = risk.check(3, "AAA", -8.0, 100.0);
+    EXPECT_EQ(d.action, RiskAction::ACCEPT);
+    RiskChecker strict(limits, false);
+    strict.on_fill("AAA", 8.0, 100.0);
+    d = strict.check(4, "AAA", 5.0, 100.0);
+    EXPECT_EQ(d.action, RiskAction::REJECT);
+    EXPECT_DOUBLE_EQ(d.quantity, 0.0);
+    ASSERT_EQ(risk.get_events().size(), 2u);
+    EXPECT_DOUBLE_EQ(risk.get_events()[0].requested, 5.0);
+}
+
+TEST(RiskChecker, DailyLossLimitBlocksNewExposureUntilNextDay) {
+    RiskLimits limits;
+    limits.max_daily_loss = 100.0;
+    RiskChecker risk(limits, true, 100);
+    risk.on_fill("AAA", 10.0, 50.0);
+    risk.update_equity(0, 1000.0);
+    risk.update_equity(50, 890.0);
+    RiskDecision d = risk.check(50, "AAA", 1.0, 50.0);
+    EXPECT_EQ(d.action, RiskAction::REJECT);
+    EXPECT_EQ(d.violation, RiskViolation::DAILY_LOSS);
+    EXPECT_EQ(risk.check(50, "AAA", -5.0, 50.0).action, RiskAction::ACCEPT);
+    risk.update_equity(120, 890.0);
+    EXPECT_EQ(risk.check(120, "AAA", 1.0, 50.0).action, RiskAction::ACCEPT);
+}
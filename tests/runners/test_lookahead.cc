@@ -0,0 +1,87 @@
+#include <gtest/gtest.h>
+#include <fstream>
+#include <vector>
+#include "runners.h"
+
+using namespace tzu;
+
+// Decides on the bar's close but claims to execute at its open.
+class PeekingStrat: public Strategy<PeekingStrat, Ohlcv> {
+public:
+    Signal update(const Ohlcv& data) {
+        Side side = data.close > data.open ? Side::BUY : Side::SELL;
+        return Signal{data.timestamp, side, data.open};
+    }
+};
+
+// Executes at a price from the next bar.
+class ShiftedStrat: public Strategy<ShiftedStrat, Ohlcv> {
+    double last = 0.0;
+public:
+    Signal update(const Ohlcv& data) {
+        Signal signal{data.timestamp, Side::BUY, last > 0.0 ? last : data.close};
+        last = data.close * 2.0;
+        return signal;
+    }
+};
+
+static std::vector<Ohlcv> load_bars() {
+    std::ifstream file("../data/btcusd.csv");
+    std::vector<Ohlcv> data;
+    Csv<Ohlcv> csv(file);
+    for (const auto& row : csv) data.push_back(row);
+    return data;
+}
+
+TEST(Lookahead, AcceptsStreamingStrategy) {
+    auto data = load_bars();
+    ASSERT_FALSE(data.empty());
+    LookaheadReport report = check_lookahead(RSIStrat(), data);
+    EXPECT_GT(report.num_signals, 0u);
+    EXPECT_TRUE(report.ok());
+}
+
+TEST(Lookahead, DetectsDecisionAfterOpen) {
+    std::vector<Ohlcv> data = {
+        Ohlcv{1, 10.0, 12.0, 9.0, 11.0, 1.0},
+        Ohlcv{2, 11.0, 12.0, 9.0, 10.0, 1.0},
+    };
+    LookaheadReport report = check_lookahead(PeekingStrat(), data);
+    ASSERT_FALSE(report.ok());
+    EXPECT_EQ(report.findings[0].issue, LookaheadIssue::USES_DATA_AFTER_DECISION);
+}
+
+TEST(Lookahead, HonorsMaxReplays) {
+    std::vector<Ohlcv> data;
+    for (int64_t i = 0; i < 25; i++)
+        data.push_back(Ohlcv{i, 10.0, 12.0, 9.0, 11.0, 1.0});
+    LookaheadReport report = check_lookahead(PeekingStrat(), data, 20);
+    EXPECT_EQ(report.num_signals, 25u);
+    EXPECT_EQ(report.findings.size(), 20u);
+    EXPECT_EQ(report.findings.back().index, 23u);
+    EXPECT_TRUE(check_lookahead(PeekingStrat(), data, 0).ok());
+}
+
+TEST(Lookahead, DetectsPriceOutsideBar) {
+    std::vector<Ohlcv> data = {
+        Ohlcv{1, 10.0, 12.0, 9.0, 11.0, 1.0},
+        Ohlcv{2, 11.0, 12.0, 9.0, 10.0, 1.0},
+    };
+    LookaheadReport report = check_lookahead(ShiftedStrat(), data);
+    ASSERT_EQ(report.findings.size(), 1u);
+    EXPECT_EQ(report.findings[0].index, 1u);
+    EXPECT_EQ(report.findings[0].issue, LookaheadIssue::PRICE_OUTSIDE_BAR);
+}
+
+TEST(GuardedRunner, AbortsOnLookahead) {
+    std::ifstream file("../data/btcusd.csv");
+    Csv<Ohlcv> csv(file);
+    BasicPortfolio portfolio;
+    PeekingStrat strat;
+    GuardedRunner<BasicPortfolio, PeekingStrat, Csv<Ohlcv>> runner(
+            portfolio, strat, csv);
+    testing::internal::CaptureStderr();
+    EXPECT_FALSE(runner.run());
+    EXPECT_NE(testing::internal::GetCapturedStderr().find("USES_DATA_AFTER_DECISION"),
+            std::string::npos);
+}
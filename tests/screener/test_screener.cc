@@ -0,0 +1,100 @@
+#include <gtest/gtest.h>
+#include <string>
+#include <vector>
+#include "screener.h"
+
+using namespace tzu;
+
+namespace {
+
+Ohlcv bar_at(int64_t t, double close) {
+    return Ohlcv(t, close - 0.5, close + 1.0, close - 1.0, close, 100.0);
+}
+
+// "UP" rises steadily, "DOWN" falls steadily, "FLAT" stays at 50
+std::vector<SymbolBar> universe(int n) {
+    std::vector<SymbolBar> bars;
+    for (int t = 0; t < n; t++) {
+        bars.push_back(SymbolBar{"UP", bar_at(t, 50.0 + t)});
+        bars.push_back(SymbolBar{"DOWN", bar_at(t, 50.0 - 0.5 * t)});
+        bars.push_back(SymbolBar{"FLAT", bar_at(t, 50.0)});
+    }
+    return bars;
+}
+
+} // namespace
+
+TEST(Screener, ReturnsSymbolsMeetingAllConditions) {
+    Screener screener;
+    std::string error;
+    ASSERT_TRUE(screener.add_indicator("sma", "SMA(5)", error)) << error;
+    ASSERT_TRUE(screener.add_condition("close > sma", error)) << error;
+    ASSERT_TRUE(screener.add_condition("rsi(3) > 70", error)) << error;
+    std::vector<ScreenerMatch> found = screener.scan(universe(20));
+    ASSERT_EQ(found.size(), 1u);
+    EXPECT_EQ(found[0].symbol, "UP");
+    EXPECT_EQ(found[0].bar.timestamp, 19);
+    EXPECT_DOUBLE_EQ(found[0].bar.close, 69.0);
+    EXPECT_DOUBLE_EQ(found[0].values.at("sma"), 67.0);
+    // steady gains
+    EXPECT_DOUBLE_EQ(found[0].values.at("rsi(3)"), 100.0);
+    EXPECT_EQ(screener.size(), 3u);
+}
+
+TEST(Screener, UsesTheLatestBar) {
+    Screener screener;
+    std::string error;
+    ASSERT_TRUE(screener.add_condition("close < 45", error)) << error;
+    screener.update(universe(12));
+    std::vector<ScreenerMatch> found = screener.matches();
+    ASSERT_EQ(found.size(), 1u);
+    EXPECT_EQ(found[0].symbol, "DOWN");
+    screener.update("DOWN", bar_at(12, 46.0));
+    EXPECT_TRUE(screener.matches().empty());
+    EXPECT_FALSE(screener.update("DOWN", bar_at(12, 40.0)));
+    EXPECT_TRUE(screener.matches().empty());
+}
+
+TEST(Screener, LeavesOutStaleSymbols) {
+    Screener screener;
+    std::string error;
+    ASSERT_TRUE(screener.add_condition("close >= 50", error)) << error;
+    screener.update(universe(5));
+    screener.update("UP", bar_at(5, 60.0));
+    EXPECT_EQ(screener.matches().size(), 2u);
+    std::vector<ScreenerMatch> found = screener.matches(5);
+    ASSERT_EQ(found.size(), 1u);
+    EXPECT_EQ(found[0].symbol, "UP");
+}
+
+TEST(Screener, WithoutConditionsEverySymbolMatches) {
+    Screener screener;
+    EXPECT_EQ(screener.scan(universe(3)).size(), 3u);
+    EXPECT_EQ(screener.num_conditions(), 0u);
+}
+
+TEST(Screener, ReportsInvalidIndicatorsAndConditions) {
+    Screener screener;
+    std::string error;
+    EXPECT_FALSE(screener.add_indicator("x", "Nope(3)", error));
+    EXPECT_EQ(error, "x: unknown indicator 'Nope'");
+    ASSERT_TRUE(screener.add_indicator("sma", "SMA(5)", error));
+    EXPECT_FALSE(screener.add_indicator("sma", "SMA(10)", error));
+    EXPECT_EQ(error, "duplicate indicator 'sma'");
+    EXPECT_FALSE(screener.add_condition("close > ema", error));
+    EXPECT_FALSE(screener.add_condition("close >", error));
+    EXPECT_EQ(screener.num_conditions(), 0u);
+}
+
+TEST(Screener, CrossesSeeEveryBar) {
+    Screener screener;
+    std::string error;
+    ASSERT_TRUE(screener.add_condition("close crosses_above 55", error));
+    std::vector<SymbolBar> bars;
+    for (int t = 0; t < 10; t++)
+        bars.push_back(SymbolBar{"A", bar_at(t, 50.0 + t)});
+    EXPECT_TRUE(screener.scan(bars).empty());
+    screener.update("A", bar_at(10, 54.0));
+    screener.update("A", bar_at(11, 56.0));
+    ASSERT_EQ(screener.matches().size(), 1u);
+}
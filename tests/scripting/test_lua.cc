@@ -0,0 +1,49 @@
+#ifdef TZU_WITH_LUA
+
+#include <gtest/gtest.h>
+#include "scripting.h"
+
+using namespace tzu;
+
+TEST(LuaStrat, ScriptEmitsSignals) {
+    LuaStrat strat = LuaStrat::from_string(
+        "function on_bar(bar, ind)\n"
+        "  if bar.close > bar.open then buy(0.5) end\n"
+        "  if bar.close < bar.open then sell() end\n"
+        "end\n");
+    ASSERT_TRUE(strat.ok());
+    Signal s = strat.update(Ohlcv(1, 10.0, 12.0, 9.0, 11.0, 100.0));
+    EXPECT_EQ(s.side, Side::BUY);
+    EXPECT_DOUBLE_EQ(s.price, 11.0);
+    EXPECT_DOUBLE_EQ(s.volume, 0.5);
+    EXPECT_EQ(strat.update(Ohlcv(2, 11.0, 11.0, 9.0, 10.0, 100.0)).side,
+            Side::SELL);
+}
+
+TEST(LuaStrat, ExposesIndicatorValues) {
+    LuaStrat strat = LuaStrat::from_string(
+        "function on_bar(bar, ind)\n"
+        "  if ind.sma == ind.sma and bar.close > ind.sma then buy() end\n"
+        "end\n");
+    strat.add_indicator("sma", SMA(2));
+    EXPECT_EQ(strat.update(Ohlcv(1, 0.0, 0.0, 0.0, 10.0, 0.0)).side, Side::NONE);
+    EXPECT_EQ(strat.update(Ohlcv(2, 0.0, 0.0, 0.0, 12.0, 0.0)).side, Side::BUY);
+}
+
+TEST(LuaStrat, ReportsScriptErrors) {
+    LuaStrat missing = LuaStrat::from_string("x = 1");
+    EXPECT_FALSE(missing.ok());
+    LuaStrat failing = LuaStrat::from_string(
+        "function on_bar(bar, ind) error('boom') end");
+    ASSERT_TRUE(failing.ok());
+    EXPECT_EQ(failing.update(Ohlcv()).side, Side::NONE);
+    EXPECT_FALSE(failing.ok());
+    EXPECT_NE(failing.get_error().find("boom"), std::string::npos);
+    LuaStrat raising = LuaStrat::from_string(
+        "function on_bar(bar, ind) error({}) end");
+    raising.update(Ohlcv());
+    EXPECT_FALSE(raising.ok());
+    EXPECT_EQ(raising.get_error(), "error object is not a string");
+}
+
+#endif // TZU_WITH_LUA
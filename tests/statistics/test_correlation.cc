@@ -0,0 +1,59 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include "statistics.h"
+
+using namespace tzu;
+
+TEST(Correlation, RollingPearsonCorrelation) {
+    Correlation corr(3);
+    EXPECT_TRUE(std::isnan(corr.update({1.0, 2.0})));
+    EXPECT_TRUE(std::isnan(corr.update({2.0, 4.0})));
+    EXPECT_DOUBLE_EQ(corr.update({3.0, 6.0}), 1.0);
+    // (2, 4), (3, 6), (4, 0): covariance -4 / 2, variances 2 / 2, 56 / 6
+    EXPECT_NEAR(corr.update({4.0, 0.0}), -4.0 / std::sqrt(2.0 * 56.0 / 3.0),
+            1e-12);
+    Correlation inverse(4);
+    for (int i = 0; i < 10; ++i)
+        inverse.update({i * 1.0, 5.0 - 2.0 * i});
+    EXPECT_DOUBLE_EQ(inverse.get(), -1.0);
+    Correlation constant(2);
+    constant.update({1.0, 3.0});
+    EXPECT_TRUE(std::isnan(constant.update({2.0, 3.0})));
+}
+
+TEST(Beta, RegressionSlope) {
+    Beta beta(5);
+    double b[] = {0.01, -0.02, 0.015, 0.005, -0.01, 0.02};
+    for (double x : b)
+        beta.update({1.5 * x + 0.001, x});
+    EXPECT_NEAR(beta.get(), 1.5, 1e-9);
+    EXPECT_EQ(beta.required_warmup(), 5u);
+}
+
+TEST(Beta, AccurateAtLargePriceLevels) {
+    // small moves around 1e6, the second series twice the first
+    Beta beta(10);
+    Correlation corr(10);
+    for (int i = 0; i < 60; ++i) {
+        double x = 0.01 * std::sin(i);
+        beta.update({1e6 + x, 1e6 + 2.0 * x});
+        corr.update({1e6 + x, 1e6 + 2.0 * x});
+        if (i >= 9) {
+            EXPECT_NEAR(beta.get(), 0.5, 1e-6) << i;
+            EXPECT_NEAR(corr.get(), 1.0, 1e-6) << i;
+        }
+    }
+}
+
+TEST(Beta, SavesAndRestoresState) {
+    Beta beta(3);
+    beta.update({2.0, 1.0});
+    beta.update({4.0, 2.0});
+    std::stringstream ss;
+    ASSERT_TRUE(beta.save_state(ss));
+    Beta restored(3);
+    ASSERT_TRUE(restored.load_state(ss));
+    EXPECT_DOUBLE_EQ(restored.update({7.0, 3.0}), beta.update({7.0, 3.0}));
+    EXPECT_DOUBLE_EQ(restored.get(), 2.5);
+}
@@ -0,0 +1,54 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include "statistics.h"
+
+using namespace tzu;
+
+TEST(Percentile, ExactRollingQuantile) {
+    Percentile median(0.5, 4);
+    EXPECT_TRUE(std::isnan(median.update(4.0)));
+    median.update(1.0);
+    median.update(3.0);
+    // 1, 2, 3, 4
+    EXPECT_DOUBLE_EQ(median.update(2.0), 2.5);
+    // 1, 3, 2, 10
+    EXPECT_DOUBLE_EQ(median.update(10.0), 2.5);
+    // 3, 2, 10, 10
+    EXPECT_DOUBLE_EQ(median.update(10.0), 6.5);
+    Percentile top(1.0, 3);
+    top.update(5.0);
+    top.update(7.0);
+    EXPECT_DOUBLE_EQ(top.update(6.0), 7.0);
+    EXPECT_DOUBLE_EQ(top.update(1.0), 7.0);
+    EXPECT_DOUBLE_EQ(top.update(2.0), 6.0);
+}
+
+TEST(Percentile, ClampsPAndSkipsNaN) {
+    Percentile low(-0.5, 3);
+    Percentile high(1.5, 3);
+    for (double x : {2.0, 1.0, 3.0}) {
+        low.update(x);
+        high.update(x);
+    }
+    EXPECT_DOUBLE_EQ(low.get(), 1.0);
+    EXPECT_DOUBLE_EQ(high.get(), 3.0);
+    EXPECT_TRUE(std::isnan(low.update(std::nan(""))));
+    // the NaN is not in the window: 1, 3, 0
+    EXPECT_DOUBLE_EQ(low.update(0.0), 0.0);
+    EXPECT_DOUBLE_EQ(low.update(5.0), 0.0);
+}
+
+TEST(PercentRank, RanksAgainstPreviousValues) {
+    PercentRank rank(4);
+    for (double x : {10.0, 20.0, 30.0, 40.0})
+        EXPECT_TRUE(std::isnan(rank.update(x)));
+    EXPECT_DOUBLE_EQ(rank.update(50.0), 1.0);
+    // previous 20, 30, 40, 50
+    EXPECT_DOUBLE_EQ(rank.update(5.0), 0.0);
+    // previous 30, 40, 50, 5; the tie counts half
+    EXPECT_DOUBLE_EQ(rank.update(40.0), 0.625);
+    EXPECT_EQ(rank.required_warmup(), 5u);
+    EXPECT_TRUE(std::isnan(rank.update(std::nan(""))));
+    // previous 40, 50, 5, 40
+    EXPECT_DOUBLE_EQ(rank.update(45.0), 0.75);
+}
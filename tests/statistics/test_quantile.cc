@@ -0,0 +1,39 @@
+#include <gtest/gtest.h>
+#include "statistics.h"
+#include <cmath>
+#include <random>
+
+using namespace tzu;
+
+TEST(P2Quantile, ReturnsNaNUntilFiveValues) {
+    P2Quantile q(0.5);
+    for (int i = 0; i < 4; ++i)
+        EXPECT_TRUE(std::isnan(q.update(i)));
+    EXPECT_DOUBLE_EQ(q.update(10.0), 2.0);
+}
+
+TEST(P2Quantile, ApproximatesQuantilesOfLargeSamples) {
+    std::mt19937 rng(42);
+    std::normal_distribution<double> normal(0.0, 1.0);
+    P2Quantile median(0.5);
+    P2Quantile p95(0.95);
+    for (int i = 0; i < 20000; ++i) {
+        double x = normal(rng);
+        median.update(x);
+        p95.update(x);
+    }
+    EXPECT_NEAR(median.get(), 0.0, 0.05);
+    EXPECT_NEAR(p95.get(), 1.645, 0.05);
+}
+
+TEST(Quantile, WindowedEstimateFollowsRegimeChanges) {
+    Quantile all(0.5);
+    Quantile recent(0.5, 100);
+    for (int i = 0; i < 1000; ++i) {
+        double x = (i < 500 ? 0.0 : 100.0) + (i % 10);
+        all.update(x);
+        recent.update(x);
+    }
+    EXPECT_NEAR(recent.get(), 104.5, 1.0);
+    EXPECT_LT(all.get(), recent.get());
+}
@@ -0,0 +1,60 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <utility>
+#include <vector>
+#include "synthetic.h"
+
+using namespace tzu;
+
+TEST(Synthetic, SpreadAndRatioBars) {
+    Ohlcv a(1, 10.0, 12.0, 9.0, 11.0, 100.0);
+    Ohlcv b(1, 5.0, 6.0, 4.0, 5.5, 50.0);
+    Synthetic spread(SyntheticKind::SPREAD, 0, 2.0);
+    Ohlcv s = spread.update({a, b});
+    EXPECT_EQ(s.timestamp, 1);
+    EXPECT_DOUBLE_EQ(s.open, 0.0);
+    EXPECT_DOUBLE_EQ(s.high, 12.0 - 8.0);
+    EXPECT_DOUBLE_EQ(s.low, 9.0 - 12.0);
+    EXPECT_DOUBLE_EQ(s.close, 0.0);
+    EXPECT_DOUBLE_EQ(s.volume, 0.0);
+    Synthetic ratio(SyntheticKind::RATIO);
+    Ohlcv r = ratio.update({a, b});
+    EXPECT_DOUBLE_EQ(r.close, 2.0);
+    EXPECT_DOUBLE_EQ(r.high, 3.0);
+    EXPECT_DOUBLE_EQ(r.low, 1.5);
+    Synthetic logs(SyntheticKind::LOG_SPREAD);
+    EXPECT_DOUBLE_EQ(logs.update({a, b}).close, std::log(2.0));
+    // misaligned bars are skipped
+    Ohlcv late(2, 1.0, 1.0, 1.0, 1.0, 1.0);
+    EXPECT_DOUBLE_EQ(ratio.update({late, b}).close, 2.0);
+    EXPECT_EQ(ratio.num_skipped(), 1u);
+}
+
+TEST(Synthetic, RollingHedgeRatio) {
+    Synthetic spread(SyntheticKind::SPREAD, 3);
+    EXPECT_EQ(spread.required_warmup(), 4u);
+    std::vector<double> b = {10.0, 11.0, 13.0, 12.0, 14.0};
+    for (size_t i = 0; i < 3; ++i) {
+        double a = 1.5 * b[i] + 2.0;
+        Ohlcv out = spread.update({Ohlcv(i, a, a, a, a, 1.0),
+                Ohlcv(i, b[i], b[i], b[i], b[i], 1.0)});
+        EXPECT_TRUE(std::isnan(out.close));
+    }
+    EXPECT_NEAR(spread.hedge_ratio(), 1.5, 1e-9);
+    Ohlcv out = spread.update({Ohlcv(3, 20.0, 20.0, 20.0, 20.0, 1.0),
+            Ohlcv(3, 12.0, 12.0, 12.0, 12.0, 1.0)});
+    EXPECT_NEAR(out.close, 20.0 - 1.5 * 12.0, 1e-9);
+}
+
+TEST(Synthetic, SynthesizeMatchesTimestamps) {
+    std::vector<Ohlcv> a = {Ohlcv(1, 4, 4, 4, 4, 1), Ohlcv(2, 6, 6, 6, 6, 1),
+        Ohlcv(4, 8, 8, 8, 8, 1)};
+    std::vector<Ohlcv> b = {Ohlcv(2, 2, 2, 2, 2, 1), Ohlcv(3, 1, 1, 1, 1, 1),
+        Ohlcv(4, 4, 4, 4, 4, 1)};
+    std::vector<Ohlcv> out = synthesize(a, b, Synthetic(SyntheticKind::RATIO));
+    ASSERT_EQ(out.size(), 2u);
+    EXPECT_EQ(out[0].timestamp, 2);
+    EXPECT_DOUBLE_EQ(out[0].close, 3.0);
+    EXPECT_EQ(out[1].timestamp, 4);
+    EXPECT_DOUBLE_EQ(out[1].close, 2.0);
+}
@@ -0,0 +1,43 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <memory>
+#include "registry.h"
+#include "statistics.h"
+
+using namespace tzu;
+
+TEST(ZScore, StandardizesTheLatestValue) {
+    ZScore z(3);
+    EXPECT_TRUE(std::isnan(z.update(1.0)));
+    EXPECT_TRUE(std::isnan(z.update(2.0)));
+    // mean 2, sample stddev 1
+    EXPECT_DOUBLE_EQ(z.update(3.0), 1.0);
+    // 2, 3, 7: mean 4, sample variance 7
+    EXPECT_NEAR(z.update(7.0), 3.0 / std::sqrt(7.0), 1e-12);
+    ZScore population(3, 0);
+    population.update(1.0);
+    population.update(2.0);
+    EXPECT_NEAR(population.update(3.0), 1.0 / std::sqrt(2.0 / 3.0), 1e-12);
+}
+
+TEST(ZScore, ZeroWithoutDispersion) {
+    ZScore z(2);
+    z.update(5.0);
+    EXPECT_DOUBLE_EQ(z.update(5.0), 0.0);
+    std::unique_ptr<DynIndicator> dyn = builtin_indicators().make("ZScore(2)");
+    ASSERT_TRUE(dyn != nullptr);
+    dyn->update(Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_NEAR(dyn->update(Ohlcv(1, 3.0, 3.0, 3.0, 3.0, 1.0)),
+            1.0 / std::sqrt(2.0), 1e-12);
+}
+
+TEST(ZScore, ScalesTheDispersionThreshold) {
+    // small values still have a dispersion
+    ZScore small(3);
+    small.update(1e-15);
+    small.update(2e-15);
+    EXPECT_NEAR(small.update(3e-15), 1.0, 1e-9);
+    ZScore constant(3);
+    for (int i = 0; i < 10; ++i)
+        EXPECT_TRUE(constant.update(0.1) == 0.0 || i < 2);
+}
@@ -0,0 +1,50 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include "store.h"
+
+using namespace tzu;
+
+TEST(PointInTime, HidesRecordsNotYetIngested) {
+    PointInTime<Ohlcv> store;
+    store.insert(15, Ohlcv{10, 1.0, 1.0, 1.0, 1.0, 1.0});
+    Ohlcv bar;
+    EXPECT_FALSE(store.as_of(12, 10, bar));
+    EXPECT_TRUE(store.as_of(15, 10, bar));
+    EXPECT_DOUBLE_EQ(bar.close, 1.0);
+}
+
+TEST(PointInTime, RevisionsApplyFromIngestTime) {
+    PointInTime<Ohlcv> store;
+    store.insert(30, Ohlcv{10, 1.0, 1.0, 1.0, 1.5, 1.0});
+    store.insert(10, Ohlcv{10, 1.0, 1.0, 1.0, 1.0, 1.0});
+    store.insert(20, Ohlcv{20, 2.0, 2.0, 2.0, 2.0, 1.0});
+    EXPECT_EQ(store.revisions(10), 2u);
+    Ohlcv bar;
+    store.as_of(25, 10, bar);
+    EXPECT_DOUBLE_EQ(bar.close, 1.0);
+    store.as_of(30, 10, bar);
+    EXPECT_DOUBLE_EQ(bar.close, 1.5);
+    std::vector<Ohlcv> early = store.history(15, 0, 100);
+    ASSERT_EQ(early.size(), 1u);
+    std::vector<Ohlcv> late = store.history(40, 0, 100);
+    ASSERT_EQ(late.size(), 2u);
+    EXPECT_DOUBLE_EQ(late[0].close, 1.5);
+    EXPECT_EQ(late[1].timestamp, 20);
+}
+
+TEST(PointInTime, LoadsVersionedCsv) {
+    std::istringstream input(
+        "ingest,timestamp,value\n"
+        "11,10,100.0\n"
+        "21,20,101.0\n"
+        "25,10,99.0\n");
+    Csv<Versioned<SingleValue>> csv(input);
+    PointInTime<SingleValue> store;
+    store.load(csv);
+    EXPECT_EQ(store.size(), 3u);
+    SingleValue value;
+    ASSERT_TRUE(store.as_of(24, 10, value));
+    EXPECT_DOUBLE_EQ(value.value, 100.0);
+    ASSERT_TRUE(store.as_of(25, 10, value));
+    EXPECT_DOUBLE_EQ(value.value, 99.0);
+}
@@ -0,0 +1,55 @@
+#ifdef TZU_WITH_SQLITE
+
+#include <gtest/gtest.h>
+#include <cmath>
+#include <vector>
+#include "store.h"
+
+using namespace tzu;
+
+TEST(SqliteBarStore, UpsertsAndQueriesRanges) {
+    SqliteBarStore store;
+    ASSERT_TRUE(store.ok());
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 5; i++)
+        bars.push_back(Ohlcv(i * 60, 10.0 + i, 11.0 + i, 9.0 + i, 10.5 + i, 100.0));
+    ASSERT_TRUE(store.upsert("BTC", "1m", bars));
+    ASSERT_TRUE(store.upsert("ETH", "1m", bars[0]));
+    // a revised bar replaces the stored one
+    ASSERT_TRUE(store.upsert("BTC", "1m", Ohlcv(120, 12.0, 13.0, 11.0, 12.25, 150.0)));
+    std::vector<Ohlcv> got = store.range("BTC", "1m", 60, 180);
+    ASSERT_EQ(got.size(), 3u);
+    EXPECT_EQ(got[0].timestamp, 60);
+    EXPECT_DOUBLE_EQ(got[1].close, 12.25);
+    EXPECT_DOUBLE_EQ(got[1].volume, 150.0);
+    EXPECT_EQ(store.range("BTC", "1m").size(), 5u);
+    EXPECT_TRUE(store.range("BTC", "1d").empty());
+}
+
+TEST(SqliteBarStore, ReportsLastTimestamp) {
+    SqliteBarStore store;
+    int64_t last = 0;
+    EXPECT_FALSE(store.last_timestamp("BTC", "1m", last));
+    store.upsert("BTC", "1m", Ohlcv(300, 1.0, 1.0, 1.0, 1.0, 1.0));
+    store.upsert("BTC", "1m", Ohlcv(60, 1.0, 1.0, 1.0, 1.0, 1.0));
+    ASSERT_TRUE(store.last_timestamp("BTC", "1m", last));
+    EXPECT_EQ(last, 300);
+    SqliteBarStore broken("/nonexistent/dir/bars.db");
+    EXPECT_FALSE(broken.ok());
+    EXPECT_FALSE(broken.upsert("BTC", "1m", Ohlcv(1, 1.0, 1.0, 1.0, 1.0, 1.0)));
+}
+
+TEST(SqliteBarStore, RoundTripsMissingValues) {
+    SqliteBarStore store;
+    double nan = std::nan("");
+    ASSERT_TRUE(store.upsert("BTC", "1m", Ohlcv(60, 1.0, nan, 0.5, 1.5, nan)));
+    std::vector<Ohlcv> got = store.range("BTC", "1m");
+    ASSERT_EQ(got.size(), 1u);
+    EXPECT_DOUBLE_EQ(got[0].open, 1.0);
+    EXPECT_TRUE(std::isnan(got[0].high));
+    EXPECT_DOUBLE_EQ(got[0].low, 0.5);
+    EXPECT_DOUBLE_EQ(got[0].close, 1.5);
+    EXPECT_TRUE(std::isnan(got[0].volume));
+}
+
+#endif // TZU_WITH_SQLITE
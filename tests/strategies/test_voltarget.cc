@@ -0,0 +1,39 @@
+#include <gtest/gtest.h>
+#include <fstream>
+#include <sstream>
+#include <vector>
+#include "streamers.h"
+#include "strategies.h"
+#include "portfolios.h"
+
+using namespace tzu;
+
+class AlwaysBuy: public Strategy<AlwaysBuy, Ohlcv> {
+public:
+    Signal update(const Ohlcv& data) {
+        return Signal{data.timestamp, Side::BUY, data.close};
+    }
+};
+
+TEST(VolTarget, DropsBuysUntilVolatilityIsKnown) {
+    VolTarget<AlwaysBuy> strat(AlwaysBuy(), EWMAVol(10));
+    Signal signal = strat.update(Ohlcv{1, 100.0, 100.0, 100.0, 100.0, 1.0});
+    EXPECT_EQ(signal.side, Side::NONE);
+}
+
+TEST(VolTarget, ScalesVolumeToTarget) {
+    VolTarget<AlwaysBuy> strat(AlwaysBuy(), EWMAVol(10), 0.10, 100.0);
+    strat.update(Ohlcv{1, 100.0, 100.0, 100.0, 100.0, 1.0});
+    // 2% per period, 20% annualized with 100 periods per year
+    Signal signal = strat.update(Ohlcv{2, 102.0, 102.0, 102.0, 102.0, 1.0});
+    EXPECT_EQ(signal.side, Side::BUY);
+    EXPECT_NEAR(signal.volume, 0.5, 1e-12);
+}
+
+TEST(VolTarget, PortfolioUsesSignalVolume) {
+    BasicPortfolio portfolio(1000.0);
+    portfolio.update(Signal{1, Side::BUY, 10.0, 0.5});
+    std::ostringstream out;
+    out << portfolio;
+    EXPECT_NE(out.str().find("curr_cash:500.0000"), std::string::npos);
+}
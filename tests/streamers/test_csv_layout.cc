@@ -0,0 +1,75 @@
+#include <gtest/gtest.h>
+#include <fstream>
+#include <sstream>
+#include "streamers.h"
+
+using namespace tzu;
+
+TEST(ParseTime, ParsesDatesAndTimes) {
+    int64_t ts = 0;
+    ASSERT_TRUE(parse_time("2024-03-01", "%Y-%m-%d", ts));
+    EXPECT_EQ(ts, 1709251200);
+    ASSERT_TRUE(parse_time("2024-03-01T13:45:30.250Z", "%Y-%m-%dT%H:%M:%SZ", ts));
+    EXPECT_EQ(ts, 1709251200 + 13 * 3600 + 45 * 60 + 30);
+    ASSERT_TRUE(parse_time("03/01/1969", "%m/%d/%Y", ts));
+    EXPECT_EQ(ts, -26438400);
+    EXPECT_FALSE(parse_time("2024-13-01", "%Y-%m-%d", ts));
+    EXPECT_FALSE(parse_time("2024-03-01 10:00", "%Y-%m-%d", ts));
+    EXPECT_FALSE(parse_time("2023-02-29", "%Y-%m-%d", ts));
+    EXPECT_FALSE(parse_time("2024-04-31", "%Y-%m-%d", ts));
+    EXPECT_TRUE(parse_time("2024-02-29", "%Y-%m-%d", ts));
+    EXPECT_FALSE(parse_time("1900-02-29", "%Y-%m-%d", ts));
+    EXPECT_TRUE(parse_time("2000-02-29", "%Y-%m-%d", ts));
+}
+
+TEST(CsvBars, MapsColumnsFromHeaderAndParsesDates) {
+    std::istringstream input(
+        "Date;Close;High;Low;Open;Volume\n"
+        "2024-03-01;11;12;9;10;100\n"
+        "bad line\n"
+        "2024-03-02;12;13;10;11;200\n");
+    CsvLayout layout;
+    layout.delimiter = ';';
+    layout.time_format = "%Y-%m-%d";
+    layout.time_scale = 1000;
+    layout.columns_from_header = true;
+    CsvBars reader(input, layout);
+    ASSERT_TRUE(reader.valid());
+    std::vector<Ohlcv> bars = reader.load();
+    ASSERT_EQ(bars.size(), 2u);
+    EXPECT_EQ(bars[0].timestamp, 1709251200000);
+    EXPECT_DOUBLE_EQ(bars[0].open, 10.0);
+    EXPECT_DOUBLE_EQ(bars[0].close, 11.0);
+    EXPECT_DOUBLE_EQ(bars[1].volume, 200.0);
+    EXPECT_EQ(reader.skipped(), 1u);
+}
+
+TEST(CsvBars, EpochTimestampsIgnoreUtcOffset) {
+    std::istringstream input("ts,open,high,low,close,volume\n"
+            "1709251200,10,12,9,11,100\n");
+    CsvLayout layout;
+    layout.time_format = "%s";
+    layout.utc_offset = -18000;
+    std::vector<Ohlcv> bars = CsvBars(input, layout).load();
+    ASSERT_EQ(bars.size(), 1u);
+    EXPECT_EQ(bars[0].timestamp, 1709251200);
+}
+
+TEST(CsvBars, DefaultLayoutMatchesCsv) {
+    std::ifstream a("../data/btcusd.csv");
+    std::ifstream b("../data/btcusd.csv");
+    ASSERT_TRUE(a.is_open());
+    Csv<Ohlcv> csv(a);
+    CsvBars bars(b);
+    auto it = bars.begin();
+    int count = 0;
+    for (const auto& row : csv) {
+        ASSERT_TRUE(it != bars.end());
+        EXPECT_EQ(it->timestamp, row.timestamp);
+        EXPECT_DOUBLE_EQ(it->close, row.close);
+        ++it;
+        ++count;
+    }
+    EXPECT_TRUE(it == bars.end());
+    EXPECT_GT(count, 1000);
+}
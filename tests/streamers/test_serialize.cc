@@ -0,0 +1,44 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <iomanip>
+#include <sstream>
+#include "serialize.h"
+
+using namespace tzu;
+
+TEST(Serialize, OhlcvRoundTrip) {
+    Ohlcv bar(1700000000, 10.1, 12.25, 9.0, 11.0 / 3.0, 100.0);
+    std::stringstream ss;
+    ss << std::setprecision(17) << bar;
+    EXPECT_EQ(ss.str().substr(0, 32), "timestamp:1700000000 open:10.1 h");
+    Ohlcv read;
+    ASSERT_TRUE(ss >> read);
+    EXPECT_EQ(read.timestamp, bar.timestamp);
+    EXPECT_DOUBLE_EQ(read.close, bar.close);
+    EXPECT_DOUBLE_EQ(read.volume, bar.volume);
+}
+
+TEST(Serialize, RoundTripsOtherTypesAndNaN) {
+    std::stringstream ss;
+    ss << Tick(5, 1.5, 2.0, Side::SELL) << "\n"
+       << SingleValue(6, std::nan("")) << "\n"
+       << MACDResult{1.0, 0.5, 0.5} << "\n"
+       << Signal(7, Side::BUY, 3.0, 0.25) << "\n";
+    Tick tick;
+    SingleValue value;
+    MACDResult macd{0.0, 0.0, 0.0};
+    Signal signal;
+    ASSERT_TRUE(ss >> tick >> value >> macd >> signal);
+    EXPECT_EQ(tick.side, Side::SELL);
+    EXPECT_TRUE(std::isnan(value.value));
+    EXPECT_DOUBLE_EQ(macd.histogram, 0.5);
+    EXPECT_EQ(signal.side, Side::BUY);
+    EXPECT_DOUBLE_EQ(signal.volume, 0.25);
+}
+
+TEST(Serialize, FailsOnUnexpectedFields) {
+    std::istringstream ss("timestamp:1 open:1 high:1 close:1 low:1 volume:1");
+    Ohlcv bar(9);
+    EXPECT_FALSE(ss >> bar);
+    EXPECT_EQ(bar.timestamp, 9);
+}
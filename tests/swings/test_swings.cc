@@ -0,0 +1,59 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+#include "swings.h"
+
+using namespace tzu;
+
+TEST(SwingDetector, ConfirmsSwingsLate) {
+    SwingDetector swings(2, 1);
+    double prices[] = {1.0, 2.0, 5.0, 3.0, 4.0, 0.5, 2.0};
+    std::vector<Swing> found;
+    for (int i = 0; i < 7; i++) {
+        bool confirmed = swings.update(i, prices[i]);
+        EXPECT_EQ(confirmed, !swings.get().empty());
+        for (const auto& s : swings.get()) found.push_back(s);
+    }
+    // 5 at t = 2 is above the two prices before and the one after; 0.5
+    // at t = 5 is below them
+    ASSERT_EQ(found.size(), 2u);
+    EXPECT_EQ(found[0].type, SwingType::HIGH);
+    EXPECT_EQ(found[0].timestamp, 2);
+    EXPECT_DOUBLE_EQ(found[0].price, 5.0);
+    EXPECT_EQ(found[0].confirmed, 3);
+    EXPECT_EQ(found[1].type, SwingType::LOW);
+    EXPECT_EQ(found[1].timestamp, 5);
+    EXPECT_EQ(found[1].confirmed, 6);
+    std::ostringstream os;
+    os << found[0];
+    EXPECT_EQ(os.str(), "timestamp:2 swing:HIGH price:5 confirmed:3");
+}
+
+TEST(SwingDetector, FractalsUseBarExtremes) {
+    std::vector<Ohlcv> bars;
+    double highs[] = {10.0, 11.0, 13.0, 12.0, 11.0, 12.0, 12.5};
+    double lows[] = {9.0, 8.0, 10.0, 7.0, 9.0, 9.5, 10.0};
+    for (int i = 0; i < 7; i++)
+        bars.push_back(Ohlcv(i, highs[i] - 0.5, highs[i], lows[i],
+                    highs[i] - 0.5, 1.0));
+    std::vector<Swing> swings = find_swings(bars, SwingDetector::fractals());
+    ASSERT_EQ(swings.size(), 2u);
+    EXPECT_EQ(swings[0].type, SwingType::HIGH);
+    EXPECT_EQ(swings[0].timestamp, 2);
+    EXPECT_DOUBLE_EQ(swings[0].price, 13.0);
+    EXPECT_EQ(swings[1].type, SwingType::LOW);
+    EXPECT_EQ(swings[1].timestamp, 3);
+    EXPECT_DOUBLE_EQ(swings[1].price, 7.0);
+    EXPECT_EQ(swings[1].confirmed, 5);
+}
+
+TEST(SwingDetector, EqualExtremesAreNotSwings) {
+    SwingDetector swings(1, 1);
+    swings.update(0, 1.0);
+    swings.update(1, 2.0);
+    EXPECT_FALSE(swings.update(2, 2.0));
+    swings.reset();
+    EXPECT_FALSE(swings.update(3, 1.0));
+    EXPECT_FALSE(swings.update(4, 3.0));
+    EXPECT_TRUE(swings.update(5, 1.0));
+}
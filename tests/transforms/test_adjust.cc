@@ -0,0 +1,55 @@
+#include <gtest/gtest.h>
+#include <sstream>
+#include <vector>
+#include "transforms.h"
+#include "streamers.h"
+
+using namespace tzu;
+
+static std::vector<Ohlcv> split_history() {
+    return {
+        Ohlcv{1, 100.0, 100.0, 100.0, 100.0, 10.0},
+        Ohlcv{2, 100.0, 100.0, 100.0, 100.0, 10.0},
+        Ohlcv{3, 50.0, 50.0, 50.0, 50.0, 20.0},
+    };
+}
+
+TEST(CorporateActions, LoadsFromCsv) {
+    std::istringstream input("timestamp,split,dividend\n3,2,0\n5,1,0.5\n");
+    Csv<CorporateAction> csv(input);
+    std::vector<CorporateAction> actions;
+    for (const auto& action : csv) actions.push_back(action);
+    ASSERT_EQ(actions.size(), 2u);
+    EXPECT_DOUBLE_EQ(actions[0].split, 2.0);
+    EXPECT_EQ(actions[1].timestamp, 5);
+    EXPECT_DOUBLE_EQ(actions[1].dividend, 0.5);
+}
+
+TEST(CorporateActions, BackAdjustRemovesSplitJump) {
+    std::vector<Ohlcv> bars = split_history();
+    back_adjust(bars, {CorporateAction{3, 2.0, 0.0}});
+    EXPECT_DOUBLE_EQ(bars[0].close, 50.0);
+    EXPECT_DOUBLE_EQ(bars[1].volume, 20.0);
+    EXPECT_DOUBLE_EQ(bars[2].close, 50.0);
+    EXPECT_DOUBLE_EQ(bars[2].volume, 20.0);
+}
+
+TEST(CorporateActions, BackAdjustAppliesDividendFactor) {
+    std::vector<Ohlcv> bars = {
+        Ohlcv{1, 100.0, 100.0, 100.0, 100.0, 10.0},
+        Ohlcv{2, 98.0, 98.0, 98.0, 98.0, 10.0},
+    };
+    back_adjust(bars, {CorporateAction{2, 1.0, 2.0}});
+    EXPECT_DOUBLE_EQ(bars[0].close, 98.0);
+    EXPECT_DOUBLE_EQ(bars[1].close, 98.0);
+}
+
+TEST(CorporateActions, ForwardAdjustIsContinuous) {
+    ForwardAdjust adjust({CorporateAction{3, 2.0, 0.0}});
+    std::vector<Ohlcv> bars = split_history();
+    EXPECT_DOUBLE_EQ(adjust.update(bars[0]).close, 100.0);
+    EXPECT_DOUBLE_EQ(adjust.update(bars[1]).close, 100.0);
+    Ohlcv result = adjust.update(bars[2]);
+    EXPECT_DOUBLE_EQ(result.close, 100.0);
+    EXPECT_DOUBLE_EQ(result.volume, 10.0);
+}
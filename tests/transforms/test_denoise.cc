@@ -0,0 +1,62 @@
+#include <gtest/gtest.h>
+#include "transforms.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(WaveletDenoise, ReturnsNaNDuringWarmup) {
+    WaveletDenoise denoise(4);
+    EXPECT_TRUE(std::isnan(denoise.update(1.0)));
+    EXPECT_TRUE(std::isnan(denoise.update(2.0)));
+    EXPECT_TRUE(std::isnan(denoise.update(3.0)));
+    EXPECT_FALSE(std::isnan(denoise.update(4.0)));
+}
+
+TEST(WaveletDenoise, ZeroThresholdReconstructsInput) {
+    WaveletDenoise denoise(8, 3, 0.0);
+    for (int i = 0; i < 20; ++i) {
+        double value = 100.0 + std::sin(i) * 5.0;
+        double result = denoise.update(value);
+        if (i >= 7) {
+            EXPECT_NEAR(result, value, 1e-9);
+        }
+    }
+}
+
+TEST(WaveletDenoise, ReducesNoise) {
+    WaveletDenoise denoise(16, 2);
+    double err_in = 0.0;
+    double err_out = 0.0;
+    for (int i = 0; i < 400; ++i) {
+        double clean = 100.0 + 10.0 * std::sin(i / 20.0);
+        double noise = (i % 2 == 0 ? 1.0 : -1.0) * ((i * 7) % 5) * 0.5;
+        double result = denoise.update(clean + noise);
+        if (i >= 15) {
+            err_in += noise * noise;
+            err_out += (result - clean) * (result - clean);
+        }
+    }
+    EXPECT_LT(err_out, err_in);
+}
+
+TEST(Chain, DoesNotForwardWarmupValues) {
+    Chain<WaveletDenoise, SMA> chain(WaveletDenoise(2, 1, 0.0), SMA(2));
+    EXPECT_TRUE(std::isnan(chain.update(10.0)));
+    EXPECT_TRUE(std::isnan(chain.update(20.0)));
+    EXPECT_DOUBLE_EQ(chain.update(30.0), 25.0);
+}
+
+TEST(WaveletDenoise, RoundsWindowDownToPowerOfTwo) {
+    EXPECT_EQ(WaveletDenoise(12).required_warmup(), 8u);
+    EXPECT_EQ(WaveletDenoise(0).required_warmup(), 2u);
+    EXPECT_EQ(WaveletDenoise(16).required_warmup(), 16u);
+    // more levels than the window allows still reconstruct the input
+    WaveletDenoise denoise(6, 5, 0.0);
+    for (int i = 0; i < 10; ++i) {
+        double value = 100.0 + i;
+        double result = denoise.update(value);
+        if (i >= 3) {
+            EXPECT_NEAR(result, value, 1e-9);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+#include <gtest/gtest.h>
+#include "transforms.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(FracDiff, ReturnsNaNDuringWarmup) {
+    FracDiff fd(0.5, 3);
+    EXPECT_TRUE(std::isnan(fd.update(1.0)));
+    EXPECT_TRUE(std::isnan(fd.update(2.0)));
+    EXPECT_FALSE(std::isnan(fd.update(3.0)));
+}
+
+TEST(FracDiff, OrderOneIsFirstDifference) {
+    FracDiff fd(1.0, 4);
+    fd.update(10.0);
+    fd.update(12.0);
+    fd.update(15.0);
+    EXPECT_NEAR(fd.update(19.0), 4.0, 1e-12);
+    EXPECT_NEAR(fd.update(20.0), 1.0, 1e-12);
+}
+
+TEST(FracDiff, AppliesFractionalWeights) {
+    // weights for d = 0.5: 1, -0.5, -0.125
+    FracDiff fd(0.5, 3);
+    fd.update(4.0);
+    fd.update(2.0);
+    EXPECT_NEAR(fd.update(1.0), 1.0 - 0.5 * 2.0 - 0.125 * 4.0, 1e-12);
+}
+
+TEST(FracDiff, WindowFromThreshold) {
+    EXPECT_EQ(frac_diff_window(1.0, 1e-4), 2u);
+    size_t n = frac_diff_window(0.4, 1e-3);
+    EXPECT_GT(n, 10u);
+    EXPECT_LT(n, 10000u);
+}
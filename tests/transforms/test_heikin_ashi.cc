@@ -0,0 +1,32 @@
+#include <gtest/gtest.h>
+#include "transforms.h"
+
+using namespace tzu;
+
+TEST(HeikinAshi, ConvertsBars) {
+    HeikinAshi ha;
+    Ohlcv first = ha.update(Ohlcv(1, 10.0, 14.0, 8.0, 12.0, 5.0));
+    EXPECT_EQ(first.timestamp, 1);
+    EXPECT_DOUBLE_EQ(first.open, 11.0);
+    EXPECT_DOUBLE_EQ(first.close, 11.0);
+    EXPECT_DOUBLE_EQ(first.high, 14.0);
+    EXPECT_DOUBLE_EQ(first.low, 8.0);
+    EXPECT_DOUBLE_EQ(first.volume, 5.0);
+    Ohlcv second = ha.update(Ohlcv(2, 12.0, 13.0, 11.5, 12.5, 7.0));
+    EXPECT_DOUBLE_EQ(second.open, 11.0);
+    EXPECT_DOUBLE_EQ(second.close, 12.25);
+    EXPECT_DOUBLE_EQ(second.high, 13.0);
+    EXPECT_DOUBLE_EQ(second.low, 11.0);
+}
+
+TEST(HeikinAshi, ChainsIntoBarIndicator) {
+    Chain<HeikinAshi, RSI> chained(HeikinAshi(), RSI(3));
+    HeikinAshi ha;
+    RSI rsi(3);
+    for (int i = 0; i < 10; ++i) {
+        Ohlcv bar(i, 10.0 + i % 3, 12.0 + i % 3, 9.0, 11.0 + i % 4, 1.0);
+        chained.update(bar);
+        rsi.update(ha.update(bar));
+    }
+    EXPECT_DOUBLE_EQ(chained.get(), rsi.get());
+}
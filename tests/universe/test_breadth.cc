@@ -0,0 +1,51 @@
+#include <gtest/gtest.h>
+#include "universe.h"
+#include <cmath>
+
+using namespace tzu;
+
+static Ohlcv bar(int64_t ts, double close) {
+    return Ohlcv{ts, close, close, close, close, 1000.0};
+}
+
+TEST(Breadth, CompletesPeriodOnNewTimestamp) {
+    Breadth breadth(2, 2);
+    EXPECT_FALSE(breadth.update("AAA", bar(1, 10.0)));
+    EXPECT_FALSE(breadth.update("BBB", bar(1, 20.0)));
+    EXPECT_TRUE(breadth.update("AAA", bar(2, 11.0)));
+    EXPECT_EQ(breadth.get().timestamp, 1);
+    EXPECT_EQ(breadth.size(), 2u);
+}
+
+TEST(Breadth, CountsAdvancesAndDeclines) {
+    Breadth breadth(2, 2);
+    breadth.update("AAA", bar(1, 10.0));
+    breadth.update("BBB", bar(1, 20.0));
+    breadth.update("CCC", bar(1, 30.0));
+    breadth.update("AAA", bar(2, 11.0));
+    breadth.update("BBB", bar(2, 19.0));
+    breadth.update("CCC", bar(2, 31.0));
+    BreadthResult result = breadth.flush();
+    EXPECT_EQ(result.timestamp, 2);
+    EXPECT_EQ(result.advances, 2u);
+    EXPECT_EQ(result.declines, 1u);
+    EXPECT_EQ(result.unchanged, 0u);
+    EXPECT_DOUBLE_EQ(result.ad_line, 1.0);
+    // AAA: ma 10.5 < 11, BBB: ma 19.5 > 19, CCC: ma 30.5 < 31
+    EXPECT_NEAR(result.pct_above_ma, 200.0 / 3.0, 1e-10);
+}
+
+TEST(Breadth, CountsNewHighsAndLows) {
+    Breadth breadth(2, 2);
+    breadth.update("AAA", bar(1, 10.0));
+    breadth.update("BBB", bar(1, 20.0));
+    breadth.update("AAA", bar(2, 10.0));
+    breadth.update("BBB", bar(2, 20.0));
+    EXPECT_FALSE(std::isnan(breadth.flush().pct_above_ma));
+    breadth.update("AAA", bar(3, 12.0));
+    breadth.update("BBB", bar(3, 18.0));
+    BreadthResult result = breadth.flush();
+    EXPECT_EQ(result.new_highs, 1u);
+    EXPECT_EQ(result.new_lows, 1u);
+    EXPECT_DOUBLE_EQ(result.ad_line, 0.0);
+}
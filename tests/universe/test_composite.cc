@@ -0,0 +1,57 @@
+#include <gtest/gtest.h>
+#include "universe.h"
+#include <cmath>
+
+using namespace tzu;
+
+static Ohlcv bar(int64_t ts, double close) {
+    return Ohlcv{ts, close, close, close, close, 10.0};
+}
+
+TEST(CompositeIndex, StartsAtBaseWhenAllComponentsHaveData) {
+    CompositeIndex index({{"AAA", 0.5}, {"BBB", 0.5}});
+    index.update("AAA", bar(1, 10.0));
+    EXPECT_FALSE(index.update("AAA", bar(2, 11.0)));
+    index.update("BBB", bar(2, 20.0));
+    EXPECT_TRUE(index.flush());
+    EXPECT_EQ(index.get().timestamp, 2);
+    EXPECT_DOUBLE_EQ(index.get().close, 100.0);
+    EXPECT_DOUBLE_EQ(index.get().volume, 20.0);
+}
+
+TEST(CompositeIndex, FixedWeightsFollowWeightedReturns) {
+    CompositeIndex index({{"AAA", 0.5}, {"BBB", 0.5}});
+    index.update("AAA", bar(1, 10.0));
+    index.update("BBB", bar(1, 20.0));
+    index.update("AAA", bar(2, 11.0));
+    EXPECT_FALSE(index.update("BBB", bar(2, 18.0)));
+    index.flush();
+    // +10% and -10% with equal weights
+    EXPECT_DOUBLE_EQ(index.get().close, 100.0);
+}
+
+TEST(CompositeIndex, PriceWeightedUsesUnitsOfOne) {
+    CompositeIndex index({{"AAA", 1.0}, {"BBB", 1.0}}, IndexWeighting::PRICE);
+    index.update("AAA", bar(1, 10.0));
+    index.update("BBB", bar(1, 30.0));
+    index.update("AAA", bar(2, 20.0));
+    index.update("BBB", bar(2, 30.0));
+    index.flush();
+    EXPECT_DOUBLE_EQ(index.get().close, 125.0);
+}
+
+TEST(CompositeIndex, RebalanceKeepsIndexContinuous) {
+    CompositeIndex index({{"AAA", 1.0}});
+    index.rebalance(3, {{"AAA", 0.5}, {"BBB", 0.5}});
+    index.update("AAA", bar(1, 10.0));
+    index.update("BBB", bar(1, 50.0));
+    index.update("AAA", bar(2, 20.0));
+    index.update("BBB", bar(2, 50.0));
+    index.flush();
+    EXPECT_DOUBLE_EQ(index.get().close, 200.0);
+    index.update("AAA", bar(3, 20.0));
+    index.update("BBB", bar(3, 100.0));
+    index.flush();
+    // half of the value in BBB, which doubled
+    EXPECT_DOUBLE_EQ(index.get().close, 300.0);
+}
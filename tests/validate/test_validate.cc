@@ -0,0 +1,50 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <sstream>
+#include <vector>
+#include "validate.h"
+
+using namespace tzu;
+
+TEST(BarValidator, RejectsInconsistentBars) {
+    BarValidator validator;
+    EXPECT_TRUE(validator.update(Ohlcv(1, 10.0, 11.0, 9.0, 10.5, 100.0)));
+    EXPECT_TRUE(validator.get().empty());
+    EXPECT_FALSE(validator.update(Ohlcv(2, 12.0, 11.0, 9.0, 8.0, -1.0)));
+    ASSERT_EQ(validator.get().size(), 3u);
+    EXPECT_EQ(validator.get()[0].issue, BarIssue::OPEN_OUTSIDE_RANGE);
+    EXPECT_EQ(validator.get()[1].issue, BarIssue::CLOSE_OUTSIDE_RANGE);
+    EXPECT_EQ(validator.get()[2].issue, BarIssue::NEGATIVE_VOLUME);
+    EXPECT_FALSE(validator.update(Ohlcv(3, 10.0, 9.0, 11.0, 10.0, 1.0)));
+    EXPECT_EQ(validator.get()[0].issue, BarIssue::HIGH_BELOW_LOW);
+    EXPECT_FALSE(validator.update(Ohlcv(4, NAN, 11.0, 9.0, 10.0, 1.0)));
+    EXPECT_EQ(validator.get()[0].issue, BarIssue::NON_FINITE);
+    std::stringstream ss;
+    ss << validator.get()[0];
+    EXPECT_EQ(ss.str(), "index:3 timestamp:4 issue:non_finite");
+}
+
+TEST(BarValidator, ChecksTimestamps) {
+    BarValidator validator;
+    validator.update(Ohlcv(10, 1.0, 1.0, 1.0, 1.0, 1.0));
+    EXPECT_FALSE(validator.update(Ohlcv(10, 1.0, 1.0, 1.0, 1.0, 1.0)));
+    EXPECT_EQ(validator.get()[0].issue, BarIssue::DUPLICATE_TIME);
+    EXPECT_FALSE(validator.update(Ohlcv(5, 1.0, 1.0, 1.0, 1.0, 1.0)));
+    EXPECT_EQ(validator.get()[0].issue, BarIssue::OUT_OF_ORDER);
+    EXPECT_TRUE(validator.update(Ohlcv(11, 1.0, 1.0, 1.0, 1.0, 1.0)));
+    EXPECT_EQ(validator.count(BarIssue::OUT_OF_ORDER), 1u);
+    EXPECT_EQ(validator.num_bars(), 4u);
+}
+
+TEST(BarValidator, ReportsGapsWithoutRejecting) {
+    std::vector<Ohlcv> bars = {
+        Ohlcv(0, 1.0, 1.0, 1.0, 1.0, 1.0),
+        Ohlcv(60, 1.0, 1.0, 1.0, 1.0, 1.0),
+        Ohlcv(300, 1.0, 1.0, 1.0, 1.0, 1.0)
+    };
+    std::vector<BarFinding> findings = validate_bars(bars, 60);
+    ASSERT_EQ(findings.size(), 1u);
+    EXPECT_EQ(findings[0].issue, BarIssue::GAP);
+    EXPECT_EQ(findings[0].index, 2u);
+    EXPECT_TRUE(validate_bars(bars).empty());
+}
@@ -0,0 +1,58 @@
+#include <gtest/gtest.h>
+#include <algorithm>
+#include <vector>
+#include "validation.h"
+
+using namespace tzu;
+
+static std::vector<Span> spans(size_t n, int64_t horizon) {
+    std::vector<Span> out;
+    for (size_t i = 0; i < n; i++)
+        out.push_back(Span(static_cast<int64_t>(i), static_cast<int64_t>(i) + horizon));
+    return out;
+}
+
+static bool contains(const std::vector<size_t>& v, size_t x) {
+    return std::find(v.begin(), v.end(), x) != v.end();
+}
+
+TEST(PurgedKFold, WithoutOverlapIsPlainKFold) {
+    auto folds = purged_kfold(spans(10, 0), 5);
+    ASSERT_EQ(folds.size(), 5u);
+    for (const auto& fold : folds) {
+        EXPECT_EQ(fold.test.size(), 2u);
+        EXPECT_EQ(fold.train.size(), 8u);
+    }
+}
+
+TEST(PurgedKFold, PurgesOverlappingTrainingSamples) {
+    auto folds = purged_kfold(spans(10, 2), 5);
+    // second fold tests samples 2 and 3, spanning times 2 to 5
+    const Fold& fold = folds[1];
+    EXPECT_FALSE(contains(fold.train, 0));
+    EXPECT_FALSE(contains(fold.train, 1));
+    EXPECT_FALSE(contains(fold.train, 4));
+    EXPECT_FALSE(contains(fold.train, 5));
+    EXPECT_TRUE(contains(fold.train, 6));
+}
+
+TEST(PurgedKFold, AppliesEmbargoAfterTestSet) {
+    auto folds = purged_kfold(spans(10, 0), 5, 0.2);
+    const Fold& fold = folds[0];
+    EXPECT_FALSE(contains(fold.train, 2));
+    EXPECT_FALSE(contains(fold.train, 3));
+    EXPECT_TRUE(contains(fold.train, 4));
+}
+
+TEST(PurgedKFold, CrossValidateEvaluatesEachFold) {
+    std::vector<Ohlcv> bars;
+    for (int i = 0; i < 12; ++i)
+        bars.push_back(Ohlcv{i, 1.0, 1.0, 1.0, 1.0 + i, 1.0});
+    auto scores = cross_validate(bars, horizon_spans(bars, 1), 3, 0.0,
+        [](const std::vector<Ohlcv>& train, const std::vector<Ohlcv>& test) {
+            return static_cast<double>(train.size() + test.size());
+        });
+    ASSERT_EQ(scores.size(), 3u);
+    EXPECT_DOUBLE_EQ(scores[0], 11.0);
+    EXPECT_DOUBLE_EQ(scores[1], 10.0);
+}
@@ -0,0 +1,53 @@
+#include <gtest/gtest.h>
+#include <fstream>
+#include <vector>
+#include "labeling.h"
+#include "streamers.h"
+
+using namespace tzu;
+
+static std::vector<Ohlcv> path(const std::vector<double>& closes) {
+    std::vector<Ohlcv> bars;
+    for (size_t i = 0; i < closes.size(); i++) {
+        double c = closes[i];
+        bars.push_back(Ohlcv{static_cast<int64_t>(i), c, c, c, c, 1.0});
+    }
+    return bars;
+}
+
+TEST(TripleBarrier, LabelsProfitTouch) {
+    auto bars = path({100.0, 101.0, 100.0, 110.0, 90.0});
+    TripleBarrier config;
+    config.max_bars = 3;
+    BarrierLabel label = barrier_label(bars, 1, 0.05, config);
+    EXPECT_EQ(label.label, 1);
+    EXPECT_EQ(label.touch, 3);
+    EXPECT_NEAR(label.ret, 0.05, 1e-12);
+}
+
+TEST(TripleBarrier, LabelsStopAndTimeLimit) {
+    auto bars = path({100.0, 100.0, 94.0, 100.0, 101.0, 102.0});
+    TripleBarrier config;
+    config.max_bars = 2;
+    EXPECT_EQ(barrier_label(bars, 1, 0.05, config).label, -1);
+    BarrierLabel timeout = barrier_label(bars, 3, 0.05, config);
+    EXPECT_EQ(timeout.label, 0);
+    EXPECT_EQ(timeout.touch, 5);
+    EXPECT_NEAR(timeout.ret, 0.02, 1e-12);
+}
+
+TEST(TripleBarrier, LabelsFeedPurgedKFold) {
+    std::ifstream file("../data/btcusd.csv");
+    std::vector<Ohlcv> bars;
+    Csv<Ohlcv> csv(file);
+    for (const auto& row : csv) bars.push_back(row);
+    TripleBarrier config;
+    auto labels = triple_barrier_labels(bars, config);
+    ASSERT_GT(labels.size(), 100u);
+    for (const auto& label : labels) {
+        EXPECT_GE(label.touch, label.start);
+        EXPECT_TRUE(label.label >= -1 && label.label <= 1);
+    }
+    auto folds = purged_kfold(label_spans(labels), 5, 0.01);
+    EXPECT_EQ(folds.size(), 5u);
+}
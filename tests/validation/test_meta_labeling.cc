@@ -0,0 +1,71 @@
+#include <gtest/gtest.h>
+#include <fstream>
+#include <vector>
+#include "labeling.h"
+#include "strategies.h"
+#include "streamers.h"
+
+using namespace tzu;
+
+// Feature builder keeping its own indicator state.
+struct SmaDistance {
+    SMA sma{10};
+    std::vector<double> operator()(const Ohlcv& bar) {
+        double ma = sma.update(bar.close);
+        return {bar.close / ma - 1.0};
+    }
+};
+
+static std::vector<Ohlcv> load_bars() {
+    std::ifstream file("../data/btcusd.csv");
+    std::vector<Ohlcv> data;
+    Csv<Ohlcv> csv(file);
+    for (const auto& row : csv) data.push_back(row);
+    return data;
+}
+
+TEST(MetaLabeling, LabelsEachPrimaryBuySignal) {
+    auto bars = load_bars();
+    RSIStrat primary;
+    auto samples = meta_labels(primary, bars, SmaDistance(), TripleBarrier());
+    ASSERT_FALSE(samples.empty());
+    for (const auto& sample : samples) {
+        EXPECT_EQ(sample.signal.side, Side::BUY);
+        EXPECT_EQ(sample.features.size(), 1u);
+        EXPECT_EQ(sample.label, sample.barrier.ret > 0.0 ? 1 : 0);
+    }
+}
+
+TEST(MetaLabeling, ShortSignalsWinWhenPriceFalls) {
+    std::vector<Ohlcv> bars;
+    double prices[] = {100.0, 101.0, 99.0, 90.0, 80.0};
+    for (int i = 0; i < 5; ++i)
+        bars.push_back(Ohlcv{i, prices[i], prices[i], prices[i], prices[i], 1.0});
+    BarrierLabel label = barrier_label(bars, 1, 0.05, TripleBarrier(), Side::SELL);
+    EXPECT_EQ(label.label, 1);
+    EXPECT_GT(label.ret, 0.0);
+}
+
+TEST(MetaLabelStrat, FiltersAndSizesBuySignals) {
+    auto bars = load_bars();
+    auto reject = [](const std::vector<double>&) { return 0.2; };
+    auto accept = [](const std::vector<double>&) { return 0.8; };
+    MetaLabelStrat<RSIStrat, SmaDistance, decltype(reject)> filtered(
+            RSIStrat(), SmaDistance(), reject);
+    MetaLabelStrat<RSIStrat, SmaDistance, decltype(accept)> sized(
+            RSIStrat(), SmaDistance(), accept, 0.5, true);
+    bool sell = false;
+    bool sized_buy = false;
+    for (const auto& bar : bars) {
+        Signal a = filtered.update(bar);
+        Signal b = sized.update(bar);
+        EXPECT_NE(a.side, Side::BUY);
+        if (a.side == Side::SELL) sell = true;
+        if (b.side == Side::BUY) {
+            EXPECT_DOUBLE_EQ(b.volume, 0.8);
+            sized_buy = true;
+        }
+    }
+    EXPECT_TRUE(sell);
+    EXPECT_TRUE(sized_buy);
+}
@@ -0,0 +1,26 @@
+#include <gtest/gtest.h>
+#include "volatility.h"
+#include <cmath>
+
+using namespace tzu;
+
+TEST(EWMAVol, ReturnsNaNUntilFirstReturn) {
+    EWMAVol vol(10);
+    EXPECT_TRUE(std::isnan(vol.update(100.0)));
+    EXPECT_NEAR(vol.update(110.0), 0.1, 1e-12);
+}
+
+TEST(EWMAVol, AppliesExponentialWeights) {
+    EWMAVol vol(3);
+    vol.update(100.0);
+    vol.update(110.0);
+    double result = vol.update(99.0);
+    double alpha = 0.5;
+    EXPECT_NEAR(result, std::sqrt(alpha * 0.01 + (1.0 - alpha) * 0.01), 1e-12);
+}
+
+TEST(EWMAVol, ConstantPricesHaveZeroVolatility) {
+    EWMAVol vol(5);
+    for (int i = 0; i < 10; ++i) vol.update(50.0);
+    EXPECT_DOUBLE_EQ(vol.get(), 0.0);
+}
@@ -0,0 +1,84 @@
+#include <gtest/gtest.h>
+#include <cmath>
+#include <cstdint>
+#include <vector>
+#include "volatility.h"
+
+using namespace tzu;
+
+namespace {
+
+// Prices with GARCH(1,1) returns, from a fixed LCG
+std::vector<double> garch_prices(double omega, double alpha, double beta,
+        size_t n) {
+    uint64_t state = 42;
+    auto uniform = [&state]() {
+        state = state * 6364136223846793005ULL + 1442695040888963407ULL;
+        return static_cast<double>(state >> 11) / 9007199254740992.0;
+    };
+    std::vector<double> out = {100.0};
+    double var = omega / (1.0 - alpha - beta);
+    for (size_t i = 0; i < n; i++) {
+        double z = 0.0;
+        for (int k = 0; k < 12; k++) z += uniform();
+        double r = std::sqrt(var) * (z - 6.0);
+        out.push_back(out.back() * (1.0 + r));
+        var = omega + alpha * r * r + beta * var;
+    }
+    return out;
+}
+
+} // namespace
+
+TEST(GARCH, FollowsTheRecursion) {
+    GARCH garch(1e-5, 0.1, 0.8);
+    EXPECT_TRUE(std::isnan(garch.update(100.0)));
+    // the variance starts at its long-run value 1e-4
+    double var = 1e-5 + 0.1 * 0.0004 + 0.8 * 1e-4;
+    EXPECT_NEAR(garch.update(102.0), std::sqrt(var), 1e-12);
+    EXPECT_NEAR(garch.variance(), var, 1e-15);
+    var = 1e-5 + 0.1 * 0.0001 + 0.8 * var;
+    garch.update(102.0 * 0.99);
+    EXPECT_NEAR(garch.variance(), var, 1e-15);
+}
+
+TEST(GARCH, FitRecoversParameters) {
+    std::vector<double> prices = garch_prices(2e-6, 0.1, 0.88, 3000);
+    GARCH garch = GARCH::fit(3000);
+    EXPECT_EQ(garch.required_warmup(), 3001u);
+    double out = std::nan("");
+    for (size_t i = 0; i + 1 < prices.size(); i++)
+        EXPECT_TRUE(std::isnan(garch.update(prices[i])));
+    out = garch.update(prices.back());
+    EXPECT_FALSE(std::isnan(out));
+    EXPECT_NEAR(garch.alpha(), 0.1, 0.04);
+    EXPECT_NEAR(garch.beta(), 0.88, 0.05);
+    EXPECT_NEAR(garch.omega() / (1.0 - garch.alpha() - garch.beta()),
+            2e-6 / 0.02, 3e-5);
+}
+
+TEST(GARCH, FitRecoversOtherParameters) {
+    std::vector<double> prices = garch_prices(1e-5, 0.25, 0.6, 5000);
+    GARCH garch = GARCH::fit(5000);
+    for (double price : prices) garch.update(price);
+    EXPECT_NEAR(garch.alpha(), 0.25, 0.05);
+    EXPECT_NEAR(garch.beta(), 0.6, 0.07);
+    EXPECT_LT(garch.alpha() + garch.beta(), 0.999);
+}
+
+TEST(GARCH, RefitsPeriodically) {
+    std::vector<double> prices = garch_prices(2e-6, 0.1, 0.88, 400);
+    GARCH garch = GARCH::fit(200, 50);
+    GARCH once = GARCH::fit(200);
+    for (size_t i = 0; i <= 201; i++) {
+        garch.update(prices[i]);
+        once.update(prices[i]);
+    }
+    EXPECT_DOUBLE_EQ(garch.alpha(), once.alpha());
+    for (size_t i = 202; i < prices.size(); i++) {
+        garch.update(prices[i]);
+        once.update(prices[i]);
+    }
+    EXPECT_NE(garch.beta(), once.beta());
+    EXPECT_GT(garch.variance(), 0.0);
+}
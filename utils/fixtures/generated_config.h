@@ -0,0 +1,50 @@
+#ifndef GENERATED_CONFIG_H
+#define GENERATED_CONFIG_H
+#include <string>
+#include <iostream>
+#include <vector>
+#include <cstdlib>
+
+struct Config {
+    std::string indicators = "sma:20,ema:20,rsi:14";
+    std::string golden = "golden.csv";
+    bool write = false;
+    double tolerance = 1e-9;
+    std::string label = "unlabeled";
+    std::string input = "";
+};
+
+inline void print_help() {
+    std::cout << "Usage: [options] [input]\n\n";
+    std::cout << "Options:\n";
+    std::cout << "    -i,  --indicators     Comma separated indicators, as name:period (Default: sma:20,ema:20,rsi:14)\n";
+    std::cout << "    -g,  --golden         Golden fixture file (Default: golden.csv)\n";
+    std::cout << "    -w,  --write          Write the golden fixture instead of comparing against it (Default: false)\n";
+    std::cout << "    -t,  --tolerance      Relative tolerance for comparisons (Default: 1e-9)\n";
+    std::cout << "    -l,  --label          Label stored in the golden fixture (Default: unlabeled)\n";
+}
+
+inline void parse_args(Config& cfg, int argc, char** argv) {
+    for (int i = 1; i < argc; ++i) {
+        std::string arg = argv[i];
+        if (arg == "--help" || arg == "-h") { print_help(); std::exit(0); }
+        if ((arg == "--indicators" || arg == "-i") && i + 1 < argc) {
+            cfg.indicators = argv[++i]; continue;
+        }
+        if ((arg == "--golden" || arg == "-g") && i + 1 < argc) {
+            cfg.golden = argv[++i]; continue;
+        }
+        if ((arg == "--write" || arg == "-w")) { cfg.write = true; continue; }
+        if ((arg == "--tolerance" || arg == "-t") && i + 1 < argc) {
+            cfg.tolerance = std::stod(argv[++i]); continue;
+        }
+        if ((arg == "--label" || arg == "-l") && i + 1 < argc) {
+            cfg.label = argv[++i]; continue;
+        }
+        if (arg[0] != '-') {
+            cfg.input = arg;
+            continue;
+        }
+    }
+}
+#endif
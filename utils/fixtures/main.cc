@@ -0,0 +1,48 @@
+#include <fstream>
+#include <iostream>
+#include <sstream>
+#include <vector>
+#include "generated_config.h"
+#include "tzu.h"
+
+using namespace tzu;
+
+int main(int argc, char* argv[]) {
+    Config config;
+    parse_args(config, argc, argv);
+    std::ifstream input(config.input);
+    if (!input.is_open()) {
+        std::cerr << "Failed to open the input file." << std::endl;
+        return 1;
+    }
+    std::vector<Ohlcv> bars;
+    Csv<Ohlcv> csv(input);
+    for (const auto& bar : csv)
+        bars.push_back(bar);
+    FixtureSet set;
+    std::istringstream specs(config.indicators);
+    std::string spec;
+    while (std::getline(specs, spec, ',')) {
+        if (!set.add(spec)) {
+            std::cerr << "Invalid indicator: " << spec << std::endl;
+            return 1;
+        }
+    }
+    Fixture current = set.run(bars, config.label);
+    if (config.write) {
+        std::ofstream out(config.golden);
+        write_fixture(out, current);
+        std::cout << "fixture_rows:" << current.timestamps.size()
+                  << " fixture_columns:" << current.columns.size() << std::endl;
+        return 0;
+    }
+    std::ifstream in(config.golden);
+    Fixture golden;
+    if (!in.is_open() || !read_fixture(in, golden)) {
+        std::cerr << "Failed to read the golden fixture." << std::endl;
+        return 1;
+    }
+    FixtureReport report = compare_fixtures(golden, current, config.tolerance);
+    std::cout << report << std::endl;
+    return report.ok() ? 0 : 2;
+}
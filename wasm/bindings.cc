@@ -0,0 +1,151 @@
+/**
+ * JavaScript bindings of the indicators, to compute them in a browser
+ * (e.g. for charting front-ends) with the same code used in backtests.
+ * Built with Emscripten when the `TZU_WITH_WASM` CMake option is set:
+ *
+ *     emcmake cmake -DTZU_WITH_WASM=ON .. && cmake --build . --target tzu_wasm
+ *
+ * which produces `tzu_wasm.js` and `tzu_wasm.wasm`, loaded as a module
+ * factory named `tzu`:
+ *
+ *     const lib = await tzu();
+ *     const sma = new lib.SMA(20);
+ *     const values = sma.batch(closes);   // array in, array out
+ *     sma.delete();
+ *
+ * Indicators on bars take objects with `open`, `high`, `low` and
+ * `close` fields (and optionally `timestamp` and `volume`), as used by
+ * most charting libraries. Objects created from JavaScript must be
+ * released with `delete()`.
+ */
+
+#include <emscripten/bind.h>
+#include <emscripten/val.h>
+#include <cmath>
+#include <vector>
+#include "tzu/indicators.h"
+
+using namespace emscripten;
+using namespace tzu;
+
+namespace {
+
+Ohlcv to_bar(const val& b) {
+    auto field = [&b](const char* name, double fallback) {
+        val v = b[name];
+        return v.isUndefined() ? fallback : v.as<double>();
+    };
+    return Ohlcv(static_cast<int64_t>(field("timestamp", 0.0)),
+            field("open", 0.0), field("high", 0.0), field("low", 0.0),
+            field("close", 0.0), field("volume", 0.0));
+}
+
+/**
+ * Registers the update, get and batch functions of an indicator. The
+ * input is a number or a bar, depending on the indicator.
+ */
+template <typename Ind, typename In>
+class_<Ind> bind_indicator(const char* name) {
+    return class_<Ind>(name)
+        .function("get", optional_override([](const Ind& self) {
+            return self.get();
+        }))
+        .function("update", optional_override([](Ind& self, const val& v) {
+            return self.update(In::convert(v));
+        }))
+        .function("batch", optional_override([](Ind& self, const val& values) {
+            std::vector<typename Ind::output_type> out;
+            size_t n = values["length"].as<size_t>();
+            out.reserve(n);
+            for (size_t i = 0; i < n; i++)
+                out.push_back(self.update(In::convert(values[i])));
+            return val::array(out);
+        }));
+}
+
+struct BollingerResult {
+    double middle;
+    double upper;
+    double lower;
+};
+
+/**
+ * Bollinger bands, `k` standard deviations of the last `period` values
+ * around their SMA, built from `SMA` and `MVar` since the library has
+ * no indicator of its own for them.
+ */
+class Bollinger {
+    SMA sma;
+    MVar mvar;
+    double k;
+    BollingerResult data;
+public:
+    using output_type = BollingerResult;
+    Bollinger(size_t period, double k)
+        : sma(period), mvar(period, 0), k(k),
+          data{std::nan(""), std::nan(""), std::nan("")} {}
+    BollingerResult get() const { return data; }
+    BollingerResult update(double value) {
+        double middle = sma.update(value);
+        double width = k * std::sqrt(mvar.update(value));
+        data = BollingerResult{middle, middle + width, middle - width};
+        return data;
+    }
+};
+
+struct Number {
+    static double convert(const val& v) { return v.as<double>(); }
+};
+
+struct Bar {
+    static Ohlcv convert(const val& v) { return to_bar(v); }
+};
+
+} // namespace
+
+EMSCRIPTEN_BINDINGS(tzu) {
+    value_object<MACDResult>("MACDResult")
+        .field("macd", &MACDResult::macd)
+        .field("signal", &MACDResult::signal)
+        .field("histogram", &MACDResult::histogram);
+    value_object<ADXResult>("ADXResult")
+        .field("adx", &ADXResult::adx)
+        .field("plus_di", &ADXResult::plus_di)
+        .field("minus_di", &ADXResult::minus_di);
+    value_object<LinRegResult>("LinRegResult")
+        .field("slope", &LinRegResult::slope)
+        .field("intercept", &LinRegResult::intercept)
+        .field("value", &LinRegResult::value)
+        .field("forecast", &LinRegResult::forecast)
+        .field("r2", &LinRegResult::r2)
+        .field("upper", &LinRegResult::upper)
+        .field("lower", &LinRegResult::lower);
+    value_object<BollingerResult>("BollingerResult")
+        .field("middle", &BollingerResult::middle)
+        .field("upper", &BollingerResult::upper)
+        .field("lower", &BollingerResult::lower);
+
+    bind_indicator<SMA, Number>("SMA").constructor<size_t>();
+    bind_indicator<EMA, Number>("EMA").constructor<size_t, double>();
+    bind_indicator<WMA, Number>("WMA").constructor<size_t>();
+    bind_indicator<HMA, Number>("HMA").constructor<size_t>();
+    bind_indicator<ZLEMA, Number>("ZLEMA").constructor<size_t, double>();
+    bind_indicator<T3, Number>("T3").constructor<size_t, double>();
+    bind_indicator<MACD, Number>("MACD")
+        .constructor<size_t, size_t, size_t, double>();
+    bind_indicator<LinReg, Number>("LinReg").constructor<size_t, double>();
+    bind_indicator<RSI, Bar>("RSI").constructor<size_t>();
+    bind_indicator<ATR, Bar>("ATR").constructor<size_t>();
+    bind_indicator<ADX, Bar>("ADX").constructor<size_t>();
+    bind_indicator<Bollinger, Number>("Bollinger")
+        .constructor<size_t, double>();
+
+    class_<MVar>("MVar")
+        .constructor<size_t, size_t>()
+        .function("get", optional_override([](const MVar& self) {
+            return self.get();
+        }))
+        .function("update", optional_override([](MVar& self, double v) {
+            return self.update(v);
+        }));
+}